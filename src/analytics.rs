@@ -0,0 +1,68 @@
+// --- REPLY ANALYTICS ---
+// Aggregates the 👍/👎 ratings, quality labels and self-reported confidence stored in
+// session metadata, per model, so choosing a model for a class can rest on more than a hunch.
+
+use crate::confidence::UNSURE_BELOW;
+use crate::session::Rating;
+use crate::session_store::SessionStore;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, Default)]
+pub struct ModelStats {
+    pub model: String,
+    pub up: usize,
+    pub down: usize,
+    pub labels: BTreeMap<String, usize>,
+    pub sessions: usize, // Sessions with at least one reaction for this model
+    pub confidence_sum: u64,
+    pub confidence_reports: usize,
+    pub unsure: usize, // Reports below UNSURE_BELOW
+}
+
+impl ModelStats {
+    // Share of rated replies that got a 👍
+    pub fn approval(&self) -> Option<f32> {
+        let rated = self.up + self.down;
+        (rated > 0).then(|| self.up as f32 / rated as f32)
+    }
+
+    pub fn mean_confidence(&self) -> Option<f32> {
+        (self.confidence_reports > 0).then(|| self.confidence_sum as f32 / self.confidence_reports as f32)
+    }
+}
+
+// Blocking: reads every saved session. Most-rated models first.
+pub fn aggregate(store: &SessionStore) -> Vec<ModelStats> {
+    let mut by_model: BTreeMap<String, ModelStats> = BTreeMap::new();
+    for entry in store.list() {
+        let Ok(file) = store.load(&entry.name) else { continue };
+        let mut seen = Vec::new();
+        for reaction in &file.meta.reactions {
+            let model = if reaction.model.is_empty() { &file.meta.model } else { &reaction.model };
+            let stats = by_model.entry(model.clone()).or_insert_with(|| ModelStats { model: model.clone(), ..Default::default() });
+            match reaction.rating {
+                Some(Rating::Up) => stats.up += 1,
+                Some(Rating::Down) => stats.down += 1,
+                None => {}
+            }
+            if let Some(percent) = reaction.confidence {
+                stats.confidence_sum += percent as u64;
+                stats.confidence_reports += 1;
+                if percent < UNSURE_BELOW {
+                    stats.unsure += 1;
+                }
+            }
+            for label in &reaction.labels {
+                *stats.labels.entry(label.clone()).or_default() += 1;
+            }
+            if !seen.contains(model) {
+                seen.push(model.clone());
+                stats.sessions += 1;
+            }
+        }
+    }
+
+    let mut stats: Vec<ModelStats> = by_model.into_values().collect();
+    stats.sort_by(|a, b| (b.up + b.down).cmp(&(a.up + a.down)).then_with(|| a.model.cmp(&b.model)));
+    stats
+}
@@ -0,0 +1,86 @@
+// --- SESSION ASSETS ---
+// Attached images are kept as files under sessions/assets and referenced from their
+// message, so they outlive the send and can be browsed in the gallery later.
+
+use crate::session::Message;
+use crate::session_store::SessionStore;
+use base64::Engine;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const ASSETS_DIR: &str = "assets";
+
+#[derive(Clone, Debug)]
+pub struct GalleryItem {
+    pub file: PathBuf,       // Absolute-ish path to the image
+    pub session: PathBuf,    // Session file that references it
+    pub message_index: usize,
+    pub created: i64,        // Unix seconds
+    pub caption: String,     // The message it was sent with
+}
+
+// Writes the image and returns the path to store in Message::attachment
+pub fn store(sessions_dir: &Path, b64: &str, name: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(b64.trim())
+        .map_err(|e| format!("Invalid image data: {}", e))?;
+    let dir = sessions_dir.join(ASSETS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    let safe_name: String = name.chars().map(|c| if c.is_alphanumeric() || c == '.' || c == '-' { c } else { '_' }).collect();
+    let file_name = format!("{}_{}", chrono::Local::now().format("%Y%m%d_%H%M%S_%3f"), safe_name);
+    let path = dir.join(&file_name);
+    fs::write(&path, bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(format!("{}/{}", ASSETS_DIR, file_name))
+}
+
+pub fn load_base64(sessions_dir: &Path, attachment: &str) -> Result<String, String> {
+    let path = sessions_dir.join(attachment);
+    let bytes = fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+fn item(sessions_dir: &Path, session: &Path, index: usize, msg: &Message) -> Option<GalleryItem> {
+    let file = sessions_dir.join(msg.attachment.as_ref()?);
+    if !file.is_file() {
+        return None; // Deleted by hand
+    }
+    let created = msg.timestamp.map(|ms| ms / 1000).unwrap_or_else(|| {
+        fs::metadata(&file)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs() as i64)
+    });
+    Some(GalleryItem {
+        file,
+        session: session.to_path_buf(),
+        message_index: index,
+        created,
+        caption: msg.content.chars().take(120).collect(),
+    })
+}
+
+// Blocking: every attachment referenced by a saved session, newest first. The images
+// are files under `sessions_dir` whichever store keeps the messages.
+pub fn collect(sessions_dir: &Path, store: &SessionStore) -> Vec<GalleryItem> {
+    let mut items: Vec<GalleryItem> = store
+        .list()
+        .iter()
+        .filter_map(|entry| store.load(&entry.name).ok().map(|file| (sessions_dir.join(&entry.name), file)))
+        .flat_map(|(path, file)| {
+            file.messages.iter().enumerate()
+                .filter_map(|(i, msg)| item(sessions_dir, &path, i, msg))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    items.sort_by_key(|i| std::cmp::Reverse(i.created));
+    items
+}
+
+// Downscaled RGBA pixels for a gallery tile
+pub fn thumbnail(file: &Path, max_side: u32) -> Result<([usize; 2], Vec<u8>), String> {
+    let img = image::open(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let thumb = img.thumbnail(max_side, max_side).to_rgba8();
+    Ok(([thumb.width() as usize, thumb.height() as usize], thumb.into_raw()))
+}
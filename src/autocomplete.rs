@@ -0,0 +1,117 @@
+// --- INPUT AUTOCOMPLETE ---
+// Prefix trie over the names that already came up: words from the conversation and the
+// last retrieved documents. Identifier-like tokens (LM741, R_load, V_out) count as one
+// word; the most frequent completion wins. The trie stops growing at MAX_NODES, so a
+// long session or a big document only counts the words already in it.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+const MIN_WORD_CHARS: usize = 4; // Shorter words aren't worth completing
+const MAX_WORD_CHARS: usize = 40; // Longer "words" are hashes and base64
+const MIN_PREFIX_CHARS: usize = 2;
+const MAX_NODES: usize = 200_000;
+
+#[derive(Default)]
+struct Node {
+    children: BTreeMap<char, usize>,
+    count: u32,      // Times a word ended here
+    best_below: u32, // Highest count in this subtree, so the search can skip weaker branches
+}
+
+pub struct Trie {
+    nodes: Vec<Node>,
+    cache: RefCell<Option<(String, Option<String>)>>, // Last prefix asked for; the input asks every frame
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self { nodes: vec![Node::default()], cache: RefCell::new(None) }
+    }
+}
+
+// Identifier-ish tokens: letters, digits and '_', with at least one letter
+pub fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| (MIN_WORD_CHARS..=MAX_WORD_CHARS).contains(&w.chars().count()) && w.chars().any(char::is_alphabetic))
+}
+
+impl Trie {
+    pub fn insert(&mut self, word: &str) {
+        let mut path = vec![0];
+        for c in word.chars() {
+            let at = *path.last().expect("starts at the root");
+            let next = match self.nodes[at].children.get(&c) {
+                Some(&next) => next,
+                // Full: new words are dropped, known ones still count
+                None if self.nodes.len() + word.len() > MAX_NODES => return,
+                None => {
+                    self.nodes.push(Node::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[at].children.insert(c, next);
+                    next
+                }
+            };
+            path.push(next);
+        }
+        let end = *path.last().expect("starts at the root");
+        self.nodes[end].count += 1;
+        let count = self.nodes[end].count;
+        for node in path {
+            self.nodes[node].best_below = self.nodes[node].best_below.max(count);
+        }
+        *self.cache.get_mut() = None;
+    }
+
+    pub fn insert_text(&mut self, text: &str) {
+        for word in words(text) {
+            self.insert(word);
+        }
+    }
+
+    // Most frequent word that extends `prefix` (case-sensitive), or None
+    pub fn complete(&self, prefix: &str) -> Option<String> {
+        if prefix.chars().count() < MIN_PREFIX_CHARS {
+            return None;
+        }
+        if let Some((cached, word)) = self.cache.borrow().as_ref() {
+            if cached == prefix {
+                return word.clone();
+            }
+        }
+        let word = self.search(prefix);
+        *self.cache.borrow_mut() = Some((prefix.to_string(), word.clone()));
+        word
+    }
+
+    fn search(&self, prefix: &str) -> Option<String> {
+        let mut at = 0;
+        for c in prefix.chars() {
+            at = *self.nodes[at].children.get(&c)?;
+        }
+
+        // Depth-first over the subtree; ties go to the shorter word. A branch is not
+        // entered when its best count can't beat the word found so far, or could only tie
+        // with words at least as long.
+        let mut best: Option<(u32, String)> = None;
+        let mut stack = vec![(at, prefix.to_string())];
+        while let Some((node, word)) = stack.pop() {
+            let below = self.nodes[node].best_below;
+            if best.as_ref().is_some_and(|(n, w)| below < *n || (below == *n && word.len() >= w.len())) {
+                continue;
+            }
+            let count = self.nodes[node].count;
+            let better = match &best {
+                Some((n, w)) => count > *n || (count == *n && word.len() < w.len()),
+                None => true,
+            };
+            if count > 0 && word != prefix && better {
+                best = Some((count, word.clone()));
+            }
+            for (c, &child) in &self.nodes[node].children {
+                stack.push((child, format!("{}{}", word, c)));
+            }
+        }
+        best.map(|(_, word)| word)
+    }
+}
@@ -0,0 +1,534 @@
+// --- BACKEND CLIENT ---
+// Connection settings for the model server, including auth for reverse proxies, and
+// the Ollama API calls. Requests go through our own reqwest client so custom headers
+// can be attached. Chat itself goes through `chat_backend::ChatBackend`.
+
+use crate::llama_server::ChatTemplate;
+use crate::secrets;
+use ollama_rs::generation::chat::request::ChatMessageRequest;
+use ollama_rs::generation::chat::ChatMessageResponse;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AuthMode {
+    None,
+    Bearer, // Authorization: Bearer <token>
+    Basic,  // Authorization: Basic base64(user:password)
+}
+
+// Which API the server speaks; see chat_backend.rs
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum BackendKind {
+    #[default]
+    Ollama,
+    OpenAi,   // /v1/chat/completions: vLLM, LM Studio, ...
+    LlamaCpp, // llama.cpp's llama-server, with its /health and /slots status
+}
+
+impl BackendKind {
+    pub const ALL: [BackendKind; 3] = [BackendKind::Ollama, BackendKind::OpenAi, BackendKind::LlamaCpp];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Ollama => "Ollama",
+            Self::OpenAi => "OpenAI-compatible",
+            Self::LlamaCpp => "llama.cpp",
+        }
+    }
+}
+
+// One entry of /api/tags
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct LocalModel {
+    pub name: String,
+    pub size: u64, // Bytes on disk, roughly what it needs in VRAM
+    pub details: ModelDetails,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ModelDetails {
+    pub family: String,
+    pub parameter_size: String,     // "8.0B"
+    pub quantization_level: String, // "Q4_K_M"
+}
+
+// /api/show: what `ollama show` prints
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ModelCard {
+    pub name: String, // Filled in by us, not part of the response
+    pub license: String,
+    pub modelfile: String,
+    pub parameters: String,
+    pub template: String,
+    pub details: ModelDetails,
+    pub model_info: serde_json::Map<String, serde_json::Value>, // "llama.context_length", ...
+}
+
+impl ModelCard {
+    pub fn architecture(&self) -> Option<&str> {
+        self.model_info.get("general.architecture").and_then(|v| v.as_str())
+    }
+
+    pub fn context_length(&self) -> Option<u64> {
+        let arch = self.architecture()?;
+        self.model_info.get(&format!("{}.context_length", arch)).and_then(|v| v.as_u64())
+    }
+
+    pub fn parameter_count(&self) -> Option<u64> {
+        self.model_info.get("general.parameter_count").and_then(|v| v.as_u64())
+    }
+
+    fn arch_value(&self, key: &str) -> Option<u64> {
+        let arch = self.architecture()?;
+        self.model_info.get(&format!("{}.{}", arch, key)).and_then(|v| v.as_u64())
+    }
+
+    // The window Ollama actually allocates: the Modelfile's num_ctx, else its default
+    pub fn num_ctx(&self) -> u64 {
+        self.parameters
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("num_ctx")?.trim().parse().ok())
+            .unwrap_or(OLLAMA_DEFAULT_CTX)
+    }
+
+    // f16 keys and values for every layer: 2 * layers * kv_heads * head_dim * 2 bytes per token
+    pub fn kv_cache_bytes(&self, tokens: u64) -> Option<u64> {
+        let layers = self.arch_value("block_count")?;
+        let embedding = self.arch_value("embedding_length")?;
+        let heads = self.arch_value("attention.head_count")?.max(1);
+        let kv_heads = self.arch_value("attention.head_count_kv").unwrap_or(heads);
+        Some(2 * layers * kv_heads * (embedding / heads) * 2 * tokens)
+    }
+}
+
+const OLLAMA_DEFAULT_CTX: u64 = 2048;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct BackendConfig {
+    pub kind: BackendKind,
+    pub host: String, // Scheme included, e.g. "http://127.0.0.1"
+    pub port: u16,
+    pub auth: AuthMode,
+    pub basic_user: String,
+    pub headers: Vec<(String, String)>, // Extra headers; a secret one has an empty value here
+    pub secret_headers: Vec<String>, // Names of the headers whose value is in the keyring
+    pub keep_alive: String, // Sent with every chat ("10m", "2h", "-1" = until unloaded); empty = Ollama's 5m
+    pub chat_template: ChatTemplate, // llama-server's /completion fallback only
+    #[serde(skip)]
+    pub account: Option<String>, // Keyring account to use instead of the per-host one (hosted APIs)
+}
+
+impl Default for BackendConfig {
+    fn default() -> Self {
+        let (host, port) = std::env::var("OLLAMA_HOST")
+            .ok()
+            .and_then(|value| parse_address(&value))
+            .unwrap_or_else(|| ("http://127.0.0.1".to_string(), 11434));
+        Self {
+            kind: BackendKind::Ollama,
+            host,
+            port,
+            auth: AuthMode::None,
+            basic_user: String::new(),
+            headers: Vec::new(),
+            secret_headers: Vec::new(),
+            keep_alive: String::new(),
+            chat_template: ChatTemplate::default(),
+            account: None,
+        }
+    }
+}
+
+// Header names that usually carry a credential ("X-API-Key", "X-Auth-Token", ...)
+pub fn looks_secret(header: &str) -> bool {
+    let header = header.to_ascii_lowercase();
+    ["key", "token", "secret", "auth", "password", "cookie"].iter().any(|word| header.contains(word))
+}
+
+// Ollama takes a duration string ("30m") or a number of seconds (-1 = forever)
+fn keep_alive_value(keep_alive: &str) -> serde_json::Value {
+    match keep_alive.trim().parse::<i64>() {
+        Ok(seconds) => serde_json::json!(seconds),
+        Err(_) => serde_json::json!(keep_alive.trim()),
+    }
+}
+
+// OLLAMA_HOST as the ollama CLI reads it: "10.0.0.5", "10.0.0.5:8080", "https://lab.example.org"
+fn parse_address(value: &str) -> Option<(String, u16)> {
+    let value = value.trim().trim_end_matches('/');
+    if value.is_empty() {
+        return None;
+    }
+    let (scheme, rest) = match value.split_once("://") {
+        Some((scheme, rest)) => (scheme, rest),
+        None => ("http", value),
+    };
+    let default_port = if scheme == "https" { 443 } else { 11434 };
+    let (host, port) = match rest.rsplit_once(':') {
+        Some((host, port)) if !host.ends_with(':') => (host, port.parse().ok()?),
+        _ => (rest, default_port),
+    };
+    let host = if host == "0.0.0.0" { "127.0.0.1" } else { host };
+    Some((format!("{}://{}", scheme, host), port))
+}
+
+impl BackendConfig {
+    pub fn uri(&self) -> String {
+        format!("{}:{}", self.host.trim_end_matches('/'), self.port)
+    }
+
+    // For the `ollama` CLI, which otherwise talks to 127.0.0.1:11434
+    pub fn cli_env(&self) -> Vec<(&'static str, String)> {
+        vec![("OLLAMA_HOST", self.uri())]
+    }
+
+    // Loopback servers never see data leave the machine
+    pub fn is_local(&self) -> bool {
+        let host = self.host.trim_start_matches("http://").trim_start_matches("https://");
+        matches!(host.trim_end_matches('/'), "127.0.0.1" | "localhost" | "[::1]" | "::1")
+    }
+
+    // Keyring account holding this host's token / password
+    pub fn secret_account(&self) -> String {
+        match &self.account {
+            Some(account) => account.clone(),
+            None => format!("ollama-auth@{}", self.uri()),
+        }
+    }
+
+    // Keyring account holding the value of the custom header `name`
+    pub fn header_account(&self, name: &str) -> String {
+        format!("{}:header:{}", self.secret_account(), name.trim())
+    }
+
+    pub fn is_secret_header(&self, name: &str) -> bool {
+        self.secret_headers.iter().any(|h| h.eq_ignore_ascii_case(name.trim()))
+    }
+
+    // Moves the value of header `i` (if any) into the keyring and marks the header secret.
+    // Returns the account, for the list of secret names.
+    pub fn lock_header(&mut self, i: usize) -> Result<String, String> {
+        let name = self.headers[i].0.trim().to_string();
+        if name.is_empty() {
+            return Err("Name the header first".to_string());
+        }
+        let account = self.header_account(&name);
+        if !self.headers[i].1.is_empty() {
+            secrets::set(&account, &self.headers[i].1)?;
+            self.headers[i].1.clear();
+        }
+        if !self.is_secret_header(&name) {
+            self.secret_headers.push(name);
+        }
+        Ok(account)
+    }
+
+    // Brings the value of a secret header back into the config and out of the keyring
+    pub fn unlock_header(&mut self, i: usize) -> Result<String, String> {
+        let name = self.headers[i].0.trim().to_string();
+        let account = self.header_account(&name);
+        if let Some(value) = secrets::get(&account) {
+            self.headers[i].1 = value;
+        }
+        secrets::delete(&account)?;
+        self.secret_headers.retain(|h| !h.eq_ignore_ascii_case(&name));
+        Ok(account)
+    }
+
+    pub fn remove_header(&mut self, i: usize) -> Result<(), String> {
+        let (name, _) = self.headers.remove(i);
+        if self.is_secret_header(&name) {
+            self.secret_headers.retain(|h| !h.eq_ignore_ascii_case(name.trim()));
+            secrets::delete(&self.header_account(&name))?;
+        }
+        Ok(())
+    }
+
+    fn header_map(&self) -> Result<HeaderMap, String> {
+        let mut headers = HeaderMap::new();
+
+        for (key, value) in &self.headers {
+            if key.trim().is_empty() {
+                continue;
+            }
+            let name = HeaderName::from_bytes(key.trim().as_bytes()).map_err(|e| format!("Header '{}': {}", key, e))?;
+            let value = match self.is_secret_header(key) {
+                true => {
+                    let Some(secret) = secrets::get(&self.header_account(key)) else { continue };
+                    let mut value = HeaderValue::from_str(secret.trim()).map_err(|e| format!("Header '{}': {}", key, e))?;
+                    value.set_sensitive(true);
+                    value
+                }
+                false => HeaderValue::from_str(value.trim()).map_err(|e| format!("Header '{}': {}", key, e))?,
+            };
+            headers.insert(name, value);
+        }
+
+        let auth_value = match self.auth {
+            AuthMode::None => None,
+            AuthMode::Bearer => secrets::get(&self.secret_account()).map(|token| format!("Bearer {}", token)),
+            AuthMode::Basic => secrets::get(&self.secret_account()).map(|password| {
+                use base64::Engine;
+                let raw = format!("{}:{}", self.basic_user, password);
+                format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(raw))
+            }),
+        };
+        if let Some(value) = auth_value {
+            let mut value = HeaderValue::from_str(&value).map_err(|e| e.to_string())?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+
+    pub fn client(&self) -> Result<reqwest::Client, String> {
+        reqwest::Client::builder()
+            .default_headers(self.header_map()?)
+            .build()
+            .map_err(|e| e.to_string())
+    }
+
+    // The configured keep_alive on a /api/chat body, unless it is left to the server
+    fn add_keep_alive(&self, body: &mut serde_json::Value) {
+        if !self.keep_alive.trim().is_empty() {
+            body["keep_alive"] = keep_alive_value(&self.keep_alive);
+        }
+    }
+
+    // Non-streaming /api/chat call, same wire format ollama-rs uses
+    pub async fn send_chat(&self, request: &ChatMessageRequest) -> Result<ChatMessageResponse, String> {
+        let mut body = serde_json::to_value(request).map_err(|e| e.to_string())?;
+        self.add_keep_alive(&mut body);
+        let res = self.client()?
+            .post(format!("{}/api/chat", self.uri()))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("{}: {}", status, text));
+        }
+        res.json::<ChatMessageResponse>().await.map_err(|e| e.to_string())
+    }
+
+    // Streaming /api/chat: `on_token` gets each content piece as it arrives and returns
+    // false to stop early. The request's own `stream` flag is overridden.
+    pub async fn send_chat_stream(&self, request: &ChatMessageRequest, mut on_token: impl FnMut(&str) -> bool) -> Result<(), String> {
+        let mut body = serde_json::to_value(request).map_err(|e| e.to_string())?;
+        body["stream"] = serde_json::Value::Bool(true);
+        self.add_keep_alive(&mut body);
+        let mut res = self.client()?
+            .post(format!("{}/api/chat", self.uri()))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("{}: {}", status, text));
+        }
+
+        // NDJSON; a chunk can end mid-line (or mid-character), so bytes are buffered until '\n'
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+            buffer.extend_from_slice(&chunk);
+            while let Some(newline) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=newline).collect();
+                let Ok(event) = serde_json::from_slice::<serde_json::Value>(&line) else { continue };
+                if let Some(err) = event["error"].as_str() {
+                    return Err(err.to_string());
+                }
+                if let Some(token) = event["message"]["content"].as_str().filter(|t| !t.is_empty()) {
+                    if !on_token(token) {
+                        return Ok(());
+                    }
+                }
+                if event["done"].as_bool() == Some(true) {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Evicts a model from memory now: an empty /api/generate with keep_alive 0
+    pub async fn unload_model(&self, name: &str) -> Result<(), String> {
+        let res = self.client()?
+            .post(format!("{}/api/generate", self.uri()))
+            .json(&serde_json::json!({ "model": name, "keep_alive": 0 }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("{}: {}", status, text));
+        }
+        Ok(())
+    }
+
+    // Loads a model into memory without generating anything, and keeps it there for
+    // `keep_alive` ("2h", "30m", "-1" = until unloaded)
+    pub async fn load_model(&self, name: &str, keep_alive: &str) -> Result<(), String> {
+        let res = self.client()?
+            .post(format!("{}/api/generate", self.uri()))
+            .json(&serde_json::json!({ "model": name, "keep_alive": keep_alive_value(keep_alive) }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("{}: {}", status, text));
+        }
+        Ok(())
+    }
+
+    // Removes a model and its unshared layers from the server's disk (/api/delete)
+    pub async fn delete_model(&self, name: &str) -> Result<(), String> {
+        let res = self.client()?
+            .delete(format!("{}/api/delete", self.uri()))
+            .json(&serde_json::json!({ "name": name }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("{}: {}", status, text));
+        }
+        Ok(())
+    }
+
+    // Models installed on the server (/api/tags)
+    pub async fn list_local_models(&self) -> Result<Vec<LocalModel>, String> {
+        #[derive(Deserialize)]
+        struct Tags {
+            models: Vec<LocalModel>,
+        }
+        let res = self.client()?
+            .get(format!("{}/api/tags", self.uri()))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            return Err(format!("/api/tags: {}", res.status()));
+        }
+        res.json::<Tags>().await.map(|t| t.models).map_err(|e| e.to_string())
+    }
+
+    // Models the server holds in memory right now (/api/ps)
+    pub async fn running_models(&self) -> Result<Vec<String>, String> {
+        #[derive(Deserialize)]
+        struct Running {
+            name: String,
+        }
+        #[derive(Deserialize)]
+        struct Ps {
+            models: Vec<Running>,
+        }
+        let res = self.client()?
+            .get(format!("{}/api/ps", self.uri()))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            return Err(format!("/api/ps: {}", res.status()));
+        }
+        res.json::<Ps>().await.map(|p| p.models.into_iter().map(|m| m.name).collect()).map_err(|e| e.to_string())
+    }
+
+    pub async fn show_model(&self, name: &str) -> Result<ModelCard, String> {
+        let res = self.client()?
+            .post(format!("{}/api/show", self.uri()))
+            .json(&serde_json::json!({ "name": name }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("{}: {}", status, text));
+        }
+        let mut card = res.json::<ModelCard>().await.map_err(|e| e.to_string())?;
+        card.name = name.to_string();
+        Ok(card)
+    }
+
+    // /api/pull, reporting each NDJSON status line ("pulling manifest", "downloading 42%")
+    // with the completed fraction of the layer being downloaded, when there is one
+    pub async fn pull_model(&self, name: &str, mut on_status: impl FnMut(String, Option<f32>)) -> Result<(), String> {
+        let mut res = self.client()?
+            .post(format!("{}/api/pull", self.uri()))
+            .json(&serde_json::json!({ "name": name, "stream": true }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("{}: {}", status, text));
+        }
+
+        let mut buffer = String::new();
+        while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+            while let Some(newline) = buffer.find('\n') {
+                let line: String = buffer.drain(..=newline).collect();
+                let Ok(event) = serde_json::from_str::<serde_json::Value>(line.trim()) else { continue };
+                if let Some(err) = event["error"].as_str() {
+                    return Err(err.to_string());
+                }
+                let status = event["status"].as_str().unwrap_or_default();
+                match (event["completed"].as_u64(), event["total"].as_u64()) {
+                    (Some(done), Some(total)) if total > 0 => {
+                        on_status(format!("{} {}%", status, done * 100 / total), Some(done as f32 / total as f32))
+                    }
+                    _ => on_status(status.to_string(), None),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{looks_secret, parse_address};
+
+    #[test]
+    fn parses_ollama_host_values() {
+        assert_eq!(parse_address("10.0.0.5"), Some(("http://10.0.0.5".to_string(), 11434)));
+        assert_eq!(parse_address("10.0.0.5:8080"), Some(("http://10.0.0.5".to_string(), 8080)));
+        assert_eq!(parse_address("https://lab.example.org"), Some(("https://lab.example.org".to_string(), 443)));
+        assert_eq!(parse_address("http://localhost:11434/"), Some(("http://localhost".to_string(), 11434)));
+    }
+
+    #[test]
+    fn binds_all_becomes_loopback() {
+        assert_eq!(parse_address("0.0.0.0:11434"), Some(("http://127.0.0.1".to_string(), 11434)));
+    }
+
+    #[test]
+    fn rejects_empty_and_bad_ports() {
+        assert_eq!(parse_address("  "), None);
+        assert_eq!(parse_address("lab:ollama"), None);
+    }
+
+    #[test]
+    fn spots_credential_headers() {
+        assert!(looks_secret("X-API-Key"));
+        assert!(looks_secret("X-Auth-Token"));
+        assert!(!looks_secret("X-Request-Source"));
+    }
+}
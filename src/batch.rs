@@ -0,0 +1,143 @@
+// --- HEADLESS BATCH RUNS ---
+// `--run-session <template> --prompt-file <file>`: runs a list of prompts through one
+// conversation without opening the window, using the model, system prompt and RAG
+// settings from a small TOML template, and writes the result as a normal session in the
+// profile's store.
+
+use crate::config::AppConfig;
+use crate::context::{self, ContextPlacement};
+use crate::profile::Profile;
+use crate::rag_trigger::{self, RagMode};
+use crate::session::Message;
+use crate::session_store::{SessionStore, StoreKind};
+use ollama_rs::generation::chat::request::ChatMessageRequest;
+use serde::Deserialize;
+use std::path::Path;
+
+// Prompts in the prompt file are separated by lines containing only this
+pub const PROMPT_SEPARATOR: &str = "---";
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SessionTemplate {
+    pub model: String,
+    pub system_prompt: String, // Empty = the profile's own
+    pub rag: RagMode,
+    pub research_dir: String,
+    pub section: String, // e.g. "Chapter 6"; empty = whole documents
+    pub context_placement: ContextPlacement,
+    pub tags: Vec<String>,
+}
+
+impl Default for SessionTemplate {
+    fn default() -> Self {
+        Self {
+            model: String::new(),
+            system_prompt: String::new(),
+            rag: RagMode::Off,
+            research_dir: "./research".to_string(),
+            section: String::new(),
+            context_placement: ContextPlacement::PrependUser,
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl SessionTemplate {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let template: Self = toml::from_str(&raw).map_err(|e| format!("{}: {}", path.display(), e))?;
+        if template.model.trim().is_empty() {
+            return Err(format!("{}: template has no model", path.display()));
+        }
+        Ok(template)
+    }
+}
+
+pub fn read_prompts(path: &Path) -> Result<Vec<String>, String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let prompts: Vec<String> = raw
+        .split(&format!("\n{}\n", PROMPT_SEPARATOR))
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if prompts.is_empty() {
+        return Err(format!("{}: no prompts", path.display()));
+    }
+    Ok(prompts)
+}
+
+// Blocking: returns where the session was written
+pub fn run(profile: &Profile, template_path: &Path, prompt_file: &Path) -> Result<String, String> {
+    let template = SessionTemplate::load(template_path)?;
+    let prompts = read_prompts(prompt_file)?;
+    let config = AppConfig::load(&profile.config_path());
+    profile.create_dirs()?;
+
+    let filters = config.research_filters.get(&template.research_dir).cloned().unwrap_or_default();
+    let store = SessionStore::open(config.session_store, &profile.sessions_dir())?;
+    // Runs started back to back by a script must not overwrite each other
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+    let mut name = format!("batch_{}.json", stamp);
+    let mut n = 1;
+    while store.load(&name).is_ok() {
+        n += 1;
+        name = format!("batch_{}_{}.json", stamp, n);
+    }
+
+    let system = if template.system_prompt.trim().is_empty() { &config.system_prompt } else { &template.system_prompt };
+    let chat = crate::chat_backend::for_config(&config.backend);
+    let mut messages: Vec<Message> = Vec::new();
+    for (i, prompt) in prompts.iter().enumerate() {
+        eprintln!("[{}/{}] {}", i + 1, prompts.len(), prompt.lines().next().unwrap_or_default());
+
+        // 1. Retrieval, decided the same way the GUI does (minus the manual override)
+        let retrieve = match template.rag {
+            RagMode::Off => false,
+            RagMode::Always => true,
+            RagMode::Auto => rag_trigger::heuristic(prompt, &config.rag)
+                .unwrap_or_else(|| match config.rag.classifier {
+                    true => rag_trigger::classify(&config.backend, &template.model, prompt),
+                    false => rag_trigger::Decision::new(false, "no heuristic matched"),
+                })
+                .retrieve,
+        };
+        let sources = if retrieve {
+            let sources = crate::research::scan(&template.research_dir, &filters, prompt, &template.section);
+            eprintln!("  research: {} documents", sources.len());
+            sources
+        } else {
+            Vec::new()
+        };
+        let research = crate::research::context_text(&sources);
+
+        // 2. Earlier turns of this run, then the prompt with its research data
+        let earlier = context::history(&messages, config.history_turns);
+        let history = context::build_turns(template.context_placement, system, earlier, &research, prompt.clone());
+
+        // 3. Ask; a failed prompt is recorded and the run carries on
+        messages.push(Message::new("user", prompt.clone(), false));
+        let request = ChatMessageRequest::new(template.model.clone(), history);
+        let text = match crate::runtime::block_on(chat.chat(&request)) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("  failed: {}", e);
+                format!("Error: {}", e)
+            }
+        };
+        let mut reply = Message::new("assistant", text, false);
+        reply.sources = sources;
+        reply.model = template.model.clone();
+        messages.push(reply);
+
+        // 4. Written after every prompt so an interrupted run keeps what it had
+        store.write_messages(&name, &messages, |meta| {
+            meta.model = template.model.clone();
+            meta.tags = template.tags.clone();
+        })?;
+    }
+    Ok(match store.kind() {
+        StoreKind::Json => profile.sessions_dir().join(&name).display().to_string(),
+        StoreKind::Sqlite => format!("{} in {}", name, profile.sessions_dir().join(crate::session_store::DB_FILE).display()),
+    })
+}
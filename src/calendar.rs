@@ -0,0 +1,123 @@
+// --- ACTION ITEMS -> CALENDAR ---
+// The model pulls deadlines and TODOs out of a conversation; after review they are
+// written as iCalendar events, either to a new .ics file or appended to a local calendar.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use crate::session::Message;
+use crate::summary;
+use chrono::{Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub const CALENDAR_FILE: &str = "calendar.ics";
+
+const EXTRACTOR: &str = "You extract deadlines and to-do items from a conversation. Resolve relative dates \
+('Friday', 'next week') against today's date. Reply with ONLY a JSON array like \
+[{\"title\": \"Lab report due\", \"due\": \"2025-03-14\", \"time\": \"17:00\", \"notes\": \"ECE 310\"}]. \
+Use null for an unknown time. Reply [] if there are none.";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ActionItem {
+    pub title: String,
+    pub due: String,          // YYYY-MM-DD, editable in the review dialog
+    pub time: Option<String>, // HH:MM; None = all-day
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default = "yes")]
+    pub include: bool,
+}
+
+fn yes() -> bool {
+    true
+}
+
+impl ActionItem {
+    pub fn due_date(&self) -> Option<NaiveDate> {
+        NaiveDate::parse_from_str(self.due.trim(), "%Y-%m-%d").ok()
+    }
+
+    fn due_time(&self) -> Option<NaiveTime> {
+        self.time.as_deref().and_then(|t| NaiveTime::parse_from_str(t.trim(), "%H:%M").ok())
+    }
+}
+
+// Blocking
+pub fn extract(backend: &BackendConfig, model: &str, messages: &[Message]) -> Result<Vec<ActionItem>, String> {
+    let today = Local::now().format("%A %Y-%m-%d");
+    let prompt = format!("Today is {}.\n\nConversation:\n{}", today, summary::transcript(messages));
+    let reply = llm::complete(backend, model, EXTRACTOR, &prompt)?;
+
+    let start = reply.find('[').ok_or("Model reply had no JSON list")?;
+    let end = reply.rfind(']').ok_or("Model reply had no JSON list")?;
+    serde_json::from_str(&reply[start..=end]).map_err(|e| format!("Bad action item JSON: {}", e))
+}
+
+// RFC 5545 TEXT escaping
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(';', "\\;").replace(',', "\\,").replace('\n', "\\n")
+}
+
+// Lines longer than 75 octets continue on the next line after a space
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+fn events(items: &[ActionItem]) -> Result<String, String> {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+    let mut out = String::new();
+    for (i, item) in items.iter().filter(|item| item.include).enumerate() {
+        let date = item.due_date().ok_or_else(|| format!("'{}' has no valid date (YYYY-MM-DD)", item.title))?;
+        let start = match item.due_time() {
+            Some(time) => format!("DTSTART:{}", date.and_time(time).format("%Y%m%dT%H%M%S")),
+            None => format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+        };
+        out.push_str("BEGIN:VEVENT\r\n");
+        out.push_str(&fold(&format!("UID:{}-{}@ship-of-theseus", stamp, i)));
+        out.push_str(&fold(&format!("DTSTAMP:{}", stamp)));
+        out.push_str(&fold(&start));
+        out.push_str(&fold(&format!("SUMMARY:{}", escape(&item.title))));
+        if !item.notes.trim().is_empty() {
+            out.push_str(&fold(&format!("DESCRIPTION:{}", escape(&item.notes))));
+        }
+        out.push_str("END:VEVENT\r\n");
+    }
+    Ok(out)
+}
+
+pub fn to_ics(items: &[ActionItem]) -> Result<String, String> {
+    Ok(format!(
+        "BEGIN:VCALENDAR\r\nVERSION:2.0\r\nPRODID:-//Ship of Theseus//Action Items//EN\r\n{}END:VCALENDAR\r\n",
+        events(items)?
+    ))
+}
+
+pub fn export_ics(path: &Path, items: &[ActionItem]) -> Result<(), String> {
+    fs::write(path, to_ics(items)?).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Inserts the events before the calendar's END:VCALENDAR, creating the file if needed.
+// A calendar that can't be read, or doesn't look like one, is left as it is.
+pub fn append_to_calendar(path: &Path, items: &[ActionItem]) -> Result<(), String> {
+    let raw = match fs::read_to_string(path) {
+        Ok(existing) => match existing.rfind("END:VCALENDAR") {
+            Some(end) => format!("{}{}{}", &existing[..end], events(items)?, &existing[end..]),
+            None => return Err(format!("{} has no END:VCALENDAR; not touching it", path.display())),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => to_ics(items)?,
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    crate::session::write_atomic(path, raw.as_bytes()).map_err(|e| format!("{}: {}", path.display(), e))
+}
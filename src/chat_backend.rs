@@ -0,0 +1,478 @@
+// --- CHAT BACKENDS ---
+// Generation goes through `ChatBackend`, so one request (ollama-rs types: system prompt,
+// history, base64 images, temperature) can be sent to Ollama, to any server with an
+// OpenAI-compatible /v1/chat/completions route, straight to llama.cpp's llama-server,
+// or to a hosted OpenAI / Anthropic model picked per chat. Model management (pull,
+// show, delete) stays Ollama-only on `BackendConfig`.
+
+use crate::backend::{BackendConfig, BackendKind, LocalModel};
+use crate::backend::AuthMode;
+use crate::llama_server;
+use crate::secrets;
+use ollama_rs::generation::chat::request::ChatMessageRequest;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::pin::Pin;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+// `on_token` gets each content piece as it arrives and returns false to stop early
+pub type TokenSink<'a> = dyn FnMut(&str) -> bool + Send + 'a;
+
+pub trait ChatBackend: Send + Sync {
+    // For messages like "Failed to connect to Ollama"
+    fn name(&self) -> &'static str;
+
+    fn chat<'a>(&'a self, request: &'a ChatMessageRequest) -> BoxFuture<'a, Result<String, String>>;
+
+    fn chat_stream<'a>(&'a self, request: &'a ChatMessageRequest, on_token: &'a mut TokenSink<'_>) -> BoxFuture<'a, Result<(), String>>;
+
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<LocalModel>, String>>;
+}
+
+pub fn for_config(config: &BackendConfig) -> Box<dyn ChatBackend> {
+    match config.kind {
+        BackendKind::Ollama => Box::new(Ollama(config.clone())),
+        BackendKind::OpenAi => Box::new(OpenAi(config.clone())),
+        BackendKind::LlamaCpp => Box::new(LlamaCpp(OpenAi(config.clone()))),
+    }
+}
+
+// Server-sent events: "data: {json}" lines, ending with "data: [DONE]" or when `on_event`
+// returns false
+async fn read_sse(mut res: reqwest::Response, mut on_event: impl FnMut(&Value) -> Result<bool, String>) -> Result<(), String> {
+    let mut buffer: Vec<u8> = Vec::new();
+    while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(newline) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else { continue };
+            let data = data.trim();
+            if data == "[DONE]" {
+                return Ok(());
+            }
+            let Ok(event) = serde_json::from_str::<Value>(data) else { continue };
+            if let Some(err) = event["error"]["message"].as_str() {
+                return Err(err.to_string());
+            }
+            if !on_event(&event)? {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HostedProvider {
+    OpenAi,
+    Anthropic,
+}
+
+impl HostedProvider {
+    pub const ALL: [HostedProvider; 2] = [HostedProvider::OpenAi, HostedProvider::Anthropic];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OpenAi => "OpenAI",
+            Self::Anthropic => "Anthropic",
+        }
+    }
+
+    // Keyring account of the API key, one of secrets::KNOWN_SECRETS
+    pub fn secret_account(self) -> &'static str {
+        match self {
+            Self::OpenAi => "openai_api_key",
+            Self::Anthropic => "anthropic_api_key",
+        }
+    }
+
+    pub fn default_model(self) -> &'static str {
+        match self {
+            Self::OpenAi => "gpt-4o",
+            Self::Anthropic => "claude-sonnet-4-5",
+        }
+    }
+}
+
+// A chat's cloud model: the request goes there instead of the configured server
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HostedModel {
+    pub provider: HostedProvider,
+    pub model: String,
+}
+
+pub fn hosted(choice: &HostedModel) -> Result<Box<dyn ChatBackend>, String> {
+    let account = choice.provider.secret_account();
+    let key = secrets::get(account).ok_or_else(|| format!("No {} API key: store it as '{}' under 🔑 Secrets", choice.provider.label(), account))?;
+    Ok(match choice.provider {
+        HostedProvider::OpenAi => Box::new(OpenAi(BackendConfig {
+            kind: BackendKind::OpenAi,
+            host: "https://api.openai.com".to_string(),
+            port: 443,
+            auth: AuthMode::Bearer,
+            account: Some(account.to_string()),
+            ..Default::default()
+        })),
+        HostedProvider::Anthropic => Box::new(Anthropic { key }),
+    })
+}
+
+struct Ollama(BackendConfig);
+
+impl ChatBackend for Ollama {
+    fn name(&self) -> &'static str {
+        "Ollama"
+    }
+
+    fn chat<'a>(&'a self, request: &'a ChatMessageRequest) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let response = self.0.send_chat(request).await?;
+            response.message.map(|m| m.content).ok_or_else(|| "Ollama returned an empty reply".to_string())
+        })
+    }
+
+    fn chat_stream<'a>(&'a self, request: &'a ChatMessageRequest, on_token: &'a mut TokenSink<'_>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(self.0.send_chat_stream(request, on_token))
+    }
+
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<LocalModel>, String>> {
+        Box::pin(self.0.list_local_models())
+    }
+}
+
+struct OpenAi(BackendConfig);
+
+// Why a chat.completions request failed: the server's answer when there was one, so
+// callers can branch on the status instead of the message
+enum PostError {
+    Status(reqwest::StatusCode, String),
+    Request(String),
+}
+
+impl From<PostError> for String {
+    fn from(error: PostError) -> String {
+        match error {
+            PostError::Status(status, text) => format!("{}: {}", status, text),
+            PostError::Request(e) => e,
+        }
+    }
+}
+
+impl OpenAi {
+    fn base(&self) -> String {
+        format!("{}/v1", self.0.uri())
+    }
+
+    // Ollama's wire format (what ollama-rs serializes) -> chat.completions. Images become
+    // data-URL content parts on their message; options that both APIs share carry over.
+    fn body(request: &ChatMessageRequest, stream: bool) -> Result<Value, String> {
+        let ollama = serde_json::to_value(request).map_err(|e| e.to_string())?;
+        let messages: Vec<Value> = ollama["messages"]
+            .as_array()
+            .map(|messages| messages.iter().map(Self::message).collect())
+            .unwrap_or_default();
+        let mut body = json!({ "model": ollama["model"], "messages": messages, "stream": stream });
+        let options = &ollama["options"];
+        for (from, to) in [("temperature", "temperature"), ("top_p", "top_p"), ("num_predict", "max_tokens"), ("seed", "seed")] {
+            if !options[from].is_null() {
+                body[to] = options[from].clone();
+            }
+        }
+        Ok(body)
+    }
+
+    fn message(message: &Value) -> Value {
+        let role = message["role"].as_str().unwrap_or("user");
+        let text = message["content"].as_str().unwrap_or_default();
+        let images = message["images"].as_array().filter(|images| !images.is_empty());
+        let Some(images) = images else {
+            return json!({ "role": role, "content": text });
+        };
+        let mut parts = vec![json!({ "type": "text", "text": text })];
+        for image in images.iter().filter_map(|i| i.as_str()) {
+            let mime = crate::images::mime_type(image);
+            parts.push(json!({ "type": "image_url", "image_url": { "url": format!("data:{};base64,{}", mime, image) } }));
+        }
+        json!({ "role": role, "content": parts })
+    }
+
+    async fn post(&self, body: &Value) -> Result<reqwest::Response, PostError> {
+        let res = self.0.client()
+            .map_err(PostError::Request)?
+            .post(format!("{}/chat/completions", self.base()))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| PostError::Request(e.to_string()))?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(PostError::Status(status, text));
+        }
+        Ok(res)
+    }
+
+    async fn reply(res: reqwest::Response) -> Result<String, String> {
+        let reply: Value = res.json().await.map_err(|e| e.to_string())?;
+        reply["choices"][0]["message"]["content"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| "The server returned an empty reply".to_string())
+    }
+
+    async fn read_deltas(res: reqwest::Response, on_token: &mut TokenSink<'_>) -> Result<(), String> {
+        read_sse(res, |event| {
+            Ok(match event["choices"][0]["delta"]["content"].as_str().filter(|t| !t.is_empty()) {
+                Some(token) => on_token(token),
+                None => true,
+            })
+        })
+        .await
+    }
+}
+
+impl ChatBackend for OpenAi {
+    fn name(&self) -> &'static str {
+        "OpenAI-compatible server"
+    }
+
+    fn chat<'a>(&'a self, request: &'a ChatMessageRequest) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let res = self.post(&Self::body(request, false)?).await?;
+            Self::reply(res).await
+        })
+    }
+
+    fn chat_stream<'a>(&'a self, request: &'a ChatMessageRequest, on_token: &'a mut TokenSink<'_>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let res = self.post(&Self::body(request, true)?).await?;
+            Self::read_deltas(res, on_token).await
+        })
+    }
+
+    // /v1/models has ids only: no sizes or quantization
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<LocalModel>, String>> {
+        Box::pin(async move {
+            let res = self.0.client()?.get(format!("{}/models", self.base())).send().await.map_err(|e| e.to_string())?;
+            if !res.status().is_success() {
+                return Err(format!("/v1/models: {}", res.status()));
+            }
+            let list: Value = res.json().await.map_err(|e| e.to_string())?;
+            Ok(list["data"]
+                .as_array()
+                .map(|models| {
+                    models
+                        .iter()
+                        .filter_map(|m| m["id"].as_str())
+                        .map(|id| LocalModel { name: id.to_string(), ..Default::default() })
+                        .collect()
+                })
+                .unwrap_or_default())
+        })
+    }
+}
+
+// llama-server speaks chat.completions too; builds from before that route existed get
+// the native /completion route with the chat rendered into a prompt instead
+struct LlamaCpp(OpenAi);
+
+impl LlamaCpp {
+    fn config(&self) -> &BackendConfig {
+        &self.0 .0
+    }
+}
+
+impl ChatBackend for LlamaCpp {
+    fn name(&self) -> &'static str {
+        "llama.cpp server"
+    }
+
+    fn chat<'a>(&'a self, request: &'a ChatMessageRequest) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let body = OpenAi::body(request, false)?;
+            match self.0.post(&body).await {
+                Ok(res) => OpenAi::reply(res).await,
+                Err(PostError::Status(reqwest::StatusCode::NOT_FOUND, _)) => {
+                    let body = llama_server::completion_body(self.config(), &body).await?;
+                    let res = llama_server::post_completion(self.config(), &body).await?;
+                    let reply: Value = res.json().await.map_err(|e| e.to_string())?;
+                    reply["content"].as_str().map(String::from).ok_or_else(|| "llama-server returned an empty reply".to_string())
+                }
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn chat_stream<'a>(&'a self, request: &'a ChatMessageRequest, on_token: &'a mut TokenSink<'_>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let body = OpenAi::body(request, true)?;
+            let res = match self.0.post(&body).await {
+                Ok(res) => return OpenAi::read_deltas(res, on_token).await,
+                Err(PostError::Status(reqwest::StatusCode::NOT_FOUND, _)) => {
+                    let body = llama_server::completion_body(self.config(), &body).await?;
+                    llama_server::post_completion(self.config(), &body).await?
+                }
+                Err(e) => return Err(e.into()),
+            };
+            // /completion events: {"content": "...", "stop": false}
+            read_sse(res, |event| {
+                if let Some(token) = event["content"].as_str().filter(|t| !t.is_empty()) {
+                    if !on_token(token) {
+                        return Ok(false);
+                    }
+                }
+                Ok(!event["stop"].as_bool().unwrap_or(false))
+            })
+            .await
+        })
+    }
+
+    // One model per server, whatever the request names; older builds have no /v1/models
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<LocalModel>, String>> {
+        Box::pin(async move {
+            match self.0.list_models().await {
+                Ok(models) if !models.is_empty() => Ok(models),
+                _ => {
+                    let status = llama_server::status(self.config()).await?;
+                    let name = if status.model.is_empty() { "llama-server".to_string() } else { status.model };
+                    Ok(vec![LocalModel { name, ..Default::default() }])
+                }
+            }
+        })
+    }
+}
+
+// The Messages API: system prompt beside the turns, images as base64 source blocks, and
+// max_tokens required. Consecutive turns of one role are merged by the API itself.
+struct Anthropic {
+    key: String,
+}
+
+const ANTHROPIC_URL: &str = "https://api.anthropic.com/v1";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const ANTHROPIC_MAX_TOKENS: u64 = 4096;
+
+impl Anthropic {
+    fn body(request: &ChatMessageRequest, stream: bool) -> Result<Value, String> {
+        let ollama = serde_json::to_value(request).map_err(|e| e.to_string())?;
+        let mut system = Vec::new();
+        let mut messages = Vec::new();
+        for message in ollama["messages"].as_array().into_iter().flatten() {
+            let text = message["content"].as_str().unwrap_or_default();
+            match message["role"].as_str().unwrap_or("user") {
+                "system" => system.push(text.to_string()),
+                role => {
+                    let mut parts = Vec::new();
+                    for image in message["images"].as_array().into_iter().flatten().filter_map(|i| i.as_str()) {
+                        let mime = crate::images::mime_type(image);
+                        parts.push(json!({ "type": "image", "source": { "type": "base64", "media_type": mime, "data": image } }));
+                    }
+                    parts.push(json!({ "type": "text", "text": text }));
+                    let role = if role == "assistant" { "assistant" } else { "user" };
+                    messages.push(json!({ "role": role, "content": parts }));
+                }
+            }
+        }
+        let options = &ollama["options"];
+        let mut body = json!({
+            "model": ollama["model"],
+            "messages": messages,
+            "max_tokens": options["num_predict"].as_u64().unwrap_or(ANTHROPIC_MAX_TOKENS),
+            "stream": stream,
+        });
+        if !system.is_empty() {
+            body["system"] = json!(system.join("\n\n"));
+        }
+        for key in ["temperature", "top_p"] {
+            if !options[key].is_null() {
+                body[key] = options[key].clone();
+            }
+        }
+        Ok(body)
+    }
+
+    fn client(&self) -> Result<reqwest::Client, String> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let mut key = reqwest::header::HeaderValue::from_str(&self.key).map_err(|e| e.to_string())?;
+        key.set_sensitive(true);
+        headers.insert("x-api-key", key);
+        headers.insert("anthropic-version", reqwest::header::HeaderValue::from_static(ANTHROPIC_VERSION));
+        reqwest::Client::builder().default_headers(headers).build().map_err(|e| e.to_string())
+    }
+
+    async fn post(&self, body: &Value) -> Result<reqwest::Response, String> {
+        let res = self.client()?
+            .post(format!("{}/messages", ANTHROPIC_URL))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            return Err(format!("{}: {}", status, text));
+        }
+        Ok(res)
+    }
+}
+
+impl ChatBackend for Anthropic {
+    fn name(&self) -> &'static str {
+        "Anthropic API"
+    }
+
+    fn chat<'a>(&'a self, request: &'a ChatMessageRequest) -> BoxFuture<'a, Result<String, String>> {
+        Box::pin(async move {
+            let res = self.post(&Self::body(request, false)?).await?;
+            let reply: Value = res.json().await.map_err(|e| e.to_string())?;
+            let text: String = reply["content"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|block| block["text"].as_str())
+                .collect();
+            if text.is_empty() {
+                return Err("The API returned an empty reply".to_string());
+            }
+            Ok(text)
+        })
+    }
+
+    // Events are content_block_delta with text deltas, then message_stop
+    fn chat_stream<'a>(&'a self, request: &'a ChatMessageRequest, on_token: &'a mut TokenSink<'_>) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            let res = self.post(&Self::body(request, true)?).await?;
+            read_sse(res, |event| {
+                Ok(match event["type"].as_str() {
+                    Some("message_stop") => false,
+                    Some("content_block_delta") => match event["delta"]["text"].as_str().filter(|t| !t.is_empty()) {
+                        Some(token) => on_token(token),
+                        None => true,
+                    },
+                    _ => true,
+                })
+            })
+            .await
+        })
+    }
+
+    fn list_models(&self) -> BoxFuture<'_, Result<Vec<LocalModel>, String>> {
+        Box::pin(async move {
+            let res = self.client()?.get(format!("{}/models", ANTHROPIC_URL)).send().await.map_err(|e| e.to_string())?;
+            if !res.status().is_success() {
+                return Err(format!("/v1/models: {}", res.status()));
+            }
+            let list: Value = res.json().await.map_err(|e| e.to_string())?;
+            Ok(list["data"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|m| m["id"].as_str())
+                .map(|id| LocalModel { name: id.to_string(), ..Default::default() })
+                .collect())
+        })
+    }
+}
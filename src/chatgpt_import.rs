@@ -0,0 +1,139 @@
+// --- CHATGPT IMPORT ---
+// Reads `conversations.json` from a ChatGPT data export and writes every conversation as
+// a normal session, so old history shows up in the browser, search and topic index.
+// A conversation is a tree of edits and regenerations; the branch that was last shown
+// (`current_node` back to the root) is the one imported.
+
+use crate::session::{Message, SessionFile};
+use crate::session_store::SessionStore;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Deserialize)]
+struct Conversation {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    create_time: Option<f64>, // Unix seconds, fractional
+    #[serde(default)]
+    current_node: Option<String>,
+    #[serde(default)]
+    mapping: HashMap<String, Node>,
+    #[serde(default)]
+    default_model_slug: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    #[serde(default)]
+    message: Option<ExportMessage>,
+    #[serde(default)]
+    parent: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ExportMessage {
+    author: Author,
+    #[serde(default)]
+    content: Option<Content>,
+    #[serde(default)]
+    create_time: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    role: String, // "system", "user", "assistant", "tool"
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    parts: Vec<serde_json::Value>, // Text, or objects for images and files
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped: usize, // Already imported or nothing to keep
+}
+
+impl ImportReport {
+    pub fn describe(&self) -> String {
+        format!("Imported {} conversations ({} skipped)", self.imported, self.skipped)
+    }
+}
+
+fn session_name(conv: &Conversation) -> String {
+    let when = conv
+        .create_time
+        .and_then(|t| chrono::DateTime::from_timestamp(t as i64, 0))
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y%m%d_%H%M%S").to_string())
+        .unwrap_or_else(|| "undated".to_string());
+    let id: String = conv.id.chars().filter(char::is_ascii_alphanumeric).take(8).collect();
+    format!("chatgpt_{}_{}.json", when, id)
+}
+
+// The shown branch, oldest first, keeping only what a Ship of Theseus session can hold
+fn messages(conv: &Conversation) -> Vec<Message> {
+    let mut chain = Vec::new();
+    let mut at = conv.current_node.clone();
+    while let Some(node) = at.as_ref().and_then(|id| conv.mapping.get(id)) {
+        chain.push(node);
+        at = node.parent.clone();
+        if chain.len() > conv.mapping.len() {
+            break; // A broken export with a cycle
+        }
+    }
+    chain.reverse();
+
+    let mut out = Vec::new();
+    for message in chain.into_iter().filter_map(|n| n.message.as_ref()) {
+        let role = message.author.role.as_str();
+        if role != "user" && role != "assistant" {
+            continue; // System prompts and tool calls
+        }
+        let parts = message.content.as_ref().map(|c| c.parts.as_slice()).unwrap_or_default();
+        let text: Vec<&str> = parts.iter().filter_map(|p| p.as_str()).filter(|t| !t.trim().is_empty()).collect();
+        let has_image = parts.iter().any(|p| p.is_object());
+        if text.is_empty() && !has_image {
+            continue;
+        }
+        let mut msg = Message::new(role, text.join("\n\n"), has_image);
+        msg.timestamp = message.create_time.map(|t| (t * 1000.0) as i64);
+        out.push(msg);
+    }
+    out
+}
+
+// Blocking: the export can be hundreds of megabytes
+pub fn import(path: &Path, store: &SessionStore) -> Result<ImportReport, String> {
+    let raw = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let conversations: Vec<Conversation> =
+        serde_json::from_str(&raw).map_err(|e| format!("{}: not a ChatGPT conversations.json ({})", path.display(), e))?;
+    let existing: Vec<String> = store.list().into_iter().map(|s| s.name).collect();
+
+    let mut report = ImportReport::default();
+    for conv in &conversations {
+        let name = session_name(conv);
+        let messages = messages(conv);
+        // Re-importing a newer export only adds the conversations that are new
+        if messages.is_empty() || existing.contains(&name) {
+            report.skipped += 1;
+            continue;
+        }
+        let mut file = SessionFile { messages, ..Default::default() };
+        file.meta.title = conv.title.clone().unwrap_or_default();
+        // The model stays empty: a ChatGPT slug is no model here, and opening the chat would
+        // register it in the selector. The slug is kept as a tag for search.
+        file.meta.tags = vec!["chatgpt".to_string()];
+        if let Some(slug) = conv.default_model_slug.as_deref().filter(|s| !s.is_empty()) {
+            file.meta.tags.push(format!("chatgpt:{}", slug));
+        }
+        store.save(&name, &file)?;
+        report.imported += 1;
+    }
+    Ok(report)
+}
@@ -0,0 +1,40 @@
+// --- CONFIDENCE ELICITATION ---
+// Optional instruction that makes the model end each reply with a self-reported
+// confidence. The line is parsed off the reply and kept with the message's reaction,
+// so analytics can show where each model tends to be unsure.
+
+const INSTRUCTION: &str = "\n\nAfter your answer, on its own final line, write `Confidence: N%` where N (0-100) is \
+the probability that your answer is correct and complete. Be calibrated: of all answers you give N%, about N% \
+should turn out right. Use a low number when you are guessing, extrapolating, or unsure of a value.";
+
+pub const UNSURE_BELOW: u8 = 50;
+
+pub fn with_instruction(system: &str) -> String {
+    format!("{}{}", system, INSTRUCTION)
+}
+
+// "Confidence: 72%", "**Confidence:** 0.72", "confidence - 72 / 100" -> 72
+fn parse_line(line: &str) -> Option<u8> {
+    let lower = line.trim().trim_matches(|c: char| c == '*' || c == '_' || c == '`').to_lowercase();
+    let rest = lower.strip_prefix("confidence")?;
+    let number: String = rest
+        .trim_start_matches(|c: char| !c.is_ascii_digit())
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let value: f32 = number.parse().ok()?;
+    let percent = if value <= 1.0 && number.contains('.') { value * 100.0 } else { value };
+    (0.0..=100.0).contains(&percent).then(|| percent.round() as u8)
+}
+
+// The reported confidence and the reply without its confidence line; None when the
+// model didn't report one in its last few lines
+pub fn extract(reply: &str) -> Option<(u8, String)> {
+    let lines: Vec<&str> = reply.lines().collect();
+    let last = lines.iter().rposition(|l| !l.trim().is_empty())?;
+    let at = (last.saturating_sub(2)..=last).rev().find(|&i| parse_line(lines[i]).is_some())?;
+    let percent = parse_line(lines[at])?;
+    let mut kept: Vec<&str> = lines[..at].to_vec();
+    kept.extend_from_slice(&lines[at + 1..]);
+    Some((percent, kept.join("\n").trim_end().to_string()))
+}
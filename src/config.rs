@@ -0,0 +1,385 @@
+// --- PERSISTENT CONFIG ---
+// User preferences that survive restarts, stored as TOML next to the sessions folder
+// (one file per profile).
+
+use crate::backend::BackendConfig;
+use crate::calendar::CALENDAR_FILE;
+use crate::context::{ContextPlacement, DEFAULT_SYSTEM_PROMPT, USER_PROFILE};
+use crate::notation::NotationConfig;
+use crate::rag_trigger::RagTriggerConfig;
+use crate::refusal::RetryConfig;
+use crate::research::DirFilters;
+use crate::runtime::WorkerConfig;
+use crate::session_store::StoreKind;
+use crate::timestamps::TimeConfig;
+use crate::tts::VoiceConfig;
+use crate::voice_chat::VoiceChatConfig;
+use crate::warmup::Warmup;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_FILE: &str = "ship_config.toml";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AppConfig {
+    pub backend: BackendConfig, // Which Ollama server to talk to, and how
+    pub system_prompt: String,  // Sent first with every chat request
+    pub personas: Vec<Persona>, // Named alternatives to system_prompt
+    pub persona: String,        // Id of the active persona; empty = system_prompt
+    pub models: ModelLayout,
+    pub confirm_exit: bool, // Ask before closing while a generation is running
+    pub auto_summary: bool, // Summarize a session when closing or leaving it
+    pub strip_image_metadata: bool, // Remove EXIF/GPS before images go to a remote backend
+    pub research_filters: HashMap<String, DirFilters>, // Keyed by research directory
+    pub rag: RagTriggerConfig,                         // When prompts go through the document scan
+    pub context_placement: ContextPlacement,           // Where retrieved text goes in the request
+    pub history_turns: usize,                          // Earlier user/assistant pairs sent with each prompt
+    pub voice: VoiceConfig,                            // Default TTS voice
+    pub persona_voices: HashMap<String, VoiceConfig>,  // Keyed by persona id; the rest use `voice`
+    pub voice_chat: VoiceChatConfig,                   // Recorder, speech-to-text and pause detection
+    pub secret_names: Vec<String>,                     // Keyring accounts we created (no values)
+    pub collapse_titles: Vec<String>,                  // Headings hidden behind a <details> toggle
+    pub notation: NotationConfig,                      // SI-prefix formatting of quantities in replies
+    pub advisor_email: String,                         // Default recipient for email drafts
+    pub calendar_file: String,                         // Local .ics that action items get appended to; relative = in the profile folder
+    pub translation_check: bool,                       // Back-translate ES <-> EN replies and score them
+    pub reaction_labels: Vec<String>,                  // Quick labels offered on every reply
+    pub definition_model: String,                      // Model for Ctrl+hover definitions; empty = selected model
+    pub session_store: StoreKind,                      // JSON files or the SQLite database
+    pub tee_file: String,                              // Every reply is appended here as it streams; empty = off
+    pub unload_headroom_mb: u64,                       // Unload idle models when free VRAM drops below this; 0 = never
+    pub chat_layout: ChatLayout,                       // Column width, spacing and bubbles in the chat view
+    pub pomodoro_minutes: u32,                         // Length of a study block
+    pub research_dir: String,                          // Folder the document scan reads
+    pub model_list: Vec<String>,                       // Models offered before Ollama has been asked
+    pub default_model: String,                         // Selected at startup; the last one used
+    pub window: WindowConfig,
+    pub warmups: Vec<Warmup>,                          // Models loaded ahead of time on a schedule
+    pub theme: Theme,
+    pub ui_scale: f32,                                 // egui zoom factor
+    pub search_snippet_tokens: i32,                    // Words around a match in full-text search results
+    pub find_snippet_chars: usize,                     // Chars on each side of a match in the sidebar find
+    pub event_stream: String,                          // JSONL events to a file or unix:/socket; empty = off
+    pub ask_confidence: bool,                          // Have replies end with a self-reported confidence
+    pub time: TimeConfig,                              // Time zone and date style of displayed timestamps
+    pub developer_mode: bool,                          // Show tools such as the retrieval eval
+    pub refusal_retry: RetryConfig,                    // Send the prompt again when a reply is a boilerplate refusal
+    pub vram_poll_ms: u64,                             // How often the background thread reads VRAM
+    pub workers: WorkerConfig,                         // Priority and cores of indexing, parsing and summary jobs
+
+    #[serde(skip)]
+    path: PathBuf, // Where this config was loaded from
+    #[serde(skip)]
+    pub load_error: Option<String>, // The file could not be read; saving is held back so it isn't overwritten
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            backend: BackendConfig::default(),
+            system_prompt: DEFAULT_SYSTEM_PROMPT.to_string(),
+            personas: Vec::new(),
+            persona: String::new(),
+            models: ModelLayout::default(),
+            confirm_exit: true,
+            auto_summary: true,
+            strip_image_metadata: true,
+            research_filters: HashMap::new(),
+            rag: RagTriggerConfig::default(),
+            context_placement: ContextPlacement::PrependUser,
+            history_turns: 10,
+            voice: VoiceConfig::default(),
+            persona_voices: HashMap::new(),
+            voice_chat: VoiceChatConfig::default(),
+            secret_names: Vec::new(),
+            collapse_titles: vec!["Solution".to_string(), "Answer".to_string()],
+            notation: NotationConfig::default(),
+            advisor_email: String::new(),
+            calendar_file: CALENDAR_FILE.to_string(),
+            translation_check: false,
+            definition_model: String::new(),
+            session_store: StoreKind::Json,
+            tee_file: String::new(),
+            unload_headroom_mb: 2048,
+            chat_layout: ChatLayout::default(),
+            pomodoro_minutes: 25,
+            research_dir: default_research_dir(),
+            model_list: vec!["gemma3:27b".to_string(), "gpt-oss:20b".to_string()],
+            default_model: "gemma3:27b".to_string(),
+            window: WindowConfig::default(),
+            warmups: Vec::new(),
+            theme: Theme::Dark,
+            ui_scale: 1.0,
+            search_snippet_tokens: 16,
+            find_snippet_chars: 60,
+            event_stream: String::new(),
+            ask_confidence: false,
+            time: TimeConfig::default(),
+            developer_mode: false,
+            refusal_retry: RetryConfig::default(),
+            vram_poll_ms: crate::gpu::DEFAULT_POLL_MS,
+            workers: WorkerConfig::default(),
+            reaction_labels: ["hallucinated", "great derivation", "wrong units", "too verbose"].iter().map(|s| s.to_string()).collect(),
+            path: PathBuf::from(CONFIG_FILE),
+            load_error: None,
+        }
+    }
+}
+
+// The user's Documents folder where there is one
+fn default_research_dir() -> String {
+    directories::UserDirs::new()
+        .and_then(|dirs| dirs.document_dir().map(|d| d.display().to_string()))
+        .unwrap_or_else(|| "./research".to_string())
+}
+
+// A named system prompt ("EE student", "Code reviewer") picked per chat
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Persona {
+    pub id: String,   // Stable across renames; what sessions and voices refer to
+    pub name: String, // Unique and non-empty, enforced by the editor
+    pub prompt: String,
+}
+
+// Main window size, remembered on exit
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: f32,
+    pub height: f32,
+    pub maximized: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self { width: 1280.0, height: 820.0, maximized: false }
+    }
+}
+
+impl AppConfig {
+    // A missing config means defaults. A broken one also falls back to defaults instead
+    // of blocking startup, but is copied aside and flagged in `load_error`, and `save`
+    // refuses to replace it until it is fixed or explicitly overwritten.
+    pub fn load(path: &Path) -> Self {
+        let mut config: Self = match fs::read_to_string(path) {
+            Ok(raw) => toml::from_str(&raw).map(|config: Self| config.with_old_system_prompt(&raw)).unwrap_or_else(|e| {
+                let mut backup = path.as_os_str().to_os_string();
+                backup.push(".bad");
+                let kept = match fs::copy(path, &backup) {
+                    Ok(_) => format!("; a copy was kept as {}", Path::new(&backup).display()),
+                    Err(_) => String::new(),
+                };
+                let error = format!("{} is not valid ({}){}", path.display(), e.to_string().trim(), kept);
+                Self { load_error: Some(error), ..Self::default() }
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => Self { load_error: Some(format!("{}: {}", path.display(), e)), ..Self::default() },
+        };
+        config.path = path.to_path_buf();
+        config.migrate_personas();
+        config
+    }
+
+    // A config saved before the system prompt was a setting has no `system_prompt`; those
+    // chats went out with the built-in profile, so that text becomes the setting
+    fn with_old_system_prompt(mut self, raw: &str) -> Self {
+        if raw.parse::<toml::Table>().is_ok_and(|table| !table.contains_key("system_prompt")) {
+            self.system_prompt = USER_PROFILE.to_string();
+        }
+        self
+    }
+
+    // Configs from before persona ids named personas everywhere; voices were keyed
+    // by whatever was selected, so only names of existing personas carry over
+    fn migrate_personas(&mut self) {
+        for i in 0..self.personas.len() {
+            if self.personas[i].id.is_empty() {
+                self.personas[i].id = self.new_persona_id();
+                let (id, name) = (self.personas[i].id.clone(), self.personas[i].name.clone());
+                if self.persona == name {
+                    self.persona = id.clone();
+                }
+                if let Some(voice) = self.persona_voices.remove(&name) {
+                    self.persona_voices.insert(id, voice);
+                }
+            }
+        }
+        let ids: Vec<&str> = self.personas.iter().map(|p| p.id.as_str()).collect();
+        self.persona_voices.retain(|key, _| ids.contains(&key.as_str()));
+    }
+
+    fn new_persona_id(&self) -> String {
+        let mut n = chrono::Utc::now().timestamp_micros();
+        while self.personas.iter().any(|p| p.id == format!("p{}", n)) {
+            n += 1;
+        }
+        format!("p{}", n)
+    }
+
+    pub fn add_persona(&mut self, name: String, prompt: String) {
+        let id = self.new_persona_id();
+        self.personas.push(Persona { id, name, prompt });
+    }
+
+    pub fn persona(&self, id: &str) -> Option<&Persona> {
+        self.personas.iter().find(|p| p.id == id)
+    }
+
+    // Sessions saved before persona ids name the persona; an id passes through
+    pub fn persona_id(&self, key: &str) -> String {
+        match self.personas.iter().find(|p| p.id == key).or_else(|| self.personas.iter().find(|p| p.name == key)) {
+            Some(p) => p.id.clone(),
+            None => key.to_string(),
+        }
+    }
+
+    // "Default" for the plain system prompt, the name otherwise
+    pub fn persona_label(&self, id: &str) -> String {
+        match self.persona(id) {
+            _ if id.is_empty() => "Default".to_string(),
+            Some(p) => p.name.clone(),
+            None => "(deleted persona)".to_string(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // System prompt of a persona; the default one when it was deleted
+    pub fn system_prompt_for(&self, id: &str) -> &str {
+        self.persona(id).map_or(&self.system_prompt, |p| &p.prompt)
+    }
+
+    // The voice remembered for a persona, falling back to the default one
+    pub fn voice_for(&self, persona: &str) -> &VoiceConfig {
+        self.persona_voices.get(persona).unwrap_or(&self.voice)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        if let Some(e) = &self.load_error {
+            return Err(format!("not saved while {}", e));
+        }
+        let raw = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        crate::session::write_atomic(&self.path, raw.as_bytes()).map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Dark => "Dark",
+            Self::Light => "Light",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Density {
+    Compact,
+    Comfortable,
+}
+
+impl Density {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Compact => "Compact",
+            Self::Comfortable => "Comfortable",
+        }
+    }
+
+    // Vertical gap between messages (and within them), in points
+    pub fn spacing(self) -> f32 {
+        match self {
+            Self::Compact => 2.0,
+            Self::Comfortable => 8.0,
+        }
+    }
+}
+
+// How the chat is laid out on wide screens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ChatLayout {
+    pub max_width: f32, // Content column in points; 0 = use the whole window
+    pub density: Density,
+    pub bubbles: bool, // Your messages on the right, replies on the left
+}
+
+impl Default for ChatLayout {
+    fn default() -> Self {
+        Self { max_width: 820.0, density: Density::Comfortable, bubbles: false }
+    }
+}
+
+// How the model selector is arranged: manual order, favorites on top, clutter hidden
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct ModelLayout {
+    pub order: Vec<String>,
+    pub pinned: Vec<String>,
+    pub hidden: Vec<String>,
+}
+
+impl ModelLayout {
+    // Every known model in display order (pinned first), hidden ones included
+    pub fn arrange(&self, models: &[String]) -> Vec<String> {
+        let rank = |m: &String| self.order.iter().position(|o| o == m).unwrap_or(usize::MAX);
+
+        let mut arranged = models.to_vec();
+        // Models missing from `order` go last; the stable sort keeps their original relative order
+        arranged.sort_by_key(|m| (!self.is_pinned(m), rank(m)));
+        arranged
+    }
+
+    // What the dropdown actually shows
+    pub fn visible(&self, models: &[String]) -> Vec<String> {
+        self.arrange(models).into_iter().filter(|m| !self.is_hidden(m)).collect()
+    }
+
+    pub fn is_pinned(&self, model: &str) -> bool {
+        self.pinned.iter().any(|m| m == model)
+    }
+
+    pub fn is_hidden(&self, model: &str) -> bool {
+        self.hidden.iter().any(|m| m == model)
+    }
+
+    pub fn toggle_pinned(&mut self, model: &str) {
+        toggle(&mut self.pinned, model);
+    }
+
+    pub fn toggle_hidden(&mut self, model: &str) {
+        toggle(&mut self.hidden, model);
+    }
+
+    // Moves `arranged[from]` so it lands before index `to` (as seen in the arranged list)
+    pub fn move_model(&mut self, arranged: &[String], from: usize, to: usize) {
+        if from >= arranged.len() || from == to || from + 1 == to {
+            return;
+        }
+        let mut order = arranged.to_vec();
+        let item = order.remove(from);
+        let to = if to > from { to - 1 } else { to };
+        order.insert(to.min(order.len()), item);
+        self.order = order;
+    }
+}
+
+fn toggle(list: &mut Vec<String>, model: &str) {
+    if let Some(i) = list.iter().position(|m| m == model) {
+        list.remove(i);
+    } else {
+        list.push(model.to_string());
+    }
+}
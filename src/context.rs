@@ -0,0 +1,76 @@
+// --- CONTEXT INJECTION ---
+// Where retrieved research text goes in the chat request. Models differ a lot in how
+// well they use each placement, so it's a setting rather than a fixed choice.
+
+use crate::session::Message;
+use ollama_rs::generation::chat::{ChatMessage, MessageRole};
+use serde::{Deserialize, Serialize};
+
+// System prompt for a fresh config; the user's own lives in AppConfig::system_prompt
+pub const DEFAULT_SYSTEM_PROMPT: &str = "You are a helpful research and study assistant. Always provide detailed explanations and practical examples.";
+
+// What every chat was sent before the prompt became a setting; configs saved back then
+// keep it, see AppConfig::load
+pub const USER_PROFILE: &str = "You are an Electrical Engineering student at Texas State University named Raul. You have a strong background in circuits, signal processing, and embedded systems. Concentration on Micro and Nano Device Systems. Always provide detailed explanations and practical examples.";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ContextPlacement {
+    SystemMessage,  // A second system message after the profile
+    PrependUser,    // "### RESEARCH DATA ... ### USER QUERY" in the user message (original behaviour)
+    SyntheticTurn,  // An earlier user turn with the data, acknowledged by the assistant
+}
+
+impl ContextPlacement {
+    pub const ALL: [ContextPlacement; 3] = [ContextPlacement::SystemMessage, ContextPlacement::PrependUser, ContextPlacement::SyntheticTurn];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContextPlacement::SystemMessage => "System message",
+            ContextPlacement::PrependUser => "Prepend to my message",
+            ContextPlacement::SyntheticTurn => "Earlier turn",
+        }
+    }
+}
+
+// Earlier chat messages in API form: at most `max_turns` user/assistant pairs, newest kept
+pub fn history(messages: &[Message], max_turns: usize) -> Vec<ChatMessage> {
+    let skip = messages.len().saturating_sub(max_turns * 2);
+    messages[skip..]
+        .iter()
+        .map(|msg| {
+            let role = match msg.role.as_str() {
+                "user" => MessageRole::User,
+                "system" => MessageRole::System,
+                _ => MessageRole::Assistant,
+            };
+            ChatMessage::new(role, msg.content.clone())
+        })
+        .collect()
+}
+
+// Full request: system prompt, earlier turns, then the research data wherever it goes.
+// The user's prompt is always the last message so images can be attached to it.
+pub fn build_turns(placement: ContextPlacement, system: &str, history: Vec<ChatMessage>, research: &str, prompt: String) -> Vec<ChatMessage> {
+    let mut turns = vec![ChatMessage::new(MessageRole::System, system.to_string())];
+    turns.extend(history);
+    if research.is_empty() {
+        turns.push(ChatMessage::new(MessageRole::User, prompt));
+        return turns;
+    }
+
+    match placement {
+        ContextPlacement::SystemMessage => {
+            turns.push(ChatMessage::new(MessageRole::System, format!("### RESEARCH DATA:\n{}", research)));
+            turns.push(ChatMessage::new(MessageRole::User, prompt));
+        }
+        ContextPlacement::PrependUser => {
+            turns.push(ChatMessage::new(MessageRole::User, format!("### RESEARCH DATA:\n{}\n\n### USER QUERY:\n{}", research, prompt)));
+        }
+        ContextPlacement::SyntheticTurn => {
+            turns.push(ChatMessage::new(MessageRole::User, format!("Here are excerpts from my documents:\n{}", research)));
+            turns.push(ChatMessage::new(MessageRole::Assistant, "I've read the excerpts and will use them to answer.".to_string()));
+            turns.push(ChatMessage::new(MessageRole::User, prompt));
+        }
+    }
+    turns
+}
@@ -0,0 +1,301 @@
+// --- DESKTOP INTEGRATION ---
+// `--install-context-menu` registers an "Ask Ship of Theseus" entry with the file
+// manager (a .desktop file on Linux, a per-user registry key on Windows) that starts
+// the app with `--attach <file>`. Nothing here needs admin rights.
+// `--install-url-handler` does the same for shipoftheseus:// links.
+
+use std::path::{Path, PathBuf};
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+const ENTRY_NAME: &str = "Ask Ship of Theseus";
+#[cfg(target_os = "linux")]
+const DESKTOP_FILE: &str = "ship-of-theseus-ask.desktop";
+#[cfg(target_os = "windows")]
+const REGISTRY_KEY: &str = r"HKCU\Software\Classes\*\shell\AskShipOfTheseus";
+#[cfg(target_os = "linux")]
+const URL_DESKTOP_FILE: &str = "ship-of-theseus-url.desktop";
+#[cfg(target_os = "windows")]
+const URL_REGISTRY_KEY: &str = r"HKCU\Software\Classes\shipoftheseus";
+
+// shipoftheseus://session/<name> and shipoftheseus://ask?q=<prompt>
+pub const URL_SCHEME: &str = "shipoftheseus";
+
+// Longest attached document text kept for the conversation
+pub const MAX_ATTACHED_CHARS: usize = 60_000;
+
+fn current_exe() -> Result<PathBuf, String> {
+    std::env::current_exe().map_err(|e| format!("Could not locate the executable: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+fn applications_dir() -> Result<PathBuf, String> {
+    let data = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/share")))
+        .ok_or("Neither XDG_DATA_HOME nor HOME is set")?;
+    Ok(data.join("applications"))
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_file() -> Result<PathBuf, String> {
+    Ok(applications_dir()?.join(DESKTOP_FILE))
+}
+
+// Returns a description of what was registered
+pub fn install_context_menu() -> Result<String, String> {
+    let exe = current_exe()?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let path = desktop_file()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName={name}\nExec=\"{exe}\" --attach %f\n\
+             MimeType=application/pdf;text/plain;image/png;image/jpeg;\nNoDisplay=false\nTerminal=false\n\
+             Actions=attach;\n\n[Desktop Action attach]\nName={name}\nExec=\"{exe}\" --attach %f\n",
+            name = ENTRY_NAME,
+            exe = exe.display()
+        );
+        std::fs::write(&path, entry).map_err(|e| format!("{}: {}", path.display(), e))?;
+        // Refreshing the cache is best-effort; file managers also rescan on their own
+        let _ = std::process::Command::new("update-desktop-database").arg(path.parent().unwrap_or(Path::new("."))).status();
+        Ok(format!("Wrote {} (shows up under \"Open With\")", path.display()))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let command = format!("\"{}\" --attach \"%1\"", exe.display());
+        reg(&["add", REGISTRY_KEY, "/ve", "/d", ENTRY_NAME, "/f"])?;
+        reg(&["add", &format!(r"{}\command", REGISTRY_KEY), "/ve", "/d", &command, "/f"])?;
+        Ok(format!("Registered {}", REGISTRY_KEY))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let _ = exe;
+        Err("Context-menu install is only supported on Linux and Windows".to_string())
+    }
+}
+
+pub fn uninstall_context_menu() -> Result<String, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let path = desktop_file()?;
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("{}: {}", path.display(), e)),
+            _ => Ok(format!("Removed {}", path.display())),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        reg(&["delete", REGISTRY_KEY, "/f"])?;
+        Ok(format!("Removed {}", REGISTRY_KEY))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        Err("Context-menu install is only supported on Linux and Windows".to_string())
+    }
+}
+
+pub fn install_url_handler() -> Result<String, String> {
+    let exe = current_exe()?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let dir = applications_dir()?;
+        std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let path = dir.join(URL_DESKTOP_FILE);
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=Ship of Theseus\nExec=\"{exe}\" %u\n\
+             MimeType=x-scheme-handler/{scheme};\nNoDisplay=true\nTerminal=false\n",
+            exe = exe.display(),
+            scheme = URL_SCHEME
+        );
+        std::fs::write(&path, entry).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let status = std::process::Command::new("xdg-mime")
+            .args(["default", URL_DESKTOP_FILE, &format!("x-scheme-handler/{}", URL_SCHEME)])
+            .status()
+            .map_err(|e| format!("xdg-mime: {}", e))?;
+        if !status.success() {
+            return Err(format!("xdg-mime failed ({})", status));
+        }
+        Ok(format!("Wrote {} and made it the {}:// handler", path.display(), URL_SCHEME))
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let command = format!("\"{}\" \"%1\"", exe.display());
+        reg(&["add", URL_REGISTRY_KEY, "/ve", "/d", "URL:Ship of Theseus", "/f"])?;
+        reg(&["add", URL_REGISTRY_KEY, "/v", "URL Protocol", "/d", "", "/f"])?;
+        reg(&["add", &format!(r"{}\shell\open\command", URL_REGISTRY_KEY), "/ve", "/d", &command, "/f"])?;
+        Ok(format!("Registered {}", URL_REGISTRY_KEY))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        let _ = exe;
+        Err("URL handler install is only supported on Linux and Windows".to_string())
+    }
+}
+
+pub fn uninstall_url_handler() -> Result<String, String> {
+    #[cfg(target_os = "linux")]
+    {
+        let path = applications_dir()?.join(URL_DESKTOP_FILE);
+        match std::fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("{}: {}", path.display(), e)),
+            _ => Ok(format!("Removed {}", path.display())),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        reg(&["delete", URL_REGISTRY_KEY, "/f"])?;
+        Ok(format!("Removed {}", URL_REGISTRY_KEY))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "windows")))]
+    {
+        Err("URL handler install is only supported on Linux and Windows".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn reg(args: &[&str]) -> Result<(), String> {
+    let status = std::process::Command::new("reg").args(args).status().map_err(|e| e.to_string())?;
+    if status.success() { Ok(()) } else { Err(format!("reg {} failed ({})", args[0], status)) }
+}
+
+pub enum Attachment {
+    Image { name: String, base64: String },
+    Document { name: String, text: String },
+}
+
+// Images go out with the next message; PDFs and text files become pinned context
+pub fn load_attachment(path: &Path) -> Result<Attachment, String> {
+    use base64::Engine;
+
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let ext = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let text = match ext.as_str() {
+        "png" | "jpg" | "jpeg" | "gif" | "bmp" | "webp" => {
+            let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            let base64 = base64::engine::general_purpose::STANDARD.encode(bytes);
+            return Ok(Attachment::Image { name, base64 });
+        }
+        "pdf" => pdf_extract::extract_text(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        _ => std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+    };
+    let text: String = text.chars().take(MAX_ATTACHED_CHARS).collect();
+    Ok(Attachment::Document { name, text })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    Session(String), // Session file name without ".json"
+    Ask(String),     // Prompt to put in the input box
+}
+
+impl DeepLink {
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix(URL_SCHEME)
+            .and_then(|r| r.strip_prefix("://"))
+            .ok_or_else(|| format!("Not a {}:// link: {}", URL_SCHEME, url))?;
+        let rest = rest.trim_end_matches('/');
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        if let Some(name) = path.strip_prefix("session/") {
+            let name = percent_decode(name);
+            let name = name.trim_end_matches(".json");
+            // A bare file name only; links must not reach outside the sessions folder
+            if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+                return Err(format!("Bad session name in link: {}", url));
+            }
+            return Ok(Self::Session(name.to_string()));
+        }
+        if path == "ask" {
+            let prompt = query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("q="))
+                .map(percent_decode)
+                .unwrap_or_default();
+            return Ok(Self::Ask(prompt));
+        }
+        Err(format!("Unknown link: {}", url))
+    }
+
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::Session(name) => format!("{}://session/{}", URL_SCHEME, percent_encode(name)),
+            Self::Ask(prompt) => format!("{}://ask?q={}", URL_SCHEME, percent_encode(prompt)),
+        }
+    }
+}
+
+// The first command-line argument that is one of our links
+pub fn find_link(args: &[String]) -> Option<&str> {
+    args.iter().map(String::as_str).find(|a| a.starts_with(&format!("{}://", URL_SCHEME)))
+}
+
+// %XX escapes and '+' as space; invalid escapes are kept as-is
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => match text.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                Some(b) => {
+                    out.push(b);
+                    i += 2;
+                }
+                None => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_session_links() {
+        let link = format!("{}://session/2025-03-14%20lab.json/", URL_SCHEME);
+        assert_eq!(DeepLink::parse(&link), Ok(DeepLink::Session("2025-03-14 lab".to_string())));
+    }
+
+    #[test]
+    fn rejects_names_outside_the_sessions_folder() {
+        for name in ["..%2Fconfig", ".hidden", "a%5Cb", ""] {
+            assert!(DeepLink::parse(&format!("{}://session/{}", URL_SCHEME, name)).is_err(), "{}", name);
+        }
+        assert!(DeepLink::parse("https://example.org/session/x").is_err());
+    }
+
+    #[test]
+    fn ask_links_round_trip() {
+        let link = format!("{}://ask?q=what+is+a%20BJT", URL_SCHEME);
+        assert_eq!(DeepLink::parse(&link), Ok(DeepLink::Ask("what is a BJT".to_string())));
+        let ask = DeepLink::Ask("¿Qué es 1+1 & 2?".to_string());
+        assert_eq!(DeepLink::parse(&ask.to_url()), Ok(ask));
+    }
+}
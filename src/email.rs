@@ -0,0 +1,75 @@
+// --- EMAIL DRAFTS ---
+// Turns a response into an email: either a mailto: link for the desktop mail client
+// or a standalone .eml file (marked unsent so clients open it as a draft).
+
+use base64::Engine;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Debug, Default)]
+pub struct EmailDraft {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl EmailDraft {
+    // Subject from the first non-empty line, markup stripped
+    pub fn from_response(content: &str, to: &str) -> Self {
+        let body = strip_markup(content);
+        let first = body.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("Update");
+        let mut subject: String = first.trim_start_matches('#').trim().trim_matches('*').chars().take(70).collect();
+        if first.chars().count() > 70 {
+            subject.push('…');
+        }
+        Self { to: to.to_string(), subject, body }
+    }
+
+    pub fn mailto_url(&self) -> String {
+        // CRLF line breaks per RFC 6068
+        let body = self.body.replace("\r\n", "\n").replace('\n', "\r\n");
+        format!("mailto:{}?subject={}&body={}", percent_encode(&self.to), percent_encode(&self.subject), percent_encode(&body))
+    }
+
+    pub fn to_eml(&self) -> String {
+        let date = chrono::Local::now().to_rfc2822();
+        let body = self.body.replace("\r\n", "\n").replace('\n', "\r\n");
+        format!(
+            "To: {}\r\nSubject: {}\r\nDate: {}\r\nX-Unsent: 1\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n{}\r\n",
+            self.to, encode_header(&self.subject), date, body
+        )
+    }
+
+    pub fn save_eml(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_eml()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+// Collapsible / spoiler tags mean nothing in a mail client
+fn strip_markup(content: &str) -> String {
+    let mut out = content.to_string();
+    for tag in ["<details>", "</details>", "<summary>", "</summary>", "<spoiler>", "</spoiler>"] {
+        out = out.replace(tag, "");
+    }
+    out.replace("||", "").trim().to_string()
+}
+
+// RFC 2047 encoded-word for non-ASCII subjects
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", base64::engine::general_purpose::STANDARD.encode(value))
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::new();
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'@' => out.push(byte as char),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
@@ -0,0 +1,289 @@
+// --- DATASET EXPORT ---
+// Converts saved sessions into JSONL fine-tuning datasets (one conversation per line),
+// and the open conversation into a Markdown file for lab notes, with its research
+// excerpts as numbered footnotes.
+
+use crate::research::SourceChunk;
+use crate::session::Message;
+use crate::session_store::SessionStore;
+use crate::timestamps::Clock;
+use serde_json::json;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum DatasetFormat {
+    OpenAi,   // {"messages": [{"role": "user", "content": ...}]}
+    ShareGpt, // {"conversations": [{"from": "human", "value": ...}]}
+}
+
+// Rules for dropping turns that would teach the model bad habits
+#[derive(Clone, Debug)]
+pub struct TurnFilter {
+    pub skip_errors: bool,      // "Error: Failed to connect to Ollama." etc.
+    pub skip_image_turns: bool, // The image bytes are not stored, so the answer lacks context
+    pub min_reply_chars: usize, // Drop one-word / truncated answers
+}
+
+impl Default for TurnFilter {
+    fn default() -> Self {
+        Self {
+            skip_errors: true,
+            skip_image_turns: true,
+            min_reply_chars: 20,
+        }
+    }
+}
+
+impl TurnFilter {
+    fn keeps(&self, user: &Message, reply: &Message) -> bool {
+        let answer = reply.content.trim();
+        if user.content.trim().is_empty() || answer.is_empty() {
+            return false;
+        }
+        if self.skip_errors && answer.starts_with("Error:") {
+            return false;
+        }
+        if self.skip_image_turns && user.has_image {
+            return false;
+        }
+        answer.chars().count() >= self.min_reply_chars
+    }
+}
+
+// Pair every user message with the assistant reply that follows it
+fn collect_turns<'a>(messages: &'a [Message], filter: &TurnFilter) -> Vec<(&'a Message, &'a Message)> {
+    let mut turns = Vec::new();
+    for pair in messages.windows(2) {
+        if pair[0].role == "user" && pair[1].role == "assistant" && filter.keeps(&pair[0], &pair[1]) {
+            turns.push((&pair[0], &pair[1]));
+        }
+    }
+    turns
+}
+
+fn format_line(turns: &[(&Message, &Message)], format: DatasetFormat, system_prompt: Option<&str>) -> serde_json::Value {
+    match format {
+        DatasetFormat::OpenAi => {
+            let mut messages = Vec::new();
+            if let Some(system) = system_prompt {
+                messages.push(json!({ "role": "system", "content": system }));
+            }
+            for (user, reply) in turns {
+                messages.push(json!({ "role": "user", "content": user.content }));
+                messages.push(json!({ "role": "assistant", "content": reply.content }));
+            }
+            json!({ "messages": messages })
+        }
+        DatasetFormat::ShareGpt => {
+            let mut conversations = Vec::new();
+            if let Some(system) = system_prompt {
+                conversations.push(json!({ "from": "system", "value": system }));
+            }
+            for (user, reply) in turns {
+                conversations.push(json!({ "from": "human", "value": user.content }));
+                conversations.push(json!({ "from": "gpt", "value": reply.content }));
+            }
+            json!({ "conversations": conversations })
+        }
+    }
+}
+
+// Returns the number of conversations written
+pub fn export_jsonl(
+    store: &SessionStore,
+    sessions: &[String],
+    out: &Path,
+    format: DatasetFormat,
+    filter: &TurnFilter,
+    system_prompt: Option<&str>,
+) -> Result<usize, String> {
+    let mut file = fs::File::create(out).map_err(|e| format!("{}: {}", out.display(), e))?;
+    let mut written = 0;
+
+    for name in sessions {
+        let messages = store.load(name)?.messages;
+        let turns = collect_turns(&messages, filter);
+
+        // A session with nothing usable left would just be an empty sample
+        if turns.is_empty() {
+            continue;
+        }
+
+        let line = format_line(&turns, format, system_prompt);
+        writeln!(file, "{}", line).map_err(|e| e.to_string())?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+// The sources a bracketed reference names: the one with that citation, or every source
+// of the file when the reply gives just the file name. Indexes into `sources`.
+fn cited(reference: &str, sources: &[SourceChunk]) -> Vec<usize> {
+    let reference = reference.strip_prefix("SOURCE:").unwrap_or(reference).trim();
+    let exact: Vec<usize> = (0..sources.len()).filter(|&i| sources[i].citation() == reference).collect();
+    if !exact.is_empty() {
+        return exact;
+    }
+    (0..sources.len()).filter(|&i| sources[i].file_name() == reference).collect()
+}
+
+// A footnote is the citations it stands for; the same set again reuses its number
+fn note_number(notes: &mut Vec<Vec<String>>, cited: Vec<String>) -> usize {
+    let mut citations = Vec::new();
+    for citation in cited {
+        if !citations.contains(&citation) {
+            citations.push(citation);
+        }
+    }
+    match notes.iter().position(|n| *n == citations) {
+        Some(i) => i + 1,
+        None => {
+            notes.push(citations);
+            notes.len()
+        }
+    }
+}
+
+// Every source the reply names inline ("[SOURCE: Razavi.pdf › …]", "[Razavi.pdf]") becomes
+// `marker(n)`; sources it never names get their markers after the reply. Numbers follow
+// the order references appear in, across all of `notes`. Fenced code is left alone.
+pub fn with_footnotes(content: &str, sources: &[SourceChunk], notes: &mut Vec<Vec<String>>, marker: impl Fn(usize) -> String) -> String {
+    let mut named = vec![false; sources.len()];
+    let mut text = String::new();
+    let mut in_code = false;
+    for (i, line) in content.trim_end().lines().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if in_code || line.trim_start().starts_with("```") {
+            text.push_str(line);
+            continue;
+        }
+        let mut rest = line;
+        while let Some(open) = rest.find('[') {
+            let Some(len) = rest[open..].find(']') else { break };
+            text.push_str(&rest[..open]);
+            let hits = cited(&rest[open + 1..open + len], sources);
+            if hits.is_empty() {
+                // Not a citation; a '[' inside it may still open one
+                text.push('[');
+                rest = &rest[open + 1..];
+                continue;
+            }
+            for &hit in &hits {
+                named[hit] = true;
+            }
+            let number = note_number(notes, hits.iter().map(|&i| sources[i].citation()).collect());
+            text.push_str(&marker(number));
+            rest = &rest[open + len + 1..];
+        }
+        text.push_str(rest);
+    }
+
+    let mut trailing = Vec::new();
+    for (source, _) in sources.iter().zip(&named).filter(|(_, named)| !**named) {
+        let number = marker(note_number(notes, vec![source.citation()]));
+        if !trailing.contains(&number) {
+            trailing.push(number);
+        }
+    }
+    if !trailing.is_empty() {
+        // On their own line, since the reply may end in a code fence
+        text.push_str(&format!("\n\nSources: {}", trailing.concat()));
+    }
+    text
+}
+
+// One "## You" / "## Assistant" section per message; code fences are kept as written.
+// Research excerpts become numbered footnotes, listed once at the end of the document.
+pub fn conversation_markdown(messages: &[Message], title: &str, model: &str, clock: &Clock) -> String {
+    let mut notes = Vec::new();
+    let mut out = format!("# {}\n\n", if title.trim().is_empty() { "Conversation" } else { title.trim() });
+    if !model.is_empty() {
+        out.push_str(&format!("_Model: {}_\n\n", model));
+    }
+    for message in messages {
+        let role = match message.role.as_str() {
+            "user" => "You".to_string(),
+            "assistant" => "Assistant".to_string(),
+            other => other.to_string(),
+        };
+        let when = message.timestamp.map(|ms| format!(" · {}", clock.date_time_millis(ms))).unwrap_or_default();
+        out.push_str(&format!("## {}{}\n\n", role, when));
+
+        // The image itself stays in the sessions folder
+        if message.has_image || message.attachment.is_some() {
+            let name = message.attachment.as_deref().and_then(|a| Path::new(a).file_name()).map(|n| n.to_string_lossy().to_string());
+            match name {
+                Some(name) => out.push_str(&format!("_[image: {}]_\n\n", name)),
+                None => out.push_str("_[image]_\n\n"),
+            }
+        }
+        // A reply cut off mid-block would swallow every heading after it, footnotes included
+        let unclosed = message.content.lines().filter(|l| l.trim_start().starts_with("```")).count() % 2 == 1;
+        let content = if unclosed { format!("{}\n```", message.content.trim_end()) } else { message.content.clone() };
+        out.push_str(&with_footnotes(&content, &message.sources, &mut notes, |n| format!("[^{}]", n)));
+        out.push_str("\n\n");
+    }
+    for (i, citations) in notes.iter().enumerate() {
+        out.push_str(&format!("[^{}]: {}\n", i + 1, citations.join("; ")));
+    }
+    out
+}
+
+pub fn export_markdown(out: &Path, messages: &[Message], title: &str, model: &str, clock: &Clock) -> Result<(), String> {
+    fs::write(out, conversation_markdown(messages, title, model, clock)).map_err(|e| format!("{}: {}", out.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(page: usize, section: &str) -> SourceChunk {
+        SourceChunk { file: "/library/Razavi.pdf".to_string(), page: Some(page), section: section.to_string(), ..Default::default() }
+    }
+
+    fn markdown(content: &str, sources: &[SourceChunk], notes: &mut Vec<Vec<String>>) -> String {
+        with_footnotes(content, sources, notes, |n| format!("[^{}]", n))
+    }
+
+    #[test]
+    fn numbers_follow_the_text_not_the_sources() {
+        let sources = [source(212, "6 Frequency Response"), source(230, "6.2 Miller Effect")];
+        let content = "Miller [SOURCE: Razavi.pdf › 6.2 Miller Effect, p. 230], gain [Razavi.pdf › 6 Frequency Response, p. 212].";
+        let mut notes = Vec::new();
+        assert_eq!(markdown(content, &sources, &mut notes), "Miller [^1], gain [^2].");
+        assert_eq!(notes, vec![vec![sources[1].citation()], vec![sources[0].citation()]]);
+    }
+
+    #[test]
+    fn a_bare_file_name_cites_every_source_of_that_file() {
+        let sources = [source(212, "6 Frequency Response"), source(230, "6.2 Miller Effect")];
+        let mut notes = Vec::new();
+        assert_eq!(markdown("See [Razavi.pdf].", &sources, &mut notes), "See [^1].");
+        assert_eq!(notes, vec![vec![sources[0].citation(), sources[1].citation()]]);
+    }
+
+    #[test]
+    fn code_fences_are_left_alone() {
+        let sources = [source(212, "6 Frequency Response")];
+        let mut notes = Vec::new();
+        let content = "```\nlet x = v[Razavi.pdf];\n```\nSee [Razavi.pdf].";
+        assert_eq!(markdown(content, &sources, &mut notes), "```\nlet x = v[Razavi.pdf];\n```\nSee [^1].");
+    }
+
+    #[test]
+    fn unnamed_sources_trail_and_numbers_carry_across_replies() {
+        let sources = [source(212, "6 Frequency Response")];
+        let mut notes = Vec::new();
+        assert_eq!(markdown("No citations [here].", &sources, &mut notes), "No citations [here].\n\nSources: [^1]");
+        assert_eq!(markdown("Again [Razavi.pdf].", &sources, &mut notes), "Again [^1].");
+        assert_eq!(notes.len(), 1);
+    }
+}
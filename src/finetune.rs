@@ -0,0 +1,149 @@
+// --- FINE-TUNE LAUNCHER ---
+// Drives an external training pipeline over an exported JSONL dataset, then bakes
+// the resulting adapter into an Ollama model with `ollama create`.
+
+use crate::backend::BackendConfig;
+use crate::shell;
+use std::fs;
+use std::path::Path;
+use crossbeam_channel::Sender;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Pipeline {
+    Unsloth,
+    Axolotl,
+    OllamaOnly, // Adapter already trained elsewhere, just register it
+    Custom,
+}
+
+impl Pipeline {
+    pub const ALL: [Pipeline; 4] = [Pipeline::Unsloth, Pipeline::Axolotl, Pipeline::OllamaOnly, Pipeline::Custom];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Pipeline::Unsloth => "Unsloth",
+            Pipeline::Axolotl => "Axolotl",
+            Pipeline::OllamaOnly => "Ollama create only",
+            Pipeline::Custom => "Custom command",
+        }
+    }
+
+    // Training command template. Placeholders: {dataset} {base} {output_dir} {config}.
+    // Run without a shell: each word is one argument, quotes group words.
+    pub fn default_command(&self) -> &'static str {
+        match self {
+            Pipeline::Unsloth => "python unsloth-cli.py --model_name {base} --dataset {dataset} --output_dir {output_dir} --save_model",
+            Pipeline::Axolotl => "axolotl train {config}",
+            Pipeline::OllamaOnly => "",
+            Pipeline::Custom => "",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FinetuneJob {
+    pub pipeline: Pipeline,
+    pub dataset: String,     // JSONL from the dataset exporter
+    pub hf_base: String,     // Training base (HuggingFace id, e.g. unsloth/gemma-3-4b-it)
+    pub ollama_base: String, // FROM line of the Modelfile (e.g. gemma3:4b)
+    pub output_dir: String,  // Where the trainer writes the LoRA adapter
+    pub model_name: String,  // Name registered in Ollama and in the selector
+    pub command: String,     // Editable training command
+}
+
+impl FinetuneJob {
+    // Defaults, with the adapter going into the profile's data folder
+    pub fn new(root: &Path) -> Self {
+        Self {
+            pipeline: Pipeline::Unsloth,
+            dataset: String::new(),
+            hf_base: String::new(),
+            ollama_base: "gemma3:27b".to_string(),
+            output_dir: Self::default_output_dir(root),
+            model_name: "raul-tuned".to_string(),
+            command: Pipeline::Unsloth.default_command().to_string(),
+        }
+    }
+
+    pub fn default_output_dir(root: &Path) -> String {
+        root.join("finetune").join("adapter").display().to_string()
+    }
+
+    fn config_path(&self) -> String {
+        format!("{}/axolotl.yml", self.output_dir)
+    }
+
+    fn modelfile_path(&self) -> String {
+        format!("{}/Modelfile", self.output_dir)
+    }
+
+    // Placeholders are filled per word, so a path with spaces or quotes stays one argument
+    pub fn training_command(&self) -> Result<Vec<String>, String> {
+        let words = shell::split_words(&self.command)?;
+        Ok(words
+            .into_iter()
+            .map(|w| {
+                w.replace("{dataset}", &self.dataset)
+                    .replace("{base}", &self.hf_base)
+                    .replace("{output_dir}", &self.output_dir)
+                    .replace("{config}", &self.config_path())
+            })
+            .collect())
+    }
+
+    // Minimal LoRA config for axolotl reading OpenAI-style "messages" lines
+    fn axolotl_config(&self) -> String {
+        format!(
+            "base_model: {}\nadapter: lora\nlora_r: 16\nlora_alpha: 32\nlora_dropout: 0.05\nlora_target_linear: true\n\
+             datasets:\n  - path: {}\n    type: chat_template\n    field_messages: messages\n\
+             sequence_len: 4096\nmicro_batch_size: 1\ngradient_accumulation_steps: 4\nnum_epochs: 3\n\
+             learning_rate: 0.0002\noutput_dir: {}\n",
+            self.hf_base, self.dataset, self.output_dir
+        )
+    }
+
+    // Ollama resolves a relative ADAPTER against the Modelfile's folder, which is the
+    // output folder itself, so the adapter is named by its absolute path
+    fn modelfile(&self, adapter: &Path) -> String {
+        format!("FROM {}\nADAPTER {}\n", self.ollama_base, adapter.display())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model_name.trim().is_empty() || self.ollama_base.trim().is_empty() {
+            return Err("Model name and Ollama base are required".to_string());
+        }
+        crate::modelfile::validate_model_name(self.model_name.trim())?;
+        if self.pipeline != Pipeline::OllamaOnly {
+            if !Path::new(&self.dataset).is_file() {
+                return Err(format!("Dataset not found: {}", self.dataset));
+            }
+            if self.training_command()?.is_empty() {
+                return Err("Training command is empty".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    // Blocking: train, write the Modelfile, then `ollama create` against the configured
+    // server. Progress lines go out as "__FINETUNE__:<line>".
+    pub fn run(&self, backend: &BackendConfig, tx: &Sender<String>) -> Result<(), String> {
+        fs::create_dir_all(&self.output_dir).map_err(|e| e.to_string())?;
+
+        // 1. Training
+        if self.pipeline != Pipeline::OllamaOnly {
+            if self.pipeline == Pipeline::Axolotl {
+                fs::write(self.config_path(), self.axolotl_config()).map_err(|e| e.to_string())?;
+            }
+            let cmd = self.training_command()?;
+            let _ = tx.send(format!("__FINETUNE__:$ {}", shell::display_command(&cmd)));
+            shell::stream_command(&cmd, tx, "__FINETUNE__")?;
+        }
+
+        // 2. Bake the adapter into a real Ollama model
+        let adapter = fs::canonicalize(&self.output_dir).map_err(|e| format!("{}: {}", self.output_dir, e))?;
+        fs::write(self.modelfile_path(), self.modelfile(&adapter)).map_err(|e| e.to_string())?;
+        let create: Vec<String> = ["ollama", "create", self.model_name.trim(), "-f", &self.modelfile_path()].iter().map(|s| s.to_string()).collect();
+        let _ = tx.send(format!("__FINETUNE__:$ {}", shell::display_command(&create)));
+        shell::stream_command_env(&create, &backend.cli_env(), tx, "__FINETUNE__")
+    }
+}
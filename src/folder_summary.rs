@@ -0,0 +1,166 @@
+// --- FOLDER OVERVIEWS ---
+// "Summarize folder" in the Research Station: every document under one research folder
+// is summarized on its own (map), the summaries are merged into an overview (reduce), and
+// the result is written into the folder as Markdown so the research scan picks it up.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use crate::research::{self, DirFilters};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// "Datasheets/Datasheets.overview.md"; found by `research::collect_documents`
+pub const OVERVIEW_SUFFIX: &str = ".overview.md";
+
+const MAX_DOCUMENT_CHARS: usize = 12_000; // Of each document's text shown to the mapper
+const MAX_REDUCE_CHARS: usize = 24_000;   // Of summaries per reduce call; more are merged in rounds
+
+const MAPPER: &str = "You write study notes about technical documents. Summarize the document you are \
+given in 3-5 sentences: what it is, its main topics, and the key results, values or definitions a \
+student would look it up for. Then a line 'Keywords:' with at most 8 comma-separated terms. No preamble.";
+
+const REDUCER: &str = "You are given summaries of the documents in one folder. Write an overview of the \
+folder: 1-2 paragraphs on what the collection covers, then a line 'Themes:' followed by at most 6 \
+bullet points starting with '- ' that name the recurring themes and which documents cover each. \
+Only use what the summaries say. No preamble.";
+
+// Every folder under `root` that holds documents (directly or deeper), with how many
+pub fn folders(root: &str, filters: &DirFilters) -> Vec<(PathBuf, usize)> {
+    let root_path = Path::new(root);
+    let mut counts: BTreeMap<PathBuf, usize> = BTreeMap::new();
+    for document in research::collect_documents(root, filters).iter().filter(|p| research::is_pdf(p)) {
+        for dir in document.ancestors().skip(1) {
+            if !dir.starts_with(root_path) {
+                break;
+            }
+            *counts.entry(dir.to_path_buf()).or_default() += 1;
+        }
+    }
+    counts.into_iter().collect()
+}
+
+pub fn overview_path(folder: &Path) -> PathBuf {
+    let name = folder.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "folder".to_string());
+    folder.join(format!("{}{}", name, OVERVIEW_SUFFIX))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+// Merges summaries until one text is left; large folders take several rounds
+fn reduce(backend: &BackendConfig, model: &str, mut parts: Vec<String>, progress: &mut impl FnMut(&str)) -> Result<String, String> {
+    loop {
+        let count = parts.len();
+        let mut batches: Vec<String> = Vec::new();
+        for part in parts {
+            match batches.last_mut() {
+                Some(current) if current.len() + part.len() <= MAX_REDUCE_CHARS => {
+                    current.push_str("\n\n");
+                    current.push_str(&part);
+                }
+                _ => batches.push(part),
+            }
+        }
+        // Parts too long to pair up would never shrink: one oversized call instead
+        if batches.len() == count && count > 1 {
+            batches = vec![batches.join("\n\n")];
+        }
+        let rounds = batches.len();
+        let mut merged = Vec::with_capacity(rounds);
+        for (i, batch) in batches.iter().enumerate() {
+            progress(&format!("Writing overview {}/{}...", i + 1, rounds));
+            merged.push(llm::complete(backend, model, REDUCER, batch)?.trim().to_string());
+        }
+        if merged.len() == 1 {
+            return Ok(merged.remove(0));
+        }
+        parts = merged;
+    }
+}
+
+// Blocking: `progress` is called before each model call. Returns the overview file.
+pub fn summarize(
+    backend: &BackendConfig,
+    model: &str,
+    root: &str,
+    filters: &DirFilters,
+    folder: &Path,
+    mut progress: impl FnMut(&str),
+) -> Result<PathBuf, String> {
+    let documents: Vec<PathBuf> = research::collect_documents(root, filters)
+        .into_iter()
+        .filter(|p| research::is_pdf(p) && p.starts_with(folder))
+        .collect();
+    if documents.is_empty() {
+        return Err(format!("No PDFs in {}", folder.display()));
+    }
+
+    // 1. Map: one summary per document; unreadable ones are noted, not fatal
+    let mut summaries: Vec<(PathBuf, Result<String, String>)> = Vec::new();
+    for (i, document) in documents.iter().enumerate() {
+        progress(&format!("Summarizing {}/{}: {}", i + 1, documents.len(), file_name(document)));
+        let summary = pdf_extract::extract_text(document)
+            .map_err(|e| format!("could not read: {}", e))
+            .and_then(|text| {
+                let text: String = text.chars().take(MAX_DOCUMENT_CHARS).collect();
+                if text.trim().is_empty() {
+                    return Err("no extractable text".to_string());
+                }
+                let prompt = format!("Document: {}\n\n{}", file_name(document), text);
+                llm::complete(backend, model, MAPPER, &prompt).map(|s| s.trim().to_string())
+            });
+        summaries.push((document.clone(), summary));
+    }
+
+    // 2. Reduce
+    let parts: Vec<String> = summaries
+        .iter()
+        .filter_map(|(path, summary)| summary.as_ref().ok().map(|s| format!("## {}\n{}", file_name(path), s)))
+        .collect();
+    if parts.is_empty() {
+        return Err("None of the documents could be summarized".to_string());
+    }
+    let overview = reduce(backend, model, parts, &mut progress)?;
+
+    // 3. Note with a contents list linking to each document's section and file
+    progress("Saving overview...");
+    let path = overview_path(folder);
+    std::fs::write(&path, markdown(folder, &overview, &summaries)).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+// GitHub-style heading anchor: "RC Filters.pdf" -> "rc-filterspdf"
+fn anchor(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_')
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+fn markdown(folder: &Path, overview: &str, summaries: &[(PathBuf, Result<String, String>)]) -> String {
+    let mut out = format!("# Overview: {}\n\n", file_name(folder));
+    out.push_str(&format!(
+        "_{} documents, generated {}_\n\n{}\n\n## Contents\n\n",
+        summaries.len(),
+        chrono::Local::now().format("%Y-%m-%d %H:%M"),
+        overview
+    ));
+    for (path, _) in summaries {
+        let name = file_name(path);
+        out.push_str(&format!("- [{}](#{})\n", name, anchor(&name)));
+    }
+    out.push_str("\n## Documents\n");
+    for (path, summary) in summaries {
+        let name = file_name(path);
+        let rel = path.strip_prefix(folder).unwrap_or(path).to_string_lossy().replace(' ', "%20");
+        out.push_str(&format!("\n### {}\n\n[Open file]({})\n\n", name, rel));
+        match summary {
+            Ok(text) => out.push_str(&format!("{}\n", text)),
+            Err(e) => out.push_str(&format!("_Not summarized: {}_\n", e)),
+        }
+    }
+    out
+}
@@ -0,0 +1,33 @@
+// --- QUICK DEFINITIONS ---
+// One- or two-sentence definitions for Ctrl+hovered terms, from a (preferably small)
+// model, and the notes file they can be pinned to.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use std::io::Write;
+use std::path::Path;
+
+pub const NOTES_FILE: &str = "notes.md";
+
+const DEFINER: &str = "Define the user's term in at most two sentences for an electrical engineering \
+student. If a context sentence is given, define the term as it is used there. Reply with the definition only.";
+
+// Blocking
+pub fn define(backend: &BackendConfig, model: &str, term: &str, context: &str) -> Result<String, String> {
+    let prompt = if context.is_empty() {
+        term.to_string()
+    } else {
+        format!("Term: {}\nContext: {}", term, context)
+    };
+    llm::complete(backend, model, DEFINER, &prompt).map(|d| d.trim().to_string())
+}
+
+// Appends a "- **term**: definition" line
+pub fn pin(notes: &Path, term: &str, definition: &str) -> Result<(), String> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(notes)
+        .map_err(|e| format!("{}: {}", notes.display(), e))?;
+    writeln!(file, "- **{}**: {}", term, definition.replace('\n', " ")).map_err(|e| e.to_string())
+}
@@ -0,0 +1,232 @@
+// --- GPU MONITOR ---
+// VRAM, load and temperature of the main GPU for the sidebar readout, the model-fit
+// check and idle unloading. NVIDIA cards are read through NVML; AMD cards through the
+// amdgpu sysfs files, or `rocm-smi` where those are missing; Intel cards through what
+// i915/xe expose in sysfs. With several cards of a vendor the one with the most VRAM is
+// read, so an APU's small carve-out doesn't hide the discrete card next to it. The
+// source is picked once per monitor, and readings are taken
+// on a background thread (see status_bar.rs), never in update().
+
+use nvml_wrapper::enum_wrappers::device::TemperatureSensor;
+use nvml_wrapper::Nvml;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const DEFAULT_POLL_MS: u64 = 2000;
+pub const MIN_POLL_MS: u64 = 250;
+
+const MIB: u64 = 1024 * 1024;
+const DRM_DIR: &str = "/sys/class/drm";
+const AMD_VENDOR: &str = "0x1002";
+const INTEL_VENDOR: &str = "0x8086";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Vendor {
+    Nvidia,
+    Amd,
+    Intel,
+}
+
+impl Vendor {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Nvidia => "NVIDIA",
+            Self::Amd => "AMD",
+            Self::Intel => "Intel",
+        }
+    }
+}
+
+// All zero / None when no supported GPU was found
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Reading {
+    pub vendor: Option<Vendor>,
+    pub used_mb: u64,
+    pub total_mb: u64,            // 0 for integrated GPUs, which share system RAM
+    pub utilization: Option<u32>, // Percent of the last sample period the GPU was busy
+    pub temperature: Option<u32>, // °C at the die
+}
+
+impl Reading {
+    // "VRAM: 10240 / 24576 MB · 87% · 71°C"
+    pub fn summary(&self) -> String {
+        let mut text = match self.vendor {
+            Some(vendor) if self.total_mb == 0 => format!("{} GPU, shared memory", vendor.label()),
+            // xe reports no usage, so a "0 / total" would read as an empty card
+            Some(Vendor::Intel) if self.used_mb == 0 => format!("Intel GPU, {} MB VRAM", self.total_mb),
+            _ => format!("VRAM: {} / {} MB", self.used_mb, self.total_mb),
+        };
+        if let Some(load) = self.utilization {
+            text.push_str(&format!(" · {}%", load));
+        }
+        if let Some(temp) = self.temperature {
+            text.push_str(&format!(" · {}°C", temp));
+        }
+        text
+    }
+}
+
+enum Source {
+    Nvml(Nvml),
+    AmdSysfs(PathBuf), // The card's device directory
+    RocmSmi,
+    IntelSysfs(PathBuf),
+    None,
+}
+
+pub struct Monitor {
+    source: Source,
+}
+
+impl Monitor {
+    // Finds the GPU once; keep the monitor instead of making one per reading
+    pub fn load() -> Self {
+        if let Ok(nvml) = Nvml::init() {
+            if nvml.device_count().is_ok_and(|n| n > 0) {
+                return Self { source: Source::Nvml(nvml) };
+            }
+        }
+        let cards = drm_devices();
+        let largest = |vendor: &str, total: fn(&Path) -> Option<u64>| {
+            cards
+                .iter()
+                .filter(|(v, _)| v == vendor)
+                .filter_map(|(_, dir)| Some((total(dir)?, dir)))
+                .max_by_key(|(size, _)| *size)
+                .map(|(_, dir)| dir.clone())
+        };
+        let intel = cards.iter().find(|(vendor, _)| vendor == INTEL_VENDOR).map(|(_, dir)| dir.clone());
+        let source = if let Some(dir) = largest(AMD_VENDOR, amd_vram_total) {
+            Source::AmdSysfs(dir)
+        } else if rocm_smi_reading().is_some() {
+            Source::RocmSmi
+        } else if let Some(dir) = largest(INTEL_VENDOR, intel_vram_total).or(intel) {
+            // Integrated ones report no VRAM; any Intel card still gives a temperature
+            Source::IntelSysfs(dir)
+        } else {
+            Source::None
+        };
+        Self { source }
+    }
+
+    pub fn read(&self) -> Reading {
+        match &self.source {
+            Source::Nvml(nvml) => nvml_reading(nvml),
+            Source::AmdSysfs(dir) => amd_reading(dir),
+            Source::RocmSmi => rocm_smi_reading().unwrap_or_default(),
+            Source::IntelSysfs(dir) => intel_reading(dir),
+            Source::None => Reading::default(),
+        }
+    }
+}
+
+fn nvml_reading(nvml: &Nvml) -> Reading {
+    let Ok(device) = nvml.device_by_index(0) else { return Reading::default() };
+    // Each query can fail on its own (some cards report no temperature), so none sinks the rest
+    let memory = device.memory_info().ok();
+    Reading {
+        vendor: Some(Vendor::Nvidia),
+        used_mb: memory.as_ref().map_or(0, |m| m.used / MIB),
+        total_mb: memory.as_ref().map_or(0, |m| m.total / MIB),
+        utilization: device.utilization_rates().ok().map(|u| u.gpu),
+        temperature: device.temperature(TemperatureSensor::Gpu).ok(),
+    }
+}
+
+// (vendor id, device directory) of every card, in card order
+fn drm_devices() -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(DRM_DIR) else { return Vec::new() };
+    let mut cards: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            // card0, not card0-DP-1 (connectors)
+            name.strip_prefix("card").is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .filter_map(|e| {
+            let dir = e.path().join("device");
+            let vendor = fs::read_to_string(dir.join("vendor")).ok()?;
+            Some((vendor.trim().to_string(), dir))
+        })
+        .collect();
+    cards.sort_by(|a, b| a.1.cmp(&b.1));
+    cards
+}
+
+fn read_number(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+// The first hwmon sensor of the card, in millidegrees
+fn hwmon_temperature(dir: &Path) -> Option<u32> {
+    let mut sensors: Vec<PathBuf> = fs::read_dir(dir.join("hwmon")).ok()?.flatten().map(|e| e.path()).collect();
+    sensors.sort();
+    sensors.iter().find_map(|s| read_number(&s.join("temp1_input"))).map(|milli| (milli / 1000) as u32)
+}
+
+fn amd_vram_total(dir: &Path) -> Option<u64> {
+    read_number(&dir.join("mem_info_vram_total"))
+}
+
+fn amd_reading(dir: &Path) -> Reading {
+    Reading {
+        vendor: Some(Vendor::Amd),
+        used_mb: read_number(&dir.join("mem_info_vram_used")).unwrap_or(0) / MIB,
+        total_mb: amd_vram_total(dir).unwrap_or(0) / MIB,
+        utilization: read_number(&dir.join("gpu_busy_percent")).map(|p| p as u32),
+        temperature: hwmon_temperature(dir),
+    }
+}
+
+// `rocm-smi --showmeminfo vram --showuse --showtemp --json`: one object per card, values as strings
+fn rocm_smi_reading() -> Option<Reading> {
+    let output = Command::new("rocm-smi").args(["--showmeminfo", "vram", "--showuse", "--showtemp", "--json"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+    let number = |card: &serde_json::Value, key: &str| card.get(key).and_then(|v| v.as_str()).and_then(|s| s.trim().parse::<f64>().ok());
+    let card = json
+        .as_object()?
+        .iter()
+        .filter(|(k, _)| k.starts_with("card"))
+        .max_by(|a, b| number(a.1, "VRAM Total Memory (B)").unwrap_or(0.0).total_cmp(&number(b.1, "VRAM Total Memory (B)").unwrap_or(0.0)))?
+        .1;
+    let number = |key: &str| number(card, key);
+    let temperature = card
+        .as_object()?
+        .iter()
+        .find(|(k, _)| k.starts_with("Temperature") && k.contains("edge"))
+        .and_then(|(_, v)| v.as_str()?.trim().parse::<f64>().ok());
+    Some(Reading {
+        vendor: Some(Vendor::Amd),
+        used_mb: number("VRAM Total Used Memory (B)").unwrap_or(0.0) as u64 / MIB,
+        total_mb: number("VRAM Total Memory (B)")? as u64 / MIB,
+        utilization: number("GPU use (%)").map(|p| p as u32),
+        temperature: temperature.map(|t| t as u32),
+    })
+}
+
+// Discrete cards report their VRAM size: xe under the device, i915 (Arc, DG1) as
+// lmem_total_bytes on the card itself, where `dir` is card/device
+fn intel_vram_total(dir: &Path) -> Option<u64> {
+    read_number(&dir.join("tile0/vram0/physical_vram_size_bytes"))
+        .or_else(|| dir.parent().and_then(|card| read_number(&card.join("lmem_total_bytes"))))
+        .filter(|bytes| *bytes > 0)
+}
+
+// Only i915 tells how much is free (lmem_avail_bytes); xe keeps usage in debugfs, so its
+// readings carry only what fits the model-fit check and the readout
+fn intel_reading(dir: &Path) -> Reading {
+    let total = intel_vram_total(dir).unwrap_or(0);
+    let available = dir.parent().and_then(|card| read_number(&card.join("lmem_avail_bytes")));
+    Reading {
+        vendor: Some(Vendor::Intel),
+        used_mb: available.map_or(0, |free| total.saturating_sub(free) / MIB),
+        total_mb: total / MIB,
+        utilization: None,
+        temperature: hwmon_temperature(dir),
+    }
+}
@@ -0,0 +1,34 @@
+// Files handed over with `--attach` (file-manager context menu): images ride along with
+// the next message, documents stay pinned as context for the whole chat
+
+use super::ShipApp;
+use crate::desktop::{self, Attachment};
+use eframe::egui;
+use std::path::Path;
+
+impl ShipApp {
+    pub(super) fn attach_file(&mut self, path: &Path) {
+        match desktop::load_attachment(path) {
+            Ok(Attachment::Image { name, base64 }) => {
+                self.log_event(&format!("Attached image {}", name));
+                self.current_image_base64 = Some(base64);
+                self.current_image_path = Some(name);
+            }
+            Ok(Attachment::Document { name, text }) => {
+                self.log_event(&format!("Pinned {} ({} KB of text)", name, text.len() / 1024));
+                self.completions.insert_text(&text);
+                self.pinned_document = Some((name, text));
+            }
+            Err(e) => self.report_error(&format!("Could not attach file: {}", e)),
+        }
+    }
+
+    // Shown next to the input while a document is pinned
+    pub(super) fn pinned_document_chip(&mut self, ui: &mut egui::Ui) {
+        let Some((name, text)) = &self.pinned_document else { return };
+        ui.small(format!("📄 {}", name)).on_hover_text(format!("Sent as context with every prompt ({} chars)", text.chars().count()));
+        if ui.small_button("✖").on_hover_text("Unpin").clicked() {
+            self.pinned_document = None;
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// Chat input autocomplete: Tab accepts the suggested completion of the word being typed
+
+use super::{AppState, ShipApp};
+use crate::autocomplete::Trie;
+use eframe::egui;
+
+pub(super) const INPUT_ID: &str = "chat_input";
+
+impl ShipApp {
+    // Adds finished messages to the trie; starts over when the conversation was cleared
+    fn refresh_completions(&mut self) {
+        if self.messages.len() < self.completion_indexed {
+            self.completions = Trie::default();
+            self.completion_indexed = 0;
+        }
+        // The reply being streamed is indexed once it is done
+        let done = match self.state {
+            AppState::Generating => self.messages.len().saturating_sub(1),
+            _ => self.messages.len(),
+        };
+        for msg in self.messages.iter().take(done).skip(self.completion_indexed) {
+            self.completions.insert_text(&msg.content);
+        }
+        self.completion_indexed = self.completion_indexed.max(done);
+    }
+
+    // Retrieved documents feed the trie too, so their part numbers complete
+    pub(super) fn index_research_vocabulary(&mut self) {
+        self.completions.insert_text(&self.research_results);
+    }
+
+    fn pending_completion(&self) -> Option<(String, String)> {
+        let start = self.input_text
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !(c.is_alphanumeric() || *c == '_'))
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let prefix = &self.input_text[start..];
+        self.completions.complete(prefix).map(|word| (prefix.to_string(), word))
+    }
+
+    // Call before the input TextEdit so Tab never reaches the focus handling
+    pub(super) fn accept_completion_key(&mut self, ui: &egui::Ui) {
+        self.refresh_completions();
+        let focused = ui.memory(|m| m.has_focus(egui::Id::new(INPUT_ID)));
+        if !focused {
+            return;
+        }
+        if let Some((prefix, word)) = self.pending_completion() {
+            if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab)) {
+                self.input_text.push_str(&word[prefix.len()..]);
+                self.input_text.push(' ');
+                // Keep the cursor at the end of the completed text
+                if let Some(mut state) = egui::TextEdit::load_state(ui.ctx(), egui::Id::new(INPUT_ID)) {
+                    let end = egui::text::CCursor::new(self.input_text.chars().count());
+                    state.set_ccursor_range(Some(egui::text_edit::CCursorRange::one(end)));
+                    state.store(ui.ctx(), egui::Id::new(INPUT_ID));
+                }
+            }
+        }
+    }
+
+    pub(super) fn completion_hint(&self, ui: &mut egui::Ui) {
+        if let Some((_, word)) = self.pending_completion() {
+            ui.weak(format!("Tab ↹ {}", word));
+        }
+    }
+}
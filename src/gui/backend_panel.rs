@@ -0,0 +1,215 @@
+// Sidebar section: model server API and address, auth mode and custom headers, and the
+// health of a llama-server backend
+
+use super::ShipApp;
+use crate::backend::{AuthMode, BackendKind};
+use crate::llama_server::{self, ChatTemplate};
+use crate::secrets;
+use eframe::egui;
+
+impl ShipApp {
+    // The reply arrives as __LLAMA_STATUS__ with the line to show
+    fn refresh_llama_status(&mut self) {
+        self.llama_status = "Checking...".to_string();
+        let config = self.config.backend.clone();
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            let line = match llama_server::status(&config).await {
+                Ok(status) if status.health == "ok" => format!("✅ {}", status.summary()),
+                Ok(status) => format!("⏳ {}", status.summary()),
+                Err(e) => format!("❌ {}", e),
+            };
+            let _ = tx.send(format!("__LLAMA_STATUS__:{}", line));
+        });
+    }
+
+    pub(super) fn backend_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Backend 🔌").id_source("backend_panel").show(ui, |ui| {
+            // From the cache, before `backend` borrows the config
+            let account = self.config.backend.secret_account();
+            let stored = self.config.backend.auth != AuthMode::None && self.has_secret(&account);
+            let header_accounts: Vec<(bool, String)> = self.config.backend.headers.iter()
+                .map(|(key, _)| (self.config.backend.is_secret_header(key), self.config.backend.header_account(key)))
+                .collect();
+            let header_stored: Vec<bool> = header_accounts.iter().map(|(secret, account)| *secret && self.has_secret(account)).collect();
+            let backend = &mut self.config.backend;
+            // Text and drag edits are saved when they end, not on every keystroke
+            let mut changed = false;
+
+            // 1. API and address
+            ui.horizontal(|ui| {
+                ui.label("API:");
+                for kind in BackendKind::ALL {
+                    changed |= ui.radio_value(&mut backend.kind, kind, kind.label()).changed();
+                }
+            });
+            match backend.kind {
+                BackendKind::Ollama => {}
+                BackendKind::OpenAi => {
+                    ui.small("Chat goes to {host}:{port}/v1/chat/completions (vLLM, LM Studio, ...). Pull, model cards and unloading need Ollama.");
+                }
+                BackendKind::LlamaCpp => {
+                    ui.small("Chat goes to llama-server (usually port 8080); builds without /v1/chat/completions use /completion. The model is the one it was started with.");
+                    ui.horizontal(|ui| {
+                        ui.label("/completion template:");
+                        egui::ComboBox::from_id_source("llama_chat_template")
+                            .selected_text(backend.chat_template.label())
+                            .show_ui(ui, |ui| {
+                                for template in ChatTemplate::ALL {
+                                    changed |= ui.selectable_value(&mut backend.chat_template, template, template.label()).changed();
+                                }
+                            });
+                    });
+                }
+            }
+            ui.horizontal(|ui| {
+                ui.label("Host:");
+                changed |= ui.text_edit_singleline(&mut backend.host).lost_focus();
+            });
+            ui.horizontal(|ui| {
+                ui.label("Port:");
+                let port = ui.add(egui::DragValue::new(&mut backend.port));
+                changed |= port.drag_released() || (port.changed() && !port.dragged());
+            });
+
+            // 2. Auth (the secret itself goes to the keyring)
+            ui.horizontal(|ui| {
+                changed |= ui.radio_value(&mut backend.auth, AuthMode::None, "No auth").changed();
+                changed |= ui.radio_value(&mut backend.auth, AuthMode::Bearer, "Bearer").changed();
+                changed |= ui.radio_value(&mut backend.auth, AuthMode::Basic, "Basic").changed();
+            });
+            if backend.auth == AuthMode::Basic {
+                ui.horizontal(|ui| {
+                    ui.label("User:");
+                    changed |= ui.text_edit_singleline(&mut backend.basic_user).lost_focus();
+                });
+            }
+            if backend.auth != AuthMode::None {
+                ui.horizontal(|ui| {
+                    let hint = if stored { "•••• (stored in keyring)" } else { "token / password" };
+                    ui.add(egui::TextEdit::singleline(&mut self.backend_secret_input).password(true).hint_text(hint));
+                    if ui.button("Save").clicked() && !self.backend_secret_input.is_empty() {
+                        self.secret_present.remove(&account);
+                        self.backend_secret_status = match secrets::set(&account, &self.backend_secret_input) {
+                            Ok(()) => {
+                                secrets::remember(&mut self.config.secret_names, &account);
+                                changed = true;
+                                "Saved to OS keyring".to_string()
+                            }
+                            Err(e) => format!("Keyring error: {}", e),
+                        };
+                        self.backend_secret_input.clear();
+                    }
+                    if stored && ui.button("Forget").clicked() {
+                        self.secret_present.remove(&account);
+                        self.backend_secret_status = match secrets::delete(&account) {
+                            Ok(()) => "Removed from keyring".to_string(),
+                            Err(e) => format!("Keyring error: {}", e),
+                        };
+                    }
+                });
+                if !self.backend_secret_status.is_empty() {
+                    ui.small(&self.backend_secret_status);
+                }
+            }
+
+            if ui.checkbox(&mut self.config.strip_image_metadata, "Strip EXIF/GPS from images sent to remote hosts").changed() {
+                changed = true;
+            }
+
+            // 3. Extra headers for proxies; a locked one keeps its value in the keyring
+            ui.label("Custom headers:");
+            let mut remove = None;
+            let mut lock = None; // (row, lock it)
+            let mut header_error = None;
+            for (i, (key, value)) in backend.headers.iter_mut().enumerate() {
+                let secret = header_accounts.get(i).is_some_and(|(secret, _)| *secret);
+                ui.horizontal(|ui| {
+                    // A secret header's name is its keyring account, so it stays fixed while locked
+                    let name = ui.add_enabled(!secret, egui::TextEdit::singleline(key).hint_text("X-Header").desired_width(90.0));
+                    if name.lost_focus() {
+                        changed = true;
+                        if crate::backend::looks_secret(key) {
+                            lock = Some((i, true));
+                        }
+                    }
+                    let hint = if header_stored.get(i).copied().unwrap_or(false) { "•••• (in keyring)" } else { "value" };
+                    let field = ui.add(egui::TextEdit::singleline(value).password(secret).hint_text(hint).desired_width(110.0));
+                    if field.lost_focus() {
+                        match secret {
+                            true if !value.is_empty() => lock = Some((i, true)),
+                            true => {}
+                            false => changed = true,
+                        }
+                    }
+                    let (icon, tip) = match secret {
+                        true => ("🔒", "Value in the OS keyring; click to keep it in the config file instead"),
+                        false => ("🔓", "Value in the config file; click to move it to the OS keyring"),
+                    };
+                    if ui.small_button(icon).on_hover_text(tip).clicked() {
+                        lock = Some((i, !secret));
+                    }
+                    if ui.small_button("✖").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some((i, locked)) = lock {
+                let result = match locked {
+                    true => backend.lock_header(i),
+                    false => backend.unlock_header(i),
+                };
+                match result {
+                    Ok(account) => {
+                        if locked {
+                            secrets::remember(&mut self.config.secret_names, &account);
+                        }
+                        self.secret_present.remove(&account);
+                        changed = true;
+                    }
+                    Err(e) => header_error = Some(e),
+                }
+            }
+            if let Some(i) = remove {
+                if let Err(e) = backend.remove_header(i) {
+                    header_error = Some(e);
+                }
+                changed = true;
+            }
+            if ui.small_button("➕ Header").clicked() {
+                backend.headers.push((String::new(), String::new()));
+                changed = true;
+            }
+
+            if changed {
+                self.save_config();
+            }
+            if let Some(e) = header_error {
+                self.report_error(&format!("Header secret: {}", e));
+            }
+
+            // 4. Apply: the model list and card come from whichever server is set now
+            ui.horizontal(|ui| {
+                if ui.button("🔌 Connect").on_hover_text("Reload the model list from this server").clicked() {
+                    self.model_card = None;
+                    self.model_card_for.clear();
+                    self.refresh_models();
+                    if self.config.backend.kind == BackendKind::LlamaCpp {
+                        self.refresh_llama_status();
+                    }
+                }
+                ui.small(self.config.backend.uri());
+            });
+            if self.config.backend.kind == BackendKind::LlamaCpp {
+                ui.horizontal(|ui| {
+                    if ui.small_button("🩺").on_hover_text("Check /health and /slots again").clicked() {
+                        self.refresh_llama_status();
+                    }
+                    let status = if self.llama_status.is_empty() { "Status not checked yet" } else { self.llama_status.as_str() };
+                    ui.small(status);
+                });
+            }
+            ui.small("Defaults to $OLLAMA_HOST when set, else localhost:11434");
+        });
+    }
+}
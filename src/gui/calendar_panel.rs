@@ -0,0 +1,112 @@
+// Window: review action items the model found, then write them to .ics or the local calendar
+
+use super::ShipApp;
+use crate::calendar::{self, ActionItem};
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn extract_action_items(&mut self) {
+        if self.messages.is_empty() || self.calendar_busy {
+            return;
+        }
+        self.calendar_busy = true;
+        self.calendar_items.clear();
+        self.calendar_status = "Looking for deadlines...".to_string();
+
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        let messages = self.messages.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            match calendar::extract(&backend, &model, &messages) {
+                Ok(items) => { let _ = tx.send(format!("__ACTION_ITEMS__:{}", serde_json::to_string(&items).unwrap_or_default())); }
+                Err(e) => { let _ = tx.send(format!("__ACTION_ITEMS_FAILED__:{}", e)); }
+            }
+        });
+    }
+
+    pub(super) fn accept_action_items(&mut self, json: &str) {
+        self.calendar_busy = false;
+        self.calendar_items = serde_json::from_str::<Vec<ActionItem>>(json).unwrap_or_default();
+        self.calendar_status = if self.calendar_items.is_empty() {
+            "No deadlines or TODOs found.".to_string()
+        } else {
+            format!("{} items found. Check the dates before writing.", self.calendar_items.len())
+        };
+    }
+
+    pub(super) fn calendar_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_calendar;
+        egui::Window::new("Action Items 📅")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let label = if self.calendar_busy { "Extracting..." } else { "🔍 Extract from this chat" };
+                if ui.add_enabled(!self.calendar_busy, egui::Button::new(label)).clicked() {
+                    self.extract_action_items();
+                }
+
+                // 1. Review: everything is editable, nothing is written yet
+                if !self.calendar_items.is_empty() {
+                    ui.separator();
+                    let mut remove = None;
+                    egui::Grid::new("calendar_items").striped(true).num_columns(5).show(ui, |ui| {
+                        for label in ["", "Title", "Due (YYYY-MM-DD)", "Time", ""] {
+                            ui.strong(label);
+                        }
+                        ui.end_row();
+                        for (i, item) in self.calendar_items.iter_mut().enumerate() {
+                            ui.checkbox(&mut item.include, "");
+                            ui.text_edit_singleline(&mut item.title);
+                            let due = ui.add(egui::TextEdit::singleline(&mut item.due).desired_width(100.0));
+                            if item.due_date().is_none() {
+                                due.on_hover_text("Not a valid date");
+                                ui.colored_label(ui.visuals().error_fg_color, "⚠");
+                            }
+                            let mut time = item.time.clone().unwrap_or_default();
+                            if ui.add(egui::TextEdit::singleline(&mut time).hint_text("all day").desired_width(60.0)).changed() {
+                                item.time = if time.trim().is_empty() { None } else { Some(time) };
+                            }
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                    if let Some(i) = remove {
+                        self.calendar_items.remove(i);
+                    }
+
+                    // 2. Write
+                    ui.horizontal(|ui| {
+                        if ui.button("💾 Export .ics").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().add_filter("iCalendar", &["ics"]).set_file_name("action_items.ics").save_file() {
+                                self.calendar_status = match calendar::export_ics(&path, &self.calendar_items) {
+                                    Ok(()) => format!("Saved {}", path.display()),
+                                    Err(e) => e,
+                                };
+                            }
+                        }
+                        // A relative name is inside the profile's data folder
+                        let path = self.profile.root().join(&self.config.calendar_file);
+                        if ui.button("📅 Append to local calendar").on_hover_text(path.display().to_string()).clicked() {
+                            self.calendar_status = match calendar::append_to_calendar(&path, &self.calendar_items) {
+                                Ok(()) => format!("Appended to {}", path.display()),
+                                Err(e) => e,
+                            };
+                        }
+                    });
+                }
+                ui.horizontal(|ui| {
+                    ui.label("Local calendar:");
+                    if ui.text_edit_singleline(&mut self.config.calendar_file).lost_focus() {
+                        self.save_config();
+                    }
+                });
+                if !self.calendar_status.is_empty() {
+                    ui.label(&self.calendar_status);
+                }
+            });
+        self.show_calendar = open;
+    }
+}
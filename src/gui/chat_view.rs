@@ -0,0 +1,262 @@
+// Chat message rendering: collapsible <details> sections, click-to-reveal spoilers,
+// optional engineering notation for quantities, paged rendering of very long text and
+// Ctrl+hover term lookup; plus the research excerpts each reply was given. Messages sit
+// in a centered column of configurable width, optionally as bubbles.
+
+use super::ShipApp;
+use crate::config::{ChatLayout, Density};
+use crate::notation::{self, DecimalMark};
+use crate::render::{self, Block};
+use crate::research::SourceChunk;
+use eframe::egui;
+
+const PAGE_LINES: usize = 200;  // Longer text blocks are shown a page at a time
+const CHUNK_LINES: usize = 40;  // Unit that is skipped when scrolled out of view
+const BUBBLE_WIDTH: f32 = 0.85; // Share of the column a bubble may use
+
+// Byte ranges of CHUNK_LINES-line pieces of `text`
+fn line_chunks(text: &str) -> Vec<std::ops::Range<usize>> {
+    let mut chunks = Vec::new();
+    let (mut start, mut lines) = (0, 0);
+    for (i, _) in text.match_indices('\n') {
+        lines += 1;
+        if lines == CHUNK_LINES {
+            chunks.push(start..i + 1);
+            start = i + 1;
+            lines = 0;
+        }
+    }
+    if start < text.len() {
+        chunks.push(start..text.len());
+    }
+    chunks
+}
+
+// Temp-data key for (word, sentence) under the pointer this frame (Ctrl held)
+pub(super) const HOVERED_TERM: &str = "hovered_term";
+
+// The word (letters, digits, '_' and '-') around char index `at`
+fn term_at(text: &str, at: usize) -> Option<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_word = |c: &char| c.is_alphanumeric() || *c == '_' || *c == '-';
+    if !chars.get(at).is_some_and(is_word) {
+        return None;
+    }
+    let start = chars[..at].iter().rposition(|c| !is_word(c)).map_or(0, |i| i + 1);
+    let end = chars[at..].iter().position(|c| !is_word(c)).map_or(chars.len(), |i| at + i);
+    let term: String = chars[start..end].iter().collect::<String>().trim_matches('-').to_string();
+    (term.chars().count() >= 2).then_some(term)
+}
+
+// The sentence containing char index `at`, capped so a wall of text stays short
+fn sentence_at(text: &str, at: usize) -> String {
+    const MAX_SIDE: usize = 150;
+    let chars: Vec<char> = text.chars().collect();
+    let at = at.min(chars.len());
+    let is_end = |c: &char| matches!(c, '.' | '!' | '?' | '\n');
+    let start = chars[..at].iter().rposition(is_end).map_or(0, |i| i + 1).max(at.saturating_sub(MAX_SIDE));
+    let end = chars[at..].iter().position(is_end).map_or(chars.len(), |i| at + i + 1).min(at + MAX_SIDE).min(chars.len());
+    chars[start..end].iter().collect::<String>().trim().to_string()
+}
+
+impl ShipApp {
+    // One message in the content column; returns what `add` drew, for focus decoration
+    pub(super) fn message_column(
+        ui: &mut egui::Ui,
+        layout: &ChatLayout,
+        from_user: bool,
+        add: impl FnOnce(&mut egui::Ui) -> egui::Response,
+    ) -> egui::Response {
+        let available = ui.available_width();
+        let column = if layout.max_width > 0.0 { layout.max_width.min(available) } else { available };
+        let (width, offset) = match layout.bubbles {
+            true => (column * BUBBLE_WIDTH, if from_user { column * (1.0 - BUBBLE_WIDTH) } else { 0.0 }),
+            false => (column, 0.0),
+        };
+        ui.horizontal(|ui| {
+            ui.add_space((available - column) / 2.0 + offset);
+            ui.allocate_ui(egui::vec2(width, 0.0), |ui| {
+                ui.set_max_width(width);
+                if !layout.bubbles {
+                    return add(ui);
+                }
+                let visuals = ui.visuals();
+                let fill = if from_user { visuals.faint_bg_color } else { visuals.extreme_bg_color };
+                let padding = match layout.density {
+                    Density::Compact => 4.0,
+                    Density::Comfortable => 10.0,
+                };
+                egui::Frame::none().fill(fill).rounding(10.0).inner_margin(padding).show(ui, add).inner
+            })
+            .inner
+        })
+        .inner
+    }
+
+    // `open`: Some(..) forces every collapsible block in the message open or shut this frame
+    pub(super) fn render_content(&self, ui: &mut egui::Ui, content: &str, id: egui::Id, open: Option<bool>) {
+        let formatted;
+        let content = if self.config.notation.enabled {
+            formatted = notation::format_units(content, &self.config.notation);
+            &formatted
+        } else {
+            content
+        };
+        let processed = render::collapse_sections(content, &self.config.collapse_titles);
+        Self::render_blocks(ui, &render::parse(&processed), id, open, self.expand_long_messages);
+    }
+
+    fn render_blocks(ui: &mut egui::Ui, blocks: &[Block], id: egui::Id, open: Option<bool>, expand_all: bool) {
+        for (i, block) in blocks.iter().enumerate() {
+            let block_id = id.with(i);
+            match block {
+                Block::Text(text) if text.lines().count() > PAGE_LINES => {
+                    Self::render_long_text(ui, text, block_id, expand_all);
+                }
+                Block::Text(text) => {
+                    Self::term_label(ui, text);
+                }
+                Block::Details { summary, body } => {
+                    egui::CollapsingHeader::new(format!("▸ {}", summary))
+                        .id_source(block_id)
+                        .default_open(false)
+                        .open(open)
+                        .show(ui, |ui| Self::render_blocks(ui, body, block_id, open, expand_all));
+                }
+                Block::Spoiler(text) => {
+                    if let Some(revealed) = open {
+                        ui.data_mut(|d| d.insert_temp(block_id, revealed));
+                    }
+                    let revealed = ui.data(|d| d.get_temp::<bool>(block_id)).unwrap_or(false);
+                    if revealed {
+                        ui.label(text);
+                    } else {
+                        let hidden = egui::RichText::new("█████ spoiler: click to reveal").weak();
+                        if ui.add(egui::Label::new(hidden).sense(egui::Sense::click())).clicked() {
+                            ui.data_mut(|d| d.insert_temp(block_id, true));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Plain label, except while Ctrl is held: then it is laid out by hand so the word
+    // under the pointer can be found and handed to the definition popup
+    fn term_label(ui: &mut egui::Ui, text: &str) -> egui::Response {
+        if !ui.input(|i| i.modifiers.command) {
+            return ui.label(text);
+        }
+        let (pos, galley, response) = egui::Label::new(text).layout_in_ui(ui);
+        if let Some(pointer) = response.hover_pos() {
+            let cursor = galley.cursor_from_pos(pointer - pos);
+            if let Some(term) = term_at(text, cursor.ccursor.index) {
+                let context = sentence_at(text, cursor.ccursor.index);
+                ui.ctx().data_mut(|d| d.insert_temp(egui::Id::new(HOVERED_TERM), (term, context)));
+            }
+        }
+        ui.painter().galley(pos, galley, ui.visuals().text_color());
+        response
+    }
+
+    // Very long text (whole code files) is paged, and pieces scrolled out of view only
+    // reserve their last measured height instead of being laid out and painted
+    fn render_long_text(ui: &mut egui::Ui, text: &str, id: egui::Id, expand_all: bool) {
+        let chunks = line_chunks(text);
+        let pages_id = id.with("pages");
+        let pages = ui.data(|d| d.get_temp::<usize>(pages_id)).unwrap_or(1);
+        let chunks_per_page = PAGE_LINES / CHUNK_LINES;
+        let shown = if expand_all { chunks.len() } else { (pages * chunks_per_page).min(chunks.len()) };
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+        for (k, range) in chunks.iter().take(shown).enumerate() {
+            let height_id = id.with(("chunk_height", k));
+            let height = ui.data(|d| d.get_temp::<f32>(height_id)).unwrap_or(CHUNK_LINES as f32 * row_height);
+            let slot = egui::Rect::from_min_size(ui.cursor().min, egui::vec2(ui.available_width(), height));
+            if ui.is_rect_visible(slot) {
+                let measured = Self::term_label(ui, text[range.clone()].trim_end_matches('\n')).rect.height();
+                ui.data_mut(|d| d.insert_temp(height_id, measured));
+            } else {
+                ui.allocate_space(egui::vec2(ui.available_width(), height));
+            }
+        }
+
+        if shown < chunks.len() {
+            let hidden_lines = text[chunks[shown].start..].lines().count();
+            ui.horizontal(|ui| {
+                if ui.button(format!("Show {} more lines", hidden_lines.min(PAGE_LINES))).clicked() {
+                    ui.data_mut(|d| d.insert_temp(pages_id, pages + 1));
+                }
+                if ui.button(format!("Show all ({} hidden)", hidden_lines)).clicked() {
+                    ui.data_mut(|d| d.insert_temp(pages_id, chunks.len()));
+                }
+            });
+        }
+    }
+
+    // Stored with the message, so it shows what was injected back then
+    pub(super) fn sources_footer(ui: &mut egui::Ui, sources: &[SourceChunk], id: egui::Id) {
+        if sources.is_empty() {
+            return;
+        }
+        egui::CollapsingHeader::new(format!("📚 {} sources", sources.len())).id_source(id).show(ui, |ui| {
+            for source in sources {
+                ui.horizontal(|ui| {
+                    ui.small(source.citation()).on_hover_text(&source.text);
+                    ui.weak(format!("score {:.2} · bytes {}–{}", source.score, source.start, source.end))
+                        .on_hover_text(&source.file);
+                });
+            }
+        });
+    }
+
+    // Sidebar section: which headings the post-processor hides, number formatting
+    pub(super) fn output_settings(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Output ✨").id_source("output_settings").show(ui, |ui| {
+            ui.label("Collapse sections titled:");
+            let mut text = self.config.collapse_titles.join(", ");
+            if ui.add(egui::TextEdit::singleline(&mut text).hint_text("Solution, Answer")).changed() {
+                self.config.collapse_titles = text.split(',').map(|t| t.trim_start().to_string()).collect();
+                self.save_config();
+            }
+            ui.small("Models can also emit <details> blocks or ||spoilers|| directly.");
+            ui.small("Keys: j/k or ↓/↑ move between messages, Enter expands, F focus mode, Esc clears.");
+            ui.small("Hold Ctrl and hover a word in a reply for a quick definition.");
+            ui.small("In an empty input, ↑/↓ recall earlier prompts and Ctrl+R searches them.");
+            ui.horizontal(|ui| {
+                ui.label("Definitions from:");
+                if ui.add(egui::TextEdit::singleline(&mut self.config.definition_model).hint_text("selected model").desired_width(120.0)).changed() {
+                    self.save_config();
+                }
+            });
+
+            ui.separator();
+            self.tee_settings(ui);
+            if ui.add(egui::Slider::new(&mut self.config.history_turns, 0..=50).text("earlier turns sent"))
+                .on_hover_text("How much of the conversation the model sees with each prompt (0 = only the latest message)")
+                .changed()
+            {
+                self.save_config();
+            }
+            if ui.checkbox(&mut self.config.translation_check, "Back-translation check for ES ↔ EN replies")
+                .on_hover_text("Translates the reply back and shows how closely it matches your original")
+                .changed()
+            {
+                self.save_config();
+            }
+            let notation = &mut self.config.notation;
+            let mut changed = ui.checkbox(&mut notation.enabled, "Engineering notation (4.7 kΩ, 2.2 µF)").changed();
+            ui.add_enabled_ui(notation.enabled, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Decimal mark:");
+                    changed |= ui.radio_value(&mut notation.decimal, DecimalMark::Point, "4.7").changed();
+                    changed |= ui.radio_value(&mut notation.decimal, DecimalMark::Comma, "4,7").changed();
+                });
+                changed |= ui.add(egui::Slider::new(&mut notation.sig_figs, 1..=6).text("significant figures")).changed();
+            });
+            if changed {
+                self.save_config();
+            }
+        });
+    }
+}
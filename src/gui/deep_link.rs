@@ -0,0 +1,32 @@
+// shipoftheseus:// links from other apps: open a saved session or pre-fill a prompt.
+// Also what a launch asks for, whether at startup or forwarded from a second launch.
+
+use super::ShipApp;
+use crate::desktop::DeepLink;
+
+impl ShipApp {
+    pub(super) fn handle_launch_request(&mut self, request: crate::instance::Request) {
+        if let Some(path) = request.attach {
+            self.attach_file(&path);
+        }
+        if let Some(url) = request.link {
+            self.open_deep_link(&url);
+        }
+    }
+
+    pub(super) fn open_deep_link(&mut self, url: &str) {
+        match DeepLink::parse(url) {
+            Ok(DeepLink::Session(name)) => self.open_session(&format!("{}.json", name)),
+            Ok(DeepLink::Ask(prompt)) => {
+                self.log_event("Prompt received from a link");
+                self.input_text = prompt;
+            }
+            Err(e) => self.report_error(&e),
+        }
+    }
+
+    // For pasting into notes; opens this session again when clicked
+    pub(super) fn session_link(file: &str) -> String {
+        DeepLink::Session(file.trim_end_matches(".json").to_string()).to_url()
+    }
+}
@@ -0,0 +1,107 @@
+// Ctrl+hover definition popup: debounced lookup of the hovered term, pin-to-notes
+
+use super::chat_view::HOVERED_TERM;
+use super::ShipApp;
+use crate::glossary::{self, NOTES_FILE};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+const HOVER_DELAY: Duration = Duration::from_millis(400); // Pointer must rest this long before a lookup
+
+pub(super) struct DefinitionPopup {
+    pub term: String,
+    pub pos: egui::Pos2,
+}
+
+impl ShipApp {
+    pub(super) fn accept_definition(&mut self, json: &str) {
+        if let Ok((term, definition)) = serde_json::from_str::<(String, String)>(json) {
+            self.definitions.insert(term, Some(definition));
+        }
+    }
+
+    fn request_definition(&mut self, term: &str, context: String) {
+        if self.definitions.contains_key(term) {
+            return;
+        }
+        self.definitions.insert(term.to_string(), None);
+        let backend = self.config.backend.clone();
+        let model = match self.config.definition_model.trim() {
+            "" => self.selected_model.clone(),
+            m => m.to_string(),
+        };
+        let term = term.to_string();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let definition = glossary::define(&backend, &model, &term, &context).unwrap_or_else(|e| format!("⚠ {}", e));
+            let _ = tx.send(format!("__DEFINITION__:{}", serde_json::to_string(&(term, definition)).unwrap_or_default()));
+        });
+    }
+
+    // Runs after the chat has been drawn, so this frame's hovered term is known
+    pub(super) fn definition_popup(&mut self, ctx: &egui::Context) {
+        let hovered = ctx.data_mut(|d| d.remove_temp::<(String, String)>(egui::Id::new(HOVERED_TERM)));
+        let ctrl = ctx.input(|i| i.modifiers.command);
+
+        // 1. Debounce: a lookup starts once the pointer has rested on one term
+        match hovered {
+            Some((term, context)) => {
+                let rested = match &self.hover_candidate {
+                    Some((t, since)) if *t == term => since.elapsed() >= HOVER_DELAY,
+                    _ => {
+                        self.hover_candidate = Some((term.clone(), Instant::now()));
+                        false
+                    }
+                };
+                if rested && self.hover_definition.as_ref().map_or(true, |p| p.term != term) {
+                    let pos = ctx.pointer_hover_pos().unwrap_or_default() + egui::vec2(12.0, 12.0);
+                    self.request_definition(&term, context);
+                    self.hover_definition = Some(DefinitionPopup { term, pos });
+                }
+                ctx.request_repaint_after(HOVER_DELAY);
+            }
+            None => self.hover_candidate = None,
+        }
+
+        // 2. The popup itself; stays while Ctrl is held or the pointer is on it
+        let Some(popup) = &self.hover_definition else { return };
+        let term = popup.term.clone();
+        let definition = self.definitions.get(&term).cloned().flatten();
+        let mut pin = false;
+        let area = egui::Area::new("definition_popup".into())
+            .fixed_pos(popup.pos)
+            .order(egui::Order::Tooltip)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(320.0);
+                    ui.strong(&term);
+                    match &definition {
+                        Some(text) => {
+                            ui.label(text);
+                            pin = ui.small_button("📌 Pin to notes").clicked();
+                        }
+                        None => {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.weak("Looking up...");
+                            });
+                        }
+                    }
+                });
+            });
+
+        if pin {
+            if let Some(text) = &definition {
+                let notes = self.profile.root().join(NOTES_FILE);
+                match glossary::pin(&notes, &term, text) {
+                    Ok(()) => self.push_toast(&format!("Pinned '{}' to {}", term, notes.display())),
+                    Err(e) => self.report_error(&format!("Could not pin definition: {}", e)),
+                }
+            }
+        }
+        let on_popup = area.response.rect.contains(ctx.pointer_hover_pos().unwrap_or_default());
+        if (!ctrl && !on_popup) || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.hover_definition = None;
+        }
+    }
+}
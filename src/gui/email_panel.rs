@@ -0,0 +1,62 @@
+// Window: edit an email draft built from a response, then hand it to the mail client or save .eml
+
+use super::ShipApp;
+use crate::email::EmailDraft;
+use crate::shell;
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn start_email_draft(&mut self, content: &str) {
+        self.email_draft = Some(EmailDraft::from_response(content, &self.config.advisor_email));
+        self.email_status.clear();
+    }
+
+    pub(super) fn email_window(&mut self, ctx: &egui::Context) {
+        let Some(mut draft) = self.email_draft.take() else { return };
+        let mut open = true;
+        egui::Window::new("Email Draft ✉")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("email_fields").num_columns(2).show(ui, |ui| {
+                    ui.label("To:");
+                    ui.text_edit_singleline(&mut draft.to);
+                    ui.end_row();
+                    ui.label("Subject:");
+                    ui.add(egui::TextEdit::singleline(&mut draft.subject).desired_width(f32::INFINITY));
+                    ui.end_row();
+                });
+                egui::ScrollArea::vertical().id_source("email_body").max_height(300.0).show(ui, |ui| {
+                    ui.add(egui::TextEdit::multiline(&mut draft.body).desired_rows(12).desired_width(f32::INFINITY));
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button("📧 Open in mail app").clicked() {
+                        self.email_status = match shell::open_external(&draft.mailto_url()) {
+                            Ok(()) => "Handed to the mail client.".to_string(),
+                            Err(e) => e,
+                        };
+                    }
+                    if ui.button("💾 Save .eml").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Email", &["eml"]).set_file_name("draft.eml").save_file() {
+                            self.email_status = match draft.save_eml(&path) {
+                                Ok(()) => format!("Saved {}", path.display()),
+                                Err(e) => e,
+                            };
+                        }
+                    }
+                    if ui.small_button("Remember recipient").on_hover_text("Prefill this address next time").clicked() {
+                        self.config.advisor_email = draft.to.trim().to_string();
+                        self.save_config();
+                    }
+                });
+                ui.small("Long bodies may be cut off by some mail clients over mailto:; use .eml for those.");
+                if !self.email_status.is_empty() {
+                    ui.label(&self.email_status);
+                }
+            });
+        if open {
+            self.email_draft = Some(draft);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+// Keeps the GUI alive when something unexpected happens: a panicking message handler
+// or worker becomes an error toast + log entry instead of a crash or a stuck spinner.
+
+use super::{AppState, ShipApp};
+use crossbeam_channel::Sender;
+use eframe::egui;
+use std::any::Any;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use tokio::runtime::Handle;
+use tokio::task::{AbortHandle, JoinHandle};
+
+const MAX_EVENTS_PER_FRAME: usize = 512;
+
+fn panic_text(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+// Runs a task on the shared runtime. A watcher awaits it, so a panic is reported as
+// __WORKER_PANIC__ instead of leaving the UI waiting for a __DONE__ that never comes.
+// The returned handle aborts the task at its next await point.
+pub(super) fn spawn_task(
+    runtime: &Handle,
+    tx: Sender<String>,
+    task: &'static str,
+    work: impl Future<Output = ()> + Send + 'static,
+) -> AbortHandle {
+    watch(runtime, tx, task, runtime.spawn(work))
+}
+
+// Blocking work (PDF parsing) on the background pool. Aborting only works before
+// it starts, so such work should also check the cancel flag.
+pub(super) fn spawn_blocking_task(
+    runtime: &Handle,
+    tx: Sender<String>,
+    task: &'static str,
+    work: impl FnOnce() + Send + 'static,
+) -> AbortHandle {
+    watch(runtime, tx, task, crate::runtime::spawn_background(runtime, work))
+}
+
+fn watch(runtime: &Handle, tx: Sender<String>, task: &'static str, handle: JoinHandle<()>) -> AbortHandle {
+    let abort = handle.abort_handle();
+    runtime.spawn(async move {
+        if let Err(e) = handle.await {
+            if e.is_panic() {
+                let payload = e.into_panic();
+                let _ = tx.send(format!("__WORKER_PANIC__:{} crashed: {}", task, panic_text(payload.as_ref())));
+            }
+        }
+    });
+    abort
+}
+
+impl ShipApp {
+    // Step 3 of every frame: route pending worker messages. Capped per frame so a flood
+    // (e.g. a huge research payload) can't freeze the UI; the rest waits for the next frame.
+    pub(super) fn drain_events(&mut self, ctx: &egui::Context) {
+        // 1. Take a batch; the receiver is ours, no lock involved
+        let batch: Vec<String> = self.rx.try_iter().take(MAX_EVENTS_PER_FRAME).collect();
+        if !self.rx.is_empty() {
+            ctx.request_repaint();
+        }
+
+        // 2. One bad message must not take the rest (or the window) down
+        for msg in batch {
+            let label: String = msg.chars().take(40).collect();
+            if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| self.handle_message(msg))) {
+                self.report_error(&format!("Internal error handling '{}': {}", label, panic_text(payload.as_ref())));
+                self.state = AppState::Idle;
+                self.activity.clear();
+            }
+        }
+    }
+
+    pub(super) fn handle_worker_panic(&mut self, text: &str) {
+        self.report_error(text);
+        self.state = AppState::Idle;
+        self.activity.clear();
+    }
+}
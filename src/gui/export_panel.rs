@@ -0,0 +1,120 @@
+// Sidebar section: build a fine-tuning dataset from saved sessions.
+// Also the open conversation as a Markdown file or PDF report.
+
+use super::toasts::{ToastAction, ToastLevel};
+use super::ShipApp;
+use crate::export::{self, DatasetFormat};
+use crate::pdf_export;
+use eframe::egui;
+
+// One row of the saved-session list
+pub(super) struct SessionEntry {
+    pub name: String,
+    pub selected: bool,
+    pub preview: Option<String>, // Auto-summary shown on hover
+}
+
+impl ShipApp {
+    // From the session browser's list, so both show what the store holds
+    pub(super) fn export_session_list(&self) -> Vec<SessionEntry> {
+        self.session_list
+            .iter()
+            .map(|s| SessionEntry { name: s.name.clone(), selected: false, preview: s.preview.clone() })
+            .collect()
+    }
+
+    pub(super) fn conversation_title(&self) -> String {
+        self.session_list
+            .iter()
+            .find(|s| s.name == self.current_file)
+            .map(|s| s.title.clone())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn export_markdown(&mut self) {
+        let name = format!("{}.md", self.current_file.trim_end_matches(".json"));
+        let Some(path) = rfd::FileDialog::new().add_filter("Markdown", &["md"]).set_file_name(name).save_file() else {
+            return;
+        };
+        match export::export_markdown(&path, &self.messages, &self.conversation_title(), &self.selected_model, &self.chat_clock()) {
+            Ok(()) => self.push_toast_with(ToastLevel::Success, &format!("Exported {}", path.display()), Some(ToastAction::Open(path))),
+            Err(e) => self.report_error(&format!("Markdown export failed: {}", e)),
+        }
+    }
+
+    pub(super) fn export_pdf(&mut self) {
+        let name = format!("{}.pdf", self.current_file.trim_end_matches(".json"));
+        let Some(path) = rfd::FileDialog::new().add_filter("PDF", &["pdf"]).set_file_name(name).save_file() else {
+            return;
+        };
+        match pdf_export::export_pdf(&path, &self.messages, &self.conversation_title(), &self.selected_model, &self.chat_clock()) {
+            Ok(()) => self.push_toast_with(ToastLevel::Success, &format!("Exported {}", path.display()), Some(ToastAction::Open(path))),
+            Err(e) => self.report_error(&format!("PDF export failed: {}", e)),
+        }
+    }
+
+    pub(super) fn export_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Dataset Export 🧬").show(ui, |ui| {
+            if ui.button("🔄 Refresh sessions").clicked() {
+                self.refresh_session_lists();
+            }
+
+            // 1. Session picker
+            egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                for entry in &mut self.export_sessions {
+                    let name = entry.name.clone();
+                    let mut response = ui.checkbox(&mut entry.selected, &name);
+                    if let Some(preview) = &entry.preview {
+                        response = response.on_hover_text(preview);
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button("🔗 Copy link").clicked() {
+                            ui.output_mut(|o| o.copied_text = ShipApp::session_link(&name));
+                            ui.close_menu();
+                        }
+                    });
+                }
+            });
+
+            // 2. Output format
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.export_format, DatasetFormat::OpenAi, "OpenAI");
+                ui.radio_value(&mut self.export_format, DatasetFormat::ShareGpt, "ShareGPT");
+            });
+
+            // 3. Bad-turn filtering
+            ui.checkbox(&mut self.export_filter.skip_errors, "Skip error replies");
+            ui.checkbox(&mut self.export_filter.skip_image_turns, "Skip image turns");
+            ui.add(egui::Slider::new(&mut self.export_filter.min_reply_chars, 0..=500).text("Min reply chars"));
+
+            let selected: Vec<_> = self.export_sessions.iter()
+                .filter(|e| e.selected)
+                .map(|e| e.name.clone())
+                .collect();
+
+            if ui.add_enabled(!selected.is_empty(), egui::Button::new("Export JSONL...")).clicked() {
+                if let Some(out) = rfd::FileDialog::new()
+                    .add_filter("JSON Lines", &["jsonl"])
+                    .set_file_name("ship_dataset.jsonl")
+                    .save_file()
+                {
+                    match export::export_jsonl(&self.store, &selected, &out, self.export_format, &self.export_filter, Some(&self.config.system_prompt)) {
+                        Ok(count) => {
+                            self.export_status = format!("Wrote {} conversations to {}", count, out.display());
+                            let text = self.export_status.clone();
+                            self.push_toast_with(ToastLevel::Success, &text, Some(ToastAction::Open(out)));
+                        }
+                        Err(e) => {
+                            self.export_status = format!("Export failed: {}", e);
+                            self.report_error(&format!("Dataset export failed: {}", e));
+                        }
+                    }
+                }
+            }
+
+            if !self.export_status.is_empty() {
+                ui.small(&self.export_status);
+            }
+        });
+    }
+}
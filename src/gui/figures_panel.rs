@@ -0,0 +1,112 @@
+// Figures pulled out of the research PDFs: the extraction run (Research Station) and a
+// picker next to the input that attaches one to the next message for a vision model
+
+use super::ShipApp;
+use crate::assets;
+use crate::pdf_figures::{self, Figure, FigureIndex};
+use eframe::egui;
+
+const PICKER_ROWS: usize = 200;
+
+impl ShipApp {
+    pub(super) fn reload_figures(&mut self) {
+        self.figures = FigureIndex::load(&self.profile.sessions_dir()).figures;
+    }
+
+    pub(super) fn extract_figures(&mut self) {
+        if self.figures_busy {
+            return;
+        }
+        self.figures_busy = true;
+        self.figures_status = "Looking for PDFs...".to_string();
+
+        let dir = self.research_dir.clone();
+        let filters = self.config.research_filters.get(&dir).cloned().unwrap_or_default();
+        let sessions_dir = self.profile.sessions_dir();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let documents: Vec<_> = crate::research::collect_documents(&dir, &filters)
+                .into_iter()
+                .filter(|p| crate::research::is_pdf(p))
+                .collect();
+            let progress_tx = tx.clone();
+            let progress = move |step: &str| {
+                let _ = progress_tx.send(format!("__FIGURES__:{}", step));
+            };
+            let _ = match pdf_figures::update(&sessions_dir, &documents, progress) {
+                Ok(n) => tx.send(format!("__FIGURES_DONE__:{}", n)),
+                Err(e) => tx.send(format!("__FIGURES_FAILED__:{}", e)),
+            };
+        });
+    }
+
+    pub(super) fn finish_figures(&mut self, result: Result<&str, &str>) {
+        self.figures_busy = false;
+        self.figures_status = match result {
+            Ok(n) => format!("✅ {} new figures", n),
+            Err(e) => format!("❌ {}", e),
+        };
+        self.reload_figures();
+        if self.show_gallery {
+            self.open_gallery(); // Show the new ones
+        }
+    }
+
+    // In the Research Station section
+    pub(super) fn figures_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = if self.figures_busy { "Extracting..." } else { "🖼 Extract figures" };
+            if ui.add_enabled(!self.figures_busy, egui::Button::new(label))
+                .on_hover_text("Copy the images embedded in these PDFs into the gallery (only new or changed files are read)")
+                .clicked()
+            {
+                self.extract_figures();
+            }
+            ui.small(format!("{} figures", self.figures.len()));
+        });
+        if !self.figures_status.is_empty() {
+            ui.small(&self.figures_status);
+        }
+    }
+
+    pub(super) fn attach_figure(&mut self, figure: &Figure) {
+        match assets::load_base64(&self.profile.sessions_dir(), &figure.file) {
+            Ok(b64) => {
+                self.current_image_base64 = Some(b64);
+                self.current_image_path = figure.file.rsplit('/').next().map(str::to_string);
+                if self.input_text.trim().is_empty() {
+                    self.input_text = format!("In this figure from {} (p. {}), ", figure.document_name(), figure.page);
+                }
+                self.log_event(&format!("Attached {}", figure.label()));
+            }
+            Err(e) => self.report_error(&format!("Could not open figure: {}", e)),
+        }
+    }
+
+    // Input row: pick a figure to ask the (vision) model about
+    pub(super) fn figure_picker(&mut self, ui: &mut egui::Ui) {
+        if self.figures.is_empty() {
+            return;
+        }
+        let mut picked = None;
+        ui.menu_button("📈", |ui| {
+            ui.set_min_width(360.0);
+            ui.add(egui::TextEdit::singleline(&mut self.figure_filter).hint_text("filter by document or caption"));
+            let filter = self.figure_filter.to_lowercase();
+            egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                let matching = self.figures.iter().filter(|f| filter.is_empty() || f.label().to_lowercase().contains(&filter));
+                for figure in matching.take(PICKER_ROWS) {
+                    if ui.selectable_label(false, figure.label()).clicked() {
+                        picked = Some(figure.clone());
+                        ui.close_menu();
+                    }
+                }
+            });
+        })
+        .response
+        .on_hover_text("Attach a figure from your PDFs; use a vision model to ask about it");
+        if let Some(figure) = picked {
+            self.attach_figure(&figure);
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// Sidebar section: launch a fine-tune over an exported dataset
+
+use super::ShipApp;
+use crate::finetune::Pipeline;
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn finetune_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Fine-tune Lab 🧪").show(ui, |ui| {
+            let job = &mut self.finetune_job;
+
+            // 1. Pipeline preset (switching resets the command template)
+            let previous = job.pipeline;
+            egui::ComboBox::from_id_source("finetune_pipeline")
+                .selected_text(job.pipeline.label())
+                .show_ui(ui, |ui| {
+                    for p in Pipeline::ALL {
+                        ui.selectable_value(&mut job.pipeline, p, p.label());
+                    }
+                });
+            if job.pipeline != previous {
+                job.command = job.pipeline.default_command().to_string();
+            }
+
+            // 2. Inputs
+            if job.pipeline != Pipeline::OllamaOnly {
+                ui.horizontal(|ui| {
+                    ui.label("Dataset:");
+                    ui.text_edit_singleline(&mut job.dataset);
+                    if ui.button("📂").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().add_filter("JSON Lines", &["jsonl"]).pick_file() {
+                            job.dataset = path.display().to_string();
+                        }
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label("HF base:");
+                    ui.text_edit_singleline(&mut job.hf_base);
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.label("Adapter dir:");
+                ui.text_edit_singleline(&mut job.output_dir);
+            });
+            ui.horizontal(|ui| {
+                ui.label("Ollama base:");
+                ui.text_edit_singleline(&mut job.ollama_base);
+            });
+            ui.horizontal(|ui| {
+                ui.label("New model:");
+                ui.text_edit_singleline(&mut job.model_name);
+            });
+
+            if job.pipeline != Pipeline::OllamaOnly {
+                ui.label("Training command:");
+                ui.add(egui::TextEdit::multiline(&mut job.command).desired_rows(2).code_editor());
+                match job.training_command() {
+                    Ok(words) => ui.small(crate::shell::display_command(&words)),
+                    Err(e) => ui.colored_label(ui.visuals().error_fg_color, e),
+                };
+            }
+
+            // 3. Launch
+            let label = if self.finetune_running { "Training..." } else { "🚀 Launch" };
+            if ui.add_enabled(!self.finetune_running, egui::Button::new(label)).clicked() {
+                self.launch_finetune();
+            }
+
+            // 4. Streamed output
+            egui::ScrollArea::vertical()
+                .id_source("finetune_log")
+                .max_height(150.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in &self.finetune_log {
+                        ui.monospace(line);
+                    }
+                });
+        });
+    }
+
+    fn launch_finetune(&mut self) {
+        self.finetune_log.clear();
+        if let Err(e) = self.finetune_job.validate() {
+            self.finetune_log.push(format!("⚠ {}", e));
+            return;
+        }
+
+        self.finetune_running = true;
+        let job = self.finetune_job.clone();
+        let backend = self.config.backend.clone();
+        let tx = self.tx.clone();
+
+        std::thread::spawn(move || {
+            match job.run(&backend, &tx) {
+                Ok(()) => { let _ = tx.send(format!("__FINETUNE_DONE__:{}", job.model_name)); }
+                Err(e) => { let _ = tx.send(format!("__FINETUNE_FAILED__:{}", e)); }
+            }
+        });
+    }
+}
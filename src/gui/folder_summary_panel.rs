@@ -0,0 +1,135 @@
+// Research Station: the library's folders, each with a right-click "Summarize folder"
+// that writes a map-reduce overview note into it (see folder_summary.rs)
+
+use super::toasts::{ToastAction, ToastLevel};
+use super::ShipApp;
+use crate::folder_summary;
+use crate::shell;
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+impl ShipApp {
+    fn refresh_research_folders(&mut self) {
+        let filters = self.config.research_filters.get(&self.research_dir).cloned().unwrap_or_default();
+        self.research_folders = folder_summary::folders(&self.research_dir, &filters);
+    }
+
+    fn start_folder_summary(&mut self, folder: PathBuf) {
+        if self.folder_summary_busy {
+            return;
+        }
+        self.folder_summary_busy = true;
+        self.folder_summary_status = format!("Reading {}...", folder.display());
+
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        let root = self.research_dir.clone();
+        let filters = self.config.research_filters.get(&root).cloned().unwrap_or_default();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let progress_tx = tx.clone();
+            let progress = move |step: &str| {
+                let _ = progress_tx.send(format!("__FOLDER_SUMMARY__:{}", step));
+            };
+            let _ = match folder_summary::summarize(&backend, &model, &root, &filters, &folder, progress) {
+                Ok(path) => tx.send(format!("__FOLDER_SUMMARY_DONE__:{}", path.display())),
+                Err(e) => tx.send(format!("__FOLDER_SUMMARY_FAILED__:{}", e)),
+            };
+        });
+    }
+
+    pub(super) fn finish_folder_summary(&mut self, result: Result<&str, &str>) {
+        self.folder_summary_busy = false;
+        match result {
+            Ok(path) => {
+                self.folder_summary_status = format!("✅ Saved {}", path);
+                self.log_event(&format!("Folder overview written to {}", path));
+                let open = ToastAction::Open(path.into());
+                self.push_toast_with(ToastLevel::Success, "Folder overview ready; the research scan will include it", Some(open));
+            }
+            Err(e) => {
+                self.folder_summary_status = format!("❌ {}", e);
+                self.report_error(&format!("Folder summary failed: {}", e));
+            }
+        }
+    }
+
+    fn open_path(&mut self, path: &Path) {
+        if let Err(e) = shell::open_external(&path.display().to_string()) {
+            self.report_error(&e);
+        }
+    }
+
+    // Under the research folder field
+    pub(super) fn research_folders_section(&mut self, ui: &mut egui::Ui) {
+        let header = egui::CollapsingHeader::new("Folders").id_source("research_folders").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.small("Right-click a folder to summarize it");
+                if ui.small_button("🔄").on_hover_text("Rescan the folder list").clicked() {
+                    self.refresh_research_folders();
+                }
+            });
+            if self.research_folders.is_empty() {
+                ui.weak("No folders with PDFs");
+            }
+
+            let root = PathBuf::from(&self.research_dir);
+            let mut summarize = None;
+            let mut open = None;
+            egui::ScrollArea::vertical().id_source("research_folders_scroll").max_height(160.0).show(ui, |ui| {
+                for (folder, count) in &self.research_folders {
+                    let rel = folder.strip_prefix(&root).unwrap_or(folder);
+                    let depth = rel.components().count();
+                    let name = match depth {
+                        0 => "(all)".to_string(),
+                        _ => rel.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+                    };
+                    let overview = folder_summary::overview_path(folder);
+                    let has_overview = overview.exists();
+                    let label = format!(
+                        "{}📁 {} ({}){}",
+                        "   ".repeat(depth.saturating_sub(1)),
+                        name,
+                        count,
+                        if has_overview { " 📝" } else { "" }
+                    );
+                    ui.add(egui::Label::new(label).sense(egui::Sense::click()))
+                        .on_hover_text(folder.display().to_string())
+                        .context_menu(|ui| {
+                            let text = if has_overview { "Summarize folder again" } else { "Summarize folder" };
+                            if ui.add_enabled(!self.folder_summary_busy, egui::Button::new(text)).clicked() {
+                                summarize = Some(folder.clone());
+                                ui.close_menu();
+                            }
+                            if has_overview && ui.button("Open overview").clicked() {
+                                open = Some(overview.clone());
+                                ui.close_menu();
+                            }
+                            if ui.button("Open folder").clicked() {
+                                open = Some(folder.clone());
+                                ui.close_menu();
+                            }
+                        });
+                }
+            });
+            if let Some(folder) = summarize {
+                self.start_folder_summary(folder);
+            }
+            if let Some(path) = open {
+                self.open_path(&path);
+            }
+        });
+        // Listed when the section is toggled rather than rescanning the library every frame
+        if header.header_response.clicked() {
+            self.refresh_research_folders();
+        }
+        if !self.folder_summary_status.is_empty() {
+            ui.horizontal(|ui| {
+                if self.folder_summary_busy {
+                    ui.spinner();
+                }
+                ui.small(&self.folder_summary_status);
+            });
+        }
+    }
+}
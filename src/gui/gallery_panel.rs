@@ -0,0 +1,193 @@
+// Window: every image attached in any session plus the figures extracted from PDFs,
+// filterable by date and session (or document)
+
+use super::{AppState, ShipApp};
+use crate::assets::{self, GalleryItem};
+use crate::research::TimeRange;
+use crate::session::Message;
+use eframe::egui;
+use std::path::Path;
+
+const THUMB_SIDE: u32 = 160;
+const THUMBS_PER_FRAME: usize = 4; // Decoding is slow; spread it over frames
+
+pub(super) struct Gallery {
+    pub items: Vec<GalleryItem>,
+    pub time_range: TimeRange,
+    pub session: Option<std::path::PathBuf>, // None = all sessions
+    pub thumbs: std::collections::HashMap<std::path::PathBuf, Option<egui::TextureHandle>>, // None = failed to decode
+}
+
+impl ShipApp {
+    // Attaches the image to the outgoing message and keeps a copy with the session
+    pub(super) fn user_message(&mut self, text: String) -> Message {
+        let mut msg = Message::new("user", text, self.current_image_base64.is_some());
+        if let Some(b64) = &self.current_image_base64 {
+            let name = self.current_image_path.clone().unwrap_or_else(|| "image.png".to_string());
+            match assets::store(&self.profile.sessions_dir(), b64, &name) {
+                Ok(path) => msg.attachment = Some(path),
+                Err(e) => self.report_error(&format!("Could not keep a copy of the image: {}", e)),
+            }
+        }
+        msg
+    }
+
+    pub(super) fn open_gallery(&mut self) {
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+        let sessions_dir = self.profile.sessions_dir();
+        let mut items = assets::collect(&sessions_dir, &self.store);
+        self.reload_figures();
+        items.extend(self.figures.iter().map(|f| f.gallery_item(&sessions_dir)));
+        items.sort_by_key(|i| std::cmp::Reverse(i.created));
+        match &mut self.gallery {
+            Some(gallery) => gallery.items = items,
+            None => {
+                self.gallery = Some(Gallery {
+                    items,
+                    time_range: TimeRange::Any,
+                    session: None,
+                    thumbs: Default::default(),
+                })
+            }
+        }
+        self.show_gallery = true;
+    }
+
+    fn session_label(path: &Path) -> String {
+        path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    pub(super) fn gallery_window(&mut self, ctx: &egui::Context) {
+        let clock = self.clock();
+        let Some(gallery) = &mut self.gallery else { return };
+        let mut open = self.show_gallery;
+        let mut chat_about = None;
+        let mut refresh = false;
+
+        egui::Window::new("Gallery 🖼")
+            .open(&mut open)
+            .default_size([640.0, 480.0])
+            .show(ctx, |ui| {
+                // 1. Filters
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("gallery_time")
+                        .selected_text(gallery.time_range.label())
+                        .show_ui(ui, |ui| {
+                            for range in TimeRange::ALL {
+                                ui.selectable_value(&mut gallery.time_range, range, range.label());
+                            }
+                        });
+                    let mut sessions: Vec<&Path> = Vec::new();
+                    for item in &gallery.items {
+                        if !sessions.contains(&item.session.as_path()) {
+                            sessions.push(&item.session);
+                        }
+                    }
+                    let selected = gallery.session.as_deref().map(Self::session_label).unwrap_or_else(|| "All sessions".to_string());
+                    let mut pick = None;
+                    egui::ComboBox::from_id_source("gallery_session")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            if ui.selectable_label(gallery.session.is_none(), "All sessions").clicked() {
+                                pick = Some(None);
+                            }
+                            for s in &sessions {
+                                if ui.selectable_label(gallery.session.as_deref() == Some(*s), Self::session_label(s)).clicked() {
+                                    pick = Some(Some(s.to_path_buf()));
+                                }
+                            }
+                        });
+                    if let Some(p) = pick {
+                        gallery.session = p;
+                    }
+                    refresh = ui.button("🔄").on_hover_text("Rescan sessions").clicked();
+                });
+                ui.separator();
+
+                // 2. Tiles, thumbnails decoded a few per frame
+                let cutoff = gallery.time_range.cutoff_timestamp();
+                let visible: Vec<&GalleryItem> = gallery.items.iter()
+                    .filter(|i| cutoff.map_or(true, |c| i.created >= c))
+                    .filter(|i| gallery.session.as_ref().map_or(true, |s| *s == i.session))
+                    .collect();
+                if visible.is_empty() {
+                    ui.weak("No images yet. Attach a sketch or image to a message and it will show up here.");
+                    return;
+                }
+                let mut decoded = 0;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        for item in visible {
+                            if !gallery.thumbs.contains_key(&item.file) && decoded < THUMBS_PER_FRAME {
+                                decoded += 1;
+                                let texture = assets::thumbnail(&item.file, THUMB_SIDE).ok().map(|(size, pixels)| {
+                                    let image = egui::ColorImage::from_rgba_unmultiplied(size, &pixels);
+                                    ctx.load_texture(item.file.display().to_string(), image, Default::default())
+                                });
+                                gallery.thumbs.insert(item.file.clone(), texture);
+                            }
+                            ui.vertical(|ui| {
+                                ui.set_width(THUMB_SIDE as f32);
+                                match gallery.thumbs.get(&item.file) {
+                                    Some(Some(texture)) => {
+                                        ui.image((texture.id(), texture.size_vec2()));
+                                    }
+                                    Some(None) => {
+                                        ui.weak("⚠ unreadable");
+                                    }
+                                    None => {
+                                        ui.spinner();
+                                    }
+                                }
+                                let when = clock.date_time(item.created);
+                                ui.small(format!("{} · {}", when, Self::session_label(&item.session)))
+                                    .on_hover_text(&item.caption);
+                                ui.horizontal(|ui| {
+                                    if ui.small_button("💬 New chat about this").clicked() {
+                                        chat_about = Some((item.file.clone(), true));
+                                    }
+                                    if ui.small_button("📎").on_hover_text("Attach to the next message in this chat").clicked() {
+                                        chat_about = Some((item.file.clone(), false));
+                                    }
+                                });
+                            });
+                        }
+                    });
+                });
+                if decoded > 0 {
+                    ctx.request_repaint();
+                }
+            });
+        self.show_gallery = open;
+
+        if refresh {
+            self.open_gallery();
+        }
+        if let Some((file, fresh)) = chat_about {
+            self.chat_about_image(&file, fresh);
+        }
+    }
+
+    // Attaches the image to the next message; `fresh` archives the open chat first
+    fn chat_about_image(&mut self, file: &Path, fresh: bool) {
+        if self.state != AppState::Idle {
+            self.report_error("Wait for the current reply to finish first");
+            return;
+        }
+        let sessions_dir = self.profile.sessions_dir();
+        let relative = file.strip_prefix(&sessions_dir).unwrap_or(file).display().to_string();
+        match assets::load_base64(&sessions_dir, &relative) {
+            Ok(b64) => {
+                if fresh {
+                    self.new_chat();
+                }
+                self.current_image_base64 = Some(b64);
+                self.current_image_path = file.file_name().map(|n| n.to_string_lossy().to_string());
+                self.show_gallery = false;
+            }
+            Err(e) => self.report_error(&format!("Could not open image: {}", e)),
+        }
+    }
+}
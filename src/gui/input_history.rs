@@ -0,0 +1,155 @@
+// Shell-style prompt history for the chat input: Up/Down walk this session's earlier
+// prompts while the input is empty (or already showing one), Ctrl+R searches them
+
+use super::autocomplete_input::INPUT_ID;
+use super::ShipApp;
+use eframe::egui;
+
+const SEARCH_ROWS: usize = 12;
+
+// Subsequence match ("rcfil" finds "RC filter cutoff"); higher is better, None = no match.
+// Consecutive and word-start hits score more, so tight matches rank first.
+fn fuzzy_score(candidate: &str, query: &str) -> Option<i32> {
+    let query: Vec<char> = query.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let mut score = 0;
+    let mut q = 0;
+    let mut previous_hit = false;
+    let mut previous: Option<char> = None;
+    for c in candidate.to_lowercase().chars() {
+        if q < query.len() && c == query[q] {
+            score += 1;
+            if previous_hit {
+                score += 3;
+            }
+            if previous.map_or(true, |p| !p.is_alphanumeric()) {
+                score += 2;
+            }
+            q += 1;
+            previous_hit = true;
+        } else {
+            previous_hit = false;
+        }
+        previous = Some(c);
+    }
+    (q == query.len()).then_some(score)
+}
+
+impl ShipApp {
+    // Newest first, without repeats
+    fn previous_prompts(&self) -> Vec<String> {
+        let mut prompts: Vec<String> = Vec::new();
+        for msg in self.messages.iter().rev().filter(|m| m.role == "user") {
+            let text = msg.content.trim();
+            if !text.is_empty() && !prompts.iter().any(|p| p == text) {
+                prompts.push(text.to_string());
+            }
+        }
+        prompts
+    }
+
+    fn set_input(&mut self, ctx: &egui::Context, text: String) {
+        self.input_text = text;
+        // Cursor to the end so the recalled prompt can be tweaked right away
+        if let Some(mut state) = egui::TextEdit::load_state(ctx, egui::Id::new(INPUT_ID)) {
+            let end = egui::text::CCursor::new(self.input_text.chars().count());
+            state.set_ccursor_range(Some(egui::text_edit::CCursorRange::one(end)));
+            state.store(ctx, egui::Id::new(INPUT_ID));
+        }
+    }
+
+    // Call before the input TextEdit so the keys never reach it
+    pub(super) fn input_history_keys(&mut self, ui: &egui::Ui) {
+        let ctx = ui.ctx().clone();
+        if !ui.memory(|m| m.has_focus(egui::Id::new(INPUT_ID))) {
+            return;
+        }
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::R)) {
+            self.history_search = Some(String::new());
+            return;
+        }
+
+        // 1. Typing over a recalled prompt ends the walk
+        let prompts = self.previous_prompts();
+        if let Some(at) = self.history_cursor {
+            if prompts.get(at) != Some(&self.input_text) {
+                self.history_cursor = None;
+            }
+        }
+        if self.history_cursor.is_none() && !self.input_text.is_empty() {
+            return;
+        }
+
+        // 2. Up goes back in time, Down forward and finally to an empty input
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp)) {
+            let next = self.history_cursor.map_or(0, |at| at + 1);
+            if next < prompts.len() {
+                self.history_cursor = Some(next);
+                self.set_input(&ctx, prompts[next].clone());
+            }
+        } else if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown)) {
+            match self.history_cursor {
+                Some(0) | None => {
+                    self.history_cursor = None;
+                    self.input_text.clear();
+                }
+                Some(at) => {
+                    self.history_cursor = Some(at - 1);
+                    self.set_input(&ctx, prompts[at - 1].clone());
+                }
+            }
+        }
+    }
+
+    // Ctrl+R popup: type to filter, Enter or click takes the best match, Esc closes
+    pub(super) fn history_search_window(&mut self, ctx: &egui::Context) {
+        if self.history_search.is_none() {
+            return;
+        }
+        let previous = self.previous_prompts();
+        let Some(query) = &mut self.history_search else { return };
+        let mut open = true;
+        let mut picked = None;
+        let mut prompts: Vec<(i32, String)> = previous
+            .into_iter()
+            .filter_map(|p| fuzzy_score(&p, query).map(|score| (score, p)))
+            .collect();
+        // Stable, so equal scores stay newest first
+        prompts.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        egui::Window::new("Prompt history")
+            .open(&mut open)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -60.0])
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                let field = ui.add(egui::TextEdit::singleline(query).hint_text("search earlier prompts").desired_width(f32::INFINITY));
+                field.request_focus();
+                if prompts.is_empty() {
+                    ui.weak("No matching prompts in this session");
+                }
+                for (i, (_, prompt)) in prompts.iter().take(SEARCH_ROWS).enumerate() {
+                    let line: String = prompt.lines().next().unwrap_or_default().chars().take(90).collect();
+                    if ui.selectable_label(i == 0, line).on_hover_text(prompt).clicked() {
+                        picked = Some(prompt.clone());
+                    }
+                }
+                if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    picked = prompts.first().map(|(_, p)| p.clone());
+                }
+            });
+
+        let escape = ctx.input(|i| i.key_pressed(egui::Key::Escape));
+        if let Some(prompt) = picked {
+            self.history_cursor = None;
+            self.history_search = None;
+            self.set_input(ctx, prompt);
+            ctx.memory_mut(|m| m.request_focus(egui::Id::new(INPUT_ID)));
+        } else if !open || escape {
+            self.history_search = None;
+            ctx.memory_mut(|m| m.request_focus(egui::Id::new(INPUT_ID)));
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// Job journal hooks and the "resume interrupted jobs" prompt shown at launch
+
+use super::ShipApp;
+use crate::jobs::{self, JobKind, JOBS_FILE};
+use eframe::egui;
+
+impl ShipApp {
+    fn jobs_file(&self) -> std::path::PathBuf {
+        self.profile.root().join(JOBS_FILE)
+    }
+
+    // Journal problems are logged, never fatal: the job itself still runs
+    pub(super) fn begin_job(&mut self, kind: JobKind) {
+        if let Err(e) = jobs::begin(&self.jobs_file(), kind) {
+            self.log_event(&format!("Job journal: {}", e));
+        }
+    }
+
+    pub(super) fn finish_job(&mut self, kind: &JobKind) {
+        if let Err(e) = jobs::finish(&self.jobs_file(), kind) {
+            self.log_event(&format!("Job journal: {}", e));
+        }
+    }
+
+    pub(super) fn load_interrupted_jobs(&mut self) {
+        self.interrupted_jobs = jobs::load(&self.jobs_file());
+        let labels: Vec<String> = self.interrupted_jobs.iter().map(|j| j.label()).collect();
+        for label in labels {
+            self.log_event(&format!("Interrupted last time: {}", label));
+        }
+    }
+
+    pub(super) fn resume_jobs_window(&mut self, ctx: &egui::Context) {
+        if self.interrupted_jobs.is_empty() {
+            return;
+        }
+        let mut resume = None;
+        let mut discard = None;
+        let clock = self.clock();
+        egui::Window::new("Resume interrupted jobs? ⏸")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("These were still running when the app last closed:");
+                for (i, job) in self.interrupted_jobs.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let started = clock.date_time(job.started);
+                        ui.label(format!("{} (started {})", job.label(), started));
+                        if ui.button("▶ Resume").clicked() {
+                            resume = Some(i);
+                        }
+                        if ui.button("Discard").clicked() {
+                            discard = Some(i);
+                        }
+                    });
+                }
+            });
+
+        if let Some(i) = discard {
+            let job = self.interrupted_jobs.remove(i);
+            self.finish_job(&job.kind);
+        }
+        if let Some(i) = resume {
+            let job = self.interrupted_jobs.remove(i);
+            self.log_event(&format!("Resuming {}", job.label()));
+            match job.kind {
+                JobKind::Pull { tag } => self.start_pull(&tag),
+                JobKind::IndexSync => {
+                    self.show_search = true;
+                    self.rebuild_search_index();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Window: guided lab report (experiment details -> sectioned draft -> Markdown/LaTeX file)
+
+use super::ShipApp;
+use crate::lab_report::{self, ReportFormat};
+use eframe::egui;
+
+impl ShipApp {
+    fn start_lab_report(&mut self) {
+        if self.lab_report_busy {
+            return;
+        }
+        self.lab_report_busy = true;
+        self.lab_report_status = "Reading data...".to_string();
+
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        let spec = self.lab_report.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let progress_tx = tx.clone();
+            let progress = move |step: &str| {
+                let _ = progress_tx.send(format!("__LAB_REPORT__:{}", step));
+            };
+            match lab_report::generate(&backend, &model, &spec, progress) {
+                Ok(path) => { let _ = tx.send(format!("__LAB_REPORT_DONE__:{}", path.display())); }
+                Err(e) => { let _ = tx.send(format!("__LAB_REPORT_FAILED__:{}", e)); }
+            }
+        });
+    }
+
+    pub(super) fn lab_report_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_lab_report;
+        egui::Window::new("Lab Report 🧪")
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                // 1. Experiment details
+                egui::Grid::new("lab_report_form").num_columns(2).show(ui, |ui| {
+                    ui.label("Title:");
+                    ui.text_edit_singleline(&mut self.lab_report.title);
+                    ui.end_row();
+                    ui.label("Objective:");
+                    ui.add(egui::TextEdit::multiline(&mut self.lab_report.objective).desired_rows(2));
+                    ui.end_row();
+                    ui.label("Equipment:");
+                    ui.add(egui::TextEdit::multiline(&mut self.lab_report.equipment).desired_rows(3).hint_text("One item per line"));
+                    ui.end_row();
+                    ui.label("Data (CSV):");
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut self.lab_report.data_file);
+                        if ui.small_button("📂").clicked() {
+                            if let Some(path) = rfd::FileDialog::new().add_filter("CSV", &["csv"]).pick_file() {
+                                self.lab_report.data_file = path.display().to_string();
+                            }
+                        }
+                    });
+                    ui.end_row();
+                    ui.label("Format:");
+                    ui.horizontal(|ui| {
+                        for format in [ReportFormat::Markdown, ReportFormat::Latex] {
+                            ui.radio_value(&mut self.lab_report.format, format, format.label());
+                        }
+                    });
+                    ui.end_row();
+                });
+
+                // 2. Generate
+                let ready = !self.lab_report.title.trim().is_empty()
+                    && !self.lab_report.objective.trim().is_empty()
+                    && !self.lab_report.data_file.trim().is_empty();
+                let label = if self.lab_report_busy { "Generating..." } else { "📝 Generate report" };
+                if ui.add_enabled(ready && !self.lab_report_busy, egui::Button::new(label))
+                    .on_disabled_hover_text("Needs a title, objective and data file")
+                    .clicked()
+                {
+                    self.start_lab_report();
+                }
+                ui.small("The plot needs python3 with matplotlib; without it the report notes that the plot is missing.");
+                if !self.lab_report_status.is_empty() {
+                    ui.label(&self.lab_report_status);
+                }
+            });
+        self.show_lab_report = open;
+    }
+}
@@ -0,0 +1,146 @@
+// Right-hand drawer with a model's card (`ollama show`): family, size, quantization,
+// context length, license and prompt template. The key facts also sit under the model
+// selector, with whether the model fits in VRAM.
+
+use super::ShipApp;
+use crate::backend::{BackendKind, ModelCard};
+use crate::quantize;
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn open_model_card(&mut self, model: &str) {
+        self.show_model_card = true;
+        self.fetch_model_card(model);
+    }
+
+    fn fetch_model_card(&mut self, model: &str) {
+        self.model_card_for = model.to_string();
+        if self.model_card.as_ref().is_some_and(|c| c.name == model) {
+            return;
+        }
+        self.model_card = None;
+        // /api/show is Ollama's; other servers would only answer 404
+        if self.config.backend.kind != BackendKind::Ollama {
+            self.model_card_status = "Model cards need an Ollama backend".to_string();
+            return;
+        }
+        self.model_card_status = format!("Loading {}...", model);
+
+        let backend = self.config.backend.clone();
+        let model = model.to_string();
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            match backend.show_model(&model).await {
+                Ok(card) => { let _ = tx.send(format!("__MODEL_CARD__:{}", serde_json::to_string(&card).unwrap_or_default())); }
+                Err(e) => { let _ = tx.send(format!("__MODEL_CARD_FAILED__:{}: {}", model, e)); }
+            }
+        });
+    }
+
+    pub(super) fn accept_model_card(&mut self, json: &str) {
+        match serde_json::from_str::<ModelCard>(json) {
+            Ok(card) => {
+                self.model_card_status.clear();
+                self.model_card = Some(card);
+            }
+            Err(e) => self.model_card_status = format!("Bad model card: {}", e),
+        }
+    }
+
+    // Sidebar, under the model selector; fetched once per selected model
+    pub(super) fn model_info_summary(&mut self, ui: &mut egui::Ui) {
+        if self.selected_model.is_empty() || self.config.backend.kind != BackendKind::Ollama {
+            return;
+        }
+        if self.model_card_for != self.selected_model {
+            let model = self.selected_model.clone();
+            self.fetch_model_card(&model);
+        }
+        let Some(card) = self.model_card.as_ref().filter(|c| c.name == self.selected_model) else {
+            if !self.model_card_status.is_empty() {
+                ui.small(&self.model_card_status);
+            }
+            return;
+        };
+
+        // 1. Family · parameters · quantization · context
+        let mut facts = vec![card.details.family.clone(), card.details.parameter_size.clone(), card.details.quantization_level.clone()];
+        if let Some(n) = card.context_length() {
+            facts.push(format!("{}k ctx max", n / 1024));
+        }
+        facts.retain(|f| !f.is_empty());
+        ui.small(facts.join(" · "));
+
+        // 2. Weights plus the KV cache for the window Ollama will allocate
+        let Some(weights) = self.installed_models.iter().find(|m| m.name == card.name).map(|m| m.size) else { return };
+        let ctx = card.num_ctx();
+        let needed = weights + card.kv_cache_bytes(ctx).unwrap_or(0);
+        let vram_total = self.gpu.total_mb * 1024 * 1024;
+        let text = format!("Needs ≈ {} at {} tokens", quantize::gigabytes(needed), ctx);
+        let tip = format!(
+            "{} weights + {} KV cache (f16). Ollama offloads layers to the CPU when it doesn't fit.",
+            quantize::gigabytes(weights),
+            card.kv_cache_bytes(ctx).map_or("unknown".to_string(), quantize::gigabytes)
+        );
+        if vram_total == 0 {
+            ui.small(text).on_hover_text(tip);
+        } else if needed <= vram_total {
+            ui.colored_label(egui::Color32::from_rgb(90, 170, 90), format!("✔ {} (fits {})", text, quantize::gigabytes(vram_total)))
+                .on_hover_text(tip);
+        } else {
+            ui.colored_label(egui::Color32::from_rgb(220, 150, 60), format!("⚠ {} (more than {})", text, quantize::gigabytes(vram_total)))
+                .on_hover_text(tip);
+        }
+    }
+
+    pub(super) fn model_card_drawer(&mut self, ctx: &egui::Context) {
+        if !self.show_model_card {
+            return;
+        }
+        egui::SidePanel::right("model_card").default_width(320.0).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("Model card");
+                if ui.small_button("✖").clicked() {
+                    self.show_model_card = false;
+                }
+            });
+            if !self.model_card_status.is_empty() {
+                ui.label(&self.model_card_status);
+            }
+            let Some(card) = &self.model_card else { return };
+
+            // 1. Key facts
+            ui.strong(&card.name);
+            egui::Grid::new("model_card_facts").num_columns(2).striped(true).show(ui, |ui| {
+                let dash = || "—".to_string();
+                let rows = [
+                    ("Family", card.details.family.clone()),
+                    ("Architecture", card.architecture().map(String::from).unwrap_or_else(dash)),
+                    ("Parameters", match card.parameter_count() {
+                        Some(n) => format!("{} ({:.2}B)", card.details.parameter_size, n as f64 / 1e9),
+                        None => card.details.parameter_size.clone(),
+                    }),
+                    ("Quantization", card.details.quantization_level.clone()),
+                    ("Context length", card.context_length().map(|n| n.to_string()).unwrap_or_else(dash)),
+                ];
+                for (label, value) in rows {
+                    ui.label(label);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
+
+            // 2. Long text fields
+            egui::ScrollArea::vertical().id_source("model_card_text").show(ui, |ui| {
+                for (title, text) in [("Parameters", &card.parameters), ("Template", &card.template), ("License", &card.license), ("Modelfile", &card.modelfile)] {
+                    if text.trim().is_empty() {
+                        continue;
+                    }
+                    egui::CollapsingHeader::new(title).id_source(("model_card", title)).show(ui, |ui| {
+                        ui.monospace(text.trim());
+                    });
+                }
+            });
+        });
+    }
+}
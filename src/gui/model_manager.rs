@@ -0,0 +1,91 @@
+// Sidebar section: installed models by size on disk, with a confirmed delete that
+// refreshes the selector afterwards
+
+use super::ShipApp;
+use crate::quantize;
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn model_manager(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Disk usage 💾").id_source("model_manager").show(ui, |ui| {
+            if self.installed_models.is_empty() {
+                ui.weak("No answer from Ollama yet; use 🔄 above.");
+                return;
+            }
+            let mut models: Vec<_> = self.installed_models.iter().collect();
+            models.sort_by(|a, b| b.size.cmp(&a.size));
+            let total: u64 = models.iter().map(|m| m.size).sum();
+            ui.small(format!("{} models, {} in total", models.len(), quantize::gigabytes(total)));
+
+            egui::Grid::new("model_sizes").num_columns(3).striped(true).show(ui, |ui| {
+                for model in models {
+                    ui.label(&model.name).on_hover_text(format!("{} {}", model.details.parameter_size, model.details.quantization_level));
+                    ui.small(quantize::gigabytes(model.size));
+                    let busy = self.deleting_model.is_some();
+                    if ui.add_enabled(!busy, egui::Button::new("🗑").small()).on_hover_text("Delete from disk").clicked() {
+                        self.confirm_delete_model = Some(model.name.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+            if let Some(name) = &self.deleting_model {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.small(format!("Deleting {}...", name));
+                });
+            }
+        });
+    }
+
+    pub(super) fn delete_model_window(&mut self, ctx: &egui::Context) {
+        let Some(name) = self.confirm_delete_model.clone() else { return };
+        let size = self.installed_models.iter().find(|m| m.name == name).map(|m| m.size).unwrap_or(0);
+        let mut decided = false;
+        egui::Window::new("Delete model?")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Delete {} and free about {}?", name, quantize::gigabytes(size)));
+                ui.small("Layers shared with other models stay. Pulling it again downloads everything.");
+                ui.horizontal(|ui| {
+                    if ui.button("🗑 Delete").clicked() {
+                        self.start_model_delete(&name);
+                        decided = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        decided = true;
+                    }
+                });
+            });
+        if decided {
+            self.confirm_delete_model = None;
+        }
+    }
+
+    fn start_model_delete(&mut self, name: &str) {
+        self.deleting_model = Some(name.to_string());
+        let backend = self.config.backend.clone();
+        let name = name.to_string();
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            let _ = match backend.delete_model(&name).await {
+                Ok(()) => tx.send(format!("__MODEL_DELETED__:{}", name)),
+                Err(e) => tx.send(format!("__MODEL_DELETE_FAILED__:{}: {}", name, e)),
+            };
+        });
+    }
+
+    pub(super) fn finish_model_delete(&mut self, result: Result<&str, &str>) {
+        self.deleting_model = None;
+        match result {
+            Ok(name) => {
+                self.log_event(&format!("Deleted model {}", name));
+                self.push_toast(&format!("Deleted {}", name));
+                self.models.retain(|m| m != name);
+                self.installed_models.retain(|m| m.name != name);
+            }
+            Err(e) => self.report_error(&format!("Delete failed: {}", e)),
+        }
+        self.refresh_models();
+    }
+}
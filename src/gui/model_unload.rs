@@ -0,0 +1,93 @@
+// Usage-based unloading: when free VRAM runs low, the resident model that has gone
+// unused the longest is evicted (keep_alive 0) before the next switch has to wait for it.
+// The ⏏ button next to the VRAM readout evicts everything right away.
+
+use super::ShipApp;
+use crate::backend::BackendKind;
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+const UNLOAD_COOLDOWN: Duration = Duration::from_secs(15); // Lets VRAM readings catch up
+
+impl ShipApp {
+    // Called when a generation starts with `model`
+    pub(super) fn note_model_used(&mut self, model: &str) {
+        self.model_last_used.insert(model.to_string(), Instant::now());
+    }
+
+    // Called every frame; sends at most one unload per cooldown
+    pub(super) fn schedule_unloads(&mut self) {
+        // Only Ollama unloads on request, and only its resident list is known
+        if self.config.backend.kind != BackendKind::Ollama {
+            return;
+        }
+        let threshold = self.config.unload_headroom_mb;
+        let (used, total) = (self.gpu.used_mb, self.gpu.total_mb);
+        if threshold == 0 || total == 0 || total.saturating_sub(used) >= threshold {
+            return;
+        }
+        if self.last_unload.is_some_and(|at| at.elapsed() < UNLOAD_COOLDOWN) {
+            return;
+        }
+
+        // Never the selected model; models we never used count as idle longest
+        let Some(victim) = self.resident_models
+            .iter()
+            .filter(|m| **m != self.selected_model)
+            .min_by_key(|m| self.model_last_used.get(*m).copied())
+            .cloned()
+        else {
+            return;
+        };
+        self.last_unload = Some(Instant::now());
+        self.log_event(&format!("VRAM low ({} MB free): unloading idle model {}", total.saturating_sub(used), victim));
+
+        let backend = self.config.backend.clone();
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            if let Err(e) = backend.unload_model(&victim).await {
+                let _ = tx.send(format!("__ERROR__:Could not unload {}: {}", victim, e));
+            }
+        });
+    }
+
+    // Every resident model plus the selected one, which may be loading still
+    fn unload_all(&mut self) {
+        let mut models = self.resident_models.clone();
+        if !self.selected_model.is_empty() && !models.contains(&self.selected_model) {
+            models.push(self.selected_model.clone());
+        }
+        self.log_event(&format!("Unloading {}", models.join(", ")));
+        self.resident_models.clear();
+
+        let backend = self.config.backend.clone();
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            for model in models {
+                if let Err(e) = backend.unload_model(&model).await {
+                    let _ = tx.send(format!("__ERROR__:Could not unload {}: {}", model, e));
+                }
+            }
+        });
+    }
+
+    // Next to the VRAM readout
+    pub(super) fn unload_button(&mut self, ui: &mut egui::Ui) {
+        let ollama = self.config.backend.kind == BackendKind::Ollama;
+        let button = ui.add_enabled(ollama, egui::Button::new("⏏ Unload").small());
+        if button.on_hover_text("Free VRAM now: unload every model Ollama has in memory").on_disabled_hover_text("Only Ollama can unload models on request").clicked() {
+            self.unload_all();
+        }
+    }
+
+    // Under the VRAM readout
+    pub(super) fn unload_setting(&mut self, ui: &mut egui::Ui) {
+        let slider = egui::Slider::new(&mut self.config.unload_headroom_mb, 0..=16384).step_by(256.0).text("MB headroom");
+        if ui.add(slider)
+            .on_hover_text("Unload the longest-idle model when free VRAM drops below this (0 = never)")
+            .changed()
+        {
+            self.save_config();
+        }
+    }
+}
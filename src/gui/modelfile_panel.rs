@@ -0,0 +1,102 @@
+// Window: author a Modelfile and bake it with `ollama create`
+
+use super::ShipApp;
+use crate::modelfile::KNOWN_PARAMETERS;
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn modelfile_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_modelfile_editor;
+        egui::Window::new("Modelfile Editor 🧱")
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let spec = &mut self.modelfile_spec;
+
+                // 1. Identity
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut spec.name);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("FROM:");
+                    egui::ComboBox::from_id_source("modelfile_base")
+                        .selected_text(&spec.base)
+                        .show_ui(ui, |ui| {
+                            for model in &self.models {
+                                ui.selectable_value(&mut spec.base, model.clone(), model);
+                            }
+                        });
+                    ui.text_edit_singleline(&mut spec.base);
+                });
+
+                // 2. System prompt
+                ui.horizontal(|ui| {
+                    ui.label("System prompt:");
+                    if ui.small_button("Use my system prompt").clicked() {
+                        spec.system = self.config.system_prompt.clone();
+                    }
+                });
+                ui.add(egui::TextEdit::multiline(&mut spec.system).desired_rows(4).desired_width(f32::INFINITY));
+
+                // 3. Parameters
+                ui.label("Parameters:");
+                let mut remove = None;
+                for (i, (key, value)) in spec.parameters.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        egui::ComboBox::from_id_source(("modelfile_param", i))
+                            .selected_text(key.as_str())
+                            .show_ui(ui, |ui| {
+                                for known in KNOWN_PARAMETERS {
+                                    ui.selectable_value(key, known.to_string(), known);
+                                }
+                            });
+                        ui.text_edit_singleline(value);
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = remove {
+                    spec.parameters.remove(i);
+                }
+                if ui.small_button("➕ Parameter").clicked() {
+                    spec.parameters.push(("num_ctx".to_string(), "8192".to_string()));
+                }
+
+                // 4. Preview
+                ui.separator();
+                ui.collapsing("Preview", |ui| {
+                    ui.monospace(spec.render());
+                });
+
+                // 5. Build
+                let label = if self.modelfile_running { "Creating..." } else { "Create model" };
+                if ui.add_enabled(!self.modelfile_running, egui::Button::new(label)).clicked() {
+                    self.modelfile_log.clear();
+                    self.modelfile_running = true;
+                    let spec = self.modelfile_spec.clone();
+                    let root = self.profile.root();
+                    let backend = self.config.backend.clone();
+                    let tx = self.tx.clone();
+                    std::thread::spawn(move || {
+                        match spec.create(&root, &backend, &tx) {
+                            Ok(()) => { let _ = tx.send(format!("__MODELFILE_DONE__:{}", spec.name.trim())); }
+                            Err(e) => { let _ = tx.send(format!("__MODELFILE_FAILED__:{}", e)); }
+                        }
+                    });
+                }
+
+                egui::ScrollArea::vertical()
+                    .id_source("modelfile_log")
+                    .max_height(120.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in &self.modelfile_log {
+                            ui.monospace(line);
+                        }
+                    });
+            });
+        self.show_modelfile_editor = open;
+    }
+}
@@ -0,0 +1,120 @@
+// Sidebar section: model selector plus pin / hide / drag-to-reorder management.
+// The list comes from Ollama's /api/tags; picking a model (or ℹ) opens its card.
+
+use super::ShipApp;
+use crate::backend::LocalModel;
+use eframe::egui;
+
+impl ShipApp {
+    // At startup and on 🔄; the answer arrives as __MODELS__
+    pub(super) fn refresh_models(&mut self) {
+        let backend = crate::chat_backend::for_config(&self.config.backend);
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            let _ = match backend.list_models().await {
+                Ok(models) => tx.send(format!("__MODELS__:{}", serde_json::to_string(&models).unwrap_or_default())),
+                Err(e) => tx.send(format!("__MODELS_FAILED__:{}", e)),
+            };
+        });
+    }
+
+    // What is installed replaces the list; the remembered one stays when the server is unreachable
+    pub(super) fn accept_model_list(&mut self, json: &str) {
+        let installed: Vec<LocalModel> = match serde_json::from_str(json) {
+            Ok(models) => models,
+            Err(e) => {
+                self.report_error(&format!("Bad model list: {}", e));
+                return;
+            }
+        };
+        if installed.is_empty() {
+            self.log_event("Ollama has no models installed; keeping the remembered list");
+            return;
+        }
+        self.models = installed.iter().map(|m| m.name.clone()).collect();
+        if !self.models.contains(&self.selected_model) {
+            self.log_event(&format!("{} is not installed; switched to {}", self.selected_model, self.models[0]));
+            self.selected_model = self.models[0].clone();
+        }
+        self.installed_models = installed;
+    }
+
+    pub(super) fn model_selector(&mut self, ui: &mut egui::Ui) {
+        ui.label("Active Neural Net:");
+        let visible = self.config.models.visible(&self.models);
+        let mut picked = None;
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("model_selector")
+                .selected_text(&self.selected_model)
+                .show_ui(ui, |ui| {
+                    for model in &visible {
+                        let label = if self.config.models.is_pinned(model) { format!("📌 {}", model) } else { model.clone() };
+                        if ui.selectable_value(&mut self.selected_model, model.clone(), label).clicked() {
+                            picked = Some(model.clone());
+                        }
+                    }
+                });
+            if ui.small_button("ℹ").on_hover_text("Model card").clicked() {
+                picked = Some(self.selected_model.clone());
+            }
+            if ui.small_button("🔄").on_hover_text("Reload the installed models from Ollama").clicked() {
+                self.refresh_models();
+            }
+            if ui.small_button("⬇").on_hover_text("Pull a model").clicked() {
+                self.show_pull = true;
+            }
+        });
+        if let Some(model) = picked {
+            self.open_model_card(&model);
+        }
+
+        egui::CollapsingHeader::new("Arrange models").id_source("arrange_models").show(ui, |ui| {
+            ui.small("Drag ☰ to reorder");
+            let arranged = self.config.models.arrange(&self.models);
+            let mut changed = false;
+            let mut drop_move = None;
+
+            for (i, model) in arranged.iter().enumerate() {
+                let hidden = self.config.models.is_hidden(model);
+                let pinned = self.config.models.is_pinned(model);
+
+                ui.horizontal(|ui| {
+                    // 1. Drag handle + name
+                    let row = ui.dnd_drag_source(egui::Id::new(("model_row", model)), i, |ui| {
+                        let text = egui::RichText::new(format!("☰ {}", model));
+                        ui.label(if hidden { text.weak() } else { text });
+                    }).response;
+
+                    // 2. Drop target: upper half inserts before, lower half after
+                    if let (Some(pointer), Some(_)) = (ui.input(|inp| inp.pointer.interact_pos()), row.dnd_hover_payload::<usize>()) {
+                        let before = pointer.y < row.rect.center().y;
+                        let y = if before { row.rect.top() } else { row.rect.bottom() };
+                        ui.painter().hline(row.rect.x_range(), y, ui.visuals().selection.stroke);
+
+                        if let Some(from) = row.dnd_release_payload::<usize>() {
+                            drop_move = Some((*from, if before { i } else { i + 1 }));
+                        }
+                    }
+
+                    // 3. Pin / hide toggles
+                    if ui.selectable_label(pinned, "📌").on_hover_text("Pin to top").clicked() {
+                        self.config.models.toggle_pinned(model);
+                        changed = true;
+                    }
+                    if ui.selectable_label(hidden, "🙈").on_hover_text("Hide from selector").clicked() {
+                        self.config.models.toggle_hidden(model);
+                        changed = true;
+                    }
+                });
+            }
+
+            if let Some((from, to)) = drop_move {
+                self.config.models.move_model(&arranged, from, to);
+                changed = true;
+            }
+            if changed {
+                self.save_config();
+            }
+        });
+    }
+}
@@ -0,0 +1,88 @@
+// Keyboard review of the chat: j/k (or arrows) move between messages, Enter expands the
+// focused message's collapsible blocks, F dims everything but the focused exchange.
+
+use super::ShipApp;
+use eframe::egui::{self, Key};
+
+impl ShipApp {
+    pub(super) fn handle_navigation_keys(&mut self, ctx: &egui::Context) {
+        // Typing in the input box must not move the focus
+        if ctx.wants_keyboard_input() || self.messages.is_empty() {
+            return;
+        }
+        let (down, up, enter, focus, escape) = ctx.input(|i| (
+            i.key_pressed(Key::J) || i.key_pressed(Key::ArrowDown),
+            i.key_pressed(Key::K) || i.key_pressed(Key::ArrowUp),
+            i.key_pressed(Key::Enter),
+            i.key_pressed(Key::F),
+            i.key_pressed(Key::Escape),
+        ));
+        let last = self.messages.len() - 1;
+
+        if down {
+            self.focused_message = Some(self.focused_message.map_or(0, |i| (i + 1).min(last)));
+            self.scroll_to_focus = true;
+        }
+        if up {
+            self.focused_message = Some(self.focused_message.map_or(last, |i| i.saturating_sub(1)));
+            self.scroll_to_focus = true;
+        }
+        if let (true, Some(i)) = (enter, self.focused_message) {
+            let open = !self.expanded_messages.remove(&i);
+            if open {
+                self.expanded_messages.insert(i);
+            }
+            self.expand_request = Some((i, open));
+        }
+        if focus {
+            self.focus_mode = !self.focus_mode;
+            if self.focused_message.is_none() {
+                self.focused_message = Some(last);
+                self.scroll_to_focus = true;
+            }
+        }
+        if escape {
+            self.focused_message = None;
+            self.focus_mode = false;
+        }
+    }
+
+    // Forced open/shut state for message `i` this frame
+    pub(super) fn expand_override(&self, i: usize) -> Option<bool> {
+        self.expand_request.filter(|(m, _)| *m == i).map(|(_, open)| open)
+    }
+
+    // The focused message plus its question or answer
+    fn in_focused_exchange(&self, i: usize) -> bool {
+        let Some(f) = self.focused_message else { return true };
+        let partner = match self.messages.get(f).map(|m| m.role.as_str()) {
+            Some("user") => f + 1,
+            _ => f.wrapping_sub(1),
+        };
+        i == f || i == partner
+    }
+
+    // Focus ring and focus-mode dimming, drawn over a finished message row
+    pub(super) fn decorate_message_row(&self, ui: &egui::Ui, i: usize, row: &egui::Response) {
+        let rect = row.rect.expand(4.0);
+        if self.focused_message == Some(i) {
+            ui.painter().rect_stroke(rect, 4.0, ui.visuals().selection.stroke);
+            if self.scroll_to_focus {
+                row.scroll_to_me(Some(egui::Align::Center));
+            }
+        } else if self.focus_mode && !self.in_focused_exchange(i) {
+            let fill = ui.visuals().panel_fill;
+            let veil = egui::Color32::from_rgba_unmultiplied(fill.r(), fill.g(), fill.b(), 200);
+            ui.painter().rect_filled(rect, 0.0, veil);
+        }
+    }
+
+    // Per-frame requests are consumed once the chat has been drawn
+    pub(super) fn end_navigation_frame(&mut self) {
+        self.scroll_to_focus = false;
+        self.expand_request = None;
+        if self.focused_message.is_some_and(|i| i >= self.messages.len()) {
+            self.focused_message = None;
+        }
+    }
+}
@@ -0,0 +1,154 @@
+// Window: conversation-driven file organizer (propose -> review table -> execute -> undo)
+
+use super::ShipApp;
+use crate::organizer::{self, PlannedMove, JOURNAL_FILE};
+use eframe::egui;
+
+impl ShipApp {
+    // "/organize <instruction>" from the chat input lands here
+    pub(super) fn open_organizer(&mut self, instruction: &str) {
+        self.show_organizer = true;
+        if !instruction.trim().is_empty() {
+            self.organizer_instruction = instruction.trim().to_string();
+        }
+        if self.organizer_dir.is_empty() {
+            if let Some(home) = std::env::var_os("HOME") {
+                self.organizer_dir = std::path::Path::new(&home).join("Downloads").display().to_string();
+            }
+        }
+    }
+
+    pub(super) fn accept_organizer_plan(&mut self, json: &str) {
+        self.organizer_busy = false;
+        match serde_json::from_str::<Vec<PlannedMove>>(json) {
+            Ok(plan) => {
+                self.organizer_status = format!("{} moves proposed. Review and untick anything wrong.", plan.len());
+                self.organizer_plan = plan;
+            }
+            Err(e) => self.report_error(&format!("Bad organizer plan: {}", e)),
+        }
+    }
+
+    pub(super) fn organizer_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_organizer;
+        egui::Window::new("File Organizer 🗂")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                // 1. What and where
+                ui.horizontal(|ui| {
+                    ui.label("Folder:");
+                    ui.text_edit_singleline(&mut self.organizer_dir);
+                    if ui.button("📂").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                            self.organizer_dir = path.display().to_string();
+                        }
+                    }
+                });
+                ui.add(egui::TextEdit::singleline(&mut self.organizer_instruction)
+                    .hint_text("Organize datasheets into folders by manufacturer")
+                    .desired_width(f32::INFINITY));
+
+                let label = if self.organizer_busy { "Planning..." } else { "Propose plan" };
+                if ui.add_enabled(!self.organizer_busy, egui::Button::new(label)).clicked() {
+                    self.propose_organizer_plan();
+                }
+
+                // 2. Review table; nothing has moved yet
+                if !self.organizer_plan.is_empty() {
+                    ui.separator();
+                    egui::ScrollArea::vertical().id_source("organizer_plan").max_height(260.0).show(ui, |ui| {
+                        egui::Grid::new("organizer_grid").striped(true).num_columns(3).show(ui, |ui| {
+                            ui.strong("");
+                            ui.strong("File");
+                            ui.strong("→ Folder");
+                            ui.end_row();
+                            let root = std::path::PathBuf::from(&self.organizer_dir);
+                            for m in &mut self.organizer_plan {
+                                ui.checkbox(&mut m.include, "");
+                                ui.label(m.from.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default());
+                                let folder = m.to.parent().and_then(|p| p.strip_prefix(&root).ok()).unwrap_or(m.to.as_path());
+                                ui.label(folder.display().to_string());
+                                ui.end_row();
+                            }
+                        });
+                    });
+
+                    let count = self.organizer_plan.iter().filter(|m| m.include).count();
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(count > 0, egui::Button::new(format!("✅ Execute {} moves", count))).clicked() {
+                            self.execute_organizer_plan();
+                        }
+                        if ui.button("Discard plan").clicked() {
+                            self.organizer_plan.clear();
+                            self.organizer_status.clear();
+                        }
+                    });
+                }
+
+                // 3. Undo
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("↩ Undo last batch").clicked() {
+                        self.undo_organizer();
+                    }
+                    ui.small(format!("Every move is logged to {}", self.organizer_journal().display()));
+                });
+                if !self.organizer_status.is_empty() {
+                    ui.label(&self.organizer_status);
+                }
+            });
+        self.show_organizer = open;
+    }
+
+    fn organizer_journal(&self) -> std::path::PathBuf {
+        self.profile.root().join(JOURNAL_FILE)
+    }
+
+    fn propose_organizer_plan(&mut self) {
+        if self.organizer_instruction.trim().is_empty() {
+            self.organizer_status = "Describe how the files should be organized first.".to_string();
+            return;
+        }
+        self.organizer_busy = true;
+        self.organizer_plan.clear();
+        self.organizer_status = "Asking the model for a plan...".to_string();
+
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        let dir = std::path::PathBuf::from(&self.organizer_dir);
+        let instruction = self.organizer_instruction.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            match organizer::propose(&backend, &model, &dir, &instruction) {
+                Ok(plan) => { let _ = tx.send(format!("__ORGANIZE_PLAN__:{}", serde_json::to_string(&plan).unwrap_or_default())); }
+                Err(e) => { let _ = tx.send(format!("__ORGANIZE_FAILED__:{}", e)); }
+            }
+        });
+    }
+
+    fn execute_organizer_plan(&mut self) {
+        let plan = std::mem::take(&mut self.organizer_plan);
+        let journal = self.organizer_journal();
+        match organizer::execute(&plan, &journal) {
+            Ok(n) => {
+                self.organizer_status = format!("Moved {} files.", n);
+                self.log_event(&format!("Organizer moved {} files in {}", n, self.organizer_dir));
+            }
+            Err(e) => {
+                self.organizer_status = format!("Stopped: {}", e);
+                self.report_error(&format!("Organizer: {}", e));
+            }
+        }
+    }
+
+    fn undo_organizer(&mut self) {
+        match organizer::undo_last(&self.organizer_journal()) {
+            Ok(n) => {
+                self.organizer_status = format!("Restored {} files.", n);
+                self.log_event(&format!("Organizer undo restored {} files", n));
+            }
+            Err(e) => self.organizer_status = e,
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// Per-chat generation overrides (temperature, persona, RAG mode, hosted model) edited
+// from the conversation header. They are stored with the session and never touch the config;
+// an icon per active override keeps an experimental chat recognizable.
+
+use super::ShipApp;
+use crate::chat_backend::{self, ChatBackend, HostedModel, HostedProvider};
+use crate::rag_trigger::RagMode;
+use eframe::egui;
+
+impl ShipApp {
+    // Id of the persona override when there is one, else of the globally selected persona
+    pub(super) fn active_persona(&self) -> &str {
+        self.overrides.persona.as_deref().unwrap_or(&self.config.persona)
+    }
+
+    pub(super) fn effective_system_prompt(&self) -> &str {
+        self.config.system_prompt_for(self.active_persona())
+    }
+
+    // Where this chat's replies come from: its hosted model, else the configured server
+    pub(super) fn chat_backend(&self) -> Result<Box<dyn ChatBackend>, String> {
+        match &self.overrides.hosted {
+            Some(hosted) => chat_backend::hosted(hosted),
+            None => Ok(chat_backend::for_config(&self.config.backend)),
+        }
+    }
+
+    pub(super) fn chat_model(&self) -> String {
+        match &self.overrides.hosted {
+            Some(hosted) => hosted.model.clone(),
+            None => self.selected_model.clone(),
+        }
+    }
+
+    // Right side of the conversation header
+    pub(super) fn overrides_widget(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.menu_button("🎛", |ui| {
+            ui.strong("This chat only");
+            ui.small("Overrides are saved with the session; new chats start from your defaults.");
+            ui.separator();
+
+            // 1. Temperature
+            ui.horizontal(|ui| {
+                let mut on = self.overrides.temperature.is_some();
+                let mut value = self.overrides.temperature.unwrap_or(0.7);
+                changed |= ui.checkbox(&mut on, "Temperature").changed();
+                // Saved once the drag ends, not on every frame of it
+                changed |= ui.add_enabled(on, egui::Slider::new(&mut value, 0.0..=2.0).step_by(0.05)).drag_released();
+                self.overrides.temperature = on.then_some(value);
+            });
+
+            // 2. Persona
+            ui.horizontal(|ui| {
+                ui.label("Persona:");
+                let label = match &self.overrides.persona {
+                    None => "(global)".to_string(),
+                    Some(id) => self.config.persona_label(id),
+                };
+                egui::ComboBox::from_id_source("override_persona").selected_text(label).show_ui(ui, |ui| {
+                    changed |= ui.selectable_value(&mut self.overrides.persona, None, "(global)").changed();
+                    changed |= ui.selectable_value(&mut self.overrides.persona, Some(String::new()), "Default").changed();
+                    for persona in &self.config.personas {
+                        changed |= ui.selectable_value(&mut self.overrides.persona, Some(persona.id.clone()), &persona.name).changed();
+                    }
+                });
+            });
+
+            // 3. RAG mode
+            ui.horizontal(|ui| {
+                ui.label("RAG:");
+                changed |= ui.selectable_value(&mut self.overrides.rag, None, "(global)").changed();
+                for mode in RagMode::ALL {
+                    changed |= ui.selectable_value(&mut self.overrides.rag, Some(mode), mode.label()).changed();
+                }
+            });
+
+            // 4. Hosted model, for what the local GPU can't run
+            ui.horizontal(|ui| {
+                ui.label("Model from:");
+                let label = self.overrides.hosted.as_ref().map_or("(local server)", |h| h.provider.label());
+                egui::ComboBox::from_id_source("override_hosted").selected_text(label).show_ui(ui, |ui| {
+                    changed |= ui.selectable_value(&mut self.overrides.hosted, None, "(local server)").changed();
+                    for provider in HostedProvider::ALL {
+                        let selected = self.overrides.hosted.as_ref().is_some_and(|h| h.provider == provider);
+                        if ui.selectable_label(selected, provider.label()).clicked() && !selected {
+                            self.overrides.hosted = Some(HostedModel { provider, model: provider.default_model().to_string() });
+                            changed = true;
+                        }
+                    }
+                });
+            });
+            let key_missing = self.overrides.hosted.as_ref().map(|h| h.provider.secret_account()).is_some_and(|a| !self.has_secret(a));
+            if let Some(hosted) = &mut self.overrides.hosted {
+                ui.horizontal(|ui| {
+                    ui.label("Model:");
+                    changed |= ui.text_edit_singleline(&mut hosted.model).lost_focus();
+                });
+                if key_missing {
+                    ui.colored_label(egui::Color32::from_rgb(230, 160, 60), format!("No key yet: store '{}' under 🔑 Secrets", hosted.provider.secret_account()));
+                }
+                ui.small("Prompts, history and retrieved excerpts of this chat leave the machine.");
+            }
+
+            ui.separator();
+            if ui.add_enabled(!self.overrides.is_default(), egui::Button::new("Clear overrides")).clicked() {
+                self.overrides = Default::default();
+                changed = true;
+            }
+        })
+        .response
+        .on_hover_text("Generation settings for this chat only");
+
+        // Indicators, right to left next to the button
+        if let Some(hosted) = &self.overrides.hosted {
+            ui.small(format!("☁ {}", hosted.model)).on_hover_text(format!("Replies come from the {} API", hosted.provider.label()));
+        }
+        if let Some(mode) = self.overrides.rag {
+            ui.small(format!("🔬 {}", mode.label())).on_hover_text("RAG mode overridden for this chat");
+        }
+        if let Some(placement) = self.overrides.context_placement {
+            ui.small(format!("📎 {}", placement.label())).on_hover_text("Context placement overridden for this chat");
+        }
+        if let Some(id) = &self.overrides.persona {
+            ui.small(format!("🎭 {}", self.config.persona_label(id))).on_hover_text("Persona overridden for this chat");
+        }
+        if let Some(t) = self.overrides.temperature {
+            ui.small(format!("🌡 {:.2}", t)).on_hover_text("Temperature overridden for this chat");
+        }
+
+        if changed {
+            if let Err(e) = self.flush_session() {
+                self.report_error(&format!("Failed to save session: {}", e));
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+// Persona switcher under the model selector, and the editor for the named system
+// prompts in the settings window. The active persona is saved with each session.
+
+use super::ShipApp;
+use eframe::egui;
+
+const DEFAULT_LABEL: &str = "Default";
+
+impl ShipApp {
+    pub(super) fn persona_selector(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Persona:");
+            let current = self.config.persona_label(&self.config.persona);
+            egui::ComboBox::from_id_source("persona_selector")
+                .selected_text(current)
+                .show_ui(ui, |ui| {
+                    changed |= ui.selectable_value(&mut self.config.persona, String::new(), DEFAULT_LABEL)
+                        .on_hover_text(&self.config.system_prompt)
+                        .changed();
+                    for persona in &self.config.personas {
+                        changed |= ui.selectable_value(&mut self.config.persona, persona.id.clone(), &persona.name)
+                            .on_hover_text(&persona.prompt)
+                            .changed();
+                    }
+                });
+            if ui.small_button("✏").on_hover_text("Edit personas in Settings").clicked() {
+                self.show_settings = true;
+            }
+        });
+        if changed {
+            self.save_config();
+            self.log_event(&format!("Persona: {}", self.config.persona_label(&self.config.persona)));
+        }
+    }
+
+    // Settings window, under the default system prompt; returns whether anything changed.
+    // Sessions and voices refer to a persona by id, so renaming one keeps them; while any
+    // name is empty or taken twice nothing is saved.
+    pub(super) fn persona_editor(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        let mut remove = None;
+        let names: Vec<String> = self.config.personas.iter().map(|p| p.name.trim().to_lowercase()).collect();
+        for (i, persona) in self.config.personas.iter_mut().enumerate() {
+            ui.push_id(("persona", i), |ui| {
+                ui.horizontal(|ui| {
+                    changed |= ui.add(egui::TextEdit::singleline(&mut persona.name).hint_text("name").desired_width(160.0)).lost_focus();
+                    if ui.small_button("✖").on_hover_text("Delete persona").clicked() {
+                        remove = Some(i);
+                    }
+                });
+                if let Some(problem) = name_problem(&names, i) {
+                    ui.colored_label(egui::Color32::from_rgb(220, 150, 60), problem);
+                }
+                changed |= ui.add(egui::TextEdit::multiline(&mut persona.prompt).desired_rows(3).desired_width(f32::INFINITY)).lost_focus();
+            });
+        }
+        if let Some(i) = remove {
+            let removed = self.config.personas.remove(i);
+            if self.config.persona == removed.id {
+                self.config.persona.clear();
+            }
+            self.config.persona_voices.remove(&removed.id);
+            changed = true;
+        }
+        if ui.small_button("➕ Add persona").clicked() {
+            let taken = |name: &str| self.config.personas.iter().any(|p| p.name == name);
+            let mut n = self.config.personas.len() + 1;
+            while taken(&format!("Persona {}", n)) {
+                n += 1;
+            }
+            let prompt = self.config.system_prompt.clone();
+            self.config.add_persona(format!("Persona {}", n), prompt);
+            changed = true;
+        }
+        let names: Vec<String> = self.config.personas.iter().map(|p| p.name.trim().to_lowercase()).collect();
+        changed && (0..names.len()).all(|i| name_problem(&names, i).is_none())
+    }
+}
+
+fn name_problem(names: &[String], i: usize) -> Option<&'static str> {
+    if names[i].is_empty() || names[i] == DEFAULT_LABEL.to_lowercase() {
+        Some("⚠ Needs a name other than Default; not saved until then")
+    } else if names.iter().filter(|n| **n == names[i]).count() > 1 {
+        Some("⚠ Another persona has this name; not saved until they differ")
+    } else {
+        None
+    }
+}
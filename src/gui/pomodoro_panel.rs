@@ -0,0 +1,140 @@
+// Study timer in the conversation header; its starts and stops are marked in the chat,
+// and /recap summarizes the messages of the last block
+
+use super::{AppState, ShipApp};
+use crate::pomodoro;
+use crate::session::{Message, TimerEvent, TimerEventKind};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+impl ShipApp {
+    fn timer_event(&mut self, kind: TimerEventKind) {
+        self.timer_events.push(TimerEvent {
+            kind,
+            at: chrono::Utc::now().timestamp_millis(),
+            message_index: self.messages.len(),
+            minutes: self.config.pomodoro_minutes,
+        });
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+    }
+
+    fn stop_pomodoro(&mut self, kind: TimerEventKind) {
+        if self.pomodoro_started.take().is_some() {
+            self.timer_event(kind);
+        }
+    }
+
+    // Every frame while a block runs: finish it on time
+    pub(super) fn pomodoro_tick(&mut self, ctx: &egui::Context) {
+        let Some(started) = self.pomodoro_started else {
+            return;
+        };
+        if started.elapsed() >= Duration::from_secs(self.config.pomodoro_minutes as u64 * 60) {
+            self.stop_pomodoro(TimerEventKind::Finish);
+            self.push_toast("🍅 Study block done. Type /recap for a summary.");
+            self.log_event("Study block finished");
+        } else {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+    }
+
+    pub(super) fn pomodoro_widget(&mut self, ui: &mut egui::Ui) {
+        match self.pomodoro_started {
+            Some(started) => {
+                let total = self.config.pomodoro_minutes as u64 * 60;
+                let left = total.saturating_sub(started.elapsed().as_secs());
+                if ui.small_button("⏹").on_hover_text("Stop the study block").clicked() {
+                    self.stop_pomodoro(TimerEventKind::Stop);
+                }
+                ui.monospace(format!("🍅 {:02}:{:02}", left / 60, left % 60));
+            }
+            None => {
+                let mut minutes = self.config.pomodoro_minutes;
+                if ui.add(egui::DragValue::new(&mut minutes).clamp_range(5..=120).suffix(" min")).changed() {
+                    self.config.pomodoro_minutes = minutes;
+                    self.save_config();
+                }
+                if ui.small_button("🍅 Start").on_hover_text("Start a study block").clicked() {
+                    self.pomodoro_started = Some(Instant::now());
+                    self.timer_event(TimerEventKind::Start);
+                }
+            }
+        }
+    }
+
+    // Divider drawn before message `i` for every timer event placed there
+    pub(super) fn timer_marks(&self, ui: &mut egui::Ui, i: usize) {
+        for event in self.timer_events.iter().filter(|e| e.message_index == i) {
+            let when = self.chat_clock().time_millis(event.at);
+            let text = match event.kind {
+                TimerEventKind::Start => format!("🍅 {} min study block started {}", event.minutes, when),
+                TimerEventKind::Stop => format!("⏹ Study block stopped {}", when),
+                TimerEventKind::Finish => format!("✅ Study block finished {}", when),
+            };
+            ui.vertical_centered(|ui| ui.weak(text));
+        }
+    }
+
+    pub(super) fn start_recap(&mut self) {
+        let Some(block) = pomodoro::last_block(&self.timer_events, self.messages.len()) else {
+            self.report_error("No study block yet: start the 🍅 timer first");
+            return;
+        };
+        let transcript: Vec<Message> = self.messages[block].to_vec();
+        self.messages.push(Message::new("user", "Recap what we covered in the last study block.".to_string(), false));
+        self.state = AppState::Generating;
+        self.activity = "Recapping the study block...".to_string();
+
+        // Tagged with its own id: a recap that arrives after Stop, or while a newer reply
+        // streams, is dropped instead of being spliced into the chat
+        self.recap_id += 1;
+        let id = self.recap_id;
+        let cancel = self.begin_cancellable();
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        self.reply_model = model.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let result = pomodoro::recap(&backend, &model, &transcript);
+            if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                return;
+            }
+            let _ = match result {
+                Ok(text) => tx.send(format!("__RECAP__:{}:{}", id, text)),
+                Err(e) => tx
+                    .send(format!("__ERROR__:Recap failed: {}", e))
+                    .and_then(|_| tx.send(format!("__RECAP_FAILED__:{}", id))),
+            };
+        });
+    }
+
+    // "<id>:<text>" from the recap worker; anything but the running recap is ignored
+    pub(super) fn accept_recap(&mut self, tagged: &str) {
+        let Some(text) = self.current_recap(tagged) else {
+            return; // Stopped or superseded while it was running
+        };
+        let mut reply = Message::new("assistant", text.to_string(), false);
+        reply.model = self.reply_model.clone();
+        self.messages.push(reply);
+        self.state = AppState::Idle;
+        self.activity.clear();
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+    }
+
+    pub(super) fn recap_failed(&mut self, id: &str) {
+        if self.current_recap(id).is_some() {
+            self.state = AppState::Idle;
+            self.activity.clear();
+        }
+    }
+
+    fn current_recap<'a>(&self, tagged: &'a str) -> Option<&'a str> {
+        let (id, text) = tagged.split_once(':').unwrap_or((tagged, ""));
+        let live = self.state == AppState::Generating && id.parse() == Ok(self.recap_id);
+        live.then_some(text)
+    }
+}
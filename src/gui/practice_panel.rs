@@ -0,0 +1,98 @@
+// Study mode: generate a practice problem, then grade typed attempts against it
+
+use super::{AppState, ShipApp};
+use crate::practice::{self, PracticeProblem};
+use crate::session::Message;
+use eframe::egui;
+use std::sync::atomic::Ordering;
+
+impl ShipApp {
+    pub(super) fn practice_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Study 🎓").id_source("practice_panel").show(ui, |ui| {
+            ui.checkbox(&mut self.practice_mode, "Practice-problem mode");
+            if !self.practice_mode {
+                return;
+            }
+            match &self.practice_problem {
+                Some(p) => {
+                    ui.small(format!("Active: {}", p.topic));
+                    if ui.button("⏭ Next problem").clicked() {
+                        let topic = p.topic.clone();
+                        self.practice_problem = None;
+                        self.practice_send(topic);
+                    }
+                    if ui.small_button("Change topic").clicked() {
+                        self.practice_problem = None;
+                    }
+                }
+                None => {
+                    ui.small("Type a topic (e.g. \"RC low-pass filters\") and press Send.");
+                }
+            }
+        });
+    }
+
+    // Send button in practice mode: a topic without an active problem, otherwise an attempt
+    pub(super) fn practice_send(&mut self, text: String) {
+        if self.state != AppState::Idle || text.trim().is_empty() {
+            return;
+        }
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        let tx = self.tx.clone();
+        let cancel = self.begin_cancellable();
+        self.state = AppState::Generating;
+
+        match self.practice_problem.clone() {
+            None => {
+                self.activity = format!("Writing a practice problem on {}", text);
+                crate::runtime::spawn_background(&self.runtime, move || {
+                    let result = practice::generate(&backend, &model, &text);
+                    if cancel.load(Ordering::Relaxed) {
+                        return; // Stopped meanwhile
+                    }
+                    match result {
+                        Ok(problem) => {
+                            let json = serde_json::to_string(&problem).unwrap_or_default();
+                            let _ = tx.send(format!("__PRACTICE_PROBLEM__:{}", json));
+                        }
+                        Err(e) => { let _ = tx.send(format!("__ERROR__:Practice problem failed: {}", e)); }
+                    }
+                    let _ = tx.send("__DONE__".to_string());
+                });
+            }
+            Some(problem) => {
+                self.activity = "Grading your attempt".to_string();
+                self.messages.push(Message::new("user", text.clone(), false));
+                crate::runtime::spawn_background(&self.runtime, move || {
+                    let result = practice::grade(&backend, &model, &problem, &text);
+                    if cancel.load(Ordering::Relaxed) {
+                        return; // Stopped meanwhile
+                    }
+                    match result {
+                        Ok(feedback) => { let _ = tx.send(feedback); }
+                        Err(e) => { let _ = tx.send(format!("__ERROR__:Grading failed: {}", e)); }
+                    }
+                    let _ = tx.send("__DONE__".to_string());
+                });
+            }
+        }
+    }
+
+    pub(super) fn accept_practice_problem(&mut self, json: &str) {
+        match serde_json::from_str::<PracticeProblem>(json) {
+            Ok(problem) => {
+                self.messages.push(Message::new("assistant", problem.as_message(), false));
+                self.practice_problem = Some(problem);
+            }
+            Err(e) => self.report_error(&format!("Bad practice problem payload: {}", e)),
+        }
+    }
+
+    pub(super) fn practice_hint(&self) -> Option<&'static str> {
+        if !self.practice_mode {
+            return None;
+        }
+        Some(if self.practice_problem.is_some() { "Your attempt..." } else { "Practice topic..." })
+    }
+}
@@ -0,0 +1,99 @@
+// Sidebar header: switch between user profiles or create a new one
+
+use super::{AppState, ShipApp};
+use crate::config::AppConfig;
+use crate::profile::{self, Profile};
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn profile_switcher(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("👤");
+            let mut chosen = None;
+            egui::ComboBox::from_id_source("profile_switcher")
+                .selected_text(&self.profile.name)
+                .show_ui(ui, |ui| {
+                    for name in profile::list_profiles() {
+                        if ui.selectable_label(name == self.profile.name, &name).clicked() {
+                            chosen = Some(name);
+                        }
+                    }
+                });
+
+            // 1. New profile
+            ui.add(egui::TextEdit::singleline(&mut self.new_profile_name).hint_text("new profile").desired_width(90.0));
+            let valid = profile::valid_name(&self.new_profile_name);
+            if ui.add_enabled(valid, egui::Button::new("➕")).clicked() {
+                chosen = Some(self.new_profile_name.trim().to_string());
+                self.new_profile_name.clear();
+            }
+
+            if let Some(name) = chosen {
+                if name != self.profile.name {
+                    self.switch_profile(Profile::new(&name));
+                    self.apply_appearance(ui.ctx());
+                }
+            }
+        });
+    }
+
+    fn switch_profile(&mut self, next: Profile) {
+        if self.state != AppState::Idle {
+            self.report_error("Wait for the current task to finish before switching profiles");
+            return;
+        }
+
+        // 1. Leave the current profile's data on disk (summarized, since we are switching away)
+        match self.flush_session() {
+            Ok(()) if !self.messages.is_empty() => {
+                self.summarize_in_background(self.current_file.clone());
+            }
+            Ok(()) => {}
+            Err(e) => self.report_error(&format!("Failed to save session: {}", e)),
+        }
+        self.save_config();
+
+        // 2. Load everything that belongs to the next one
+        if let Err(e) = next.create_dirs() {
+            self.report_error(&format!("Failed to create profile '{}': {}", next.name, e));
+            return;
+        }
+        next.mark_active();
+        self.config = AppConfig::load(&next.config_path());
+        self.vram_poll_ms.store(self.config.vram_poll_ms, std::sync::atomic::Ordering::Relaxed);
+        crate::runtime::set_worker_config(&self.config.workers);
+        self.profile = next;
+        self.reopen_store();
+
+        // 3. Fresh conversation state
+        self.messages.clear();
+        self.current_file = crate::session::LATEST_FILE.to_string();
+        self.translation_checks.clear();
+        self.reactions.clear();
+        self.analytics.clear();
+        self.gallery = None;
+        self.reload_figures();
+        self.open_event_sink();
+        self.refresh_models();
+        self.pinned_document = None;
+        self.session_tags.clear();
+        self.timer_events.clear();
+        self.retry_trace.clear();
+        self.session_zone = None;
+        self.overrides = Default::default();
+        self.finetune_job.output_dir = crate::finetune::FinetuneJob::default_output_dir(&self.profile.root());
+        self.pomodoro_started = None;
+        self.search_index = None;
+        self.search_hits.clear();
+        self.input_text.clear();
+        self.research_results.clear();
+        self.research_sources.clear();
+        self.current_image_base64 = None;
+        self.current_image_path = None;
+        self.refresh_session_lists();
+        self.load_interrupted_jobs();
+        self.load_unsaved_chat();
+        self.report_config_error();
+        self.log_event(&format!("Switched to profile '{}'", self.profile.name));
+    }
+}
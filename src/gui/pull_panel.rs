@@ -0,0 +1,57 @@
+// Window: pull any model tag from the Ollama library, with the download progress that
+// /api/pull streams back
+
+use super::ShipApp;
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn pull_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_pull;
+        let mut pull = None;
+        egui::Window::new("Pull model ⬇")
+            .open(&mut open)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                // 1. Which tag
+                ui.horizontal(|ui| {
+                    let field = ui.add_enabled(
+                        !self.pulling,
+                        egui::TextEdit::singleline(&mut self.pull_tag).hint_text("qwen2.5:14b").desired_width(220.0),
+                    );
+                    let entered = field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let tag = self.pull_tag.trim();
+                    let clicked = ui.add_enabled(!self.pulling && !tag.is_empty(), egui::Button::new("Pull")).clicked();
+                    if (clicked || entered) && !self.pulling && !tag.is_empty() {
+                        pull = Some(tag.to_string());
+                    }
+                });
+                ui.small("Name or name:tag from ollama.com/library; a bare name pulls :latest.");
+                if self.models.iter().any(|m| m == self.pull_tag.trim()) {
+                    ui.small("Already installed; pulling again fetches any update.");
+                }
+
+                // 2. Progress of the current layer
+                if self.pulling || !self.pull_status.is_empty() {
+                    ui.separator();
+                    match self.pull_progress.filter(|_| self.pulling) {
+                        Some(fraction) => {
+                            ui.add(egui::ProgressBar::new(fraction).show_percentage());
+                        }
+                        None if self.pulling => {
+                            ui.horizontal(|ui| {
+                                ui.spinner();
+                                ui.label("Working...");
+                            });
+                        }
+                        None => {}
+                    }
+                    ui.small(&self.pull_status);
+                }
+            });
+        self.show_pull = open;
+
+        if let Some(tag) = pull {
+            self.start_pull(&tag);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+// Low-VRAM mode: warn when the selected model won't fit and offer smaller quantizations
+
+use super::ShipApp;
+use crate::jobs::JobKind;
+use crate::quantize;
+use eframe::egui;
+
+impl ShipApp {
+    // Re-checks whenever the selection changes; the answer arrives as __QUANT_SUGGEST__
+    pub(super) fn check_model_fit(&mut self) {
+        if self.fit_checked_model == self.selected_model || self.gpu.total_mb == 0 {
+            return;
+        }
+        self.fit_checked_model = self.selected_model.clone();
+        self.quant_suggestions.clear();
+
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        let vram_total = self.gpu.total_mb;
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            let Ok(installed) = backend.list_local_models().await else { return };
+            if let Some(info) = installed.iter().find(|m| m.name == model) {
+                let suggestions = quantize::suggest(info, vram_total);
+                let _ = tx.send(format!("__QUANT_SUGGEST__:{}", serde_json::to_string(&suggestions).unwrap_or_default()));
+            }
+        });
+    }
+
+    pub(super) fn accept_quant_suggestions(&mut self, json: &str) {
+        self.quant_suggestions = serde_json::from_str(json).unwrap_or_default();
+    }
+
+    // Sidebar warning under the model selector
+    pub(super) fn quant_panel(&mut self, ui: &mut egui::Ui) {
+        let Some(first) = self.quant_suggestions.first() else { return };
+        ui.colored_label(
+            ui.visuals().warn_fg_color,
+            format!("⚠ {} is {}, more than fits in {} MB VRAM", self.selected_model, quantize::gigabytes(first.current_bytes), self.gpu.total_mb),
+        );
+        egui::CollapsingHeader::new("Smaller quantizations").id_source("quant_suggestions").show(ui, |ui| {
+            for suggestion in &self.quant_suggestions {
+                ui.horizontal(|ui| {
+                    ui.label(&suggestion.quant).on_hover_text(&suggestion.tag);
+                    ui.small(suggestion.size_label());
+                    if ui.add_enabled(!self.pulling, egui::Button::new("Pull").small()).clicked() {
+                        self.pending_pull = Some(suggestion.clone());
+                    }
+                });
+            }
+            if !self.pull_status.is_empty() {
+                ui.small(&self.pull_status);
+            }
+        });
+    }
+
+    // Confirmation dialog showing the size difference before anything is downloaded
+    pub(super) fn pull_confirm_window(&mut self, ctx: &egui::Context) {
+        let Some(suggestion) = self.pending_pull.clone() else { return };
+        let mut decided = false;
+        egui::Window::new("Pull smaller model?")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!("Download {}?", suggestion.tag));
+                ui.label(format!(
+                    "Current: {}   New: {}",
+                    quantize::gigabytes(suggestion.current_bytes),
+                    suggestion.size_label()
+                ));
+                ui.small("Sizes are estimates; the tag may not exist in the library.");
+                ui.horizontal(|ui| {
+                    if ui.button("Pull").clicked() {
+                        self.start_pull(&suggestion.tag);
+                        decided = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        decided = true;
+                    }
+                });
+            });
+        if decided {
+            self.pending_pull = None;
+        }
+    }
+
+    pub(super) fn start_pull(&mut self, tag: &str) {
+        self.pulling = true;
+        self.pull_progress = None;
+        self.pull_status = format!("Pulling {}...", tag);
+        self.log_event(&self.pull_status.clone());
+        self.begin_job(JobKind::Pull { tag: tag.to_string() });
+
+        let backend = self.config.backend.clone();
+        let tag = tag.to_string();
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            match backend.pull_model(&tag, |status, fraction| {
+                let fraction = fraction.map_or("-".to_string(), |f| f.to_string());
+                let _ = tx.send(format!("__PULL__:{}:{}", fraction, status));
+            }).await {
+                Ok(()) => { let _ = tx.send(format!("__PULL_DONE__:{}", tag)); }
+                Err(e) => { let _ = tx.send(format!("__PULL_FAILED__:{}: {}", tag, e)); }
+            }
+        });
+    }
+}
@@ -0,0 +1,51 @@
+// Routes a sent prompt to the document scan or straight to the model, per the RAG mode.
+// Every automatic decision is written to the log so it can be checked afterwards.
+
+use super::{AppState, ShipApp};
+use crate::rag_trigger::{self, Decision, RagMode};
+
+impl ShipApp {
+    pub(super) fn route_prompt(&mut self, prompt: String) {
+        if let Some(retrieve) = self.rag_next.take() {
+            return self.apply_rag_decision(Decision::new(retrieve, "manual override"));
+        }
+        match self.overrides.rag.unwrap_or(self.config.rag.mode) {
+            RagMode::Off => self.trigger_ollama_generation(prompt),
+            RagMode::Always => self.scan_research(prompt),
+            RagMode::Auto => match rag_trigger::heuristic(&prompt, &self.config.rag) {
+                Some(decision) => self.apply_rag_decision(decision),
+                None if self.config.rag.classifier => {
+                    // Classifier call is blocking; the verdict comes back as __RAG_DECISION__
+                    self.state = AppState::Scanning;
+                    self.activity = "Deciding whether to search the library".to_string();
+                    let backend = self.config.backend.clone();
+                    let model = self.selected_model.clone();
+                    let tx = self.tx.clone();
+                    crate::runtime::spawn_background(&self.runtime, move || {
+                        let decision = rag_trigger::classify(&backend, &model, &prompt);
+                        let _ = tx.send(format!("__RAG_DECISION__:{}", serde_json::to_string(&decision).unwrap_or_default()));
+                    });
+                }
+                None => self.apply_rag_decision(Decision::new(false, "no heuristic matched")),
+            },
+        }
+    }
+
+    // Acts on a decision for the latest user message
+    pub(super) fn apply_rag_decision(&mut self, decision: Decision) {
+        let verdict = if decision.retrieve { "retrieve" } else { "skip retrieval" };
+        self.log_event(&format!("RAG decision: {} ({})", verdict, decision.reason));
+
+        let prompt = match self.messages.last() {
+            Some(last) if last.role == "user" => last.content.clone(),
+            _ => return,
+        };
+        let retrieve = decision.retrieve;
+        self.last_rag_decision = Some(decision);
+        if retrieve {
+            self.scan_research(prompt);
+        } else {
+            self.trigger_ollama_generation(prompt);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// 👍/👎, quality labels and self-reported confidence on replies, and the analytics window
+// that aggregates them per model
+
+use super::ShipApp;
+use crate::confidence::{self, UNSURE_BELOW};
+use crate::session::{Rating, Reaction};
+use eframe::egui;
+
+impl ShipApp {
+    // Returns the edited reaction; applied after the message loop releases `self.messages`
+    pub(super) fn reaction_buttons(&self, ui: &mut egui::Ui, i: usize) -> Option<Reaction> {
+        // The model that wrote the reply, not whichever one is selected now
+        let current = self.reactions.get(&i).cloned().unwrap_or_else(|| Reaction {
+            message_index: i,
+            model: self.messages.get(i).map(|m| m.model.clone()).unwrap_or_default(),
+            ..Default::default()
+        });
+        let mut edited = current.clone();
+
+        // 1. Rating; clicking the active one clears it
+        for (rating, icon) in [(Rating::Up, "👍"), (Rating::Down, "👎")] {
+            let active = current.rating == Some(rating);
+            if ui.selectable_label(active, icon).clicked() {
+                edited.rating = if active { None } else { Some(rating) };
+            }
+        }
+
+        // 2. Labels: presets from the config plus anything typed in
+        let title = if current.labels.is_empty() { "🏷".to_string() } else { format!("🏷 {}", current.labels.len()) };
+        ui.menu_button(title, |ui| {
+            let mut labels = self.config.reaction_labels.clone();
+            labels.extend(current.labels.iter().filter(|l| !self.config.reaction_labels.contains(l)).cloned());
+            for label in labels {
+                let mut on = current.labels.contains(&label);
+                if ui.checkbox(&mut on, &label).changed() {
+                    edited.labels.retain(|l| *l != label);
+                    if on {
+                        edited.labels.push(label);
+                    }
+                }
+            }
+            ui.separator();
+            let draft_id = egui::Id::new(("reaction_label", i));
+            let mut draft = ui.data(|d| d.get_temp::<String>(draft_id)).unwrap_or_default();
+            let response = ui.add(egui::TextEdit::singleline(&mut draft).hint_text("Other label").desired_width(140.0));
+            if response.lost_focus() && ui.input(|inp| inp.key_pressed(egui::Key::Enter)) && !draft.trim().is_empty() {
+                if !edited.labels.iter().any(|l| l == draft.trim()) {
+                    edited.labels.push(draft.trim().to_string());
+                }
+                draft.clear();
+            }
+            ui.data_mut(|d| d.insert_temp(draft_id, draft));
+        });
+
+        (edited.rating != current.rating || edited.labels != current.labels).then_some(edited)
+    }
+
+    pub(super) fn set_reaction(&mut self, reaction: Reaction) {
+        if reaction.is_empty() {
+            self.reactions.remove(&reaction.message_index);
+        } else {
+            self.reactions.insert(reaction.message_index, reaction);
+        }
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save reaction: {}", e));
+        }
+    }
+
+    // When a reply is done: moves its "Confidence: N%" line onto the reaction
+    pub(super) fn record_confidence(&mut self) {
+        if !self.config.ask_confidence {
+            return;
+        }
+        let index = self.messages.len().saturating_sub(1);
+        let Some(msg) = self.messages.last_mut().filter(|m| m.role == "assistant") else { return };
+        let Some((percent, content)) = confidence::extract(&msg.content) else { return };
+        msg.content = content;
+        let model = msg.model.clone();
+        let reaction = self.reactions.entry(index).or_insert_with(|| Reaction { message_index: index, model, ..Default::default() });
+        reaction.confidence = Some(percent);
+    }
+
+    pub(super) fn confidence_badge(&self, ui: &mut egui::Ui, i: usize) {
+        let Some(percent) = self.reactions.get(&i).and_then(|r| r.confidence) else { return };
+        let color = match percent {
+            p if p < UNSURE_BELOW => ui.visuals().error_fg_color,
+            p if p < 80 => ui.visuals().warn_fg_color,
+            _ => egui::Color32::from_rgb(90, 170, 90),
+        };
+        ui.label(egui::RichText::new(format!("{}%", percent)).small().color(color))
+            .on_hover_text("Confidence the model reported for this reply");
+    }
+
+    // In message order, for the session file
+    pub(super) fn session_reactions(&self) -> Vec<Reaction> {
+        let mut reactions: Vec<Reaction> = self.reactions.values().cloned().collect();
+        reactions.sort_by_key(|r| r.message_index);
+        reactions
+    }
+
+    pub(super) fn open_analytics(&mut self) {
+        self.show_analytics = true;
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+        self.analytics = crate::analytics::aggregate(&self.store);
+    }
+
+    pub(super) fn analytics_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_analytics;
+        let mut refresh = false;
+        let mut save = false;
+        egui::Window::new("Analytics 📊")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    refresh = ui.button("🔄 Refresh").clicked();
+                    ui.small("Ratings and labels from every saved session in this profile");
+                });
+                if ui.checkbox(&mut self.config.ask_confidence, "Ask models for their confidence")
+                    .on_hover_text("Replies end with a self-reported confidence, shown as a badge and averaged here")
+                    .changed()
+                {
+                    save = true;
+                }
+                ui.separator();
+                if self.analytics.is_empty() {
+                    ui.weak("No rated replies yet. Use 👍/👎 and 🏷 next to a reply.");
+                    return;
+                }
+                egui::Grid::new("analytics_grid").striped(true).num_columns(6).show(ui, |ui| {
+                    ui.strong("Model");
+                    ui.strong("👍");
+                    ui.strong("👎");
+                    ui.strong("Approval");
+                    ui.strong("Confidence");
+                    ui.strong("Labels");
+                    ui.end_row();
+                    for stats in &self.analytics {
+                        ui.label(&stats.model).on_hover_text(format!("{} sessions", stats.sessions));
+                        ui.label(stats.up.to_string());
+                        ui.label(stats.down.to_string());
+                        match stats.approval() {
+                            Some(a) => ui.add(egui::ProgressBar::new(a).desired_width(80.0).text(format!("{:.0}%", a * 100.0))),
+                            None => ui.weak("-"),
+                        };
+                        match stats.mean_confidence() {
+                            Some(mean) => ui.label(format!("{:.0}%", mean))
+                                .on_hover_text(format!("{} replies reported, {} below {}%", stats.confidence_reports, stats.unsure, UNSURE_BELOW)),
+                            None => ui.weak("-"),
+                        };
+                        let mut labels: Vec<_> = stats.labels.iter().collect();
+                        labels.sort_by(|a, b| b.1.cmp(a.1));
+                        let text = labels.iter().map(|(l, n)| format!("{} ×{}", l, n)).collect::<Vec<_>>().join(", ");
+                        ui.label(text);
+                        ui.end_row();
+                    }
+                });
+            });
+        if save {
+            self.save_config();
+        }
+        if refresh {
+            self.open_analytics();
+        }
+        self.show_analytics = open;
+    }
+}
@@ -0,0 +1,137 @@
+// Window: replay a saved conversation at its original pace (or faster) with a scrubber
+
+use super::ShipApp;
+use crate::replay::{self, Timeline, SPEEDS};
+use crate::session::Message;
+use crate::session_store::SessionStore;
+use eframe::egui;
+use std::time::Instant;
+
+const IDLE_CAP_SECS: f64 = 30.0;
+
+pub(super) struct ReplayState {
+    name: String,
+    messages: Vec<Message>,
+    timeline: Timeline,
+    position: f64, // Seconds into the timeline
+    playing: bool,
+    speed: f64,
+    compress_idle: bool,
+    last_tick: Instant,
+}
+
+impl ReplayState {
+    fn load(store: &SessionStore, name: String) -> Result<Self, String> {
+        let messages = store.load(&name)?.messages;
+        Ok(Self {
+            timeline: Timeline::build(&messages, Some(IDLE_CAP_SECS)),
+            name,
+            messages,
+            position: 0.0,
+            playing: false,
+            speed: 1.0,
+            compress_idle: true,
+            last_tick: Instant::now(),
+        })
+    }
+
+    fn rebuild_timeline(&mut self) {
+        let fraction = if self.timeline.duration > 0.0 { self.position / self.timeline.duration } else { 0.0 };
+        self.timeline = Timeline::build(&self.messages, self.compress_idle.then_some(IDLE_CAP_SECS));
+        self.position = fraction * self.timeline.duration;
+    }
+
+    // Advances the clock by wall time since the last frame
+    fn tick(&mut self) {
+        let now = Instant::now();
+        if self.playing {
+            self.position += now.duration_since(self.last_tick).as_secs_f64() * self.speed;
+            if self.position >= self.timeline.duration {
+                self.position = self.timeline.duration;
+                self.playing = false;
+            }
+        }
+        self.last_tick = now;
+    }
+}
+
+impl ShipApp {
+    pub(super) fn replay_window(&mut self, ctx: &egui::Context) {
+        if !self.show_replay {
+            return;
+        }
+        let mut open = true;
+        let mut pick = None;
+        egui::Window::new("Replay ⏯")
+            .open(&mut open)
+            .default_size([520.0, 480.0])
+            .show(ctx, |ui| {
+                // 1. Session picker
+                let current = self.replay.as_ref().map(|r| r.name.clone());
+                egui::ComboBox::from_id_source("replay_session")
+                    .selected_text(current.unwrap_or_else(|| "Pick a session".to_string()))
+                    .show_ui(ui, |ui| {
+                        for entry in &self.session_list {
+                            if ui.selectable_label(false, &entry.name).clicked() {
+                                pick = Some(entry.name.clone());
+                            }
+                        }
+                    });
+
+                let Some(replay) = self.replay.as_mut() else { return };
+                replay.tick();
+
+                // 2. Transport controls + scrubber
+                ui.horizontal(|ui| {
+                    if ui.button(if replay.playing { "⏸" } else { "▶" }).clicked() {
+                        if !replay.playing && replay.position >= replay.timeline.duration {
+                            replay.position = 0.0;
+                        }
+                        replay.playing = !replay.playing;
+                    }
+                    if ui.button("⏮").clicked() {
+                        replay.position = 0.0;
+                    }
+                    for speed in SPEEDS {
+                        ui.selectable_value(&mut replay.speed, speed, format!("{}×", speed));
+                    }
+                    if ui.checkbox(&mut replay.compress_idle, "Skip idle").on_hover_text("Cap pauses at 30 s").changed() {
+                        replay.rebuild_timeline();
+                    }
+                });
+                let duration = replay.timeline.duration.max(0.001);
+                ui.horizontal(|ui| {
+                    ui.add(egui::Slider::new(&mut replay.position, 0.0..=duration).show_value(false));
+                    ui.monospace(format!("{} / {}", replay::clock_label(replay.position), replay::clock_label(replay.timeline.duration)));
+                });
+
+                // 3. Conversation as of the cursor
+                ui.separator();
+                let visible = replay.timeline.visible_at(replay.position);
+                egui::ScrollArea::vertical().id_source("replay_messages").stick_to_bottom(true).show(ui, |ui| {
+                    for (msg, offset) in replay.messages.iter().zip(&replay.timeline.offsets).take(visible) {
+                        ui.horizontal(|ui| {
+                            ui.monospace(replay::clock_label(*offset));
+                            ui.label(egui::RichText::new(&msg.role).strong());
+                        });
+                        ui.label(&msg.content);
+                        ui.separator();
+                    }
+                });
+                if replay.playing {
+                    ui.ctx().request_repaint();
+                }
+            });
+
+        if let Some(name) = pick {
+            match ReplayState::load(&self.store, name) {
+                Ok(state) => self.replay = Some(state),
+                Err(e) => self.report_error(&format!("Replay: {}", e)),
+            }
+        }
+        if !open {
+            self.show_replay = false;
+            self.replay = None;
+        }
+    }
+}
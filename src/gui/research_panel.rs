@@ -0,0 +1,129 @@
+// Sidebar section: Research Station settings (RAG mode and auto-trigger, context placement, library folder, filters)
+
+use super::ShipApp;
+use crate::context::ContextPlacement;
+use crate::rag_trigger::RagMode;
+use crate::research::TimeRange;
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn research_panel(&mut self, ui: &mut egui::Ui) {
+        ui.label("Research Station 🔬");
+        ui.horizontal(|ui| {
+            ui.label("RAG:");
+            for mode in RagMode::ALL {
+                if ui.selectable_value(&mut self.config.rag.mode, mode, mode.label()).changed() {
+                    self.save_config();
+                }
+            }
+        });
+        if self.config.rag.mode == RagMode::Auto {
+            self.rag_auto_settings(ui);
+        }
+        if self.config.rag.mode != RagMode::Off || self.rag_next == Some(true) {
+            ui.horizontal(|ui| {
+                ui.label("Documents from:");
+                egui::ComboBox::from_id_source("time_range")
+                    .selected_text(self.time_range.label())
+                    .show_ui(ui, |ui| {
+                        for range in TimeRange::ALL {
+                            ui.selectable_value(&mut self.time_range, range, range.label());
+                        }
+                    });
+            });
+            ui.horizontal(|ui| {
+                ui.label("Only from:");
+                ui.add(egui::TextEdit::singleline(&mut self.research_section).hint_text("Chapter 6").desired_width(120.0))
+                    .on_hover_text("Chapter or section from the PDF bookmarks; PDFs without bookmarks are skipped while this is set");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Inject as:");
+                // Shows what this chat uses; picking one sets the default and drops the chat's own
+                let mut placement = self.overrides.context_placement.unwrap_or(self.config.context_placement);
+                let mut changed = false;
+                egui::ComboBox::from_id_source("context_placement")
+                    .selected_text(placement.label())
+                    .show_ui(ui, |ui| {
+                        for option in ContextPlacement::ALL {
+                            changed |= ui.selectable_value(&mut placement, option, option.label()).changed();
+                        }
+                    });
+                if changed {
+                    self.config.context_placement = placement;
+                    self.overrides.context_placement = None;
+                    self.save_config();
+                }
+            });
+        }
+        ui.text_edit_singleline(&mut self.research_dir);
+        ui.small("Point this to your PDFs folder");
+        self.figures_controls(ui);
+        self.research_folders_section(ui);
+
+        // Patterns are remembered per research directory
+        egui::CollapsingHeader::new("Include / exclude patterns").id_source("research_filters").show(ui, |ui| {
+            // Edited on a copy: only a folder that actually gets patterns is added to the config
+            let mut filters = self.config.research_filters.get(&self.research_dir).cloned().unwrap_or_default();
+            let mut changed = false;
+
+            ui.small("One glob per line, relative to the folder");
+            ui.label("Include (empty = all PDFs):");
+            changed |= Self::pattern_editor(ui, &mut filters.include, "**/Datasheets/**/*.pdf");
+            ui.label("Exclude:");
+            changed |= Self::pattern_editor(ui, &mut filters.exclude, "**/Archive/**");
+
+            if changed {
+                if filters.include.iter().chain(&filters.exclude).all(|p| p.trim().is_empty()) {
+                    self.config.research_filters.remove(&self.research_dir);
+                } else {
+                    self.config.research_filters.insert(self.research_dir.clone(), filters);
+                }
+                self.save_config();
+            }
+        });
+    }
+
+    fn pattern_editor(ui: &mut egui::Ui, patterns: &mut Vec<String>, hint: &str) -> bool {
+        let mut text = patterns.join("\n");
+        let changed = ui.add(
+            egui::TextEdit::multiline(&mut text)
+                .desired_rows(2)
+                .hint_text(hint)
+                .code_editor(),
+        ).changed();
+
+        if changed {
+            // split (not lines) keeps the trailing empty line while typing
+            *patterns = text.split('\n').map(String::from).collect();
+        }
+        changed
+    }
+
+    // Auto mode: one-shot override, last verdict, heuristic knobs
+    fn rag_auto_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Next message:");
+            ui.selectable_value(&mut self.rag_next, None, "Auto");
+            ui.selectable_value(&mut self.rag_next, Some(true), "Retrieve");
+            ui.selectable_value(&mut self.rag_next, Some(false), "Skip");
+        });
+        if let Some(decision) = &self.last_rag_decision {
+            let verdict = if decision.retrieve { "retrieved" } else { "skipped" };
+            ui.small(format!("Last: {} ({})", verdict, decision.reason)).on_hover_text("Every decision is in the log");
+        }
+        egui::CollapsingHeader::new("Auto-trigger rules").id_source("rag_auto_rules").show(ui, |ui| {
+            let rag = &mut self.config.rag;
+            let mut changed = ui.checkbox(&mut rag.question_words, "Questions trigger retrieval").changed();
+            changed |= ui.checkbox(&mut rag.classifier, "Ask the model when unsure").changed();
+            ui.label("Always retrieve when the prompt mentions:");
+            let mut text = rag.citation_keywords.join(", ");
+            if ui.text_edit_singleline(&mut text).changed() {
+                rag.citation_keywords = text.split(',').map(|k| k.trim_start().to_string()).collect();
+                changed = true;
+            }
+            if changed {
+                self.save_config();
+            }
+        });
+    }
+}
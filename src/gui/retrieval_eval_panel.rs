@@ -0,0 +1,164 @@
+// Developer window: edit the labeled query set, run it against the current research
+// folder and compare recall@k / MRR with the previous run
+
+use super::ShipApp;
+use crate::retrieval_eval::{self, EvalCase, EvalReport, EvalSet, EVAL_FILE};
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn open_retrieval_eval(&mut self) {
+        match EvalSet::load(&self.profile.root().join(EVAL_FILE)) {
+            Ok(set) => {
+                self.eval_set = set;
+                self.show_retrieval_eval = true;
+            }
+            Err(e) => self.report_error(&format!("Could not load the eval set: {}", e)),
+        }
+    }
+
+    fn save_eval_set(&mut self) {
+        if let Err(e) = self.eval_set.save(&self.profile.root().join(EVAL_FILE)) {
+            self.report_error(&format!("Could not save the eval set: {}", e));
+        }
+    }
+
+    fn start_retrieval_eval(&mut self) {
+        if self.eval_busy {
+            return;
+        }
+        self.save_eval_set();
+        self.eval_busy = true;
+        self.eval_status = "Scanning...".to_string();
+
+        let dir = self.research_dir.clone();
+        let filters = self.config.research_filters.get(&dir).cloned().unwrap_or_default();
+        let set = self.eval_set.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let progress_tx = tx.clone();
+            let report = retrieval_eval::run(&dir, &filters, &set, |i, n| {
+                let _ = progress_tx.send(format!("__EVAL_PROGRESS__:Query {}/{}", i, n));
+            });
+            let _ = tx.send(format!("__EVAL_DONE__:{}", serde_json::to_string(&report).unwrap_or_default()));
+        });
+    }
+
+    pub(super) fn finish_retrieval_eval(&mut self, json: &str) {
+        self.eval_busy = false;
+        let report: EvalReport = match serde_json::from_str(json) {
+            Ok(report) => report,
+            Err(e) => {
+                self.eval_status = format!("❌ Bad report: {}", e);
+                return;
+            }
+        };
+        self.eval_status = format!("recall@{} {:.3} · MRR {:.3}", report.k, report.recall_at_k, report.mrr);
+        self.log_event(&format!("Retrieval eval: {}", self.eval_status));
+        // The run before this one becomes the baseline shown next to it
+        self.eval_previous = self.eval_set.last.replace(report);
+        self.save_eval_set();
+    }
+
+    fn metric_delta(ui: &mut egui::Ui, now: f64, before: Option<f64>) {
+        let Some(before) = before else { return };
+        let delta = now - before;
+        if delta.abs() < 0.0005 {
+            ui.weak("±0");
+        } else if delta > 0.0 {
+            ui.colored_label(egui::Color32::from_rgb(90, 170, 90), format!("+{:.3}", delta));
+        } else {
+            ui.colored_label(egui::Color32::from_rgb(220, 90, 90), format!("{:.3}", delta));
+        }
+    }
+
+    pub(super) fn retrieval_eval_window(&mut self, ctx: &egui::Context) {
+        if !self.show_retrieval_eval {
+            return;
+        }
+        let mut open = self.show_retrieval_eval;
+        let mut run = false;
+        let mut save = false;
+        egui::Window::new("Retrieval eval 🎯")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.small(format!("Runs each query through the document scan of {} and checks where the expected files rank.", self.research_dir));
+
+                // 1. Labeled queries
+                let mut remove = None;
+                egui::ScrollArea::vertical().id_source("eval_cases").max_height(220.0).show(ui, |ui| {
+                    egui::Grid::new("eval_cases_grid").num_columns(4).striped(true).show(ui, |ui| {
+                        ui.strong("Query");
+                        ui.strong("Expected files (comma-separated)");
+                        ui.strong("Section");
+                        ui.end_row();
+                        for (i, case) in self.eval_set.cases.iter_mut().enumerate() {
+                            save |= ui.add(egui::TextEdit::singleline(&mut case.query).desired_width(160.0)).lost_focus();
+                            let mut expected = case.expected.join(", ");
+                            if ui.add(egui::TextEdit::singleline(&mut expected).hint_text("Razavi.pdf").desired_width(200.0)).changed() {
+                                case.expected = expected.split(',').map(|e| e.trim_start().to_string()).collect();
+                            }
+                            save |= ui.add(egui::TextEdit::singleline(&mut case.section).desired_width(80.0)).lost_focus();
+                            if ui.small_button("✖").clicked() {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+                if let Some(i) = remove {
+                    self.eval_set.cases.remove(i);
+                    save = true;
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("➕ Query").clicked() {
+                        self.eval_set.cases.push(EvalCase::default());
+                    }
+                    ui.add(egui::DragValue::new(&mut self.eval_set.k).clamp_range(1..=50).prefix("k = "));
+                    let label = if self.eval_busy { "Running..." } else { "▶ Run" };
+                    run = ui.add_enabled(!self.eval_busy && !self.eval_set.cases.is_empty(), egui::Button::new(label)).clicked();
+                });
+
+                // 2. Latest run against the one before it
+                if !self.eval_status.is_empty() {
+                    ui.separator();
+                    ui.label(&self.eval_status);
+                }
+                let Some(report) = &self.eval_set.last else { return };
+                let before = self.eval_previous.as_ref();
+                ui.horizontal(|ui| {
+                    ui.strong(format!("recall@{}: {:.3}", report.k, report.recall_at_k));
+                    Self::metric_delta(ui, report.recall_at_k, before.filter(|b| b.k == report.k).map(|b| b.recall_at_k));
+                    ui.strong(format!("MRR: {:.3}", report.mrr));
+                    Self::metric_delta(ui, report.mrr, before.map(|b| b.mrr));
+                });
+                if before.is_none() {
+                    ui.weak("Run again after a change to see the difference.");
+                }
+                egui::ScrollArea::vertical().id_source("eval_results").max_height(200.0).show(ui, |ui| {
+                    egui::Grid::new("eval_results_grid").num_columns(4).striped(true).show(ui, |ui| {
+                        ui.strong("Query");
+                        ui.strong("Recall");
+                        ui.strong("RR");
+                        ui.strong("Ranks of expected");
+                        ui.end_row();
+                        for case in &report.cases {
+                            ui.label(&case.query).on_hover_text(format!("Top {}: {}", report.k, case.retrieved.join(", ")));
+                            ui.label(format!("{:.2}", case.recall));
+                            ui.label(format!("{:.2}", case.reciprocal_rank));
+                            let ranks: Vec<String> = case.ranks.iter().map(|r| r.map_or("—".to_string(), |r| r.to_string())).collect();
+                            ui.label(ranks.join(", "));
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        self.show_retrieval_eval = open;
+
+        if run {
+            self.start_retrieval_eval();
+        } else if save || !open {
+            self.save_eval_set();
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// Refusal retry: when a finished reply is a boilerplate refusal or a non-answer, it is
+// replaced by one more attempt, and the sidebar trace shows what was caught and retried
+
+use super::ShipApp;
+use crate::refusal::{self, RetryStrategy, RetryTrace};
+use eframe::egui;
+
+impl ShipApp {
+    // On __DONE__, before the reply is saved; true when a retry has been started
+    pub(super) fn retry_refusal(&mut self) -> bool {
+        let Some(user_index) = self.messages.iter().rposition(|m| m.role == "user") else {
+            return false;
+        };
+        // No assistant message after the prompt means nothing was streamed
+        let reply = self.messages[user_index + 1..].iter().find(|m| m.role == "assistant").map(|m| m.content.clone()).unwrap_or_default();
+        let verdict = refusal::detect(&reply);
+
+        // 1. The retry itself finished: record how it went, never retry twice
+        if let Some(trace) = self.retry_trace.last_mut().filter(|t| t.outcome.is_none() && t.message_index == user_index) {
+            let outcome = match &verdict {
+                Some(reason) => format!("still a {}", reason),
+                None => "answered".to_string(),
+            };
+            let line = format!("Refusal retry with {}: {}", trace.retry_model, outcome);
+            trace.outcome = Some(outcome);
+            self.log_event(&line);
+            return false;
+        }
+
+        // 2. A fresh refusal, when retrying is on
+        let Some(reason) = verdict else { return false };
+        let config = self.config.refusal_retry.clone();
+        // The image is dropped once sent, so a retry would ask a different question
+        if !config.enabled || self.messages[user_index].has_image {
+            return false;
+        }
+        let prompt = self.messages[user_index].content.clone();
+        // A hosted chat has no local alternate, so it is always clarified
+        let (model, retry_prompt) = match config.strategy {
+            RetryStrategy::AlternateModel if !config.alternate_model.is_empty() && self.overrides.hosted.is_none() => {
+                (config.alternate_model.clone(), prompt.clone())
+            }
+            _ => (self.chat_model(), refusal::clarified_prompt(&prompt)),
+        };
+
+        // 3. The refused reply makes way; its excerpts go with the retry
+        if self.messages.len() > user_index + 1 {
+            let refused = self.messages.remove(user_index + 1);
+            self.research_results = refused.sources.iter().map(|s| s.block()).collect();
+            self.research_sources = refused.sources;
+        }
+        self.log_event(&format!("Caught a {} from {}; retrying with {}", reason, self.chat_model(), model));
+        self.retry_trace.push(RetryTrace {
+            at: chrono::Utc::now().timestamp_millis(),
+            message_index: user_index,
+            reason,
+            model: self.chat_model(),
+            refused: reply,
+            retry_model: model.clone(),
+            retry_prompt: retry_prompt.clone(),
+            outcome: None,
+        });
+        self.pending_retry = Some((model, retry_prompt));
+        self.trigger_ollama_generation(prompt);
+        true
+    }
+
+    pub(super) fn retry_trace_panel(&mut self, ui: &mut egui::Ui) {
+        let title = match self.retry_trace.len() {
+            0 => "Refusal retry 🔁".to_string(),
+            n => format!("Refusal retry 🔁 ({})", n),
+        };
+        egui::CollapsingHeader::new(title).id_source("retry_trace_panel").show(ui, |ui| {
+            // 1. Settings
+            let retry = &mut self.config.refusal_retry;
+            let mut changed = ui.checkbox(&mut retry.enabled, "Retry refusals and non-answers once").changed();
+            ui.add_enabled_ui(retry.enabled, |ui| {
+                changed |= ui.radio_value(&mut retry.strategy, RetryStrategy::Clarify, "Same model, clarified prompt").changed();
+                ui.horizontal(|ui| {
+                    changed |= ui.radio_value(&mut retry.strategy, RetryStrategy::AlternateModel, "Other model:").changed();
+                    let shown = if retry.alternate_model.is_empty() { "(none)" } else { retry.alternate_model.as_str() };
+                    egui::ComboBox::from_id_source("retry_model").selected_text(shown).show_ui(ui, |ui| {
+                        for model in &self.models {
+                            changed |= ui.selectable_value(&mut retry.alternate_model, model.clone(), model).changed();
+                        }
+                    });
+                });
+            });
+            if changed {
+                self.save_config();
+            }
+
+            // 2. What was caught in this chat, newest first
+            if self.retry_trace.is_empty() {
+                ui.weak("Nothing retried in this chat.");
+                return;
+            }
+            let clock = self.chat_clock();
+            for (i, trace) in self.retry_trace.iter().enumerate().rev() {
+                let outcome = trace.outcome.as_deref().unwrap_or("retrying...");
+                egui::CollapsingHeader::new(format!("{} · {} → {}", clock.time_millis(trace.at), trace.reason, outcome))
+                    .id_source(("retry_trace", i))
+                    .show(ui, |ui| {
+                        ui.small(format!("{} replied:", trace.model));
+                        ui.weak(&trace.refused);
+                        ui.small(format!("Sent to {}:", trace.retry_model));
+                        ui.weak(&trace.retry_prompt);
+                    });
+            }
+        });
+    }
+}
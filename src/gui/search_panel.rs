@@ -0,0 +1,135 @@
+// Window: ranked full-text search over saved sessions (SQLite FTS5), filtered by model / tag / date
+
+use super::ShipApp;
+use crate::jobs::JobKind;
+use crate::research::TimeRange;
+use crate::search_index::{SearchIndex, INDEX_FILE};
+use eframe::egui;
+
+const MAX_HITS: usize = 50;
+
+impl ShipApp {
+    // Opens lazily and catches up with files written since the last search.
+    // Journaled so a build cut short by a crash is offered again at launch.
+    fn synced_index(&mut self) -> Result<&SearchIndex, String> {
+        if self.search_index.is_none() {
+            self.search_index = Some(SearchIndex::open(&self.profile.root().join(INDEX_FILE))?);
+        }
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+        self.begin_job(JobKind::IndexSync);
+        let result = self.search_index.as_mut().expect("opened above").sync(&self.store);
+        self.finish_job(&JobKind::IndexSync);
+        result?;
+        Ok(self.search_index.as_ref().expect("opened above"))
+    }
+
+    pub(super) fn rebuild_search_index(&mut self) {
+        match self.synced_index() {
+            Ok(_) => self.search_status = "Search index is up to date".to_string(),
+            Err(e) => self.report_error(&format!("Search index: {}", e)),
+        }
+    }
+
+    fn run_search(&mut self) {
+        self.search_filter.since = self.search_time_range.cutoff_timestamp();
+        let (query, filter) = (self.search_query.clone(), self.search_filter.clone());
+        let snippet_tokens = self.config.search_snippet_tokens;
+        let result = self.synced_index().and_then(|index| {
+            let hits = index.search(&query, &filter, MAX_HITS, snippet_tokens)?;
+            Ok((hits, index.models_and_tags()))
+        });
+        match result {
+            Ok((hits, (models, tags))) => {
+                self.search_status = format!("{} results", hits.len());
+                self.search_hits = hits;
+                self.search_models = models;
+                self.search_tags = tags;
+            }
+            Err(e) => {
+                self.search_status = e.clone();
+                self.report_error(&format!("Search failed: {}", e));
+            }
+        }
+    }
+
+    pub(super) fn search_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_search;
+        let mut search = false;
+        egui::Window::new("Search History 🔎")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                // 1. Query + filters
+                ui.horizontal(|ui| {
+                    let field = ui.add(egui::TextEdit::singleline(&mut self.search_query).hint_text("words or \"exact phrase\""));
+                    if field.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        search = true;
+                    }
+                    if ui.button("Search").clicked() {
+                        search = true;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    let filter = &mut self.search_filter;
+                    let any = |s: &str| if s.is_empty() { "any".to_string() } else { s.to_string() };
+                    egui::ComboBox::from_id_source("search_model")
+                        .selected_text(format!("Model: {}", any(&filter.model)))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut filter.model, String::new(), "any");
+                            for m in &self.search_models {
+                                ui.selectable_value(&mut filter.model, m.clone(), m);
+                            }
+                        });
+                    egui::ComboBox::from_id_source("search_tag")
+                        .selected_text(format!("Tag: {}", any(&filter.tag)))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut filter.tag, String::new(), "any");
+                            for t in &self.search_tags {
+                                ui.selectable_value(&mut filter.tag, t.clone(), t);
+                            }
+                        });
+                    egui::ComboBox::from_id_source("search_time")
+                        .selected_text(self.search_time_range.label())
+                        .show_ui(ui, |ui| {
+                            for range in TimeRange::ALL {
+                                ui.selectable_value(&mut self.search_time_range, range, range.label());
+                            }
+                        });
+                });
+
+                // 2. Tags for the open chat, so it can be found this way later
+                ui.horizontal(|ui| {
+                    ui.label("This chat's tags:");
+                    let mut text = self.session_tags.join(", ");
+                    if ui.add(egui::TextEdit::singleline(&mut text).hint_text("ece310, lab3")).changed() {
+                        self.session_tags = text.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+                    }
+                });
+
+                // 3. Results
+                ui.separator();
+                if !self.search_status.is_empty() {
+                    ui.small(&self.search_status);
+                }
+                let clock = self.clock();
+                egui::ScrollArea::vertical().id_source("search_hits").max_height(360.0).show(ui, |ui| {
+                    for hit in &self.search_hits {
+                        let name = hit.path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                        let date = clock.date(hit.modified);
+                        ui.horizontal(|ui| {
+                            ui.strong(&name);
+                            ui.small(format!("#{} {} · {} · {}", hit.message_index, hit.role, date, hit.model));
+                        });
+                        ui.label(&hit.snippet);
+                        ui.separator();
+                    }
+                });
+            });
+        self.show_search = open;
+        if search {
+            self.run_search();
+        }
+    }
+}
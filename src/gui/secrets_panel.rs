@@ -0,0 +1,94 @@
+// Window: add / remove API keys stored in the OS keyring
+
+use super::ShipApp;
+use crate::secrets::{self, KNOWN_SECRETS};
+use eframe::egui;
+
+impl ShipApp {
+    // Whether the keyring holds `account`. A keyring lookup can block on D-Bus or the
+    // Keychain, so UI code asks this cache instead of the keyring every frame.
+    pub(super) fn has_secret(&mut self, account: &str) -> bool {
+        if let Some(present) = self.secret_present.get(account) {
+            return *present;
+        }
+        let present = secrets::get(account).is_some();
+        self.secret_present.insert(account.to_string(), present);
+        present
+    }
+
+    // After storing or removing a secret anywhere in the UI
+    pub(super) fn secret_changed(&mut self, account: &str) {
+        self.secret_present.remove(account);
+    }
+
+    pub(super) fn secrets_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_secrets;
+        egui::Window::new("Secrets 🔑")
+            .open(&mut open)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                ui.small("Values are kept in the OS keyring. Only the names are written to the config file.");
+                ui.separator();
+
+                // 1. Stored secrets
+                let mut forget = None;
+                let names = self.config.secret_names.clone();
+                let stored: Vec<bool> = names.iter().map(|name| self.has_secret(name)).collect();
+                egui::Grid::new("secrets_grid").striped(true).show(ui, |ui| {
+                    for (name, &present) in names.iter().zip(&stored) {
+                        ui.label(name);
+                        ui.label(if present { "✅ stored" } else { "⚠ missing" });
+                        if ui.small_button("Remove").clicked() {
+                            forget = Some(name.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(name) = forget {
+                    let result = secrets::delete(&name);
+                    self.secret_changed(&name);
+                    match result {
+                        Ok(()) => {
+                            self.config.secret_names.retain(|n| n != &name);
+                            self.save_config();
+                            self.log_event(&format!("Removed secret '{}'", name));
+                        }
+                        Err(e) => self.report_error(&format!("Keyring error: {}", e)),
+                    }
+                }
+
+                // 2. Add / replace
+                ui.separator();
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("secret_name")
+                        .selected_text(if self.secret_name_input.is_empty() { "name" } else { self.secret_name_input.as_str() })
+                        .show_ui(ui, |ui| {
+                            for known in KNOWN_SECRETS {
+                                ui.selectable_value(&mut self.secret_name_input, known.to_string(), known);
+                            }
+                        });
+                    ui.add(egui::TextEdit::singleline(&mut self.secret_name_input).desired_width(120.0));
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.secret_value_input).password(true).hint_text("value"));
+                    let ready = !self.secret_name_input.trim().is_empty() && !self.secret_value_input.is_empty();
+                    if ui.add_enabled(ready, egui::Button::new("Save")).clicked() {
+                        let name = self.secret_name_input.trim().to_string();
+                        let result = secrets::set(&name, &self.secret_value_input);
+                        self.secret_changed(&name);
+                        match result {
+                            Ok(()) => {
+                                secrets::remember(&mut self.config.secret_names, &name);
+                                self.save_config();
+                                self.log_event(&format!("Stored secret '{}'", name));
+                                self.secret_name_input.clear();
+                            }
+                            Err(e) => self.report_error(&format!("Keyring error: {}", e)),
+                        }
+                        self.secret_value_input.clear();
+                    }
+                });
+            });
+        self.show_secrets = open;
+    }
+}
@@ -0,0 +1,109 @@
+// End-of-session summaries: triggered on "New chat", profile switch and window close; plus the size header
+
+use super::{AppState, ShipApp};
+use crate::session::LATEST_FILE;
+use crate::summary;
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn summarize_in_background(&mut self, name: String) {
+        if !self.config.auto_summary {
+            return;
+        }
+        let model = self.selected_model.clone();
+        let backend = self.config.backend.clone();
+        let store = self.store.clone();
+        let tx = self.tx.clone();
+        self.log_event(&format!("Summarizing {}", name));
+
+        crate::runtime::spawn_background(&self.runtime, move || {
+            match summary::summarize_session(&backend, &store, &name, &model) {
+                Ok(_) => { let _ = tx.send(format!("__SUMMARY_DONE__:{}", name)); }
+                Err(e) => { let _ = tx.send(format!("__SUMMARY_FAILED__:{}", e)); }
+            }
+        });
+    }
+
+    // Archive the open conversation under its own name and start an empty one.
+    // A session opened from disk already has a name and is saved in place.
+    pub(super) fn new_chat(&mut self) {
+        if self.state != AppState::Idle || self.messages.is_empty() {
+            return;
+        }
+
+        let name = match self.current_file.as_str() {
+            LATEST_FILE => self.store.unused_name("chat"),
+            opened => opened.to_string(),
+        };
+        // A title given to the unsaved chat moves with it
+        let from_latest = self.current_file == LATEST_FILE;
+        let title = match from_latest {
+            true => self.store.load(LATEST_FILE).map(|f| f.meta.title).unwrap_or_default(),
+            false => String::new(),
+        };
+        let result = self.store.write_messages(&name, &self.messages, |meta| {
+            if !title.is_empty() {
+                meta.title = title;
+            }
+            meta.model = self.selected_model.clone();
+            meta.tags = self.session_tags.clone();
+            meta.timer_events = self.timer_events.clone();
+            meta.reactions = self.session_reactions();
+            meta.settings = Some(self.session_settings());
+            // Kept from the first write, wherever the session is continued later
+            self.keep_session_zone(meta);
+        });
+        match result {
+            Ok(()) => {
+                // The next chat starts from a fresh file, not this one's metadata
+                if from_latest {
+                    if let Err(e) = self.store.delete(LATEST_FILE) {
+                        self.warn_toast(&format!("Could not clear {}: {}", LATEST_FILE, e));
+                    }
+                }
+                self.messages.clear();
+                self.translation_checks.clear();
+                self.reactions.clear();
+                self.pinned_document = None;
+                self.session_tags.clear();
+                self.timer_events.clear();
+                self.retry_trace.clear();
+                self.session_zone = None;
+                self.overrides = Default::default();
+                self.pomodoro_started = None;
+                self.current_file = LATEST_FILE.to_string();
+                self.summarize_in_background(name);
+                self.refresh_session_lists();
+            }
+            Err(e) => self.report_error(&format!("Failed to archive session: {}", e)),
+        }
+    }
+
+    // Running size of the conversation above the chat, recounted only when it changes
+    pub(super) fn conversation_header(&mut self, ui: &mut egui::Ui) {
+        let key = (self.messages.len(), self.messages.last().map(|m| m.content.len()).unwrap_or(0));
+        if self.conversation_size.0 != key {
+            self.conversation_size = (key, summary::measure(&self.messages));
+        }
+        let size = self.conversation_size.1;
+
+        ui.horizontal(|ui| {
+            let text = format!("📏 {} words · {} chars · ~{} tokens", size.words, size.chars, size.tokens);
+            if size.over_summary_limit() {
+                ui.colored_label(ui.visuals().warn_fg_color, text)
+                    .on_hover_text("The end-of-session summary will only cover the most recent part");
+            } else {
+                let pct = size.chars * 100 / summary::MAX_TRANSCRIPT_CHARS;
+                ui.weak(text).on_hover_text(format!("{}% of what the session summary reads", pct));
+            }
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.toggle_value(&mut self.expand_long_messages, "⇕ Expand all")
+                    .on_hover_text("Show very long messages in full instead of a page at a time");
+                ui.separator();
+                self.pomodoro_widget(ui);
+                ui.separator();
+                self.overrides_widget(ui);
+            });
+        });
+    }
+}
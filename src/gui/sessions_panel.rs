@@ -0,0 +1,449 @@
+// Sidebar session browser: click a saved session to continue it, rename or delete it;
+// Save writes the open chat now. Also picks the store (JSON files or SQLite) and imports
+// ChatGPT exports.
+
+use super::{AppState, ShipApp};
+use crate::chatgpt_import;
+use crate::session::{self, SessionFile, SessionSettings};
+use crate::session_store::{PhraseHit, SessionStore, StoreKind};
+use crate::timestamps::CreatedZone;
+use eframe::egui;
+
+impl ShipApp {
+    // Opens the configured store; falls back to the JSON files if the database can't be used
+    pub(super) fn reopen_store(&mut self) {
+        let dir = self.profile.sessions_dir();
+        match SessionStore::open(self.config.session_store, &dir) {
+            Ok(store) => self.store = store,
+            Err(e) => {
+                self.store = SessionStore::Json { dir };
+                self.report_error(&format!("Session database unavailable, using JSON files: {}", e));
+            }
+        }
+    }
+
+    pub(super) fn refresh_session_lists(&mut self) {
+        self.session_list = self.store.list();
+        self.export_sessions = self.export_session_list();
+    }
+
+    // Loads every candidate session, so it runs in the background; the hits arrive as
+    // __SESSION_FIND__ with the phrase they are for
+    fn find_in_sessions(&mut self) {
+        let phrase = self.session_find.trim().to_string();
+        let context = self.config.find_snippet_chars;
+        let store = self.store.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let hits = store.find(&phrase, context);
+            let _ = tx.send(format!("__SESSION_FIND__:{}", serde_json::to_string(&(phrase, hits)).unwrap_or_default()));
+        });
+    }
+
+    pub(super) fn accept_session_find(&mut self, json: &str) {
+        let (phrase, hits) = match serde_json::from_str::<(String, Vec<PhraseHit>)>(json) {
+            Ok(found) => found,
+            Err(e) => {
+                self.report_error(&format!("Bad search results: {}", e));
+                return;
+            }
+        };
+        // The field was cleared or changed since: those results are not what it shows
+        if phrase != self.session_find.trim() {
+            return;
+        }
+        self.session_find_hits = hits;
+        self.session_find_done = true;
+    }
+
+    // The open chat is written to the new store too, so it keeps being saved in place
+    fn switch_store(&mut self, kind: StoreKind) {
+        self.config.session_store = kind;
+        self.save_config();
+        self.reopen_store();
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+        self.refresh_session_lists();
+        self.log_event(&format!("Sessions now stored in {}", self.store.kind().label()));
+    }
+
+    fn import_chatgpt(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("ChatGPT export", &["json"]).pick_file() else {
+            return;
+        };
+        self.importing = true;
+        let store = self.store.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let _ = match chatgpt_import::import(&path, &store) {
+                Ok(report) => tx.send(format!("__IMPORT_DONE__:{}", report.describe())),
+                Err(e) => tx.send(format!("__IMPORT_FAILED__:{}", e)),
+            };
+        });
+    }
+
+    pub(super) fn sessions_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Sessions 💾").show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let can_save = self.state == AppState::Idle && !self.messages.is_empty();
+                if ui.add_enabled(can_save, egui::Button::new("💾 Save")).on_hover_text(&self.current_file).clicked() {
+                    self.save_session_now();
+                }
+                if ui.add_enabled(!self.messages.is_empty(), egui::Button::new("📝 .md")).on_hover_text("Export as Markdown").clicked() {
+                    self.export_markdown();
+                }
+                if ui.add_enabled(!self.messages.is_empty(), egui::Button::new("📄 .pdf")).on_hover_text("Export as a PDF report, with the sources used").clicked() {
+                    self.export_pdf();
+                }
+                self.voice_memo_menu(ui);
+                if ui.button("🔄").on_hover_text("Refresh the list").clicked() {
+                    self.refresh_session_lists();
+                }
+                let mut kind = self.store.kind();
+                egui::ComboBox::from_id_source("session_store")
+                    .selected_text(kind.label())
+                    .show_ui(ui, |ui| {
+                        for option in [StoreKind::Json, StoreKind::Sqlite] {
+                            ui.selectable_value(&mut kind, option, option.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text("SQLite imports the existing JSON sessions the first time");
+                if kind != self.store.kind() && self.state == AppState::Idle {
+                    self.switch_store(kind);
+                }
+            });
+            if ui.add_enabled(!self.importing, egui::Button::new(if self.importing { "Importing..." } else { "📥 Import ChatGPT export" }))
+                .on_hover_text("conversations.json from a ChatGPT data export (unzip it first)")
+                .clicked()
+            {
+                self.import_chatgpt();
+            }
+
+            // 1. Phrase search over every session; a hit opens its conversation
+            let mut open = None;
+            ui.horizontal(|ui| {
+                let field = egui::TextEdit::singleline(&mut self.session_find).hint_text("🔎 find in all sessions");
+                let response = ui.add(field);
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    self.find_in_sessions();
+                }
+                if self.session_find_done && ui.small_button("✖").clicked() {
+                    self.session_find.clear();
+                    self.session_find_hits.clear();
+                    self.session_find_done = false;
+                }
+            });
+            if self.session_find_done {
+                if self.session_find_hits.is_empty() {
+                    ui.weak("No session mentions that");
+                }
+                egui::ScrollArea::vertical().id_source("session_find_hits").max_height(200.0).show(ui, |ui| {
+                    for hit in &self.session_find_hits {
+                        let mut job = egui::text::LayoutJob::default();
+                        let plain = egui::TextFormat { color: ui.visuals().weak_text_color(), ..Default::default() };
+                        let marked = egui::TextFormat {
+                            color: ui.visuals().strong_text_color(),
+                            background: ui.visuals().selection.bg_fill,
+                            ..Default::default()
+                        };
+                        job.append(&hit.before, 0.0, plain.clone());
+                        job.append(&hit.matched, 0.0, marked);
+                        job.append(&hit.after, 0.0, plain);
+                        let header = match hit.matches {
+                            1 => hit.title.clone(),
+                            n => format!("{} ({} messages)", hit.title, n),
+                        };
+                        if ui.selectable_label(hit.name == self.current_file, egui::RichText::new(header).strong()).clicked()
+                            | ui.add(egui::Label::new(job).sense(egui::Sense::click())).clicked()
+                        {
+                            open = Some(hit.name.clone());
+                        }
+                        ui.separator();
+                    }
+                });
+            }
+
+            // 2. One row per file: title, last change, rename and delete
+            let mut rename = None;
+            let mut delete = None;
+            let clock = self.clock();
+            egui::ScrollArea::vertical().id_source("sessions_list").max_height(220.0).show(ui, |ui| {
+                for entry in &self.session_list {
+                    let name = entry.name.clone();
+                    ui.horizontal(|ui| {
+                        if let Some((file, draft)) = self.renaming_session.as_mut().filter(|(file, _)| *file == name) {
+                            let edit = ui.text_edit_singleline(draft);
+                            edit.request_focus();
+                            if edit.lost_focus() {
+                                rename = Some(ui.input(|i| i.key_pressed(egui::Key::Enter)).then(|| (file.clone(), draft.clone())));
+                            }
+                            return;
+                        }
+                        let mut response = ui.selectable_label(name == self.current_file, &entry.title);
+                        let hover = match &entry.preview {
+                            Some(preview) => format!("{}\n\n{}", name, preview),
+                            None => name.clone(),
+                        };
+                        response = response.on_hover_text(hover);
+                        if response.clicked() {
+                            open = Some(name.clone());
+                        }
+                        if let Some(modified) = entry.modified {
+                            ui.weak(clock.date_time(modified.timestamp()));
+                        }
+                        if ui.small_button("✏").on_hover_text("Rename").clicked() {
+                            self.renaming_session = Some((name.clone(), entry.title.clone()));
+                        }
+                        if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                            delete = Some(name.clone());
+                        }
+                    });
+                }
+            });
+            // The open chat can't be reopened over itself, unless nothing of it is loaded yet
+            if let Some(name) = open.filter(|n| *n != self.current_file || self.messages.is_empty()) {
+                self.open_session(&name);
+            }
+            // Enter saves the new title, anything else that ends the edit (Esc, click away) cancels
+            if let Some(result) = rename {
+                self.renaming_session = None;
+                if let Some((file, title)) = result {
+                    self.rename_session(&file, &title);
+                }
+            }
+            if delete.is_some() {
+                self.confirm_delete_session = delete;
+            }
+        });
+    }
+
+    fn rename_session(&mut self, file: &str, title: &str) {
+        // The open chat is flushed first so its newest messages aren't lost to the rewrite
+        if file == self.current_file {
+            if let Err(e) = self.flush_session() {
+                self.report_error(&format!("Failed to save session: {}", e));
+                return;
+            }
+        }
+        match self.store.set_title(file, title) {
+            Ok(()) => self.refresh_session_lists(),
+            Err(e) => self.report_error(&format!("Rename failed: {}", e)),
+        }
+    }
+
+    // Confirmation for 🗑; deleting the open session leaves an empty chat
+    pub(super) fn delete_session_window(&mut self, ctx: &egui::Context) {
+        let Some(file) = self.confirm_delete_session.clone() else { return };
+        let mut decided = None;
+        egui::Window::new("Delete session?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("{} will be removed from disk. This can't be undone.", file));
+                ui.horizontal(|ui| {
+                    if ui.button("🗑 Delete").clicked() {
+                        decided = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        decided = Some(false);
+                    }
+                });
+            });
+        match decided {
+            Some(true) => {
+                self.confirm_delete_session = None;
+                if file == self.current_file && self.state != AppState::Idle {
+                    self.report_error("Stop the running reply before deleting this session");
+                    return;
+                }
+                match self.store.delete(&file) {
+                    Ok(()) => {
+                        if file == self.current_file {
+                            self.messages.clear();
+                            self.reactions.clear();
+                            self.translation_checks.clear();
+                            self.session_tags.clear();
+                            self.timer_events.clear();
+                            self.retry_trace.clear();
+                            self.session_zone = None;
+                            self.overrides = Default::default();
+                            self.current_file = session::LATEST_FILE.to_string();
+                        }
+                        self.log_event(&format!("Deleted session {}", file));
+                        self.refresh_session_lists();
+                    }
+                    Err(e) => self.report_error(&format!("Delete failed: {}", e)),
+                }
+            }
+            Some(false) => self.confirm_delete_session = None,
+            None => {}
+        }
+    }
+
+    fn save_session_now(&mut self) {
+        match self.flush_session() {
+            Ok(()) => {
+                self.push_toast(&format!("Saved {}", self.current_file));
+                self.refresh_session_lists();
+            }
+            Err(e) => self.report_error(&format!("Failed to save session: {}", e)),
+        }
+    }
+
+    // Archives the open chat, then continues `file` (a name in the sessions folder)
+    pub(super) fn open_session(&mut self, file: &str) {
+        if self.state != AppState::Idle {
+            self.report_error("Finish or stop the current reply before opening another session");
+            return;
+        }
+        let loaded = match self.store.load(file) {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                self.report_error(&format!("Could not open session: {}", e));
+                return;
+            }
+        };
+
+        // 1. Put the open conversation away (no-op when it is empty), and the one left
+        // from last time unless that is what is being opened
+        if file == session::LATEST_FILE {
+            self.unsaved_chat = None;
+        } else {
+            self.archive_unsaved_chat();
+        }
+        self.new_chat();
+        if !self.messages.is_empty() {
+            return; // Archiving failed and was reported
+        }
+
+        // 2. Restore the conversation and what the app keeps per session
+        self.restore_session(file, loaded);
+        self.log_event(&format!("Opened session {}", file));
+    }
+
+    fn restore_session(&mut self, file: &str, mut loaded: SessionFile) {
+        close_partial_reply(&mut loaded);
+        self.messages = loaded.messages;
+        self.current_file = file.to_string();
+        self.session_tags = loaded.meta.tags;
+        self.timer_events = loaded.meta.timer_events;
+        self.session_zone = CreatedZone::from_meta(&loaded.meta.zone, loaded.meta.utc_offset);
+        self.retry_trace.clear();
+        self.reactions = loaded.meta.reactions.into_iter().map(|r| (r.message_index, r)).collect();
+        if !loaded.meta.model.is_empty() {
+            self.register_model(&loaded.meta.model);
+            self.selected_model = loaded.meta.model;
+        }
+        match loaded.meta.settings {
+            Some(settings) => self.apply_session_settings(settings),
+            None => self.overrides = Default::default(),
+        }
+        self.completions = Default::default();
+        self.completion_indexed = 0;
+    }
+
+    // At startup and after a profile switch: the chat that was open last time (saved on
+    // every checkpoint and on exit) waits in LATEST_FILE until it is restored or archived
+    pub(super) fn load_unsaved_chat(&mut self) {
+        self.unsaved_chat = self.store.load(session::LATEST_FILE).ok().filter(|f| !f.messages.is_empty());
+    }
+
+    pub(super) fn restore_chat_window(&mut self, ctx: &egui::Context) {
+        let Some(unsaved) = &self.unsaved_chat else { return };
+        let mut restore = None;
+        egui::Window::new("Restore the last chat? ↺")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let first = unsaved.messages.iter().find(|m| m.role == "user").map(|m| m.content.as_str()).unwrap_or_default();
+                let preview: String = first.chars().take(120).collect();
+                ui.label(format!("{} messages were not archived when the app last closed:", unsaved.messages.len()));
+                ui.weak(preview);
+                if unsaved.meta.partial_reply {
+                    ui.colored_label(ui.visuals().warn_fg_color, "The last reply was cut off while it was being written.");
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("↺ Restore").clicked() {
+                        restore = Some(true);
+                    }
+                    if ui.button("Move to history").on_hover_text("Archive it as a saved session and start an empty chat").clicked() {
+                        restore = Some(false);
+                    }
+                });
+            });
+        match restore {
+            // Restoring over a chat started meanwhile would lose one of them
+            Some(true) if self.messages.is_empty() && self.state == AppState::Idle => {
+                let Some(unsaved) = self.unsaved_chat.take() else { return };
+                self.restore_session(session::LATEST_FILE, unsaved);
+                self.log_event("Restored the chat left open last time");
+            }
+            Some(_) => self.archive_unsaved_chat(),
+            None => {}
+        }
+    }
+
+    // Before anything else is written to LATEST_FILE: the old chat goes to the history
+    pub(super) fn archive_unsaved_chat(&mut self) {
+        let Some(mut unsaved) = self.unsaved_chat.take() else { return };
+        close_partial_reply(&mut unsaved);
+        let name = self.store.unused_name("chat");
+        match self.store.save(&name, &unsaved).and_then(|()| self.store.delete(session::LATEST_FILE)) {
+            Ok(()) => {
+                self.push_toast(&format!("Moved the last chat to {}", name));
+                self.summarize_in_background(name);
+                self.refresh_session_lists();
+            }
+            Err(e) => self.report_error(&format!("Failed to archive the last chat: {}", e)),
+        }
+    }
+
+    pub(super) fn session_settings(&self) -> SessionSettings {
+        SessionSettings {
+            research_dir: self.research_dir.clone(),
+            research_section: self.research_section.clone(),
+            rag: self.config.rag.mode,
+            context_placement: self.config.context_placement,
+            persona: self.config.persona.clone(),
+            overrides: self.overrides.clone(),
+        }
+    }
+
+    // The chat's RAG mode, placement and persona come back as overrides where they differ
+    // from the config, so the next save_config() doesn't make them the defaults
+    fn apply_session_settings(&mut self, settings: SessionSettings) {
+        self.research_dir = settings.research_dir;
+        self.research_section = settings.research_section;
+        self.overrides = settings.overrides;
+        if let Some(key) = &self.overrides.persona {
+            self.overrides.persona = Some(self.config.persona_id(key));
+        }
+        if self.overrides.rag.is_none() && settings.rag != self.config.rag.mode {
+            self.overrides.rag = Some(settings.rag);
+        }
+        if self.overrides.context_placement.is_none() && settings.context_placement != self.config.context_placement {
+            self.overrides.context_placement = Some(settings.context_placement);
+        }
+        let persona = self.config.persona_id(&settings.persona);
+        if self.overrides.persona.is_none() && persona != self.config.persona {
+            self.overrides.persona = Some(persona);
+        }
+    }
+}
+
+// A reply cut off mid-block would otherwise swallow everything rendered after it
+fn close_partial_reply(file: &mut SessionFile) {
+    if !file.meta.partial_reply {
+        return;
+    }
+    if let Some(last) = file.messages.last_mut().filter(|m| m.role == "assistant") {
+        if last.content.matches("```").count() % 2 == 1 {
+            last.content.push_str("\n```");
+        }
+    }
+    file.meta.partial_reply = false;
+}
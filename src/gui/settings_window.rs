@@ -0,0 +1,237 @@
+// Window: the settings that used to need an edit of ship_config.toml. Every change is
+// saved right away and takes effect on the next frame or request.
+
+use super::ShipApp;
+use crate::config::{AppConfig, Density, Theme};
+use crate::context::DEFAULT_SYSTEM_PROMPT;
+use eframe::egui;
+
+impl ShipApp {
+    // At startup and whenever the appearance settings change
+    pub(super) fn apply_appearance(&self, ctx: &egui::Context) {
+        ctx.set_visuals(match self.config.theme {
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light => egui::Visuals::light(),
+        });
+        ctx.set_zoom_factor(self.config.ui_scale.clamp(0.5, 3.0));
+    }
+
+    pub(super) fn settings_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_settings;
+        let mut changed = false;
+        let mut appearance = false;
+        egui::Window::new("Settings ⚙")
+            .open(&mut open)
+            .default_width(460.0)
+            .show(ctx, |ui| {
+                self.config_error_banner(ui);
+
+                // 1. Where things come from
+                ui.strong("General");
+                egui::Grid::new("settings_general").num_columns(2).show(ui, |ui| {
+                    ui.label("Research folder:");
+                    ui.horizontal(|ui| {
+                        changed |= ui.text_edit_singleline(&mut self.research_dir).lost_focus();
+                        if ui.small_button("📂").clicked() {
+                            if let Some(dir) = rfd::FileDialog::new().set_directory(&self.research_dir).pick_folder() {
+                                self.research_dir = dir.display().to_string();
+                                changed = true;
+                            }
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.label("Default model:");
+                    egui::ComboBox::from_id_source("settings_default_model")
+                        .selected_text(&self.selected_model)
+                        .show_ui(ui, |ui| {
+                            for model in &self.models {
+                                changed |= ui.selectable_value(&mut self.selected_model, model.clone(), model).changed();
+                            }
+                        });
+                    ui.end_row();
+
+                    let backend = &mut self.config.backend;
+                    ui.label("Ollama URL:");
+                    ui.horizontal(|ui| {
+                        changed |= ui.add(egui::TextEdit::singleline(&mut backend.host).desired_width(180.0)).lost_focus();
+                        ui.label(":");
+                        changed |= ui.add(egui::DragValue::new(&mut backend.port)).changed();
+                    });
+                    ui.end_row();
+
+                    ui.label("VRAM refresh:");
+                    let poll = egui::DragValue::new(&mut self.config.vram_poll_ms).clamp_range(crate::gpu::MIN_POLL_MS..=60_000).speed(50).suffix(" ms");
+                    if ui.add(poll).changed() {
+                        self.vram_poll_ms.store(self.config.vram_poll_ms, std::sync::atomic::Ordering::Relaxed);
+                        changed = true;
+                    }
+                    ui.end_row();
+
+                    ui.label("Keep model loaded:");
+                    changed |= ui.add(egui::TextEdit::singleline(&mut backend.keep_alive).hint_text("5m").desired_width(60.0))
+                        .on_hover_text("Ollama's keep_alive after each reply: \"30m\", \"2h\", -1 = until unloaded, 0 = unload right away. Empty = Ollama's default.")
+                        .lost_focus();
+                    ui.end_row();
+                });
+                ui.small("Auth, TLS and timeouts are under Backend 🔌 in the sidebar.");
+
+                // 2. What the model is told about you, first in every request
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.strong("System prompt");
+                    if ui.small_button("Reset").on_hover_text("Back to the built-in prompt").clicked() {
+                        self.config.system_prompt = DEFAULT_SYSTEM_PROMPT.to_string();
+                        changed = true;
+                    }
+                });
+                changed |= ui.add(
+                    egui::TextEdit::multiline(&mut self.config.system_prompt)
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("Who you are and how replies should be written"),
+                )
+                .lost_focus();
+                ui.small(format!("{} chars, used from the next message on", self.config.system_prompt.chars().count()));
+                ui.label("Personas:");
+                changed |= self.persona_editor(ui);
+
+                // 3. How much text search results show
+                ui.separator();
+                ui.strong("Snippets");
+                for slider in [
+                    ui.add(egui::Slider::new(&mut self.config.search_snippet_tokens, 4..=64).text("words per search result")),
+                    ui.add(egui::Slider::new(&mut self.config.find_snippet_chars, 20..=300).text("chars around a sidebar match")),
+                ] {
+                    // Saved once the drag ends, not on every step
+                    changed |= slider.drag_released() || (slider.changed() && !slider.dragged());
+                }
+
+                // 4. Observability
+                ui.separator();
+                ui.strong("Event log");
+                changed |= self.event_stream_settings(ui);
+
+                // 5. Timestamps
+                ui.separator();
+                ui.strong("Dates and times");
+                changed |= self.time_settings(ui);
+
+                // 6. Tools for working on the app itself
+                ui.separator();
+                ui.strong("Developer");
+                ui.horizontal(|ui| {
+                    changed |= ui.checkbox(&mut self.config.developer_mode, "Developer mode").changed();
+                    if self.config.developer_mode && ui.button("🎯 Retrieval eval").clicked() {
+                        self.open_retrieval_eval();
+                    }
+                });
+
+                // 7. Appearance
+                ui.separator();
+                ui.strong("Appearance");
+                ui.horizontal(|ui| {
+                    for theme in [Theme::Dark, Theme::Light] {
+                        appearance |= ui.radio_value(&mut self.config.theme, theme, theme.label()).changed();
+                    }
+                });
+                appearance |= ui.add(egui::Slider::new(&mut self.config.ui_scale, 0.5..=2.5).step_by(0.05).text("UI scale")).drag_released();
+                let layout = &mut self.config.chat_layout;
+                // Saved once the drag ends; the chat follows the slider meanwhile
+                let width = ui.add(egui::Slider::new(&mut layout.max_width, 0.0..=1600.0).step_by(20.0).text("chat width (0 = full)"));
+                changed |= width.drag_released() || (width.changed() && !width.dragged());
+                ui.horizontal(|ui| {
+                    for density in [Density::Compact, Density::Comfortable] {
+                        changed |= ui.radio_value(&mut layout.density, density, density.label()).changed();
+                    }
+                });
+                changed |= ui.checkbox(&mut layout.bubbles, "Bubbles").changed();
+
+                // 8. Background work: indexing, PDF parsing and summaries, kept off the cores doing generation
+                ui.separator();
+                ui.strong("Background work");
+                changed |= self.worker_settings(ui);
+                ui.small(format!("Saved to {}", self.profile.config_path().display()));
+            });
+        self.show_settings = open;
+
+        if appearance {
+            self.apply_appearance(ctx);
+        }
+        if changed || appearance {
+            self.config.default_model = self.selected_model.clone();
+            self.config.research_dir = self.research_dir.clone();
+            self.save_config();
+        }
+    }
+
+    // After loading a config that could not be read: nothing is saved until it is resolved
+    pub(super) fn report_config_error(&mut self) {
+        if let Some(e) = self.config.load_error.clone() {
+            self.report_error(&format!("{}. Using defaults; changes are not saved until the file is fixed and reloaded in ⚙ Settings, or overwritten there.", e));
+        }
+    }
+
+    fn config_error_banner(&mut self, ui: &mut egui::Ui) {
+        let Some(e) = self.config.load_error.clone() else {
+            return;
+        };
+        ui.colored_label(egui::Color32::from_rgb(220, 150, 60), format!("⚠ {}. Settings changes are not being saved.", e));
+        ui.horizontal(|ui| {
+            if ui.button("↻ Reload file").on_hover_text("After fixing it in an editor").clicked() {
+                let path = self.config.path().to_path_buf();
+                self.config = AppConfig::load(&path);
+                self.vram_poll_ms.store(self.config.vram_poll_ms, std::sync::atomic::Ordering::Relaxed);
+                crate::runtime::set_worker_config(&self.config.workers);
+                self.research_dir = self.config.research_dir.clone();
+                if !self.config.default_model.is_empty() {
+                    self.selected_model = self.config.default_model.clone();
+                }
+                self.apply_appearance(ui.ctx());
+                match self.config.load_error.is_some() {
+                    true => self.report_config_error(),
+                    false => self.push_toast("Settings reloaded"),
+                }
+            }
+            if ui.button("Overwrite with current settings").clicked() {
+                self.config.load_error = None;
+                self.save_config();
+            }
+        });
+        ui.separator();
+    }
+
+    // Applied to each job as it starts; a thread already running keeps its old setting
+    fn worker_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        if !crate::runtime::SUPPORTED {
+            ui.weak("Priority and core pinning are only available on Linux.");
+            return false;
+        }
+        let workers = &mut self.config.workers;
+        let mut changed = ui.add(egui::Slider::new(&mut workers.nice, 0..=19).text("niceness (19 = idle time only)")).changed();
+        if workers.nice < crate::runtime::nice_floor() {
+            ui.weak(format!(
+                "Workers already run at niceness {}; raising their priority back takes a restart.",
+                crate::runtime::nice_floor()
+            ));
+        }
+        ui.label("Cores (none ticked = any):");
+        ui.horizontal_wrapped(|ui| {
+            for core in 0..crate::runtime::cpu_count() {
+                let mut on = workers.cores.contains(&core);
+                if ui.checkbox(&mut on, core.to_string()).changed() {
+                    workers.cores.retain(|c| *c != core);
+                    if on {
+                        workers.cores.push(core);
+                        workers.cores.sort_unstable();
+                    }
+                    changed = true;
+                }
+            }
+        });
+        if changed {
+            crate::runtime::set_worker_config(workers);
+        }
+        changed
+    }
+}
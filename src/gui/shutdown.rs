@@ -0,0 +1,184 @@
+// Window close handling: confirm mid-generation, stop workers, flush the session.
+// Also checkpoints half-streamed replies while a generation runs, and the Stop button.
+
+use super::{AppState, ShipApp};
+use eframe::egui;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(2);
+
+impl ShipApp {
+    // Called every frame; intercepts the OS close button while work is in flight
+    pub(super) fn handle_close_request(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.viewport().close_requested()) && !self.allow_close {
+            if self.state != AppState::Idle && self.config.confirm_exit {
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.show_exit_confirm = true;
+            } else if self.state == AppState::Idle && !self.summarizing_before_exit && self.needs_exit_summary() {
+                // Hold the window open until the end-of-session summary is written
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.summarizing_before_exit = true;
+                self.summarize_in_background(self.current_file.clone());
+            }
+        }
+
+        // Summary finished (or failed): close for real
+        if self.exit_ready {
+            self.exit_ready = false;
+            self.allow_close = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+
+        if self.summarizing_before_exit {
+            egui::Window::new("Saving session")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Writing the end-of-session summary...");
+                    });
+                    if ui.button("Quit now").clicked() {
+                        self.exit_ready = true;
+                    }
+                });
+        }
+
+        if !self.show_exit_confirm {
+            return;
+        }
+
+        egui::Window::new("Quit Ship of Theseus?")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("A generation is still running. The partial answer will be saved.");
+                ui.checkbox(&mut self.config.confirm_exit, "Ask me next time");
+                ui.horizontal(|ui| {
+                    if ui.button("Quit").clicked() {
+                        self.allow_close = true;
+                        self.show_exit_confirm = false;
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                    if ui.button("Keep working").clicked() {
+                        self.show_exit_confirm = false;
+                    }
+                });
+            });
+    }
+
+    // Flushes the open session and reports whether its summary is missing or stale
+    fn needs_exit_summary(&self) -> bool {
+        if !self.config.auto_summary || self.messages.is_empty() || self.flush_session().is_err() {
+            return false;
+        }
+        self.store.load(&self.current_file).is_ok_and(|f| !f.has_fresh_summary())
+    }
+
+    pub(super) fn flush_session(&self) -> Result<(), String> {
+        if self.messages.is_empty() {
+            return Ok(());
+        }
+        self.store.write_messages(&self.current_file, &self.messages, |meta| {
+            // A write mid-generation flags the last reply as unfinished
+            meta.partial_reply = self.state == AppState::Generating;
+            meta.model = self.selected_model.clone();
+            meta.tags = self.session_tags.clone();
+            meta.timer_events = self.timer_events.clone();
+            meta.reactions = self.session_reactions();
+            meta.settings = Some(self.session_settings());
+            self.keep_session_zone(meta);
+        })
+    }
+
+    // Called per streamed token; writes at most every CHECKPOINT_INTERVAL so a crash
+    // mid-answer keeps what already arrived
+    pub(super) fn checkpoint_reply(&mut self) {
+        if self.state != AppState::Generating || self.last_checkpoint.elapsed() < CHECKPOINT_INTERVAL {
+            return;
+        }
+        self.last_checkpoint = Instant::now();
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Checkpoint failed: {}", e));
+        }
+    }
+
+    // Fresh token for a scan or generation, so stopping one never cancels the next
+    pub(super) fn begin_cancellable(&mut self) -> Arc<AtomicBool> {
+        self.cancel_flag = Arc::new(AtomicBool::new(false));
+        self.cancel_flag.clone()
+    }
+
+    // Stop button: abandons the scan or reply in flight and keeps what already arrived.
+    // Late messages from the stopped worker are dropped because the state is Idle again.
+    pub(super) fn stop_generation(&mut self) {
+        if self.state == AppState::Idle {
+            return;
+        }
+        self.cancel_flag.store(true, Ordering::Relaxed);
+        if let Some(task) = self.generation_task.take() {
+            task.abort();
+        }
+        let was_generating = self.state == AppState::Generating;
+        self.state = AppState::Idle;
+        self.activity.clear();
+        self.research_results.clear();
+        self.research_sources.clear();
+        self.reply_sources.clear();
+
+        // Cut the partial reply at a clean end: no dangling whitespace or open code block
+        if was_generating {
+            if let Some(last) = self.messages.last_mut().filter(|m| m.role == "assistant") {
+                let kept = last.content.trim_end().len();
+                last.content.truncate(kept);
+                if last.content.matches("```").count() % 2 == 1 {
+                    last.content.push_str("\n```");
+                }
+                if last.content.is_empty() {
+                    self.messages.pop();
+                }
+            }
+        }
+        self.end_tees("\n\n_[stopped]_");
+        self.log_event("Stopped by user");
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+    }
+
+    // Window geometry for the next launch; the size is kept from before maximizing
+    pub(super) fn track_window(&mut self, ctx: &egui::Context) {
+        let (rect, maximized) = ctx.input(|i| (i.viewport().inner_rect, i.viewport().maximized.unwrap_or(false)));
+        let window = &mut self.config.window;
+        window.maximized = maximized;
+        if let (Some(rect), false) = (rect, maximized) {
+            window.width = rect.width();
+            window.height = rect.height();
+        }
+    }
+
+    // Runs once from `on_exit`
+    pub(super) fn shutdown(&mut self) {
+        // 1. Tell background workers to stop sending
+        self.cancel_flag.store(true, Ordering::Relaxed);
+        if let Some(task) = self.generation_task.take() {
+            task.abort();
+        }
+        self.stop_speaking();
+
+        // 2. Flush the open conversation (including a half-streamed reply)
+        if let Err(e) = self.flush_session() {
+            eprintln!("Failed to flush session on exit: {}", e);
+        }
+
+        // 3. Preferences (e.g. the "ask me next time" choice), plus what was in use
+        self.config.default_model = self.selected_model.clone();
+        self.config.research_dir = self.research_dir.clone();
+        self.config.model_list = self.models.clone();
+        let _ = self.config.save();
+    }
+}
@@ -0,0 +1,81 @@
+// Window: whiteboard for quick circuit sketches sent as an image attachment
+
+use super::ShipApp;
+use crate::sketch::{self, Stroke, CANVAS_SIZE};
+use eframe::egui;
+
+const PEN_COLORS: [(&str, [u8; 3]); 3] = [("Black", [20, 20, 20]), ("Red", [200, 30, 30]), ("Blue", [30, 60, 200])];
+
+impl ShipApp {
+    pub(super) fn sketch_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_sketch;
+        egui::Window::new("Sketch Pad ✏️")
+            .open(&mut open)
+            .resizable(false)
+            .show(ctx, |ui| {
+                // 1. Tools
+                ui.horizontal(|ui| {
+                    for (name, color) in PEN_COLORS {
+                        let [r, g, b] = color;
+                        let swatch = egui::RichText::new("⏺").color(egui::Color32::from_rgb(r, g, b));
+                        if ui.selectable_label(self.sketch_color == color, swatch).on_hover_text(name).clicked() {
+                            self.sketch_color = color;
+                        }
+                    }
+                    ui.add(egui::Slider::new(&mut self.sketch_width, 1.0..=12.0).text("Pen"));
+                    if ui.button("↩ Undo").clicked() {
+                        self.sketch_strokes.pop();
+                    }
+                    if ui.button("🗑 Clear").clicked() {
+                        self.sketch_strokes.clear();
+                    }
+                });
+
+                // 2. Canvas
+                let size = egui::vec2(CANVAS_SIZE.0 as f32, CANVAS_SIZE.1 as f32);
+                let (response, painter) = ui.allocate_painter(size, egui::Sense::drag());
+                let origin = response.rect.min;
+                painter.rect_filled(response.rect, 0.0, egui::Color32::WHITE);
+
+                if response.drag_started() {
+                    self.sketch_strokes.push(Stroke { points: Vec::new(), width: self.sketch_width, color: self.sketch_color });
+                }
+                if response.dragged() || response.drag_started() {
+                    if let (Some(pos), Some(stroke)) = (response.interact_pointer_pos(), self.sketch_strokes.last_mut()) {
+                        let local = pos - origin;
+                        let point = [local.x.clamp(0.0, size.x), local.y.clamp(0.0, size.y)];
+                        if stroke.points.last() != Some(&point) {
+                            stroke.points.push(point);
+                        }
+                    }
+                }
+
+                for stroke in &self.sketch_strokes {
+                    let [r, g, b] = stroke.color;
+                    let pen = egui::Stroke::new(stroke.width, egui::Color32::from_rgb(r, g, b));
+                    let points: Vec<egui::Pos2> = stroke.points.iter().map(|p| origin + egui::vec2(p[0], p[1])).collect();
+                    if let [only] = points.as_slice() {
+                        painter.circle_filled(*only, stroke.width / 2.0, pen.color);
+                    } else {
+                        painter.add(egui::Shape::line(points, pen));
+                    }
+                }
+
+                // 3. Attach
+                ui.horizontal(|ui| {
+                    let can_attach = !self.sketch_strokes.is_empty();
+                    if ui.add_enabled(can_attach, egui::Button::new("📎 Attach to next message")).clicked() {
+                        match sketch::to_png_base64(&self.sketch_strokes) {
+                            Ok(b64) => {
+                                self.current_image_base64 = Some(b64);
+                                self.current_image_path = Some("sketch.png".to_string());
+                                self.show_sketch = false;
+                            }
+                            Err(e) => self.report_error(&format!("Sketch export failed: {}", e)),
+                        }
+                    }
+                });
+            });
+        self.show_sketch = open && self.show_sketch;
+    }
+}
@@ -0,0 +1,125 @@
+// Bottom status bar (activity, host, resident model, last error) and the log window
+
+use super::{AppState, ShipApp};
+use crate::backend::BackendKind;
+use crate::{gpu, system_monitor};
+use eframe::egui;
+use crossbeam_channel::Sender;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const RESIDENT_POLL: std::time::Duration = std::time::Duration::from_secs(5);
+
+impl ShipApp {
+    // Every frame; every 5 s asks the configured Ollama host (/api/ps), which may be a
+    // remote one, what it holds in memory. Other backends have no such list.
+    pub(super) fn poll_resident(&mut self) {
+        if self.config.backend.kind != BackendKind::Ollama {
+            self.resident_models.clear();
+            return;
+        }
+        if self.last_resident_poll.is_some_and(|at| at.elapsed() < RESIDENT_POLL) {
+            return;
+        }
+        self.last_resident_poll = Some(std::time::Instant::now());
+        let backend = self.config.backend.clone();
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            // Unreachable host: nothing is known to be loaded
+            let models = backend.running_models().await.unwrap_or_default();
+            let _ = tx.send(format!("__RESIDENT__:{}", models.join(",")));
+        });
+    }
+
+    // GPU and host readings; only changed ones are sent, and the interval can change while it runs
+    pub(super) fn spawn_vram_poller(tx: Sender<String>, interval_ms: Arc<AtomicU64>) {
+        std::thread::spawn(move || {
+            crate::runtime::enter_background();
+            let monitor = gpu::Monitor::load();
+            let mut host = system_monitor::Monitor::load();
+            let mut last = None;
+            let mut last_host = None;
+            loop {
+                let reading = monitor.read();
+                if last != Some(reading) {
+                    if tx.send(format!("__VRAM__:{}", serde_json::to_string(&reading).unwrap_or_default())).is_err() {
+                        break; // UI is gone
+                    }
+                    last = Some(reading);
+                }
+                let reading = host.read();
+                if last_host != Some(reading) {
+                    if tx.send(format!("__SYSTEM__:{}", serde_json::to_string(&reading).unwrap_or_default())).is_err() {
+                        break;
+                    }
+                    last_host = Some(reading);
+                }
+                let ms = interval_ms.load(Ordering::Relaxed).max(gpu::MIN_POLL_MS);
+                std::thread::sleep(std::time::Duration::from_millis(ms));
+            }
+        });
+    }
+
+    pub(super) fn status_bar(&mut self, ctx: &egui::Context) {
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+            let response = ui.horizontal(|ui| {
+                // 1. Current operation
+                let activity = if self.activity.is_empty() && self.state == AppState::Idle {
+                    "Idle".to_string()
+                } else {
+                    self.activity.clone()
+                };
+                if self.state != AppState::Idle {
+                    ui.spinner();
+                }
+                ui.label(activity);
+                ui.separator();
+
+                // 2. Backend + what it has loaded
+                ui.label(format!("🖧 {}", self.config.backend.uri()));
+                ui.separator();
+                let resident = if self.resident_models.is_empty() {
+                    "no model loaded".to_string()
+                } else {
+                    self.resident_models.join(", ")
+                };
+                ui.label(format!("🧠 {}", resident));
+
+                // 3. Last error
+                if let Some(err) = &self.last_error {
+                    ui.separator();
+                    ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {}", err));
+                }
+            }).response;
+
+            if response.interact(egui::Sense::click()).on_hover_text("Click to open the log").clicked() {
+                self.show_log = true;
+            }
+        });
+    }
+
+    pub(super) fn log_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_log;
+        egui::Window::new("Log 📜")
+            .open(&mut open)
+            .default_size([520.0, 300.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Clear").clicked() {
+                        self.event_log.clear();
+                        self.last_error = None;
+                    }
+                    if ui.button("📋 Copy").clicked() {
+                        ui.output_mut(|o| o.copied_text = self.event_log.iter().map(String::as_str).collect::<Vec<_>>().join("\n"));
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
+                    for line in &self.event_log {
+                        ui.monospace(line);
+                    }
+                });
+            });
+        self.show_log = open;
+    }
+}
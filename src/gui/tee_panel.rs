@@ -0,0 +1,87 @@
+// Tee mode: replies are mirrored into Markdown files while they stream, either all of
+// them (config) or only those of one chosen session
+
+use super::ShipApp;
+use crate::tee::Tee;
+use eframe::egui;
+use std::path::PathBuf;
+
+impl ShipApp {
+    // Called as a generation starts
+    pub(super) fn start_tees(&mut self, prompt: &str) {
+        let mut targets: Vec<PathBuf> = Vec::new();
+        if !self.config.tee_file.trim().is_empty() {
+            targets.push(PathBuf::from(self.config.tee_file.trim()));
+        }
+        if let Some((_, path)) = self.session_tee.as_ref().filter(|(file, _)| *file == self.current_file) {
+            if !targets.contains(path) {
+                targets.push(path.clone());
+            }
+        }
+        self.tees.clear();
+        for path in targets {
+            match Tee::begin(&path, &self.current_file, &self.selected_model, prompt) {
+                Ok(tee) => self.tees.push(tee),
+                Err(e) => self.report_error(&format!("Tee disabled for this reply: {}", e)),
+            }
+        }
+    }
+
+    pub(super) fn tee_token(&mut self, token: &str) {
+        let mut failed = None;
+        self.tees.retain_mut(|tee| match tee.write(token) {
+            Ok(()) => true,
+            Err(e) => {
+                failed = Some(e);
+                false
+            }
+        });
+        if let Some(e) = failed {
+            self.report_error(&format!("Tee stopped: {}", e));
+        }
+    }
+
+    pub(super) fn end_tees(&mut self, note: &str) {
+        for tee in self.tees.drain(..) {
+            tee.end(note);
+        }
+    }
+
+    // Settings: the global tee file and the per-session toggle
+    pub(super) fn tee_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Tee all replies to:");
+            let field = egui::TextEdit::singleline(&mut self.config.tee_file).hint_text("off").desired_width(140.0);
+            let mut changed = ui.add(field).lost_focus();
+            if ui.small_button("…").clicked() {
+                if let Some(path) = rfd::FileDialog::new().add_filter("Markdown", &["md", "txt"]).set_file_name("ship_replies.md").save_file() {
+                    self.config.tee_file = path.display().to_string();
+                    changed = true;
+                }
+            }
+            if changed {
+                self.save_config();
+            }
+        });
+
+        let active = self.session_tee.as_ref().filter(|(file, _)| *file == self.current_file).map(|(_, p)| p.clone());
+        ui.horizontal(|ui| {
+            match active {
+                Some(path) => {
+                    ui.small(format!("📝 This chat → {}", path.display()));
+                    if ui.small_button("✖").on_hover_text("Stop teeing this chat").clicked() {
+                        self.session_tee = None;
+                    }
+                }
+                None => {
+                    if ui.small_button("📝 Tee this chat…").clicked() {
+                        let name = format!("{}.md", self.current_file.trim_end_matches(".json"));
+                        if let Some(path) = rfd::FileDialog::new().add_filter("Markdown", &["md", "txt"]).set_file_name(name).save_file() {
+                            self.session_tee = Some((self.current_file.clone(), path));
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
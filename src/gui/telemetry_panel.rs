@@ -0,0 +1,111 @@
+// Optional JSONL event stream: where it goes (settings window) and the events the chat
+// loop emits. A sink that fails is closed and reported once instead of erroring per event.
+
+use super::toasts::{ToastAction, ToastLevel};
+use super::ShipApp;
+use crate::telemetry::EventSink;
+use eframe::egui;
+use serde_json::{json, Value};
+use std::time::Instant;
+
+impl ShipApp {
+    pub(super) fn open_event_sink(&mut self) {
+        self.event_sink = None;
+        let target = self.config.event_stream.trim().to_string();
+        if target.is_empty() {
+            return;
+        }
+        match EventSink::open(&target) {
+            Ok(sink) => {
+                self.event_sink = Some(sink);
+                self.log_event(&format!("Streaming events to {}", target));
+            }
+            Err(e) => self.report_error(&format!("Event stream: {}", e)),
+        }
+    }
+
+    pub(super) fn emit(&mut self, event: &str, fields: Value) {
+        let Some(sink) = &mut self.event_sink else { return };
+        if let Err(e) = sink.emit(event, fields) {
+            self.event_sink = None;
+            // Not report_error: that emits too
+            self.log_event(&format!("ERROR: Event stream closed: {}", e));
+            self.push_toast_with(ToastLevel::Warning, "Event stream closed; reconnect it in Settings", Some(ToastAction::Settings));
+        }
+    }
+
+    // Right before a chat request goes out
+    pub(super) fn emit_request_started(&mut self, model: &str, context_chars: usize, sources: usize) {
+        self.reply_started = Some(Instant::now());
+        self.reply_first_token = None;
+        self.reply_chunks = 0;
+        let fields = json!({
+            "model": model,
+            "persona": self.config.persona_label(self.active_persona()),
+            "session": self.current_file,
+            "history_turns": self.config.history_turns,
+            "image": self.current_image_base64.is_some(),
+            "context_chars": context_chars,
+            "sources": sources,
+        });
+        self.emit("request_started", fields);
+    }
+
+    // When a document scan ends, before the request that uses it
+    pub(super) fn emit_retrieval(&mut self, documents: usize) {
+        let fields = json!({
+            "documents": documents,
+            "context_chars": self.research_results.len(),
+            "section": self.research_section,
+            "scan_ms": self.scan_started.take().map(|t| t.elapsed().as_millis() as u64),
+        });
+        self.emit("retrieval", fields);
+    }
+
+    // Every streamed piece of the reply
+    pub(super) fn note_reply_chunk(&mut self) {
+        self.reply_chunks += 1;
+        if self.reply_first_token.is_none() {
+            self.reply_first_token = self.reply_started.map(|t| t.elapsed());
+        }
+    }
+
+    pub(super) fn emit_request_finished(&mut self) {
+        let Some(started) = self.reply_started.take() else { return };
+        let seconds = started.elapsed().as_secs_f64();
+        // Ollama streams about one token per chunk
+        let generating = seconds - self.reply_first_token.map_or(0.0, |d| d.as_secs_f64());
+        let fields = json!({
+            "model": self.selected_model,
+            "tokens": self.reply_chunks,
+            "seconds": seconds,
+            "first_token_ms": self.reply_first_token.map(|d| d.as_millis() as u64),
+            "tokens_per_sec": if generating > 0.0 { self.reply_chunks as f64 / generating } else { 0.0 },
+        });
+        self.emit("request_finished", fields);
+    }
+
+    pub(super) fn event_stream_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Event stream:");
+            let edit = ui.add(egui::TextEdit::singleline(&mut self.config.event_stream)
+                .hint_text("events.jsonl or unix:/run/ship.sock")
+                .desired_width(240.0));
+            if edit.lost_focus() {
+                changed = true;
+                self.open_event_sink();
+            }
+            let (icon, tip) = match self.event_sink {
+                Some(_) => ("🟢", "Connected"),
+                None if self.config.event_stream.trim().is_empty() => ("⚪", "Off"),
+                None => ("🔴", "Not connected"),
+            };
+            if ui.small_button(icon).on_hover_text(format!("{}; click to reconnect", tip)).clicked() {
+                self.open_event_sink();
+            }
+        });
+        ui.small("JSONL: request_started, request_finished (tokens/sec), retrieval, error");
+        changed
+    }
+}
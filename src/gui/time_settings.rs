@@ -0,0 +1,66 @@
+// Dates and times: the settings-window section, and the clocks every timestamp in the
+// UI and exports is formatted with
+
+use super::ShipApp;
+use crate::session::SessionMeta;
+use crate::timestamps::{self, Clock, CreatedZone, DateStyle};
+use eframe::egui;
+
+impl ShipApp {
+    // Session lists, jobs, search hits: anything not tied to the open chat
+    pub(super) fn clock(&self) -> Clock {
+        Clock::new(&self.config.time)
+    }
+
+    // The open chat, in the zone it was created in when that is turned on
+    pub(super) fn chat_clock(&self) -> Clock {
+        match &self.session_zone {
+            Some(created) if self.config.time.session_zone => self.clock().in_zone(created),
+            _ => self.clock(),
+        }
+    }
+
+    // On every write: the zone is set once, where the chat was started, and kept wherever
+    // it is continued later
+    pub(super) fn keep_session_zone(&self, meta: &mut SessionMeta) {
+        if meta.zone.is_empty() && meta.utc_offset.is_none() {
+            let created = self.session_zone.clone().unwrap_or_else(CreatedZone::here);
+            created.store(&mut meta.zone, &mut meta.utc_offset);
+        }
+    }
+
+    // Hover text on a message's role label
+    pub(super) fn message_time(&self, millis: Option<i64>) -> Option<String> {
+        let millis = millis?;
+        let clock = self.chat_clock();
+        let mut text = format!("{} ({})", clock.date_time_millis(millis), clock.zone_label());
+        if let Some(created) = self.session_zone.as_ref().filter(|z| !z.is_here()) {
+            text.push_str(&format!("\nCreated on a machine in {}", created.label()));
+        }
+        Some(text)
+    }
+
+    pub(super) fn time_settings(&mut self, ui: &mut egui::Ui) -> bool {
+        let time = &mut self.config.time;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Time zone:");
+            changed |= ui.add(egui::TextEdit::singleline(&mut time.zone).hint_text("system").desired_width(160.0)).lost_focus();
+            if let Err(e) = timestamps::parse_zone(&time.zone) {
+                ui.colored_label(egui::Color32::from_rgb(220, 150, 60), format!("⚠ {}; using local time", e));
+            }
+        });
+        ui.small("IANA name such as Europe/Madrid or America/Chicago, UTC, or empty for this machine's zone");
+        ui.horizontal(|ui| {
+            ui.label("Dates:");
+            for style in DateStyle::ALL {
+                changed |= ui.radio_value(&mut time.date_style, style, style.label()).changed();
+            }
+        });
+        changed |= ui.checkbox(&mut time.hour12, "12-hour clock").changed();
+        changed |= ui.checkbox(&mut time.session_zone, "Show a chat in the time zone it was created in").changed();
+        let clock = self.clock();
+        ui.small(format!("Now: {} ({})", clock.date_time(chrono::Utc::now().timestamp()), clock.zone_label()));
+        changed
+    }
+}
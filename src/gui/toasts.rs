@@ -0,0 +1,144 @@
+// Transient notifications in the top-right corner: successes, warnings and errors, each
+// dismissible and optionally carrying one action (open the file, open settings)
+
+use super::ShipApp;
+use crate::shell;
+use eframe::egui;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const MAX_TOASTS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(super) enum ToastLevel {
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastLevel {
+    // Errors stay up long enough to be read after looking away
+    fn lifetime(self) -> Duration {
+        match self {
+            Self::Success => Duration::from_secs(5),
+            Self::Warning => Duration::from_secs(10),
+            Self::Error => Duration::from_secs(15),
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Self::Success => "✅",
+            Self::Warning => "⚠",
+            Self::Error => "❌",
+        }
+    }
+
+    fn color(self, visuals: &egui::Visuals) -> egui::Color32 {
+        match self {
+            Self::Success => egui::Color32::from_rgb(90, 170, 90),
+            Self::Warning => visuals.warn_fg_color,
+            Self::Error => visuals.error_fg_color,
+        }
+    }
+}
+
+// The button a toast offers; clicking it also dismisses the toast
+#[derive(Clone)]
+pub(super) enum ToastAction {
+    Open(PathBuf), // A written file or folder, in the system viewer
+    Settings,
+}
+
+impl ToastAction {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Open(_) => "Open",
+            Self::Settings => "Settings",
+        }
+    }
+}
+
+pub(super) struct Toast {
+    level: ToastLevel,
+    text: String,
+    action: Option<ToastAction>,
+    created: Instant,
+}
+
+impl ShipApp {
+    pub(super) fn push_toast(&mut self, text: &str) {
+        self.push_toast_with(ToastLevel::Success, text, None);
+    }
+
+    pub(super) fn warn_toast(&mut self, text: &str) {
+        self.push_toast_with(ToastLevel::Warning, text, None);
+    }
+
+    pub(super) fn push_toast_with(&mut self, level: ToastLevel, text: &str, action: Option<ToastAction>) {
+        // The same message again only restarts its timer
+        self.toasts.retain(|t| t.text != text);
+        self.toasts.push(Toast { level, text: text.to_string(), action, created: Instant::now() });
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+    }
+
+    fn run_toast_action(&mut self, action: ToastAction) {
+        match action {
+            ToastAction::Open(path) => {
+                if let Err(e) = shell::open_external(&path.display().to_string()) {
+                    self.report_error(&e);
+                }
+            }
+            ToastAction::Settings => self.show_settings = true,
+        }
+    }
+
+    pub(super) fn show_toasts(&mut self, ctx: &egui::Context) {
+        // Hovering a toast keeps it up
+        let hovered = ctx.pointer_hover_pos();
+        let mut keep = None;
+        self.toasts.retain(|t| t.created.elapsed() < t.level.lifetime());
+        if self.toasts.is_empty() {
+            return;
+        }
+        let mut dismiss = None;
+        let mut run = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_TOP, [-12.0, 12.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, toast) in self.toasts.iter().enumerate() {
+                    let frame = egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_max_width(340.0);
+                        ui.horizontal(|ui| {
+                            ui.colored_label(toast.level.color(ui.visuals()), toast.level.icon());
+                            ui.label(&toast.text);
+                            if let Some(action) = &toast.action {
+                                if ui.small_button(action.label()).clicked() {
+                                    run = Some((i, action.clone()));
+                                }
+                            }
+                            if ui.small_button("✖").on_hover_text("Dismiss").clicked() {
+                                dismiss = Some(i);
+                            }
+                        });
+                    });
+                    if hovered.is_some_and(|p| frame.response.rect.contains(p)) {
+                        keep = Some(i);
+                    }
+                }
+            });
+        if let Some(i) = keep {
+            self.toasts[i].created = Instant::now();
+        }
+        if let Some((i, action)) = run {
+            self.toasts.remove(i);
+            self.run_toast_action(action);
+        } else if let Some(i) = dismiss {
+            self.toasts.remove(i);
+        }
+        ctx.request_repaint_after(Duration::from_millis(500));
+    }
+}
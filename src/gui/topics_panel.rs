@@ -0,0 +1,129 @@
+// Window: topic page built from every saved session; click a mention to jump to that message
+
+use super::{AppState, ShipApp};
+use crate::topics::{self, TopicIndex, TOPICS_FILE};
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn open_topics(&mut self) {
+        self.topic_index = TopicIndex::load(&self.profile.root().join(TOPICS_FILE)).topics();
+        self.show_topics = true;
+    }
+
+    fn update_topic_index(&mut self) {
+        if self.topics_busy {
+            return;
+        }
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+        self.topics_busy = true;
+        self.topics_status = "Reading sessions...".to_string();
+
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        let store = self.store.clone();
+        let path = self.profile.root().join(TOPICS_FILE);
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let progress_tx = tx.clone();
+            let progress = move |step: &str| {
+                let _ = progress_tx.send(format!("__TOPICS__:{}", step));
+            };
+            let skip_tx = tx.clone();
+            let skipped = move |reason: &str| {
+                let _ = skip_tx.send(format!("__TOPICS_SKIPPED__:{}", reason));
+            };
+            match topics::update(&backend, &model, &store, &path, progress, skipped) {
+                Ok(n) => { let _ = tx.send(format!("__TOPICS_DONE__:{}", n)); }
+                Err(e) => { let _ = tx.send(format!("__TOPICS_FAILED__:{}", e)); }
+            }
+        });
+    }
+
+    pub(super) fn finish_topic_index(&mut self, result: Result<&str, &str>) {
+        self.topics_busy = false;
+        self.topics_status = match result {
+            Ok(n) => format!("✅ {} sessions indexed", n),
+            Err(e) => format!("❌ {}", e),
+        };
+        // Whatever was saved before a failure is still worth showing
+        self.topic_index = TopicIndex::load(&self.profile.root().join(TOPICS_FILE)).topics();
+    }
+
+    pub(super) fn topics_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_topics;
+        let mut jump = None;
+        egui::Window::new("Topics 🏷")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    let label = if self.topics_busy { "Indexing..." } else { "🔄 Update index" };
+                    if ui.add_enabled(!self.topics_busy, egui::Button::new(label))
+                        .on_hover_text("Sessions that are new or have grown are read by the selected model")
+                        .clicked()
+                    {
+                        self.update_topic_index();
+                    }
+                    ui.add(egui::TextEdit::singleline(&mut self.topic_filter).hint_text("Filter topics").desired_width(160.0));
+                });
+                if !self.topics_status.is_empty() {
+                    ui.small(&self.topics_status);
+                }
+                if self.topic_index.is_empty() {
+                    ui.weak("No topics yet. Update the index to read your saved sessions.");
+                    return;
+                }
+                ui.separator();
+
+                // 1. Topics on the left, most-discussed first
+                let filter = self.topic_filter.trim().to_lowercase();
+                ui.columns(2, |columns| {
+                    egui::ScrollArea::vertical().id_source("topic_list").max_height(420.0).show(&mut columns[0], |ui| {
+                        for topic in self.topic_index.iter().filter(|t| t.name.to_lowercase().contains(&filter)) {
+                            let label = format!("{} ({})", topic.name, topic.sessions.len());
+                            let selected = self.selected_topic.as_deref() == Some(topic.name.as_str());
+                            if ui.selectable_label(selected, label).on_hover_text(format!("{} messages", topic.message_count())).clicked() {
+                                self.selected_topic = Some(topic.name.clone());
+                            }
+                        }
+                    });
+
+                    // 2. Where the selected topic came up, with jump links per message
+                    let Some(topic) = self.selected_topic.as_ref().and_then(|name| self.topic_index.iter().find(|t| &t.name == name)) else {
+                        columns[1].weak("Pick a topic");
+                        return;
+                    };
+                    egui::ScrollArea::vertical().id_source("topic_sessions").max_height(420.0).show(&mut columns[1], |ui| {
+                        ui.strong(&topic.name);
+                        for (session, messages) in &topic.sessions {
+                            let title = self.session_list.iter().find(|s| &s.name == session).map(|s| s.title.as_str()).unwrap_or(session);
+                            if ui.link(title).on_hover_text(session).clicked() {
+                                jump = Some((session.clone(), messages.first().copied()));
+                            }
+                            ui.horizontal_wrapped(|ui| {
+                                for &i in messages {
+                                    if ui.small_button(format!("#{}", i)).clicked() {
+                                        jump = Some((session.clone(), Some(i)));
+                                    }
+                                }
+                            });
+                            ui.separator();
+                        }
+                    });
+                });
+            });
+        self.show_topics = open;
+
+        if let Some((session, message)) = jump {
+            if session != self.current_file {
+                self.open_session(&session);
+            }
+            if self.current_file == session && self.state == AppState::Idle {
+                self.focused_message = message.filter(|&i| i < self.messages.len());
+                self.scroll_to_focus = true;
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// Back-translation confidence badges for Spanish <-> English replies
+
+use super::ShipApp;
+use crate::translation::{self, TranslationCheck};
+use eframe::egui;
+
+impl ShipApp {
+    // Runs after a reply completes: only for a translation request, and only when the
+    // reply's language differs from the text's
+    pub(super) fn maybe_check_translation(&mut self) {
+        if !self.config.translation_check || self.messages.len() < 2 {
+            return;
+        }
+        let index = self.messages.len() - 1;
+        let (request, reply) = (&self.messages[index - 1], &self.messages[index]);
+        if request.role != "user" || reply.role != "assistant" {
+            return;
+        }
+        let Some(source) = translation::translation_source(&request.content).map(String::from) else { return };
+        let (Some(source_lang), Some(reply_lang)) = (translation::detect(&source), translation::detect(&reply.content)) else { return };
+        if source_lang == reply_lang {
+            return;
+        }
+
+        let backend = self.config.backend.clone();
+        let model = self.selected_model.clone();
+        let translated = reply.content.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            match translation::check(&backend, &model, &source, &translated, source_lang, index) {
+                Ok(check) => { let _ = tx.send(format!("__TRANSLATION_CHECK__:{}", serde_json::to_string(&check).unwrap_or_default())); }
+                Err(e) => { let _ = tx.send(format!("__STATUS__:Back-translation failed: {}", e)); }
+            }
+        });
+    }
+
+    pub(super) fn accept_translation_check(&mut self, json: &str) {
+        if let Ok(check) = serde_json::from_str::<TranslationCheck>(json) {
+            self.translation_checks.insert(check.message_index, check);
+        }
+    }
+
+    pub(super) fn translation_badge(&self, ui: &mut egui::Ui, i: usize) {
+        let Some(check) = self.translation_checks.get(&i) else { return };
+        ui.label(format!("{} {:.0}%", check.badge(), check.score * 100.0))
+            .on_hover_text(format!("Round-trip similarity. Back-translation:\n\n{}", check.back_translation));
+    }
+}
@@ -0,0 +1,145 @@
+// Hands-free conversation: speak a prompt, the reply is read aloud, then it listens again.
+// The microphone is muted while a reply is generated or spoken; Space (or ✋) interrupts.
+
+use super::{AppState, ShipApp};
+use crate::voice_chat::{Microphone, VoiceChatConfig};
+use eframe::egui;
+
+impl ShipApp {
+    fn start_voice_chat(&mut self) {
+        let tx = self.tx.clone();
+        let on_utterance = move |result: Result<String, String>| {
+            let _ = match result {
+                Ok(text) => tx.send(format!("__VOICE_HEARD__:{}", text)),
+                Err(e) => tx.send(format!("__VOICE_FAILED__:{}", e)),
+            };
+        };
+        match Microphone::start(&self.config.voice_chat, on_utterance) {
+            Ok(mic) => {
+                self.voice_chat = Some(mic);
+                self.log_event("Voice conversation started");
+            }
+            Err(e) => self.report_error(&format!("Voice mode: {}", e)),
+        }
+    }
+
+    pub(super) fn stop_voice_chat(&mut self) {
+        if self.voice_chat.take().is_some() {
+            self.stop_speaking();
+            self.log_event("Voice conversation stopped");
+        }
+    }
+
+    // Push-to-interrupt: cut the reply short and go back to listening
+    fn interrupt_voice_reply(&mut self) {
+        self.stop_speaking();
+        if self.state != AppState::Idle {
+            self.stop_generation();
+        }
+    }
+
+    pub(super) fn accept_voice_prompt(&mut self, text: &str) {
+        // A transcript that lands while a reply is still running is dropped, not queued
+        if self.voice_chat.is_none() || self.state != AppState::Idle || text.trim().is_empty() {
+            return;
+        }
+        self.send_input(text.trim().to_string());
+    }
+
+    // Called on __DONE__: read the finished reply out in conversation mode
+    pub(super) fn speak_voice_reply(&mut self) {
+        if self.voice_chat.is_none() {
+            return;
+        }
+        if let Some(reply) = self.messages.last().filter(|m| m.role == "assistant").map(|m| m.content.clone()) {
+            self.speak(&reply);
+        }
+    }
+
+    // Every frame: mute while busy, reap the finished TTS process, handle Space
+    pub(super) fn voice_chat_frame(&mut self, ctx: &egui::Context) {
+        if self.voice_chat.is_none() {
+            return;
+        }
+        if let Some(child) = &mut self.tts_child {
+            if !matches!(child.try_wait(), Ok(None)) {
+                self.tts_child = None;
+            }
+        }
+        let busy = self.state != AppState::Idle || self.tts_child.is_some();
+        if let Some(mic) = &self.voice_chat {
+            mic.set_muted(busy);
+        }
+        if busy && !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::Space)) {
+            self.interrupt_voice_reply();
+        }
+        ctx.request_repaint_after(std::time::Duration::from_millis(50)); // Waveform
+    }
+
+    // Input row: toggle, live waveform and the interrupt button
+    pub(super) fn voice_chat_controls(&mut self, ui: &mut egui::Ui) {
+        let mut on = self.voice_chat.is_some();
+        if ui.toggle_value(&mut on, "🎙").on_hover_text("Hands-free conversation").changed() {
+            if on {
+                self.start_voice_chat();
+            } else {
+                self.stop_voice_chat();
+            }
+        }
+        let Some(mic) = &self.voice_chat else {
+            return;
+        };
+
+        // 1. Waveform: one bar per 30 ms frame, scaled so the threshold sits at a third
+        let levels = mic.levels();
+        let hearing = mic.is_speaking();
+        let threshold = self.config.voice_chat.vad_threshold.max(0.001);
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(90.0, 18.0), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let color = if hearing { egui::Color32::LIGHT_GREEN } else { ui.visuals().weak_text_color() };
+        let step = rect.width() / crate::voice_chat::LEVEL_HISTORY as f32;
+        for (i, level) in levels.iter().enumerate() {
+            let h = (level / threshold / 3.0).min(1.0) * rect.height();
+            let x = rect.left() + i as f32 * step;
+            painter.line_segment(
+                [egui::pos2(x, rect.center().y - h / 2.0), egui::pos2(x, rect.center().y + h / 2.0)],
+                egui::Stroke::new(step.max(1.0), color),
+            );
+        }
+
+        // 2. State and interrupt
+        let busy = self.state != AppState::Idle || self.tts_child.is_some();
+        if busy {
+            if ui.small_button("✋").on_hover_text("Interrupt and listen (Space)").clicked() {
+                self.interrupt_voice_reply();
+            }
+        } else {
+            ui.small(if hearing { "hearing..." } else { "listening" });
+        }
+    }
+
+    // Voice section: recorder, speech-to-text and the detector settings
+    pub(super) fn voice_chat_settings(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.label("Conversation mode");
+        let settings = &mut self.config.voice_chat;
+        ui.label("Recorder (raw 16 kHz mono PCM):");
+        let mut done = ui.text_edit_singleline(&mut settings.record_command).lost_focus();
+        ui.label("Speech-to-text ({wav} = audio file):");
+        done |= ui.text_edit_singleline(&mut settings.stt_command).lost_focus();
+        done |= ui.add(egui::Slider::new(&mut settings.vad_threshold, 0.002..=0.2).logarithmic(true).text("Speech level")).drag_released();
+        done |= ui.add(egui::Slider::new(&mut settings.silence_ms, 300..=3000).text("Pause (ms)")).drag_released();
+        if ui.small_button("Defaults").clicked() {
+            *settings = VoiceChatConfig::default();
+            done = true;
+        }
+        if done {
+            self.save_config();
+            // The recorder command is read at start
+            if self.voice_chat.is_some() {
+                self.stop_voice_chat();
+                self.start_voice_chat();
+            }
+        }
+    }
+}
@@ -0,0 +1,129 @@
+// "🎧" menu in the Sessions section: the last answer, the whole conversation or its
+// summary as an MP3/OGG voice memo with one chapter per part
+
+use super::toasts::{ToastAction, ToastLevel};
+use super::ShipApp;
+use crate::voice_memo::{self, Chapter, MemoFormat};
+use eframe::egui;
+
+#[derive(Clone, Copy, PartialEq)]
+enum MemoContent {
+    LastAnswer,
+    Conversation,
+    Summary,
+}
+
+impl ShipApp {
+    fn memo_chapters(&self, content: MemoContent) -> Result<Vec<Chapter>, String> {
+        match content {
+            MemoContent::LastAnswer => self.messages
+                .iter()
+                .rev()
+                .find(|m| m.role == "assistant")
+                .map(|m| vec![Chapter { title: "Answer".to_string(), text: m.content.clone() }])
+                .ok_or_else(|| "There is no answer yet".to_string()),
+            MemoContent::Conversation => {
+                let mut chapters = Vec::new();
+                let mut turn = 0;
+                for msg in &self.messages {
+                    let title = match msg.role.as_str() {
+                        "user" => {
+                            turn += 1;
+                            format!("Question {}", turn)
+                        }
+                        "assistant" => format!("Answer {}", turn),
+                        _ => continue,
+                    };
+                    chapters.push(Chapter { title, text: msg.content.clone() });
+                }
+                Ok(chapters)
+            }
+            MemoContent::Summary => {
+                let summary = self.store.load(&self.current_file).ok().and_then(|f| f.meta.summary);
+                let summary = summary.ok_or_else(|| "This session has no summary yet; save it first".to_string())?;
+                let mut chapters = vec![Chapter { title: "Summary".to_string(), text: summary.summary }];
+                if !summary.takeaways.is_empty() {
+                    chapters.push(Chapter { title: "Key takeaways".to_string(), text: summary.takeaways.join(".\n") });
+                }
+                Ok(chapters)
+            }
+        }
+    }
+
+    fn start_voice_memo(&mut self, content: MemoContent) {
+        if self.memo_busy {
+            return;
+        }
+        let chapters = match self.memo_chapters(content) {
+            Ok(chapters) if !chapters.is_empty() => chapters,
+            Ok(_) => return,
+            Err(e) => {
+                self.warn_toast(&e);
+                return;
+            }
+        };
+        let format = self.memo_format;
+        let name = format!("{}.{}", self.current_file.trim_end_matches(".json"), format.extension());
+        let Some(path) = rfd::FileDialog::new().add_filter("Audio", &[format.extension()]).set_file_name(name).save_file() else {
+            return;
+        };
+        self.memo_busy = true;
+        self.memo_status = "Starting...".to_string();
+
+        let title = match self.conversation_title() {
+            t if t.is_empty() => "Conversation".to_string(),
+            t => t,
+        };
+        let voice = self.config.voice_for(self.active_persona()).clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let progress_tx = tx.clone();
+            let progress = move |step: &str| {
+                let _ = progress_tx.send(format!("__MEMO__:{}", step));
+            };
+            let _ = match voice_memo::render(&path, &title, &chapters, &voice, format, progress) {
+                Ok(()) => tx.send(format!("__MEMO_DONE__:{}", path.display())),
+                Err(e) => tx.send(format!("__MEMO_FAILED__:{}", e)),
+            };
+        });
+    }
+
+    pub(super) fn finish_voice_memo(&mut self, result: Result<&str, &str>) {
+        self.memo_busy = false;
+        self.memo_status.clear();
+        match result {
+            Ok(path) => self.push_toast_with(ToastLevel::Success, &format!("Voice memo saved to {}", path), Some(ToastAction::Open(path.into()))),
+            Err(e) => self.report_error(&format!("Voice memo failed: {}", e)),
+        }
+    }
+
+    pub(super) fn voice_memo_menu(&mut self, ui: &mut egui::Ui) {
+        let mut pick = None;
+        let label = if self.memo_busy { format!("🎧 {}", self.memo_status) } else { "🎧".to_string() };
+        ui.add_enabled_ui(!self.messages.is_empty() && !self.memo_busy, |ui| {
+            ui.menu_button(label, |ui| {
+                ui.horizontal(|ui| {
+                    for format in MemoFormat::ALL {
+                        ui.radio_value(&mut self.memo_format, format, format.extension());
+                    }
+                });
+                for (content, text) in [
+                    (MemoContent::LastAnswer, "Last answer"),
+                    (MemoContent::Conversation, "Whole conversation (a chapter per message)"),
+                    (MemoContent::Summary, "Session summary"),
+                ] {
+                    if ui.button(text).clicked() {
+                        pick = Some(content);
+                        ui.close_menu();
+                    }
+                }
+                ui.small("Uses the voice of the selected model; needs espeak-ng and ffmpeg");
+            })
+            .response
+            .on_hover_text("Export as a voice memo");
+        });
+        if let Some(content) = pick {
+            self.start_voice_memo(content);
+        }
+    }
+}
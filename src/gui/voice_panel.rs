@@ -0,0 +1,81 @@
+// Sidebar section: TTS voice, speed and pitch, optionally per persona, and the
+// hands-free conversation settings
+
+use super::ShipApp;
+use crate::tts::{self, PREVIEW_TEXT};
+use eframe::egui;
+
+impl ShipApp {
+    pub(super) fn voice_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Voice 🔊").id_source("voice_panel").show(ui, |ui| {
+            if ui.small_button("🔄 Reload voices").clicked() {
+                self.tts_voices = tts::list_voices();
+            }
+            if self.tts_voices.is_empty() {
+                ui.small("espeak-ng not found; install it to enable speech.");
+            }
+
+            // 1. Which voice we are editing: the chat's persona's or the default
+            let persona = self.active_persona().to_string();
+            let mut per_persona = self.config.persona_voices.contains_key(&persona);
+            let label = format!("Own voice for '{}'", self.config.persona_label(&persona));
+            if !persona.is_empty() && ui.checkbox(&mut per_persona, label).changed() {
+                if per_persona {
+                    self.config.persona_voices.insert(persona.clone(), self.config.voice.clone());
+                } else {
+                    self.config.persona_voices.remove(&persona);
+                }
+                self.save_config();
+            }
+
+            let before = self.config.voice_for(&persona).clone();
+            let voices = &self.tts_voices;
+            let voice = match self.config.persona_voices.get_mut(&persona) {
+                Some(v) => v,
+                None => &mut self.config.voice,
+            };
+
+            // 2. Voice parameters
+            egui::ComboBox::from_id_source("tts_voice")
+                .selected_text(&voice.voice)
+                .show_ui(ui, |ui| {
+                    for v in voices {
+                        ui.selectable_value(&mut voice.voice, v.clone(), v);
+                    }
+                });
+            ui.add(egui::Slider::new(&mut voice.speed, 80..=400).text("Speed (wpm)"));
+            ui.add(egui::Slider::new(&mut voice.pitch, 0..=99).text("Pitch"));
+            let changed = *voice != before;
+
+            // 3. Preview / stop
+            ui.horizontal(|ui| {
+                if ui.button("▶ Preview").clicked() {
+                    self.speak(PREVIEW_TEXT);
+                }
+                if self.tts_child.is_some() && ui.button("⏹ Stop").clicked() {
+                    self.stop_speaking();
+                }
+            });
+
+            if changed {
+                self.save_config();
+            }
+            self.voice_chat_settings(ui);
+        });
+    }
+
+    pub(super) fn speak(&mut self, text: &str) {
+        self.stop_speaking();
+        match tts::speak(text, self.config.voice_for(self.active_persona())) {
+            Ok(child) => self.tts_child = Some(child),
+            Err(e) => self.report_error(&e),
+        }
+    }
+
+    pub(super) fn stop_speaking(&mut self) {
+        if let Some(mut child) = self.tts_child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
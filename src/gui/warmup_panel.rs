@@ -0,0 +1,105 @@
+// Sidebar section: scheduled warm-ups, and the check that fires them while the app runs
+
+use super::ShipApp;
+use crate::warmup::{Warmup, DAYS};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+impl ShipApp {
+    // Called every frame; looks at the schedule once a minute
+    pub(super) fn run_due_warmups(&mut self) {
+        if self.last_warmup_check.is_some_and(|at| at.elapsed() < CHECK_INTERVAL) {
+            return;
+        }
+        self.last_warmup_check = Some(Instant::now());
+
+        let now = chrono::Local::now().naive_local();
+        let due: Vec<(usize, Warmup)> = self.config.warmups
+            .iter()
+            .enumerate()
+            .filter(|(i, w)| w.is_due(now, self.warmups_run.get(i).copied()))
+            .map(|(i, w)| (i, w.clone()))
+            .collect();
+        for (i, warmup) in due {
+            self.warmups_run.insert(i, now.date());
+            self.start_warmup(&warmup);
+        }
+    }
+
+    fn start_warmup(&mut self, warmup: &Warmup) {
+        self.log_event(&format!("Warming up {} (keep alive {})", warmup.model, warmup.keep_alive));
+        self.note_model_used(&warmup.model); // Fresh, so idle unloading leaves it alone
+        let backend = self.config.backend.clone();
+        let (model, keep_alive) = (warmup.model.clone(), warmup.keep_alive.clone());
+        let tx = self.tx.clone();
+        self.runtime.spawn(async move {
+            let _ = match backend.load_model(&model, &keep_alive).await {
+                Ok(()) => tx.send(format!("__STATUS__:{} is loaded", model)),
+                Err(e) => tx.send(format!("__ERROR__:Warm-up of {} failed: {}", model, e)),
+            };
+        });
+    }
+
+    pub(super) fn warmup_panel(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Warm-up ⏰").id_source("warmup_panel").show(ui, |ui| {
+            ui.small("Load a model ahead of time so the first reply doesn't wait for it.");
+            let mut changed = false;
+            let mut remove = None;
+            let mut run_now = None;
+            for (i, warmup) in self.config.warmups.iter_mut().enumerate() {
+                ui.push_id(("warmup", i), |ui| {
+                    ui.horizontal(|ui| {
+                        changed |= ui.checkbox(&mut warmup.enabled, "").changed();
+                        changed |= ui.add(egui::TextEdit::singleline(&mut warmup.model).hint_text("model").desired_width(110.0)).lost_focus();
+                        changed |= ui.add(egui::TextEdit::singleline(&mut warmup.time).hint_text("HH:MM").desired_width(44.0)).lost_focus();
+                        if warmup.parsed_time().is_none() {
+                            ui.colored_label(ui.visuals().error_fg_color, "⚠").on_hover_text("Use 24-hour HH:MM");
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        for day in DAYS {
+                            let mut on = warmup.runs_on(day);
+                            if ui.toggle_value(&mut on, &day.to_string()[..2]).changed() {
+                                warmup.days.retain(|d| !d.eq_ignore_ascii_case(&day.to_string()));
+                                if on {
+                                    warmup.days.push(day.to_string());
+                                }
+                                changed = true;
+                            }
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("keep");
+                        changed |= ui.add(egui::TextEdit::singleline(&mut warmup.keep_alive).desired_width(40.0))
+                            .on_hover_text("How long Ollama keeps it loaded: 2h, 45m, or seconds (-1 = until unloaded)")
+                            .lost_focus();
+                        if ui.small_button("▶").on_hover_text("Warm up now").clicked() {
+                            run_now = Some(warmup.clone());
+                        }
+                        if ui.small_button("✖").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                    ui.separator();
+                });
+            }
+            if ui.small_button("➕ Add warm-up").clicked() {
+                self.config.warmups.push(Warmup { model: self.selected_model.clone(), ..Default::default() });
+                changed = true;
+            }
+            if let Some(i) = remove {
+                self.config.warmups.remove(i);
+                self.warmups_run.clear(); // Indices moved
+                changed = true;
+            }
+            if changed {
+                self.save_config();
+            }
+            if let Some(warmup) = run_now {
+                self.start_warmup(&warmup);
+            }
+        });
+    }
+}
@@ -0,0 +1,120 @@
+// Window: word cloud and topic bars for the open chat or the whole history. Words are
+// counted here; the topic bars come from the topic index (🏷) when it has been built.
+
+use super::ShipApp;
+use crate::topics::{TopicIndex, TOPICS_FILE};
+use crate::word_cloud::{self, Term, MAX_TERMS};
+use eframe::egui;
+
+const PALETTE: [egui::Color32; 5] = [
+    egui::Color32::from_rgb(90, 160, 230),
+    egui::Color32::from_rgb(110, 190, 120),
+    egui::Color32::from_rgb(230, 170, 70),
+    egui::Color32::from_rgb(200, 110, 180),
+    egui::Color32::from_rgb(100, 190, 190),
+];
+
+impl ShipApp {
+    pub(super) fn open_word_cloud(&mut self) {
+        self.show_word_cloud = true;
+        self.refresh_word_cloud();
+    }
+
+    // The session view is counted on the spot; the history one in the background
+    fn refresh_word_cloud(&mut self) {
+        let index = TopicIndex::load(&self.profile.root().join(TOPICS_FILE));
+        if !self.cloud_history {
+            self.cloud_terms = word_cloud::terms(&self.messages, MAX_TERMS);
+            self.cloud_topics = word_cloud::topic_counts(&index, Some(&self.current_file));
+            return;
+        }
+        if let Err(e) = self.flush_session() {
+            self.report_error(&format!("Failed to save session: {}", e));
+        }
+        self.cloud_topics = word_cloud::topic_counts(&index, None);
+        self.cloud_busy = true;
+        let store = self.store.clone();
+        let tx = self.tx.clone();
+        crate::runtime::spawn_background(&self.runtime, move || {
+            let terms = word_cloud::history_terms(&store, MAX_TERMS);
+            let _ = tx.send(format!("__WORD_CLOUD__:{}", serde_json::to_string(&terms).unwrap_or_default()));
+        });
+    }
+
+    pub(super) fn accept_word_cloud(&mut self, json: &str) {
+        self.cloud_busy = false;
+        // A switch back to the session view while counting wins
+        if !self.cloud_history {
+            return;
+        }
+        match serde_json::from_str::<Vec<Term>>(json) {
+            Ok(terms) => self.cloud_terms = terms,
+            Err(e) => self.report_error(&format!("Bad word counts: {}", e)),
+        }
+    }
+
+    pub(super) fn word_cloud_window(&mut self, ctx: &egui::Context) {
+        if !self.show_word_cloud {
+            return;
+        }
+        let mut open = self.show_word_cloud;
+        let mut refresh = false;
+        egui::Window::new("Word cloud ☁")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    refresh |= ui.radio_value(&mut self.cloud_history, false, "This session").changed();
+                    refresh |= ui.radio_value(&mut self.cloud_history, true, "All sessions").changed();
+                    refresh |= ui.button("🔄").on_hover_text("Count again").clicked();
+                    if self.cloud_busy {
+                        ui.spinner();
+                    }
+                });
+                ui.separator();
+
+                // 1. Cloud: alphabetical, sized by count
+                if self.cloud_terms.is_empty() {
+                    ui.weak(if self.cloud_busy { "Counting..." } else { "Not enough text yet." });
+                } else {
+                    let max = self.cloud_terms.iter().map(|t| t.count).max().unwrap_or(1) as f32;
+                    let min = self.cloud_terms.iter().map(|t| t.count).min().unwrap_or(1) as f32;
+                    let mut shown: Vec<&Term> = self.cloud_terms.iter().collect();
+                    shown.sort_by(|a, b| a.word.cmp(&b.word));
+                    egui::ScrollArea::vertical().id_source("word_cloud").max_height(260.0).show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for (i, term) in shown.iter().enumerate() {
+                                // Square root, so one dominant word doesn't shrink the rest to dots
+                                let weight = if max > min { ((term.count as f32 - min) / (max - min)).sqrt() } else { 0.5 };
+                                let text = egui::RichText::new(&term.word).size(12.0 + 24.0 * weight).color(PALETTE[i % PALETTE.len()]);
+                                ui.label(text).on_hover_text(format!("{} times", term.count));
+                            }
+                        });
+                    });
+                }
+
+                // 2. Topic bars from the index
+                ui.separator();
+                ui.strong("Topics");
+                if self.cloud_topics.is_empty() {
+                    ui.weak("No topics indexed for this yet; build the index from the 🏷 window.");
+                    return;
+                }
+                let max = self.cloud_topics.iter().map(|(_, n)| *n).max().unwrap_or(1) as f32;
+                egui::Grid::new("topic_bars").num_columns(2).show(ui, |ui| {
+                    for (topic, count) in &self.cloud_topics {
+                        ui.label(topic);
+                        let width = 260.0 * (*count as f32 / max);
+                        let (rect, response) = ui.allocate_exact_size(egui::vec2(width.max(2.0), 14.0), egui::Sense::hover());
+                        ui.painter().rect_filled(rect, 2.0, PALETTE[0]);
+                        response.on_hover_text(format!("{} messages", count));
+                        ui.end_row();
+                    }
+                });
+            });
+        self.show_word_cloud = open;
+        if refresh && !self.cloud_busy {
+            self.refresh_word_cloud();
+        }
+    }
+}
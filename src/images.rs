@@ -0,0 +1,191 @@
+// --- IMAGE PREPROCESSING ---
+// Attachments pass through here before they are put on the wire.
+
+use base64::Engine;
+use image::{ImageFormat, ImageOutputFormat};
+use std::io::Cursor;
+
+pub const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+pub const MAX_IMAGE_SIDE: u32 = 8192;
+const SUPPORTED: [ImageFormat; 3] = [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::WebP];
+
+// Checks an attachment before it goes into a request, so a bad file fails here with a
+// clear reason instead of as a generic error from the server after a long wait.
+// Returns the size in pixels.
+pub fn validate(b64: &str) -> Result<(u32, u32), String> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let bytes = engine.decode(b64.trim()).map_err(|e| format!("The attached image is not valid base64 ({})", e))?;
+    if bytes.is_empty() {
+        return Err("The attached image is empty".to_string());
+    }
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(format!("The attached image is {:.1} MB; the limit is {} MB", bytes.len() as f64 / 1048576.0, MAX_IMAGE_BYTES / 1048576));
+    }
+    let format = image::guess_format(&bytes).map_err(|_| "The attachment is not a recognizable image".to_string())?;
+    if !SUPPORTED.contains(&format) {
+        return Err(format!("{:?} images are not supported; attach a PNG, JPEG or WebP", format));
+    }
+    // Header only: catches truncated files without decoding every pixel
+    let (width, height) = image::io::Reader::with_format(Cursor::new(&bytes), format)
+        .into_dimensions()
+        .map_err(|e| format!("The attached image can't be read: {}", e))?;
+    if width == 0 || height == 0 || width.max(height) > MAX_IMAGE_SIDE {
+        return Err(format!("The attached image is {}×{}; the longest side may be at most {} px", width, height, MAX_IMAGE_SIDE));
+    }
+    Ok((width, height))
+}
+
+// Decoding and re-encoding keeps only the pixels, so EXIF blocks (GPS, camera
+// serial, timestamps) and PNG text/eXIf chunks are dropped. The EXIF orientation is
+// applied to the pixels first, or a phone photo would arrive sideways.
+pub fn strip_metadata(b64: &str) -> Result<String, String> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let bytes = engine.decode(b64.trim()).map_err(|e| format!("Invalid image data: {}", e))?;
+
+    let format = image::guess_format(&bytes).map_err(|e| e.to_string())?;
+    let img = image::load_from_memory(&bytes).map_err(|e| e.to_string())?;
+    let img = match exif_orientation(&bytes, format).unwrap_or(1) {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    };
+
+    // Photos stay JPEG so they don't balloon in size; everything else becomes PNG
+    let output = match format {
+        ImageFormat::Jpeg => ImageOutputFormat::Jpeg(92),
+        _ => ImageOutputFormat::Png,
+    };
+
+    let mut clean = Cursor::new(Vec::new());
+    img.write_to(&mut clean, output).map_err(|e| e.to_string())?;
+    Ok(engine.encode(clean.into_inner()))
+}
+
+// The Orientation tag (1-8) from the EXIF block of a JPEG, PNG or WebP
+fn exif_orientation(bytes: &[u8], format: ImageFormat) -> Option<u16> {
+    let exif = match format {
+        ImageFormat::Jpeg => jpeg_exif(bytes)?,
+        ImageFormat::Png => find_chunk(bytes.get(8..)?, b"eXIf", false)?,
+        ImageFormat::WebP => find_chunk(bytes.get(12..)?, b"EXIF", true)?,
+        _ => return None,
+    };
+    tiff_orientation(exif.strip_prefix(b"Exif\0\0").unwrap_or(exif))
+}
+
+// Payload of the APP1 "Exif" segment, looked for among the segments before the image data
+fn jpeg_exif(bytes: &[u8]) -> Option<&[u8]> {
+    let mut at = 2;
+    while bytes.get(at) == Some(&0xFF) {
+        let marker = *bytes.get(at + 1)?;
+        let len = u16::from_be_bytes([*bytes.get(at + 2)?, *bytes.get(at + 3)?]) as usize;
+        let payload = bytes.get(at + 4..at + 2 + len)?;
+        if marker == 0xE1 && payload.starts_with(b"Exif\0\0") {
+            return Some(payload);
+        }
+        if marker == 0xDA {
+            return None;
+        }
+        at += 2 + len;
+    }
+    None
+}
+
+// PNG chunks are length, type, data, CRC (big-endian); RIFF chunks are type, length
+// (little-endian), data, padded to an even size
+fn find_chunk<'a>(mut bytes: &'a [u8], kind: &[u8; 4], riff: bool) -> Option<&'a [u8]> {
+    while bytes.len() >= 8 {
+        let (name, len) = match riff {
+            true => (&bytes[..4], u32::from_le_bytes(bytes[4..8].try_into().ok()?) as usize),
+            false => (&bytes[4..8], u32::from_be_bytes(bytes[..4].try_into().ok()?) as usize),
+        };
+        let data = bytes.get(8..8 + len)?;
+        if name == kind {
+            return Some(data);
+        }
+        let next = match riff {
+            true => 8 + len + len % 2,
+            false => 8 + len + 4,
+        };
+        bytes = bytes.get(next..)?;
+    }
+    None
+}
+
+// Tag 0x0112 in IFD0 of a TIFF block
+fn tiff_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |at: usize| -> Option<u16> {
+        let b = [*tiff.get(at)?, *tiff.get(at + 1)?];
+        Some(if big_endian { u16::from_be_bytes(b) } else { u16::from_le_bytes(b) })
+    };
+    let u32_at = |at: usize| -> Option<u32> {
+        let b: [u8; 4] = tiff.get(at..at + 4)?.try_into().ok()?;
+        Some(if big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    };
+    let ifd = u32_at(4)? as usize;
+    for i in 0..u16_at(ifd)? as usize {
+        let entry = ifd + 2 + i * 12;
+        if u16_at(entry)? == 0x0112 {
+            return u16_at(entry + 8).filter(|o| (1..=8).contains(o));
+        }
+    }
+    None
+}
+
+// For data URLs ("data:image/png;base64,..."), from the first few bytes only
+pub fn mime_type(b64: &str) -> &'static str {
+    let head: String = b64.trim().chars().take(32).collect();
+    let bytes = base64::engine::general_purpose::STANDARD.decode(head).unwrap_or_default();
+    match image::guess_format(&bytes) {
+        Ok(ImageFormat::Jpeg) => "image/jpeg",
+        Ok(ImageFormat::WebP) => "image/webp",
+        Ok(ImageFormat::Gif) => "image/gif",
+        _ => "image/png",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tiff_orientation;
+
+    // Header, IFD0 at offset 8 with a single Orientation entry (SHORT, count 1)
+    fn tiff(big_endian: bool, orientation: u16) -> Vec<u8> {
+        let mut t: Vec<u8> = Vec::new();
+        let (u16b, u32b): (fn(u16) -> [u8; 2], fn(u32) -> [u8; 4]) = match big_endian {
+            true => (u16::to_be_bytes, u32::to_be_bytes),
+            false => (u16::to_le_bytes, u32::to_le_bytes),
+        };
+        t.extend_from_slice(if big_endian { b"MM" } else { b"II" });
+        t.extend(u16b(42));
+        t.extend(u32b(8));
+        t.extend(u16b(1));
+        t.extend(u16b(0x0112));
+        t.extend(u16b(3));
+        t.extend(u32b(1));
+        t.extend(u16b(orientation));
+        t.extend([0, 0]);
+        t.extend(u32b(0));
+        t
+    }
+
+    #[test]
+    fn reads_orientation_in_both_byte_orders() {
+        assert_eq!(tiff_orientation(&tiff(false, 6)), Some(6));
+        assert_eq!(tiff_orientation(&tiff(true, 8)), Some(8));
+    }
+
+    #[test]
+    fn ignores_out_of_range_and_truncated_blocks() {
+        assert_eq!(tiff_orientation(&tiff(false, 9)), None);
+        assert_eq!(tiff_orientation(&tiff(true, 3)[..12]), None);
+    }
+}
@@ -0,0 +1,128 @@
+// --- SINGLE INSTANCE ---
+// The first window listens on a localhost port and writes it (plus a random token) to
+// INSTANCE_FILE. Later launches, e.g. from the file-manager menu or a link, hand their
+// arguments to that window and exit instead of opening a second one.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const INSTANCE_FILE: &str = "instance.json";
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(300);
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Next to the profiles, so every launch finds it whatever its working directory
+fn lock_path() -> PathBuf {
+    crate::profile::data_root().join(INSTANCE_FILE)
+}
+
+#[derive(Serialize, Deserialize)]
+struct LockFile {
+    port: u16,
+    token: String,
+}
+
+// What a launch asks for; also what gets forwarded to the running window
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Request {
+    #[serde(default)]
+    token: String,
+    pub attach: Option<PathBuf>, // Absolute, since the running window has its own cwd
+    pub link: Option<String>,
+}
+
+impl Request {
+    pub fn new(attach: Option<PathBuf>, link: Option<String>) -> Self {
+        let attach = attach.map(|p| std::env::current_dir().map(|cwd| cwd.join(&p)).unwrap_or(p));
+        Self { token: String::new(), attach, link }
+    }
+}
+
+// True when a running window accepted the request
+pub fn forward(request: &Request) -> bool {
+    let Some(lock) = std::fs::read_to_string(lock_path()).ok().and_then(|raw| serde_json::from_str::<LockFile>(&raw).ok()) else {
+        return false;
+    };
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, lock.port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) else {
+        return false; // Stale file from a crashed window
+    };
+    let _ = stream.set_read_timeout(Some(REPLY_TIMEOUT));
+    let request = Request { token: lock.token, ..request.clone() };
+    let Ok(line) = serde_json::to_string(&request) else { return false };
+    if writeln!(stream, "{}", line).is_err() {
+        return false;
+    }
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == "ok"
+}
+
+pub struct Listener {
+    listener: TcpListener,
+    token: String,
+}
+
+// Claims the instance file for this process
+pub fn listen() -> Result<Listener, String> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).map_err(|e| e.to_string())?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let token = new_token();
+    let raw = serde_json::to_string(&LockFile { port, token: token.clone() }).map_err(|e| e.to_string())?;
+    let _ = std::fs::create_dir_all(crate::profile::data_root());
+    std::fs::write(lock_path(), raw).map_err(|e| format!("{}: {}", lock_path().display(), e))?;
+    Ok(Listener { listener, token })
+}
+
+impl Listener {
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    // Accepts forwarded requests on a background thread for the rest of the process
+    pub fn serve(self, on_request: impl Fn(Request) + Send + 'static) {
+        std::thread::spawn(move || {
+            for stream in self.listener.incoming().flatten() {
+                let _ = stream.set_read_timeout(Some(REPLY_TIMEOUT));
+                let mut line = String::new();
+                let Ok(mut reader) = stream.try_clone().map(BufReader::new) else { continue };
+                if reader.read_line(&mut line).is_err() {
+                    continue;
+                }
+                // Anything without our token (another local program) is ignored
+                match serde_json::from_str::<Request>(&line) {
+                    Ok(request) if request.token == self.token => {
+                        let _ = writeln!(&stream, "ok");
+                        on_request(request);
+                    }
+                    _ => {
+                        let _ = writeln!(&stream, "denied");
+                    }
+                }
+            }
+        });
+    }
+}
+
+// Removes the instance file if it is still ours
+pub fn release(token: &str) {
+    let ours = std::fs::read_to_string(lock_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str::<LockFile>(&raw).ok())
+        .is_some_and(|lock| lock.token == token);
+    if ours {
+        let _ = std::fs::remove_file(lock_path());
+    }
+}
+
+// Not cryptographic; it only has to be unguessable by other local programs in practice
+fn new_token() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0));
+    hasher.write_u32(std::process::id());
+    let high = hasher.finish();
+    hasher.write_u64(high);
+    format!("{:016x}{:016x}", high, hasher.finish())
+}
@@ -0,0 +1,68 @@
+// --- JOB JOURNAL ---
+// Long-running jobs (model pulls, search index builds) are written down when they start
+// and crossed off when they end. Anything still listed at launch was interrupted by a
+// close or crash and can be resumed instead of being forgotten.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+pub const JOBS_FILE: &str = "jobs.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum JobKind {
+    Pull { tag: String }, // Ollama keeps the layers it already downloaded, so a re-pull continues
+    IndexSync,            // Search index; unchanged sessions are skipped on the next run
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Job {
+    pub kind: JobKind,
+    pub started: i64, // Unix seconds
+}
+
+impl Job {
+    pub fn label(&self) -> String {
+        match &self.kind {
+            JobKind::Pull { tag } => format!("Pull of {}", tag),
+            JobKind::IndexSync => "Search index build".to_string(),
+        }
+    }
+}
+
+pub fn load(path: &Path) -> Vec<Job> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &Path, jobs: &[Job]) -> Result<(), String> {
+    if jobs.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("{}: {}", path.display(), e)),
+            _ => Ok(()),
+        };
+    }
+    let raw = serde_json::to_string_pretty(jobs).map_err(|e| e.to_string())?;
+    fs::write(path, raw).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Re-beginning a job that is already listed just refreshes its start time
+pub fn begin(path: &Path, kind: JobKind) -> Result<(), String> {
+    let mut jobs = load(path);
+    jobs.retain(|j| j.kind != kind);
+    jobs.push(Job { kind, started: chrono::Utc::now().timestamp() });
+    save(path, &jobs)
+}
+
+// Finished, failed or discarded: either way it is no longer pending
+pub fn finish(path: &Path, kind: &JobKind) -> Result<(), String> {
+    let mut jobs = load(path);
+    let before = jobs.len();
+    jobs.retain(|j| j.kind != *kind);
+    if jobs.len() == before {
+        return Ok(());
+    }
+    save(path, &jobs)
+}
@@ -0,0 +1,259 @@
+// --- LAB REPORT GENERATOR ---
+// Guided write-up of an experiment: the model drafts the abstract, methods, data analysis
+// and conclusion one section at a time (each step sees the ones before it), the CSV is
+// plotted with a small matplotlib script, and everything is assembled into Markdown or LaTeX.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const PREVIEW_ROWS: usize = 20; // CSV rows shown to the model besides the column statistics
+
+const WRITER: &str = "You are helping an engineering student write a lab report. Write only the \
+requested section in a clear, formal, past-tense register. Use the experiment details and data \
+you are given; do not invent measurements. No heading, no preamble.";
+
+// (heading, instruction) in the order they are generated
+const SECTIONS: [(&str, &str); 4] = [
+    ("Abstract", "Write a 100-150 word abstract: objective, method in one sentence, key result."),
+    ("Methods", "Describe the procedure and setup using the equipment list. Mention what was measured."),
+    ("Analysis", "Analyse the attached data: trends, ranges, notable points and sources of error. Quote numbers from the statistics."),
+    ("Conclusion", "Conclude whether the objective was met and what could be improved next time."),
+];
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Latex,
+}
+
+impl ReportFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Markdown => "Markdown",
+            Self::Latex => "LaTeX",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Latex => "tex",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LabReportSpec {
+    pub title: String,
+    pub objective: String,
+    pub equipment: String,
+    pub data_file: String, // CSV with a header row
+    pub format: ReportFormat,
+}
+
+struct Column {
+    name: String,
+    values: Vec<f64>, // Numeric cells only
+}
+
+pub struct CsvData {
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    columns: Vec<Column>,
+}
+
+impl CsvData {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let raw = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut lines = raw.lines().filter(|l| !l.trim().is_empty());
+        let split = |line: &str| line.split(',').map(|c| c.trim().trim_matches('"').to_string()).collect::<Vec<_>>();
+        let header = split(lines.next().ok_or_else(|| format!("{}: empty file", path.display()))?);
+        let rows: Vec<Vec<String>> = lines.map(split).collect();
+        if rows.is_empty() {
+            return Err(format!("{}: no data rows", path.display()));
+        }
+        let columns = header
+            .iter()
+            .enumerate()
+            .map(|(i, name)| Column {
+                name: name.clone(),
+                values: rows.iter().filter_map(|r| r.get(i)?.parse::<f64>().ok()).collect(),
+            })
+            .collect();
+        Ok(Self { header, rows, columns })
+    }
+
+    // Column statistics plus the first rows, as plain text for the prompt
+    pub fn describe(&self) -> String {
+        let mut out = format!("{} rows, columns: {}\n", self.rows.len(), self.header.join(", "));
+        for column in self.columns.iter().filter(|c| !c.values.is_empty()) {
+            let n = column.values.len() as f64;
+            let min = column.values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let max = column.values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let mean = column.values.iter().sum::<f64>() / n;
+            out.push_str(&format!("- {}: min {:.4}, max {:.4}, mean {:.4}\n", column.name, min, max, mean));
+        }
+        out.push_str(&format!("\nFirst rows:\n{}\n", self.header.join(",")));
+        for row in self.rows.iter().take(PREVIEW_ROWS) {
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    fn numeric_columns(&self) -> usize {
+        self.columns.iter().filter(|c| c.values.len() == self.rows.len()).count()
+    }
+}
+
+// First column on x, every other numeric column as a series. Needs python3 with matplotlib.
+const PLOT_SCRIPT: &str = r#"
+import csv, sys
+import matplotlib
+matplotlib.use("Agg")
+import matplotlib.pyplot as plt
+with open(sys.argv[1], newline="") as f:
+    rows = list(csv.reader(f))
+header, rows = rows[0], [r for r in rows[1:] if r]
+def num(v):
+    try: return float(v)
+    except ValueError: return None
+cols = list(zip(*rows))
+x = [num(v) for v in cols[0]]
+fig, ax = plt.subplots(figsize=(7, 4))
+for name, col in zip(header[1:], cols[1:]):
+    y = [num(v) for v in col]
+    if all(v is not None for v in y):
+        ax.plot(x, y, marker=".", label=name.strip())
+ax.set_xlabel(header[0].strip())
+ax.grid(True, alpha=0.3)
+ax.legend()
+fig.tight_layout()
+fig.savefig(sys.argv[2], dpi=150)
+"#;
+
+// Blocking
+pub fn plot(data_file: &Path, out: &Path) -> Result<(), String> {
+    let script = std::env::temp_dir().join(format!("ship_plot_{}.py", std::process::id()));
+    std::fs::write(&script, PLOT_SCRIPT).map_err(|e| format!("{}: {}", script.display(), e))?;
+    let result = std::process::Command::new("python3").arg(&script).arg(data_file).arg(out).output();
+    let _ = std::fs::remove_file(&script);
+    let output = result.map_err(|e| format!("python3: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("plot failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+    }
+    Ok(())
+}
+
+// Blocking: `progress` is called before each step. Returns the report file.
+pub fn generate(
+    backend: &BackendConfig,
+    model: &str,
+    spec: &LabReportSpec,
+    mut progress: impl FnMut(&str),
+) -> Result<PathBuf, String> {
+    let data_path = PathBuf::from(spec.data_file.trim());
+    let data = CsvData::load(&data_path)?;
+    let stem = data_path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "lab".to_string());
+    let dir = data_path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+    // 1. Sections, each generated with the experiment details and what came before
+    let details = format!(
+        "Title: {}\nObjective: {}\nEquipment:\n{}\n\nData ({}):\n{}",
+        spec.title, spec.objective, spec.equipment, data_path.display(), data.describe()
+    );
+    let mut sections: Vec<(&str, String)> = Vec::new();
+    for (heading, instruction) in SECTIONS {
+        progress(&format!("Writing {}...", heading.to_lowercase()));
+        let so_far: String = sections.iter().map(|(h, text)| format!("## {}\n{}\n\n", h, text)).collect();
+        let prompt = format!("{}\n\nSections written so far:\n{}\nTask: {}", details, so_far, instruction);
+        let text = llm::complete(backend, model, WRITER, &prompt)?;
+        sections.push((heading, text.trim().to_string()));
+    }
+
+    // 2. Plot; a missing python3/matplotlib leaves a note instead of failing the report
+    let figure = if data.numeric_columns() >= 2 {
+        progress("Plotting data...");
+        let png = dir.join(format!("{}_plot.png", stem));
+        plot(&data_path, &png).map(|()| png)
+    } else {
+        Err("not enough numeric columns to plot".to_string())
+    };
+
+    // 3. Assemble next to the data file
+    progress("Assembling report...");
+    let report = match spec.format {
+        ReportFormat::Markdown => markdown(spec, &sections, &figure),
+        ReportFormat::Latex => latex(spec, &sections, &figure),
+    };
+    let path = dir.join(format!("{}_report.{}", stem, spec.format.extension()));
+    std::fs::write(&path, report).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn markdown(spec: &LabReportSpec, sections: &[(&str, String)], figure: &Result<PathBuf, String>) -> String {
+    let mut out = format!("# {}\n\n**Objective:** {}\n\n", spec.title, spec.objective);
+    out.push_str("**Equipment:**\n\n");
+    for item in spec.equipment.lines().filter(|l| !l.trim().is_empty()) {
+        out.push_str(&format!("- {}\n", item.trim().trim_start_matches(['-', '*']).trim()));
+    }
+    for (heading, text) in sections {
+        out.push_str(&format!("\n## {}\n\n{}\n", heading, text));
+        if *heading == "Analysis" {
+            match figure {
+                Ok(png) => out.push_str(&format!("\n![Measured data]({})\n", file_name(png))),
+                Err(e) => out.push_str(&format!("\n_Plot not generated: {}_\n", e)),
+            }
+        }
+    }
+    out
+}
+
+fn latex_escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' => r"\textbackslash{}".to_string(),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", c),
+            '~' => r"\textasciitilde{}".to_string(),
+            '^' => r"\textasciicircum{}".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn latex(spec: &LabReportSpec, sections: &[(&str, String)], figure: &Result<PathBuf, String>) -> String {
+    let mut out = String::from("\\documentclass[11pt]{article}\n\\usepackage{graphicx}\n\\usepackage[margin=1in]{geometry}\n");
+    out.push_str(&format!("\\title{{{}}}\n\\date{{\\today}}\n\\begin{{document}}\n\\maketitle\n\n", latex_escape(&spec.title)));
+    out.push_str(&format!("\\noindent\\textbf{{Objective:}} {}\n\n", latex_escape(&spec.objective)));
+    out.push_str("\\noindent\\textbf{Equipment:}\n\\begin{itemize}\n");
+    for item in spec.equipment.lines().filter(|l| !l.trim().is_empty()) {
+        out.push_str(&format!("  \\item {}\n", latex_escape(item.trim().trim_start_matches(['-', '*']).trim())));
+    }
+    out.push_str("\\end{itemize}\n");
+    for (heading, text) in sections {
+        if *heading == "Abstract" {
+            out.push_str(&format!("\n\\begin{{abstract}}\n{}\n\\end{{abstract}}\n", latex_escape(text)));
+            continue;
+        }
+        out.push_str(&format!("\n\\section{{{}}}\n{}\n", heading, latex_escape(text)));
+        if *heading == "Analysis" {
+            match figure {
+                Ok(png) => out.push_str(&format!(
+                    "\n\\begin{{figure}}[h]\n\\centering\n\\includegraphics[width=0.8\\textwidth]{{{}}}\n\\caption{{Measured data}}\n\\end{{figure}}\n",
+                    file_name(png)
+                )),
+                Err(e) => out.push_str(&format!("\n\\emph{{Plot not generated: {}}}\n", latex_escape(e))),
+            }
+        }
+    }
+    out.push_str("\n\\end{document}\n");
+    out
+}
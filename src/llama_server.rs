@@ -0,0 +1,217 @@
+// --- LLAMA.CPP SERVER ---
+// `llama-server` without Ollama in front: its /health and /slots routes for the status
+// line, and the native /completion route for builds that predate /v1/chat/completions.
+// There the chat is rendered by the server's own /apply-template when it has one, else
+// with the template picked in the backend settings.
+
+use crate::backend::BackendConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+// How a chat becomes one /completion prompt
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ChatTemplate {
+    #[default]
+    Server, // The GGUF's own template via /apply-template; ChatML when the route is missing
+    ChatMl,
+    Llama3,
+    Mistral,
+    Gemma,
+}
+
+impl ChatTemplate {
+    pub const ALL: [ChatTemplate; 5] = [Self::Server, Self::ChatMl, Self::Llama3, Self::Mistral, Self::Gemma];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Server => "From the model",
+            Self::ChatMl => "ChatML",
+            Self::Llama3 => "Llama 3",
+            Self::Mistral => "Mistral [INST]",
+            Self::Gemma => "Gemma",
+        }
+    }
+
+    // chat.completions messages -> one prompt ending at the assistant's turn, and the
+    // strings that end that turn
+    fn render(self, messages: &[Value]) -> (String, Vec<&'static str>) {
+        let turns: Vec<(&str, String)> = messages.iter().map(|m| (m["role"].as_str().unwrap_or("user"), text_of(m))).collect();
+        let mut prompt = String::new();
+        match self {
+            Self::Server | Self::ChatMl => {
+                for (role, text) in &turns {
+                    prompt.push_str(&format!("<|im_start|>{}\n{}<|im_end|>\n", role, text));
+                }
+                prompt.push_str("<|im_start|>assistant\n");
+                (prompt, vec!["<|im_end|>"])
+            }
+            Self::Llama3 => {
+                prompt.push_str("<|begin_of_text|>");
+                for (role, text) in &turns {
+                    prompt.push_str(&format!("<|start_header_id|>{}<|end_header_id|>\n\n{}<|eot_id|>", role, text));
+                }
+                prompt.push_str("<|start_header_id|>assistant<|end_header_id|>\n\n");
+                (prompt, vec!["<|eot_id|>"])
+            }
+            // No system role: the system prompt leads the first user turn
+            Self::Mistral => {
+                let mut system = String::new();
+                for (role, text) in &turns {
+                    match *role {
+                        "system" => system = format!("{}\n\n", text),
+                        "assistant" => prompt.push_str(&format!(" {}</s>", text)),
+                        _ => prompt.push_str(&format!("[INST] {}{} [/INST]", std::mem::take(&mut system), text)),
+                    }
+                }
+                (format!("<s>{}", prompt), vec!["</s>"])
+            }
+            Self::Gemma => {
+                let mut system = String::new();
+                for (role, text) in &turns {
+                    match *role {
+                        "system" => system = format!("{}\n\n", text),
+                        "assistant" => prompt.push_str(&format!("<start_of_turn>model\n{}<end_of_turn>\n", text)),
+                        _ => prompt.push_str(&format!("<start_of_turn>user\n{}{}<end_of_turn>\n", std::mem::take(&mut system), text)),
+                    }
+                }
+                prompt.push_str("<start_of_turn>model\n");
+                (prompt, vec!["<end_of_turn>"])
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LlamaStatus {
+    pub health: String,     // "ok", "loading model", or the error the server gave
+    pub slots: usize,       // Parallel sequences (-np)
+    pub busy: usize,        // Slots processing a request right now
+    pub n_ctx: Option<u64>, // Context per slot
+    pub model: String,
+}
+
+impl LlamaStatus {
+    // "ok · 1/4 slots busy · 8192 ctx · qwen2.5-7b-instruct-q4_k_m.gguf"
+    pub fn summary(&self) -> String {
+        let mut parts = vec![self.health.clone()];
+        if self.slots > 0 {
+            parts.push(format!("{}/{} slots busy", self.busy, self.slots));
+        }
+        if let Some(n) = self.n_ctx {
+            parts.push(format!("{} ctx", n));
+        }
+        if !self.model.is_empty() {
+            parts.push(self.model.clone());
+        }
+        parts.join(" · ")
+    }
+}
+
+pub async fn status(config: &BackendConfig) -> Result<LlamaStatus, String> {
+    let client = config.client()?;
+    let mut status = LlamaStatus::default();
+
+    // 1. /health: 200 when ready, 503 while the model loads
+    let res = client.get(format!("{}/health", config.uri())).send().await.map_err(|e| e.to_string())?;
+    let ok = res.status().is_success();
+    let body: Value = res.json().await.unwrap_or_default();
+    status.health = match (ok, body["status"].as_str(), body["error"]["message"].as_str()) {
+        (true, Some(s), _) => s.to_string(),
+        (true, None, _) => "ok".to_string(),
+        (false, _, Some(message)) => message.to_string(),
+        (false, Some(s), _) => s.to_string(),
+        (false, None, None) => "unavailable".to_string(),
+    };
+
+    // 2. /slots is disabled on some servers (--no-slots); then only health is shown
+    if let Ok(res) = client.get(format!("{}/slots", config.uri())).send().await {
+        if let Ok(Value::Array(slots)) = res.json::<Value>().await {
+            status.slots = slots.len();
+            // Newer builds report is_processing, older ones state 0 = idle
+            status.busy = slots
+                .iter()
+                .filter(|s| s["is_processing"].as_bool().unwrap_or_else(|| s["state"].as_u64().is_some_and(|state| state != 0)))
+                .count();
+            status.n_ctx = slots.first().and_then(|s| s["n_ctx"].as_u64());
+        }
+    }
+
+    // 3. Which GGUF is loaded
+    if let Ok(res) = client.get(format!("{}/v1/models", config.uri())).send().await {
+        if let Ok(models) = res.json::<Value>().await {
+            status.model = models["data"][0]["id"].as_str().unwrap_or_default().rsplit('/').next().unwrap_or_default().to_string();
+        }
+    }
+    Ok(status)
+}
+
+fn text_of(message: &Value) -> String {
+    match &message["content"] {
+        Value::String(text) => text.clone(),
+        // Content parts: the text only, /completion has no image input here
+        Value::Array(parts) => parts.iter().filter_map(|p| p["text"].as_str()).collect::<Vec<_>>().join("\n"),
+        _ => String::new(),
+    }
+}
+
+// The chat rendered with the GGUF's template; None when this build has no /apply-template
+async fn apply_template(config: &BackendConfig, messages: &[Value]) -> Result<Option<String>, String> {
+    let res = config.client()?
+        .post(format!("{}/apply-template", config.uri()))
+        .json(&json!({ "messages": messages }))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if res.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !res.status().is_success() {
+        let status = res.status();
+        let text = res.text().await.unwrap_or_default();
+        return Err(format!("/apply-template: {}: {}", status, text));
+    }
+    let reply: Value = res.json().await.map_err(|e| e.to_string())?;
+    Ok(reply["prompt"].as_str().map(String::from))
+}
+
+// `chat_body` is a chat.completions request; its options carry over where they exist
+pub async fn completion_body(config: &BackendConfig, chat_body: &Value) -> Result<Value, String> {
+    let messages = chat_body["messages"].as_array().cloned().unwrap_or_default();
+    // 1. The server's template ends the turn with the model's own end-of-generation token,
+    //    so no stop strings are needed; a fixed template brings its own
+    let server = match config.chat_template {
+        ChatTemplate::Server => apply_template(config, &messages).await?,
+        _ => None,
+    };
+    let (prompt, stop) = match server {
+        Some(prompt) => (prompt, Vec::new()),
+        None => config.chat_template.render(&messages),
+    };
+    let mut body = json!({
+        "prompt": prompt,
+        "stream": chat_body["stream"].as_bool().unwrap_or(false),
+        "stop": stop,
+        "cache_prompt": true,
+    });
+    for (from, to) in [("temperature", "temperature"), ("top_p", "top_p"), ("max_tokens", "n_predict"), ("seed", "seed")] {
+        if !chat_body[from].is_null() {
+            body[to] = chat_body[from].clone();
+        }
+    }
+    Ok(body)
+}
+
+pub async fn post_completion(config: &BackendConfig, body: &Value) -> Result<reqwest::Response, String> {
+    let res = config.client()?
+        .post(format!("{}/completion", config.uri()))
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        let status = res.status();
+        let text = res.text().await.unwrap_or_default();
+        return Err(format!("{}: {}", status, text));
+    }
+    Ok(res)
+}
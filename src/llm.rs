@@ -0,0 +1,18 @@
+// --- ONE-SHOT LLM CALLS ---
+// Small blocking helpers for background jobs (summaries, classifiers) that need a
+// single answer rather than a streamed chat.
+
+use crate::backend::BackendConfig;
+use ollama_rs::generation::chat::request::ChatMessageRequest;
+use ollama_rs::generation::chat::{ChatMessage, MessageRole};
+
+// Blocking: call from a worker thread or `spawn_blocking`, never from async code
+pub fn complete(backend: &BackendConfig, model: &str, system: &str, prompt: &str) -> Result<String, String> {
+    let messages = vec![
+        ChatMessage::new(MessageRole::System, system.to_string()),
+        ChatMessage::new(MessageRole::User, prompt.to_string()),
+    ];
+    let request = ChatMessageRequest::new(model.to_string(), messages);
+
+    crate::runtime::block_on(crate::chat_backend::for_config(backend).chat(&request))
+}
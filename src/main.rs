@@ -1,31 +1,144 @@
+mod analytics;
+mod assets;
+mod autocomplete;
+mod backend;
+mod batch;
+mod calendar;
+mod chat_backend;
+mod chatgpt_import;
+mod confidence;
+mod config;
+mod context;
+mod desktop;
+mod email;
+mod export;
+mod finetune;
+mod folder_summary;
+mod glossary;
+mod gpu;
+mod images;
+mod instance;
+mod jobs;
+mod lab_report;
+mod llama_server;
+mod llm;
+mod modelfile;
+mod notation;
+mod organizer;
+mod pdf_export;
+mod pdf_figures;
+mod pdf_toc;
+mod pomodoro;
+mod practice;
+mod profile;
+mod quantize;
+mod render;
+mod replay;
+mod runtime;
+mod rag_trigger;
+mod refusal;
+mod research;
+mod retrieval_eval;
+mod search_index;
+mod secrets;
+mod session;
+mod session_store;
+mod shell;
+mod sketch;
+mod summary;
+mod system_monitor;
+mod tee;
+mod telemetry;
+mod timestamps;
+mod topics;
+mod translation;
+mod tts;
+mod voice_chat;
+mod voice_memo;
+mod warmup;
+mod word_cloud;
+
 #[cfg(feature = "gui")]
 mod gui {
     use eframe::egui;
-    use std::fs;
-    use std::process::Command;
-    use serde::{Deserialize, Serialize};
-    use std::sync::mpsc;
     use std::thread;
     use arboard::Clipboard;
 
     // Ollama Imports
-    use ollama_rs::Ollama;
-    use ollama_rs::generation::chat::{ChatMessage, MessageRole};
     use ollama_rs::generation::chat::request::ChatMessageRequest;
     use ollama_rs::generation::images::Image;
+    use ollama_rs::generation::options::GenerationOptions;
 
-    // --- 1. DATA STRUCTURES ---
+    use crate::config::AppConfig;
+    use crate::export::{DatasetFormat, TurnFilter};
+    use crate::finetune::FinetuneJob;
+    use crate::modelfile::ModelfileSpec;
+    use crate::research::TimeRange;
+    use crate::profile::Profile;
+    use crate::session::Message;
 
-    const SESSIONS_DIR: &str = "sessions";
-    // Your custom system profile
-    const USER_PROFILE: &str = "You are an Electrical Engineering student at Texas State University named Raul. You have a strong background in circuits, signal processing, and embedded systems. Concentration on Micro and Nano Device Systems. Always provide detailed explanations and practical examples."; 
+    // UI panels
+    mod email_panel;
+    mod error_boundary;
+    mod calendar_panel;
+    mod export_panel;
+    mod figures_panel;
+    mod finetune_panel;
+    mod folder_summary_panel;
+    mod gallery_panel;
+    mod input_history;
+    mod jobs_panel;
+    mod lab_report_panel;
+    mod modelfile_panel;
+    mod organizer_panel;
+    mod overrides_panel;
+    mod persona_panel;
+    mod pomodoro_panel;
+    mod practice_panel;
+    mod attach;
+    mod autocomplete_input;
+    mod backend_panel;
+    mod chat_view;
+    mod definitions;
+    mod deep_link;
+    mod model_card;
+    mod model_manager;
+    mod model_unload;
+    mod models_panel;
+    mod navigation;
+    mod profile_panel;
+    mod pull_panel;
+    mod quant_panel;
+    mod rag_routing;
+    mod reactions;
+    mod replay_panel;
+    mod research_panel;
+    mod retrieval_eval_panel;
+    mod retry_trace;
+    mod search_panel;
+    mod secrets_panel;
+    mod session_summary;
+    mod sessions_panel;
+    mod settings_window;
+    mod shutdown;
+    mod sketch_panel;
+    mod status_bar;
+    mod tee_panel;
+    mod telemetry_panel;
+    mod time_settings;
+    mod toasts;
+    mod topics_panel;
+    mod translation_check;
+    mod voice_chat_panel;
+    mod voice_memo_panel;
+    mod voice_panel;
+    mod warmup_panel;
+    mod word_cloud_panel;
 
-    #[derive(Serialize, Deserialize, Clone, Debug)]
-    struct Message {
-        role: String,
-        has_image: bool,
-        content: String, 
-    }
+    // --- 1. DATA STRUCTURES ---
+
+    const EVENT_CAPACITY: usize = 4096; // Worker -> UI messages in flight before senders block
+    const EVENT_LOG_LINES: usize = 1000; // Log panel history; the oldest lines go first
 
     // [NEW] The State Machine for the GUI
     #[derive(PartialEq, Debug)]
@@ -36,290 +149,1301 @@ mod gui {
     }
 
     struct ShipApp {
+        profile: Profile,          // Whose config and sessions are loaded
+        new_profile_name: String,
+
         // UI State
         input_text: String,
         current_file: String,
         messages: Vec<Message>,
         models: Vec<String>,
+        installed_models: Vec<crate::backend::LocalModel>, // Last /api/tags answer, with sizes
+        confirm_delete_model: Option<String>,
+        deleting_model: Option<String>,
         selected_model: String,
-        vram_usage: (u64, u64),
+        gpu: crate::gpu::Reading, // Latest from the VRAM poller
+        host: crate::system_monitor::Reading, // RAM and CPU, from the same poller
+        vram_poll_ms: std::sync::Arc<std::sync::atomic::AtomicU64>, // Read by the poller before each sleep
+        config: AppConfig,         // Persisted preferences (model layout, ...)
         
         // Research & Agent State
         state: AppState,           // [CHANGED] Replaces simple booleans
         research_results: String,  // Buffer for search results
+        research_sources: Vec<crate::research::SourceChunk>, // Structured form of research_results
+        reply_sources: Vec<crate::research::SourceChunk>,    // Excerpts for the reply being generated
+        reply_model: String, // Model of the reply being generated, recorded on its message
         research_dir: String,      // Path to your research docs
-        is_reasoning_mode: bool,   // Toggle for "Deep Research" logic
+        rag_next: Option<bool>,    // One-shot override of the RAG mode for the next prompt
+        last_rag_decision: Option<crate::rag_trigger::Decision>,
+        pending_retry: Option<(String, String)>, // (model, prompt) the next generation sends instead
+        retry_trace: Vec<crate::refusal::RetryTrace>, // Refusals caught in the open chat
+        time_range: TimeRange,     // Only retrieve documents modified within this window
+        research_section: String,  // Only retrieve from matching PDF chapters/sections ("Chapter 6")
         
         // Vision & Context Buffers
         current_image_base64: Option<String>,
         current_image_path: Option<String>,
 
+        // Sketch Pad
+        show_sketch: bool,
+        sketch_strokes: Vec<crate::sketch::Stroke>,
+        sketch_width: f32,
+        sketch_color: [u8; 3],
+
+        // Status Bar & Log
+        activity: String,              // Current operation shown bottom-left
+        last_error: Option<String>,
+        event_log: std::collections::VecDeque<String>,
+        show_log: bool,
+        resident_models: Vec<String>,  // Models Ollama currently holds in memory
+        model_last_used: std::collections::HashMap<String, std::time::Instant>, // For unloading the idlest model first
+        last_warmup_check: Option<std::time::Instant>,
+        warmups_run: std::collections::HashMap<usize, chrono::NaiveDate>, // Schedule index -> day it last fired
+        last_unload: Option<std::time::Instant>,
+        last_resident_poll: Option<std::time::Instant>,
+
+        // Backend Settings
+        backend_secret_input: String, // Typed token, cleared once it is in the keyring
+        backend_secret_status: String,
+        llama_status: String, // Health and slots of a llama-server backend
+
+        // Secrets Window
+        show_secrets: bool,
+        secret_name_input: String,
+        secret_value_input: String,
+        secret_present: std::collections::HashMap<String, bool>, // Keyring lookups by account; dropped when it is edited
+
+        // Text to Speech
+        tts_voices: Vec<String>,
+        tts_child: Option<std::process::Child>,
+        voice_chat: Option<crate::voice_chat::Microphone>, // Conversation mode is on while Some
+
+        // Dataset Export
+        export_sessions: Vec<export_panel::SessionEntry>,
+        export_format: DatasetFormat,
+        export_filter: TurnFilter,
+        export_status: String,
+
+        // Fine-tune Lab
+        finetune_job: FinetuneJob,
+        finetune_running: bool,
+        finetune_log: Vec<String>,
+
+        // Modelfile Editor
+        show_modelfile_editor: bool,
+        modelfile_spec: ModelfileSpec,
+        modelfile_running: bool,
+        modelfile_log: Vec<String>,
+
+        // Model Card Drawer
+        show_model_card: bool,
+        model_card: Option<crate::backend::ModelCard>,
+        model_card_status: String,
+        model_card_for: String, // Model the card was last requested for
+
+        // Low-VRAM Mode
+        fit_checked_model: String, // Last model compared against VRAM
+        quant_suggestions: Vec<crate::quantize::Suggestion>, // Empty when the model fits
+        pending_pull: Option<crate::quantize::Suggestion>,  // Awaiting confirmation
+        pulling: bool,
+        pull_status: String,
+        pull_progress: Option<f32>, // Layer being downloaded, 0..1
+        show_pull: bool,
+        pull_tag: String,
+
+        // Session Replay
+        show_replay: bool,
+        replay: Option<replay_panel::ReplayState>,
+
+        // History Search
+        show_search: bool,
+        search_index: Option<crate::search_index::SearchIndex>, // Opened on first search
+        search_query: String,
+        search_filter: crate::search_index::SearchFilter,
+        search_time_range: TimeRange,
+        search_hits: Vec<crate::search_index::SearchHit>,
+        search_models: Vec<String>,
+        search_tags: Vec<String>,
+        search_status: String,
+        importing: bool, // ChatGPT import running
+        show_topics: bool,
+        topic_index: Vec<crate::topics::Topic>, // Loaded when the window opens
+        topic_filter: String,
+        selected_topic: Option<String>,
+        topics_busy: bool,
+        topics_status: String,
+        show_word_cloud: bool,
+        cloud_history: bool, // Whole history instead of the open chat
+        cloud_terms: Vec<crate::word_cloud::Term>,
+        cloud_topics: Vec<(String, usize)>,
+        cloud_busy: bool,
+        session_tags: Vec<String>, // Tags of the open chat, written to its metadata
+        timer_events: Vec<crate::session::TimerEvent>, // Study-timer marks of the open chat
+        overrides: crate::session::GenerationOverrides, // Temperature/persona/RAG for the open chat only
+        session_zone: Option<crate::timestamps::CreatedZone>, // Where the open chat was created; None = this machine
+        pomodoro_started: Option<std::time::Instant>,  // Running study block
+
+        // Back-translation Badges
+        translation_checks: std::collections::HashMap<usize, crate::translation::TranslationCheck>, // By message index
+        reactions: std::collections::HashMap<usize, crate::session::Reaction>, // 👍/👎 and labels, by message index
+        show_analytics: bool,
+        analytics: Vec<crate::analytics::ModelStats>,
+
+        // Keyboard Navigation
+        focused_message: Option<usize>,
+        focus_mode: bool, // Dim everything outside the focused exchange
+        expanded_messages: std::collections::HashSet<usize>,
+        expand_request: Option<(usize, bool)>, // Applied for one frame
+        scroll_to_focus: bool,
+
+        // Action Items
+        show_calendar: bool,
+        calendar_items: Vec<crate::calendar::ActionItem>, // Awaiting review
+        calendar_busy: bool,
+        calendar_status: String,
+        show_lab_report: bool,
+        lab_report: crate::lab_report::LabReportSpec, // Form contents
+        lab_report_busy: bool,
+        lab_report_status: String,
+        show_settings: bool,
+        show_retrieval_eval: bool,
+        eval_set: crate::retrieval_eval::EvalSet, // Loaded when the window opens
+        eval_previous: Option<crate::retrieval_eval::EvalReport>, // Baseline for the deltas
+        eval_busy: bool,
+        eval_status: String,
+        history_cursor: Option<usize>,     // Recalled prompt, 0 = newest
+        history_search: Option<String>,    // Ctrl+R query while the popup is open
+        composer_error: Option<String>,    // Why the last send was refused, shown by the input
+
+        // Email Drafts
+        email_draft: Option<crate::email::EmailDraft>, // Open while Some
+        email_status: String,
+
+        // File Organizer
+        show_organizer: bool,
+        organizer_dir: String,
+        organizer_instruction: String,
+        organizer_plan: Vec<crate::organizer::PlannedMove>, // Proposed, not yet executed
+        organizer_busy: bool,
+        organizer_status: String,
+
+        // Practice Problems
+        practice_mode: bool,
+        practice_problem: Option<crate::practice::PracticeProblem>, // Active problem; input is graded against it
+
+        // Shutdown
+        cancel_flag: std::sync::Arc<std::sync::atomic::AtomicBool>, // Checked by worker threads
+        allow_close: bool,
+        show_exit_confirm: bool,
+        summarizing_before_exit: bool, // Close is deferred until the summary lands
+        exit_ready: bool,
+        last_checkpoint: std::time::Instant, // Last partial-reply write
+
+        toasts: Vec<toasts::Toast>,
+        conversation_size: ((usize, usize), crate::summary::ConversationSize), // Keyed by (message count, last message length)
+        expand_long_messages: bool, // Render very long messages in full instead of paged
+        interrupted_jobs: Vec<crate::jobs::Job>, // Left in the job journal by the previous run
+        completions: crate::autocomplete::Trie, // Vocabulary for input autocomplete
+        completion_indexed: usize,              // Messages already in `completions`
+        definitions: std::collections::HashMap<String, Option<String>>, // Ctrl+hover lookups by term; None while loading
+        hover_definition: Option<definitions::DefinitionPopup>,
+        hover_candidate: Option<(String, std::time::Instant)>, // Term under the pointer and since when
+        show_gallery: bool,
+        pinned_document: Option<(String, String)>, // (name, text) sent as context with every prompt
+        store: crate::session_store::SessionStore, // Where sessions are read and written
+        session_list: Vec<crate::session_store::StoredSession>, // Session browser rows
+        session_find: String,
+        session_find_hits: Vec<crate::session_store::PhraseHit>,
+        session_find_done: bool, // A search ran, so show its results (even if none)
+        renaming_session: Option<(String, String)>, // (file, title being typed)
+        tees: Vec<crate::tee::Tee>,                  // Open for the reply being streamed
+        session_tee: Option<(String, std::path::PathBuf)>, // (session file, target) for per-chat tee
+        confirm_delete_session: Option<String>,
+        unsaved_chat: Option<crate::session::SessionFile>, // LATEST_FILE from last time, until restored or archived
+        gallery: Option<gallery_panel::Gallery>,
+        figures: Vec<crate::pdf_figures::Figure>, // Extracted from the research PDFs
+        figures_busy: bool,
+        figures_status: String,
+        research_folders: Vec<(std::path::PathBuf, usize)>, // Listed when the Folders section is toggled
+        folder_summary_busy: bool,
+        folder_summary_status: String,
+        memo_format: crate::voice_memo::MemoFormat,
+        memo_busy: bool,
+        memo_status: String,
+        figure_filter: String,
+
         // Async Communication
-        tx: std::sync::mpsc::Sender<String>, 
-        rx: std::sync::Arc<std::sync::Mutex<std::sync::mpsc::Receiver<String>>>, 
+        runtime: tokio::runtime::Handle, // Shared runtime every Ollama/RAG task runs on
+        generation_task: Option<tokio::task::AbortHandle>,
+
+        // Event stream
+        event_sink: Option<crate::telemetry::EventSink>,
+        reply_started: Option<std::time::Instant>,
+        reply_first_token: Option<std::time::Duration>,
+        reply_chunks: usize, // Streamed pieces of the current reply
+        scan_started: Option<std::time::Instant>,
+        scan_id: u64, // Tags research messages so a stopped or earlier scan's stragglers are dropped
+        recap_id: u64, // Same for the /recap reply
+        tx: crossbeam_channel::Sender<String>,
+        rx: crossbeam_channel::Receiver<String>, // Owned by the UI thread, drained every frame
     }
 
     impl ShipApp {
-        fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+        fn new(cc: &eframe::CreationContext<'_>, profile: Profile, launch: crate::instance::Request, listener: Option<crate::instance::Listener>) -> Self {
             // Create sessions directory
-            let _ = fs::create_dir_all(SESSIONS_DIR);
+            let _ = profile.create_dirs();
+            profile.mark_active();
+            let sessions_dir = profile.sessions_dir();
+
+            // Async Channel: bounded, so a runaway worker blocks instead of growing memory
+            let (tx, rx) = crossbeam_channel::bounded::<String>(EVENT_CAPACITY);
+
+            // Background watchers for which models Ollama has loaded and for VRAM
+            let config = AppConfig::load(&profile.config_path());
+            crate::runtime::set_worker_config(&config.workers);
+            let vram_poll_ms = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(config.vram_poll_ms));
+            Self::spawn_vram_poller(tx.clone(), vram_poll_ms.clone());
+            let finetune_job = FinetuneJob::new(&profile.root());
 
-            // Async Channel (using std sync mpsc)
-            let (tx, rx) = std::sync::mpsc::channel::<String>();
+            let mut app = Self {
+                models: config.model_list.clone(), // Until Ollama answers
+                installed_models: Vec::new(),
+                confirm_delete_model: None,
+                deleting_model: None,
+                selected_model: config.default_model.clone(),
+                research_dir: config.research_dir.clone(),
+                config,
+                profile,
+                new_profile_name: String::new(),
 
-            Self {
                 input_text: String::new(),
-                current_file: "session_latest.json".to_string(),
+                current_file: crate::session::LATEST_FILE.to_string(),
                 messages: Vec::new(),
-                // My Models
-                models: vec!["gemma3:27b".to_string(), "gpt-oss:20b".to_string()], 
-                selected_model: "gemma3:27b".to_string(),
-                vram_usage: (0, 0),
+                gpu: crate::gpu::Reading::default(),
+                host: crate::system_monitor::Reading::default(),
+                vram_poll_ms,
                 
                 // Initialize State Machine
                 state: AppState::Idle,
                 research_results: String::new(),
-                research_dir: String::from("/home/raulmc/Documents"), // Your Default Path
-                is_reasoning_mode: false,
+                research_sources: Vec::new(),
+                reply_sources: Vec::new(),
+                reply_model: String::new(),
+                rag_next: None,
+                last_rag_decision: None,
+                pending_retry: None,
+                retry_trace: Vec::new(),
+                time_range: TimeRange::Any,
+                research_section: String::new(),
                 // [FIX] Error line removed here
                 current_image_base64: None,
                 current_image_path: None,
+
+                show_sketch: false,
+                sketch_strokes: Vec::new(),
+                sketch_width: 3.0,
+                sketch_color: [20, 20, 20],
+
+                activity: String::new(),
+                last_error: None,
+                event_log: std::collections::VecDeque::new(),
+                show_log: false,
+                resident_models: Vec::new(),
+                model_last_used: std::collections::HashMap::new(),
+                last_warmup_check: None,
+                warmups_run: std::collections::HashMap::new(),
+                last_unload: None,
+                last_resident_poll: None,
+
+                backend_secret_input: String::new(),
+                backend_secret_status: String::new(),
+                llama_status: String::new(),
+
+                show_secrets: false,
+                secret_name_input: String::new(),
+                secret_value_input: String::new(),
+                secret_present: std::collections::HashMap::new(),
+
+                tts_voices: crate::tts::list_voices(),
+                tts_child: None,
+                voice_chat: None,
+
+                export_sessions: Vec::new(),
+                export_format: DatasetFormat::OpenAi,
+                export_filter: TurnFilter::default(),
+                export_status: String::new(),
+
+                finetune_job,
+                finetune_running: false,
+                finetune_log: Vec::new(),
+
+                show_modelfile_editor: false,
+                modelfile_spec: ModelfileSpec::default(),
+                modelfile_running: false,
+                modelfile_log: Vec::new(),
+
+                show_model_card: false,
+                model_card: None,
+                model_card_status: String::new(),
+                model_card_for: String::new(),
+
+                fit_checked_model: String::new(),
+                quant_suggestions: Vec::new(),
+                pending_pull: None,
+                pulling: false,
+                pull_status: String::new(),
+                pull_progress: None,
+                show_pull: false,
+                pull_tag: String::new(),
+
+                show_replay: false,
+                replay: None,
+
+                show_search: false,
+                search_index: None,
+                search_query: String::new(),
+                search_filter: crate::search_index::SearchFilter::default(),
+                search_time_range: TimeRange::Any,
+                search_hits: Vec::new(),
+                search_models: Vec::new(),
+                search_tags: Vec::new(),
+                search_status: String::new(),
+                importing: false,
+                show_topics: false,
+                topic_index: Vec::new(),
+                topic_filter: String::new(),
+                selected_topic: None,
+                topics_busy: false,
+                topics_status: String::new(),
+                show_word_cloud: false,
+                cloud_history: false,
+                cloud_terms: Vec::new(),
+                cloud_topics: Vec::new(),
+                cloud_busy: false,
+                session_tags: Vec::new(),
+                timer_events: Vec::new(),
+                overrides: Default::default(),
+                session_zone: None,
+                pomodoro_started: None,
+
+                translation_checks: std::collections::HashMap::new(),
+                reactions: std::collections::HashMap::new(),
+                show_analytics: false,
+                analytics: Vec::new(),
+
+                focused_message: None,
+                focus_mode: false,
+                expanded_messages: std::collections::HashSet::new(),
+                expand_request: None,
+                scroll_to_focus: false,
+
+                show_calendar: false,
+                calendar_items: Vec::new(),
+                calendar_busy: false,
+                calendar_status: String::new(),
+                show_lab_report: false,
+                lab_report: Default::default(),
+                lab_report_busy: false,
+                lab_report_status: String::new(),
+                show_settings: false,
+                show_retrieval_eval: false,
+                eval_set: Default::default(),
+                eval_previous: None,
+                eval_busy: false,
+                eval_status: String::new(),
+                history_cursor: None,
+                history_search: None,
+                composer_error: None,
+
+                email_draft: None,
+                email_status: String::new(),
+
+                show_organizer: false,
+                organizer_dir: String::new(),
+                organizer_instruction: String::new(),
+                organizer_plan: Vec::new(),
+                organizer_busy: false,
+                organizer_status: String::new(),
+
+                practice_mode: false,
+                practice_problem: None,
+
+                cancel_flag: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+                allow_close: false,
+                show_exit_confirm: false,
+                summarizing_before_exit: false,
+                exit_ready: false,
+                last_checkpoint: std::time::Instant::now(),
                 
+                toasts: Vec::new(),
+                conversation_size: Default::default(),
+                expand_long_messages: false,
+                interrupted_jobs: Vec::new(),
+                completions: Default::default(),
+                completion_indexed: 0,
+                definitions: std::collections::HashMap::new(),
+                hover_definition: None,
+                hover_candidate: None,
+                show_gallery: false,
+                pinned_document: None,
+                store: crate::session_store::SessionStore::Json { dir: sessions_dir }, // Replaced by reopen_store below
+                session_list: Vec::new(),
+                session_find: String::new(),
+                session_find_hits: Vec::new(),
+                session_find_done: false,
+                renaming_session: None,
+                tees: Vec::new(),
+                session_tee: None,
+                confirm_delete_session: None,
+                unsaved_chat: None, // Loaded below
+                gallery: None,
+                figures: Vec::new(), // Loaded below
+                figures_busy: false,
+                figures_status: String::new(),
+                research_folders: Vec::new(),
+                folder_summary_busy: false,
+                folder_summary_status: String::new(),
+                memo_format: crate::voice_memo::MemoFormat::Mp3,
+                memo_busy: false,
+                memo_status: String::new(),
+                figure_filter: String::new(),
+
+                runtime: crate::runtime::shared().handle().clone(),
+                generation_task: None,
+                event_sink: None, // Opened below
+                reply_started: None,
+                reply_first_token: None,
+                reply_chunks: 0,
+                scan_started: None,
+                scan_id: 0,
+                recap_id: 0,
                 tx: tx,
-                rx: std::sync::Arc::new(std::sync::Mutex::new(rx)),
+                rx: rx,
+            };
+            app.apply_appearance(&cc.egui_ctx);
+            app.reload_figures();
+            app.open_event_sink();
+            app.refresh_models();
+            app.reopen_store();
+            app.refresh_session_lists();
+            app.load_interrupted_jobs();
+            app.load_unsaved_chat();
+            app.report_config_error();
+            if let Some(note) = crate::profile::migration_note() {
+                app.log_event(note);
+                app.push_toast(note);
             }
+            app.handle_launch_request(launch);
+
+            // Later launches forward their arguments here and bring this window up
+            if let Some(listener) = listener {
+                let tx = app.tx.clone();
+                let ctx = cc.egui_ctx.clone();
+                listener.serve(move |request| {
+                    let _ = tx.send(format!("__FORWARDED__:{}", serde_json::to_string(&request).unwrap_or_default()));
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+                    ctx.request_repaint();
+                });
+            }
+            app
         }
 
-        // Helper to get VRAM from nvidia-smi
-        fn get_vram_usage() -> (u64, u64) {
-            let output = Command::new("nvidia-smi")
-                .args(&["--query-gpu=memory.used,memory.total", "--format=csv,noheader,nounits"])
-                .output();
-
-            if let Ok(o) = output {
-                let s = String::from_utf8_lossy(&o.stdout);
-                if let Some(line) = s.lines().next() {
-                    let parts: Vec<&str> = line.split(',').collect();
-                    if parts.len() == 2 {
-                        let used = parts[0].trim().parse::<u64>().unwrap_or(0);
-                        let total = parts[1].trim().parse::<u64>().unwrap_or(0);
-                        return (used, total);
-                    }
-                }
+        // Add a newly created model to the selector (no duplicates)
+        fn register_model(&mut self, model: &str) {
+            if !self.models.iter().any(|m| m == model) {
+                self.models.push(model.to_string());
+            }
+        }
+
+        fn save_config(&mut self) {
+            if self.config.load_error.is_some() {
+                return; // Already reported; the settings window offers reload or overwrite
+            }
+            if let Err(e) = self.config.save() {
+                self.report_error(&format!("Failed to save config: {}", e));
             }
-            (0, 0)
+        }
+
+        // Timestamped entry for the log panel
+        fn log_event(&mut self, text: &str) {
+            let stamp = chrono::Local::now().format("%H:%M:%S");
+            if self.event_log.len() >= EVENT_LOG_LINES {
+                self.event_log.pop_front();
+            }
+            self.event_log.push_back(format!("[{}] {}", stamp, text));
+        }
+
+        fn report_error(&mut self, text: &str) {
+            self.emit("error", serde_json::json!({ "message": text }));
+            self.log_event(&format!("ERROR: {}", text));
+            self.last_error = Some(text.to_string());
+            self.push_toast_with(toasts::ToastLevel::Error, text, None);
         }
 
         // [FIXED] The Async RAG Scanner (Non-blocking)
         fn scan_research(&mut self, keyword: String) {
             let dir = self.research_dir.clone(); 
             let tx = self.tx.clone();
+            let cancel = self.begin_cancellable();
+            let filters = self.config.research_filters.get(&dir).cloned().unwrap_or_default();
+            let time_range = self.time_range;
+            let section = self.research_section.clone();
+            let terms = crate::research::query_terms(&keyword);
             
             // 1. Update State to block double-clicks
             self.state = AppState::Scanning;
+            self.scan_started = Some(std::time::Instant::now());
+            self.scan_id += 1;
+            let id = self.scan_id;
 
             // 2. Spawn thread (blocking)
-            std::thread::spawn(move || {
-                let mut found_data = String::new();
-                let pattern = format!("{}/**/*.pdf", dir);
+            // Matches are streamed as BEGIN / SOURCE... / END, one small excerpt per message,
+            // each tagged with the scan id; nothing more is sent once the scan is stopped
+            error_boundary::spawn_blocking_task(&self.runtime, self.tx.clone(), "Research scan", move || {
+                let mut found = 0;
+                let send = |msg: String| {
+                    if !cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                        let _ = tx.send(msg);
+                    }
+                };
                 
                 // Send status update
-                let _ = tx.send(format!("__STATUS__: Scanning for signal '{}'...", keyword));
-
-                if let Ok(paths) = glob::glob(&pattern) {
-                    for entry in paths.flatten() {
-                        if let Ok(content) = pdf_extract::extract_text(&entry) {
-                            if content.to_lowercase().contains(&keyword.to_lowercase()) {
-                                let filename = entry.file_name().unwrap_or_default().to_string_lossy();
-                                
-                                // Get context window
-                                let snippet = ShipApp::get_relevant_snippet(&content, &keyword);
-                                found_data.push_str(&format!("\n[SOURCE: {}]\n{}\n", filename, snippet));
-                            }
-                        }
+                send(format!("__STATUS__: Scanning for signal '{}'...", keyword));
+                send(format!("__RESEARCH_BEGIN__:{}", id));
+
+                let entries: Vec<_> = crate::research::collect_documents(&dir, &filters)
+                    .into_iter()
+                    .filter(|p| time_range.allows(p))
+                    .collect();
+                let total = entries.len();
+
+                for (i, entry) in entries.into_iter().enumerate() {
+                    if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                        return;
+                    }
+                    send(format!("__PROGRESS__:Scanning {}/{}", i + 1, total));
+                    // Source block with the chapter/section when the PDF has bookmarks
+                    if let Some(source) = crate::research::match_document(&entry, &terms, &section) {
+                        send(format!("__RESEARCH_SOURCE__:{}:{}", id, serde_json::to_string(&source).unwrap_or_default()));
+                        found += 1;
                     }
                 }
                 
-                if found_data.is_empty() {
+                if found == 0 {
                     // Signal completion with no data
-                    let _ = tx.send("__RESEARCH_EMPTY__".to_string());
+                    send(format!("__RESEARCH_EMPTY__:{}", id));
                 } else {
                     // Signal completion WITH data
-                    let _ = tx.send(format!("__RESEARCH_DATA__:{}", found_data));
+                    send(format!("__RESEARCH_END__:{}:{}", id, found));
                 }
             });
         }
 
-        // Helper to grab text around the keyword
-        fn get_relevant_snippet(content: &str, keyword: &str) -> String {
-            let lower_content = content.to_lowercase();
-            let lower_keyword = keyword.to_lowercase();
-
-            if let Some(index) = lower_content.find(&lower_keyword) {
-                let start = index.saturating_sub(200);
-                let end = (index + 500).min(content.len()); // Increased context window
-                content[start..end].to_string()
-            } else {
-                String::new()
-            }
+        // Payload of a "<scan id>:<payload>" research message, if it belongs to the
+        // scan that is still running
+        fn current_scan<'a>(&self, tagged: &'a str) -> Option<&'a str> {
+            let (id, payload) = tagged.split_once(':').unwrap_or((tagged, ""));
+            let live = self.state == AppState::Scanning && id.parse() == Ok(self.scan_id);
+            live.then_some(payload)
         }
 
         // [NEW] Trigger Ollama (Called after research OR directly)
         fn trigger_ollama_generation(&mut self, prompt: String) {
+            let backend = match self.chat_backend() {
+                Ok(backend) => backend,
+                Err(e) => {
+                    self.state = AppState::Idle;
+                    self.activity.clear();
+                    self.pending_retry = None;
+                    self.report_error(&e);
+                    return;
+                }
+            };
+            // A refusal retry swaps in its own model and prompt; history is the same
+            let (model, sent) = match self.pending_retry.take() {
+                Some((model, sent)) => (model, sent),
+                None => (self.chat_model(), prompt.clone()),
+            };
             self.state = AppState::Generating;
+            self.activity = format!("Generating with {}", model);
             let tx_clone = self.tx.clone();
-            let model = self.selected_model.clone();
+            if self.overrides.hosted.is_none() {
+                self.note_model_used(&model);
+            }
+            self.start_tees(&prompt);
             let img_data = self.current_image_base64.clone();
-            let research_context = self.research_results.clone();
+            let research_context = match &self.pinned_document {
+                Some((name, text)) => format!("\n[ATTACHED: {}]\n{}\n{}", name, text, self.research_results),
+                None => self.research_results.clone(),
+            };
+            let placement = self.overrides.context_placement.unwrap_or(self.config.context_placement);
+            let system_prompt = match self.config.ask_confidence {
+                true => crate::confidence::with_instruction(self.effective_system_prompt()),
+                false => self.effective_system_prompt().to_string(),
+            };
+            let temperature = self.overrides.temperature;
+            // Everything before the prompt, which is normally the last message already
+            let earlier = match self.messages.last() {
+                Some(last) if last.role == "user" && last.content == prompt => &self.messages[..self.messages.len() - 1],
+                _ => &self.messages[..],
+            };
+            let history = crate::context::history(earlier, self.config.history_turns);
+            let cancel = self.begin_cancellable();
+            let remote = self.overrides.hosted.is_some() || !self.config.backend.is_local();
+            let strip_metadata = self.config.strip_image_metadata && remote;
             
-            // Clear buffer now that we are using it
+            self.emit_request_started(&model, research_context.len(), self.research_sources.len());
+
+            // Clear buffer now that we are using it; its excerpts go on the reply
             self.research_results.clear();
+            self.reply_sources = std::mem::take(&mut self.research_sources);
+            self.reply_model = model.clone();
 
-            // Spawn Ollama Task
-            std::thread::spawn(move || {
-                 // Create a tokio runtime to run async Ollama calls
-                 let rt = tokio::runtime::Runtime::new().unwrap();
-                 let ollama = Ollama::default();
-                 
-                 // 1. Build History
-                 let mut api_history = Vec::new();
-                 api_history.push(ChatMessage::new(MessageRole::System, USER_PROFILE.to_string()));
+            // Ollama task on the shared runtime; the handle lets it be aborted
+            let task = error_boundary::spawn_task(&self.runtime, self.tx.clone(), "Generation", async move {
+                 // 1-3. Earlier turns, then the research data wherever the user chose to put it
+                 let mut api_history = crate::context::build_turns(placement, &system_prompt, history, &research_context, sent);
+                 let mut user_msg = api_history.pop().expect("build_turns always ends with the prompt");
                  
-                 // 2. Construct Final Prompt
-                 let final_content = if !research_context.is_empty() {
-                     format!("### RESEARCH DATA:\n{}\n\n### USER QUERY:\n{}", research_context, prompt)
-                 } else {
-                     prompt
-                 };
-
-                 // 3. Create Message
-                 let mut user_msg = ChatMessage::new(MessageRole::User, final_content);
-                 
-                 // 4. Attach Image if present
+                 // 4. Attach Image if present (scrubbed of EXIF/GPS for remote hosts)
                  if let Some(b64) = img_data {
+                     let b64 = if strip_metadata {
+                         match crate::images::strip_metadata(&b64) {
+                             Ok(clean) => clean,
+                             Err(e) => {
+                                 // Never fall back to sending the original
+                                 let _ = tx_clone.send(format!("__ERROR__:Could not strip image metadata: {}", e));
+                                 let _ = tx_clone.send("__DONE__".to_string());
+                                 return;
+                             }
+                         }
+                     } else {
+                         b64
+                     };
                      user_msg.images = Some(vec![Image::from_base64(&b64)]);
                  }
                  
                  api_history.push(user_msg);
                  
-                 let request = ChatMessageRequest::new(model, api_history);
+                 let mut request = ChatMessageRequest::new(model, api_history);
+                 if let Some(t) = temperature {
+                     request = request.options(GenerationOptions::default().temperature(t));
+                 }
                  
-                 // 5. Stream Response (blocking via runtime)
-                 match rt.block_on(ollama.send_chat_messages(request)) {
-                     Ok(response) => {
-                         if let Some(message) = response.message {
-                             let _ = tx_clone.send(message.content);
-                         }
-                     }
-                     Err(_) => {
-                         let _ = tx_clone.send("Error: Failed to connect to Ollama.".to_string());
-                     }
+                 // 5. Stream Response: each piece goes out as its own message and grows the reply
+                 let mut received = false;
+                 let result = backend.chat_stream(&request, &mut |token: &str| {
+                     received = true;
+                     !cancel.load(std::sync::atomic::Ordering::Relaxed) && tx_clone.send(format!("__TOKEN__:{}", token)).is_ok()
+                 }).await;
+                 if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                     return; // Stopped, or the app is shutting down
+                 }
+                 // Reported, never written into the chat as if the model had said it
+                 if let Err(e) = result {
+                     let what = if received { "request failed" } else { "Failed to connect" };
+                     let _ = tx_clone.send(format!("__ERROR__:{} {}: {}", backend.name(), what, e));
                  }
                  let _ = tx_clone.send("__DONE__".to_string());
             });
+            self.generation_task = Some(task);
             
             // Reset image buffer immediately
             self.current_image_base64 = None;
         }
-    }
 
-    impl eframe::App for ShipApp {
-        fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-            // 1. Hardware Monitor
-            self.vram_usage = Self::get_vram_usage();
+        // Typed prompts and voice transcripts both come through here; needs AppState::Idle
+        fn send_input(&mut self, user_text: String) {
+            // The chat left over from last time is archived, not overwritten
+            self.archive_unsaved_chat();
 
-            // 2. Request a repaint every 1 second (1000ms)
-            ctx.request_repaint_after(std::time::duration::from_millis(1000));
+            // Tool commands never reach the model directly
+            // The whole command word: "/organizer notes" is an ordinary prompt
+            let organize = user_text.trim_start().strip_prefix("/organize").filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+            if let Some(instruction) = organize {
+                self.open_organizer(instruction);
+                return;
+            }
+            if user_text.trim() == crate::pomodoro::RECAP_COMMAND {
+                self.start_recap();
+                return;
+            }
 
-            // 3. MESSAGE HANDLER (The "Brain" Loop)
-            let rx_guard = self.rx.lock().unwrap();
-            while let Ok(msg) = rx_guard.try_recv() {
-                if msg == "__DONE__" {
-                    self.state = AppState::Idle; 
-                } 
-                else if msg.starts_with("__STATUS__") {
-                     // You could log this to a status bar
-                     println!("{}", msg);
+            // Study mode owns the input: topic first, then graded attempts
+            if self.practice_mode {
+                self.practice_send(user_text);
+                return;
+            }
+
+            // A bad attachment stops here, with the prompt kept in the input
+            if let Some(b64) = &self.current_image_base64 {
+                if let Err(e) = crate::images::validate(b64) {
+                    self.log_event(&format!("ERROR: {}", e));
+                    self.composer_error = Some(e);
+                    self.input_text = user_text;
+                    return;
                 }
-                else if msg.starts_with("__RESEARCH_DATA__") {
-                    // RAG Success: Store data and trigger LLM
-                    let data = msg.trim_start_matches("__RESEARCH_DATA__");
-                    self.research_results = data.to_string();
-                    
-                    // Retrieve the user's last message to use as the prompt
-                    if let Some(last_msg) = self.messages.last() {
-                        if last_msg.role == "user" {
-                            let prompt = last_msg.content.clone();
-                            self.trigger_ollama_generation(prompt);
-                        }
+            }
+            self.composer_error = None;
+
+            // Add User Message to UI immediately
+            let msg = self.user_message(user_text.clone());
+            self.messages.push(msg);
+            // Saved before the reply starts, so a crash never loses the prompt
+            if let Err(e) = self.flush_session() {
+                self.report_error(&format!("Failed to save session: {}", e));
+            }
+
+            // DECISION TREE: Research vs. Chat
+            self.route_prompt(user_text);
+        }
+
+        // Routes one worker message ("__PREFIX__:payload" or a streamed token)
+        fn handle_message(&mut self, msg: String) {
+            if msg == "__DONE__" {
+                self.state = AppState::Idle; 
+                self.generation_task = None;
+                self.activity.clear();
+                self.end_tees("");
+                self.emit_request_finished();
+                if self.retry_refusal() {
+                    return;
+                }
+                self.record_confidence();
+                // Autosave after every exchange; also clears the partial-reply flag
+                if let Err(e) = self.flush_session() {
+                    self.report_error(&format!("Failed to save session: {}", e));
+                }
+                self.maybe_check_translation();
+                self.speak_voice_reply();
+            } 
+            else if let Some(status) = msg.strip_prefix("__STATUS__:") {
+                self.activity = status.trim().to_string();
+                self.log_event(status.trim());
+            }
+            else if let Some(progress) = msg.strip_prefix("__PROGRESS__:") {
+                // Counters like "Scanning 42/118" update the bar but would flood the log
+                self.activity = progress.to_string();
+            }
+            else if let Some(err) = msg.strip_prefix("__ERROR__:") {
+                self.report_error(err);
+            }
+            else if let Some(path) = msg.strip_prefix("__SUMMARY_DONE__:") {
+                self.log_event(&format!("Summary saved to {}", path));
+                self.refresh_session_lists();
+                if self.summarizing_before_exit {
+                    self.exit_ready = true;
+                }
+            }
+            else if let Some(err) = msg.strip_prefix("__SUMMARY_FAILED__:") {
+                self.report_error(&format!("Session summary failed: {}", err));
+                if self.summarizing_before_exit {
+                    self.exit_ready = true;
+                }
+            }
+            else if let Some(json) = msg.strip_prefix("__VRAM__:") {
+                if let Ok(reading) = serde_json::from_str(json) {
+                    self.gpu = reading;
+                }
+            }
+            else if let Some(json) = msg.strip_prefix("__SYSTEM__:") {
+                if let Ok(reading) = serde_json::from_str(json) {
+                    self.host = reading;
+                }
+            }
+            else if let Some(list) = msg.strip_prefix("__RESIDENT__:") {
+                if self.config.backend.kind != crate::backend::BackendKind::Ollama {
+                    return; // Answer from before the backend was switched
+                }
+                self.resident_models = list.split(',').filter(|m| !m.is_empty()).map(String::from).collect();
+            }
+            else if let Some(tagged) = msg.strip_prefix("__RESEARCH_BEGIN__:") {
+                if self.current_scan(tagged).is_none() {
+                    return; // Scan was stopped or superseded
+                }
+                self.research_results.clear();
+                self.research_sources.clear();
+            }
+            else if let Some(tagged) = msg.strip_prefix("__RESEARCH_SOURCE__:") {
+                let Some(json) = self.current_scan(tagged) else {
+                    return; // Scan was stopped or superseded
+                };
+                match serde_json::from_str::<crate::research::SourceChunk>(json) {
+                    Ok(source) => self.research_sources.push(source),
+                    Err(e) => self.report_error(&format!("Bad research source: {}", e)),
+                }
+            }
+            else if let Some(tagged) = msg.strip_prefix("__RESEARCH_END__:") {
+                let Some(count) = self.current_scan(tagged) else {
+                    return; // Scan was stopped or superseded
+                };
+                // RAG Success: the best excerpts go in, best first (same ranking as the retrieval eval)
+                self.research_sources = crate::research::rank(std::mem::take(&mut self.research_sources));
+                self.research_results = crate::research::context_text(&self.research_sources);
+                self.log_event(&format!(
+                    "Research: {} matching documents, best {} used, {} KB of context",
+                    count,
+                    self.research_sources.len(),
+                    self.research_results.len() / 1024
+                ));
+                self.emit_retrieval(count.parse().unwrap_or(0));
+                self.index_research_vocabulary();
+                
+                // Retrieve the user's last message to use as the prompt
+                if let Some(last_msg) = self.messages.last() {
+                    if last_msg.role == "user" {
+                        let prompt = last_msg.content.clone();
+                        self.trigger_ollama_generation(prompt);
                     }
                 }
-                else if msg == "__RESEARCH_EMPTY__" {
-                    // RAG Fail: Just trigger LLM without data
-                    if let Some(last_msg) = self.messages.last() {
-                        if last_msg.role == "user" {
-                            let prompt = last_msg.content.clone();
-                            self.trigger_ollama_generation(prompt);
-                        }
+            }
+            else if let Some(line) = msg.strip_prefix("__FINETUNE__:") {
+                self.finetune_log.push(line.to_string());
+            }
+            else if let Some(model) = msg.strip_prefix("__FINETUNE_DONE__:") {
+                // Register the freshly baked model in the selector
+                self.finetune_running = false;
+                self.finetune_log.push(format!("✅ Created model '{}'", model));
+                self.register_model(model);
+            }
+            else if let Some(err) = msg.strip_prefix("__FINETUNE_FAILED__:") {
+                self.finetune_running = false;
+                self.finetune_log.push(format!("❌ {}", err));
+            }
+            else if let Some(line) = msg.strip_prefix("__MODELFILE__:") {
+                self.modelfile_log.push(line.to_string());
+            }
+            else if let Some(model) = msg.strip_prefix("__MODELFILE_DONE__:") {
+                self.modelfile_running = false;
+                self.modelfile_log.push(format!("✅ Created model '{}'", model));
+                self.register_model(model);
+                self.selected_model = model.to_string();
+            }
+            else if let Some(err) = msg.strip_prefix("__MODELFILE_FAILED__:") {
+                self.modelfile_running = false;
+                self.modelfile_log.push(format!("❌ {}", err));
+            }
+            else if let Some(json) = msg.strip_prefix("__TRANSLATION_CHECK__:") {
+                self.accept_translation_check(json);
+            }
+            else if let Some(json) = msg.strip_prefix("__MODEL_CARD__:") {
+                self.accept_model_card(json);
+            }
+            else if let Some(err) = msg.strip_prefix("__MODEL_CARD_FAILED__:") {
+                self.model_card_status = format!("❌ {}", err);
+            }
+            else if let Some(json) = msg.strip_prefix("__MODELS__:") {
+                self.accept_model_list(json);
+            }
+            else if let Some(err) = msg.strip_prefix("__MODELS_FAILED__:") {
+                self.log_event(&format!("Could not list models: {}", err));
+                let text = format!("Could not reach {}: {}", self.config.backend.uri(), err);
+                self.push_toast_with(toasts::ToastLevel::Warning, &text, Some(toasts::ToastAction::Settings));
+            }
+            else if let Some(status) = msg.strip_prefix("__LLAMA_STATUS__:") {
+                self.llama_status = status.to_string();
+            }
+            else if let Some(name) = msg.strip_prefix("__MODEL_DELETED__:") {
+                self.finish_model_delete(Ok(name));
+            }
+            else if let Some(err) = msg.strip_prefix("__MODEL_DELETE_FAILED__:") {
+                self.finish_model_delete(Err(err));
+            }
+            else if let Some(json) = msg.strip_prefix("__QUANT_SUGGEST__:") {
+                self.accept_quant_suggestions(json);
+            }
+            else if let Some(update) = msg.strip_prefix("__PULL__:") {
+                let (fraction, status) = update.split_once(':').unwrap_or(("-", update));
+                self.pull_progress = fraction.parse().ok();
+                self.pull_status = status.to_string();
+            }
+            else if let Some(tag) = msg.strip_prefix("__PULL_DONE__:") {
+                self.pulling = false;
+                self.finish_job(&crate::jobs::JobKind::Pull { tag: tag.to_string() });
+                self.pull_status = format!("✅ Pulled {}", tag);
+                self.log_event(&self.pull_status.clone());
+                self.register_model(tag);
+                self.selected_model = tag.to_string();
+                self.refresh_models(); // Picks up its size
+            }
+            else if let Some(err) = msg.strip_prefix("__PULL_FAILED__:") {
+                self.pulling = false;
+                if let Some((tag, _)) = err.split_once(": ") {
+                    self.finish_job(&crate::jobs::JobKind::Pull { tag: tag.to_string() });
+                }
+                self.pull_status = format!("❌ {}", err);
+                self.report_error(&format!("Pull failed: {}", err));
+            }
+            else if let Some(json) = msg.strip_prefix("__FORWARDED__:") {
+                match serde_json::from_str(json) {
+                    Ok(request) => self.handle_launch_request(request),
+                    Err(e) => self.report_error(&format!("Bad forwarded launch: {}", e)),
+                }
+            }
+            else if let Some(text) = msg.strip_prefix("__VOICE_HEARD__:") {
+                self.accept_voice_prompt(text);
+            }
+            else if let Some(err) = msg.strip_prefix("__VOICE_FAILED__:") {
+                self.report_error(err);
+            }
+            else if let Some(json) = msg.strip_prefix("__DEFINITION__:") {
+                self.accept_definition(json);
+            }
+            else if let Some(json) = msg.strip_prefix("__ACTION_ITEMS__:") {
+                self.accept_action_items(json);
+            }
+            else if let Some(err) = msg.strip_prefix("__ACTION_ITEMS_FAILED__:") {
+                self.calendar_busy = false;
+                self.calendar_status = format!("❌ {}", err);
+            }
+            else if let Some(text) = msg.strip_prefix("__RECAP__:") {
+                self.accept_recap(text);
+            }
+            else if let Some(id) = msg.strip_prefix("__RECAP_FAILED__:") {
+                self.recap_failed(id);
+            }
+            else if let Some(report) = msg.strip_prefix("__IMPORT_DONE__:") {
+                self.importing = false;
+                self.log_event(report);
+                self.push_toast(report);
+                self.refresh_session_lists();
+            }
+            else if let Some(err) = msg.strip_prefix("__IMPORT_FAILED__:") {
+                self.importing = false;
+                self.report_error(&format!("Import failed: {}", err));
+            }
+            else if let Some(step) = msg.strip_prefix("__TOPICS__:") {
+                self.topics_status = step.to_string();
+            }
+            else if let Some(reason) = msg.strip_prefix("__TOPICS_SKIPPED__:") {
+                self.log_event(&format!("Topic index skipped {}", reason));
+            }
+            else if let Some(n) = msg.strip_prefix("__TOPICS_DONE__:") {
+                self.finish_topic_index(Ok(n));
+            }
+            else if let Some(err) = msg.strip_prefix("__TOPICS_FAILED__:") {
+                self.finish_topic_index(Err(err));
+            }
+            else if let Some(json) = msg.strip_prefix("__SESSION_FIND__:") {
+                self.accept_session_find(json);
+            }
+            else if let Some(json) = msg.strip_prefix("__WORD_CLOUD__:") {
+                self.accept_word_cloud(json);
+            }
+            else if let Some(step) = msg.strip_prefix("__FIGURES__:") {
+                self.figures_status = step.to_string();
+            }
+            else if let Some(n) = msg.strip_prefix("__FIGURES_DONE__:") {
+                self.finish_figures(Ok(n));
+            }
+            else if let Some(err) = msg.strip_prefix("__FIGURES_FAILED__:") {
+                self.finish_figures(Err(err));
+            }
+            else if let Some(step) = msg.strip_prefix("__EVAL_PROGRESS__:") {
+                self.eval_status = step.to_string();
+            }
+            else if let Some(json) = msg.strip_prefix("__EVAL_DONE__:") {
+                self.finish_retrieval_eval(json);
+            }
+            else if let Some(step) = msg.strip_prefix("__FOLDER_SUMMARY__:") {
+                self.folder_summary_status = step.to_string();
+            }
+            else if let Some(path) = msg.strip_prefix("__FOLDER_SUMMARY_DONE__:") {
+                self.finish_folder_summary(Ok(path));
+            }
+            else if let Some(err) = msg.strip_prefix("__FOLDER_SUMMARY_FAILED__:") {
+                self.finish_folder_summary(Err(err));
+            }
+            else if let Some(step) = msg.strip_prefix("__MEMO__:") {
+                self.memo_status = step.to_string();
+            }
+            else if let Some(path) = msg.strip_prefix("__MEMO_DONE__:") {
+                self.finish_voice_memo(Ok(path));
+            }
+            else if let Some(err) = msg.strip_prefix("__MEMO_FAILED__:") {
+                self.finish_voice_memo(Err(err));
+            }
+            else if let Some(step) = msg.strip_prefix("__LAB_REPORT__:") {
+                self.lab_report_status = step.to_string();
+            }
+            else if let Some(path) = msg.strip_prefix("__LAB_REPORT_DONE__:") {
+                self.lab_report_busy = false;
+                self.lab_report_status = format!("✅ Saved {}", path);
+                self.push_toast_with(toasts::ToastLevel::Success, "Lab report ready", Some(toasts::ToastAction::Open(path.into())));
+            }
+            else if let Some(err) = msg.strip_prefix("__LAB_REPORT_FAILED__:") {
+                self.lab_report_busy = false;
+                self.lab_report_status = format!("❌ {}", err);
+            }
+            else if let Some(json) = msg.strip_prefix("__ORGANIZE_PLAN__:") {
+                self.accept_organizer_plan(json);
+            }
+            else if let Some(err) = msg.strip_prefix("__ORGANIZE_FAILED__:") {
+                self.organizer_busy = false;
+                self.organizer_status = format!("❌ {}", err);
+            }
+            else if let Some(json) = msg.strip_prefix("__PRACTICE_PROBLEM__:") {
+                self.accept_practice_problem(json);
+            }
+            else if let Some(text) = msg.strip_prefix("__WORKER_PANIC__:") {
+                self.handle_worker_panic(text);
+            }
+            else if let Some(json) = msg.strip_prefix("__RAG_DECISION__:") {
+                if self.state != AppState::Scanning {
+                    return; // Stopped while the classifier ran
+                }
+                match serde_json::from_str(json) {
+                    Ok(decision) => self.apply_rag_decision(decision),
+                    Err(e) => self.report_error(&format!("Bad RAG decision: {}", e)),
+                }
+            }
+            else if let Some(tagged) = msg.strip_prefix("__RESEARCH_EMPTY__:") {
+                if self.current_scan(tagged).is_none() {
+                    return; // Scan was stopped or superseded
+                }
+                // RAG Fail: Just trigger LLM without data
+                self.emit_retrieval(0);
+                let text = format!("No documents in {} matched; answering without them", self.research_dir);
+                let folder = toasts::ToastAction::Open(self.research_dir.clone().into());
+                self.push_toast_with(toasts::ToastLevel::Warning, &text, Some(folder));
+                if let Some(last_msg) = self.messages.last() {
+                    if last_msg.role == "user" {
+                        let prompt = last_msg.content.clone();
+                        self.trigger_ollama_generation(prompt);
                     }
                 }
-                else {
-                    // Streamed Token from Ollama
-                    if let Some(last_msg) = self.messages.last_mut() {
-                        if last_msg.role == "assistant" {
-                            last_msg.content.push_str(&msg);
-                        } else {
-                            self.messages.push(Message {
-                                role: "assistant".to_string(),
-                                content: msg,
-                                has_image: false,
-                            });
-                        }
+            }
+            else if let Some(token) = msg.strip_prefix("__TOKEN__:") {
+                // Streamed token; one still in the channel after Stop is dropped
+                if self.state != AppState::Generating {
+                    return;
+                }
+                self.tee_token(token);
+                self.note_reply_chunk();
+                if let Some(last_msg) = self.messages.last_mut() {
+                    if last_msg.role == "assistant" {
+                        last_msg.content.push_str(token);
+                    } else {
+                        let mut reply = Message::new("assistant", token.to_string(), false);
+                        reply.sources = std::mem::take(&mut self.reply_sources);
+                        reply.model = self.reply_model.clone();
+                        self.messages.push(reply);
                     }
                 }
+                self.checkpoint_reply();
             }
+        }
+    }
+
+    impl eframe::App for ShipApp {
+        fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+            // 1. Window geometry (VRAM readings arrive as __VRAM__ events)
+            self.track_window(ctx);
+
+            // 2. Request a repaint every 1 second (1000ms)
+            ctx.request_repaint_after(std::time::Duration::from_millis(1000));
+
+            // 3. MESSAGE HANDLER (The "Brain" Loop)
+            self.drain_events(ctx);
+            self.check_model_fit();
+            self.poll_resident();
+            self.schedule_unloads();
+            self.run_due_warmups();
 
             // 4 . GUI LAYOUT
+            self.handle_close_request(ctx);
+            self.handle_navigation_keys(ctx);
+            self.voice_chat_frame(ctx);
+            self.pomodoro_tick(ctx);
+            self.status_bar(ctx);
+            self.log_window(ctx);
+            self.show_toasts(ctx);
+
             egui::SidePanel::left("sidebar").show(ctx, |ui| {
                 ui.heading("Ship of Theseus 🛳️");
+                self.profile_switcher(ui);
+                ui.horizontal(|ui| {
+                    if ui.button("🆕 New chat").on_hover_text("Archive this conversation and start fresh").clicked() {
+                        self.new_chat();
+                    }
+                    if ui.button("🔎 Search").on_hover_text("Search all saved sessions").clicked() {
+                        self.show_search = true;
+                    }
+                    if ui.button("🏷").on_hover_text("Topics discussed across all sessions").clicked() {
+                        self.open_topics();
+                    }
+                    if ui.button("☁").on_hover_text("Word cloud of this session or the whole history").clicked() {
+                        self.open_word_cloud();
+                    }
+                    if ui.button("⏯").on_hover_text("Replay a saved session").clicked() {
+                        self.show_replay = true;
+                    }
+                    if ui.button("📊").on_hover_text("Reply ratings per model").clicked() {
+                        self.open_analytics();
+                    }
+                    if ui.button("🖼").on_hover_text("Every attached image").clicked() {
+                        self.open_gallery();
+                    }
+                    if ui.button("⚙").on_hover_text("Settings").clicked() {
+                        self.show_settings = true;
+                    }
+                });
                 ui.separator();
-                ui.label(format!("VRAM: {} / {} MB", self.vram_usage.0, self.vram_usage.1));
+                ui.horizontal(|ui| {
+                    ui.label(self.gpu.summary());
+                    self.unload_button(ui);
+                });
+                ui.label(self.host.summary());
+                self.unload_setting(ui);
                 ui.separator();
                 
                 // Model Selector
-                ui.label("Active Neural Net:");
-                egui::ComboBox::from_id_source("model_selector")
-                    .selected_text(&self.selected_model)
-                    .show_ui(ui, |ui| {
-                        for model in &self.models {
-                            ui.selectable_value(&mut self.selected_model, model.clone(), model);
-                        }
-                    });
+                self.model_selector(ui);
+                self.model_info_summary(ui);
+                self.persona_selector(ui);
+                self.quant_panel(ui);
+                self.model_manager(ui);
+                self.warmup_panel(ui);
+                if ui.small_button("🧱 Modelfile Editor").clicked() {
+                    self.show_modelfile_editor = true;
+                }
+
+                ui.separator();
+                self.research_panel(ui);
+
+                ui.separator();
+                self.backend_panel(ui);
+                if ui.small_button("🔑 Secrets").clicked() {
+                    self.show_secrets = true;
+                }
+                if ui.small_button("📅 Action items").on_hover_text("Export deadlines from this chat to a calendar").clicked() {
+                    self.show_calendar = true;
+                }
+                if ui.small_button("🧪 Lab report").on_hover_text("Draft a lab report from experiment notes and a CSV").clicked() {
+                    self.show_lab_report = true;
+                }
+                if ui.small_button("🗂 File Organizer").on_hover_text("Or type /organize <instruction> in the chat").clicked() {
+                    self.open_organizer("");
+                }
+                self.voice_panel(ui);
+                self.output_settings(ui);
+                self.practice_panel(ui);
+                self.retry_trace_panel(ui);
 
                 ui.separator();
-                ui.label("Research Station 🔬");
-                ui.checkbox(&mut self.is_reasoning_mode, "Reasoning Mode (RAG)");
-                ui.text_edit_singleline(&mut self.research_dir);
-                ui.small("Point this to your PDFs folder");
+                self.sessions_panel(ui);
+                self.export_panel(ui);
+                self.finetune_panel(ui);
             });
 
+            self.model_card_drawer(ctx);
+            self.modelfile_window(ctx);
+            self.sketch_window(ctx);
+            self.secrets_window(ctx);
+            self.pull_confirm_window(ctx);
+            self.pull_window(ctx);
+            self.delete_model_window(ctx);
+            self.organizer_window(ctx);
+            self.email_window(ctx);
+            self.calendar_window(ctx);
+            self.lab_report_window(ctx);
+            self.settings_window(ctx);
+            self.retrieval_eval_window(ctx);
+            self.history_search_window(ctx);
+            self.search_window(ctx);
+            self.topics_window(ctx);
+            self.word_cloud_window(ctx);
+            self.replay_window(ctx);
+            self.analytics_window(ctx);
+            self.gallery_window(ctx);
+            self.resume_jobs_window(ctx);
+            self.delete_session_window(ctx);
+            self.restore_chat_window(ctx);
+
             egui::CentralPanel::default().show(ctx, |ui| {
+                self.conversation_header(ui);
+                ui.separator();
+
                 // Chat History
+                let mut to_speak = None;
+                let mut to_email = None;
+                let mut reacted = None;
+                let layout = self.config.chat_layout.clone();
                 egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
-                    for msg in &self.messages {
-                        ui.horizontal(|ui| {
-                            ui.label(egui::RichText::new(&msg.role).strong());
-                            ui.label(&msg.content);
-                        });
-                        ui.separator();
+                    ui.spacing_mut().item_spacing.y = layout.density.spacing();
+                    for (i, msg) in self.messages.iter().enumerate() {
+                        self.timer_marks(ui, i);
+                        let row = Self::message_column(ui, &layout, msg.role == "user", |ui| ui.horizontal(|ui| {
+                            let role = ui.label(egui::RichText::new(&msg.role).strong());
+                            if let Some(when) = self.message_time(msg.timestamp) {
+                                role.on_hover_text(when);
+                            }
+                            ui.vertical(|ui| {
+                                self.render_content(ui, &msg.content, egui::Id::new(("msg", i)), self.expand_override(i));
+                                Self::sources_footer(ui, &msg.sources, egui::Id::new(("sources", i)));
+                            });
+                            if msg.role == "assistant" && ui.small_button("🔊").on_hover_text("Read aloud").clicked() {
+                                to_speak = Some(msg.content.clone());
+                            }
+                            self.translation_badge(ui, i);
+                            if msg.role == "assistant" && ui.small_button("✉").on_hover_text("Email draft").clicked() {
+                                to_email = Some(msg.content.clone());
+                            }
+                            if msg.role == "assistant" {
+                                reacted = self.reaction_buttons(ui, i).or(reacted);
+                                self.confidence_badge(ui, i);
+                            }
+                        }).response);
+                        self.decorate_message_row(ui, i, &row);
+                        if !layout.bubbles {
+                            ui.separator();
+                        }
                     }
+                    self.timer_marks(ui, self.messages.len());
                 });
+                self.end_navigation_frame();
+                if let Some(reaction) = reacted {
+                    self.set_reaction(reaction);
+                }
+                if let Some(text) = to_speak {
+                    self.speak(&text);
+                }
+                if let Some(text) = to_email {
+                    self.start_email_draft(&text);
+                }
 
                 ui.separator();
 
                 // Input Area
                 ui.horizontal(|ui| {
-                    ui.text_edit_singleline(&mut self.input_text);
+                    self.voice_chat_controls(ui);
+                    if ui.button("✏️").on_hover_text("Sketch Pad").clicked() {
+                        self.show_sketch = true;
+                    }
+                    self.figure_picker(ui);
+                    if let Some(name) = &self.current_image_path {
+                        if self.current_image_base64.is_some() {
+                            ui.small(format!("📎 {}", name));
+                        }
+                    }
+                    if let Some(err) = &self.composer_error {
+                        ui.colored_label(ui.visuals().error_fg_color, format!("⚠ {}", err));
+                        if ui.small_button("✖").on_hover_text("Remove the attachment").clicked() {
+                            self.composer_error = None;
+                            self.current_image_base64 = None;
+                            self.current_image_path = None;
+                        }
+                    }
+                    self.pinned_document_chip(ui);
+                    self.input_history_keys(ui);
+                    self.accept_completion_key(ui);
+                    let hint = self.practice_hint();
+                    let input = egui::TextEdit::singleline(&mut self.input_text).id(egui::Id::new(autocomplete_input::INPUT_ID));
+                    match hint {
+                        Some(hint) => ui.add(input.hint_text(hint)),
+                        None => ui.add(input),
+                    };
+                    self.completion_hint(ui);
                     
                     // Dynamic Button Label
                     let btn_text = match self.state {
@@ -328,48 +1452,159 @@ mod gui {
                         AppState::Generating => "Thinking...",
                     };
 
+                    // STOP: only while a scan or reply is running
+                    if self.state != AppState::Idle && ui.button("⏹ Stop").clicked() {
+                        self.stop_generation();
+                    }
+
                     // SEND LOGIC
                     if ui.button(btn_text).clicked() && self.state == AppState::Idle {
                         let user_text = self.input_text.clone();
-                        
-                        // Add User Message to UI immediately
-                        self.messages.push(Message {
-                            role: "user".to_string(),
-                            content: user_text.clone(),
-                            has_image: self.current_image_base64.is_some(),
-                        });
                         self.input_text.clear();
-
-                        // DECISION TREE: Research vs. Chat
-                        if self.is_reasoning_mode {
-                            // Path A: Scan Docs -> Then Chat
-                            self.scan_research(user_text); 
-                        } else {
-                            // Path B: Chat Directly
-                            self.trigger_ollama_generation(user_text);
-                        }
+                        self.send_input(user_text);
                     }
                 });
             });
+            self.definition_popup(ctx);
+        }
+
+        fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+            self.shutdown();
         }
     }
 
-    pub fn run() -> Result<(), eframe::Error> {
-        let options = eframe::NativeOptions::default();
-        eframe::run_native(
+    // `attach`: file from `--attach`, e.g. the file-manager context menu;
+    // `link`: a shipoftheseus:// URL the OS launched us with
+    pub fn run(profile: Profile, attach: Option<std::path::PathBuf>, link: Option<String>) -> Result<(), eframe::Error> {
+        // A window is already open: hand it the arguments instead of opening another
+        let launch = crate::instance::Request::new(attach, link);
+        if crate::instance::forward(&launch) {
+            return Ok(());
+        }
+        let listener = crate::instance::listen()
+            .map_err(|e| eprintln!("Single-instance listener unavailable: {}", e))
+            .ok();
+        let token = listener.as_ref().map(|l| l.token().to_string());
+
+        let window = AppConfig::load(&profile.config_path()).window;
+        let options = eframe::NativeOptions {
+            viewport: egui::ViewportBuilder::default()
+                .with_inner_size([window.width, window.height])
+                .with_maximized(window.maximized),
+            ..Default::default()
+        };
+        let result = eframe::run_native(
             "Ship of Theseus",
             options,
-            Box::new(|cc| Box::new(ShipApp::new(cc))),
-        )
+            Box::new(|cc| Box::new(ShipApp::new(cc, profile, launch, listener))),
+        );
+        if let Some(token) = token {
+            crate::instance::release(&token);
+        }
+        result
+    }
+}
+
+// Value following `flag` on the command line
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).map(String::as_str)
+}
+
+// An invalid `--profile` name would point the data folder outside the profiles directory
+fn launch_profile(args: &[String]) -> profile::Profile {
+    match arg_value(args, "--profile") {
+        Some(name) if name.trim() == profile::DEFAULT_PROFILE || profile::valid_name(name) => profile::Profile::new(name),
+        Some(name) => {
+            eprintln!("Invalid profile name '{}' (letters, digits, '-' and '_' only); using the default profile", name);
+            profile::Profile::new(profile::DEFAULT_PROFILE)
+        }
+        None => profile::Profile::last_active(),
+    }
+}
+
+// `--run-session <template> --prompt-file <file>`: headless batch run, then exit.
+// `--install-context-menu` / `--uninstall-context-menu`: file-manager entry, then exit.
+// `--install-url-handler` / `--uninstall-url-handler`: shipoftheseus:// links, then exit.
+// `--eval-retrieval <set.json>`: score the document scan on a labeled query set, then exit.
+fn run_headless(args: &[String]) -> Option<i32> {
+    let has = |flag: &str| args.iter().any(|a| a == flag);
+    let result = if has("--install-context-menu") {
+        Some(desktop::install_context_menu())
+    } else if has("--uninstall-context-menu") {
+        Some(desktop::uninstall_context_menu())
+    } else if has("--install-url-handler") {
+        Some(desktop::install_url_handler())
+    } else if has("--uninstall-url-handler") {
+        Some(desktop::uninstall_url_handler())
+    } else {
+        None
+    };
+    if let Some(result) = result {
+        return Some(match result {
+            Ok(done) => {
+                println!("{}", done);
+                0
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                1
+            }
+        });
+    }
+    if let Some(set) = arg_value(args, "--eval-retrieval") {
+        return Some(eval_retrieval(args, std::path::Path::new(set)));
+    }
+    let template = arg_value(args, "--run-session")?;
+    let Some(prompts) = arg_value(args, "--prompt-file") else {
+        eprintln!("--run-session needs --prompt-file <file>");
+        return Some(2);
+    };
+    match batch::run(&launch_profile(args), std::path::Path::new(template), std::path::Path::new(prompts)) {
+        Ok(written) => {
+            println!("{}", written);
+            Some(0)
+        }
+        Err(e) => {
+            eprintln!("Batch run failed: {}", e);
+            Some(1)
+        }
     }
 }
 
+// Library from `--research-dir`, else the profile's research folder
+fn eval_retrieval(args: &[String], path: &std::path::Path) -> i32 {
+    let set = match retrieval_eval::EvalSet::load(path) {
+        Ok(set) => set,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let config = config::AppConfig::load(&launch_profile(args).config_path());
+    let dir = arg_value(args, "--research-dir").map(String::from).unwrap_or_else(|| config.research_dir.clone());
+    let filters = config.research_filters.get(&dir).cloned().unwrap_or_default();
+    let report = retrieval_eval::run(&dir, &filters, &set, |i, n| eprintln!("[{}/{}]", i, n));
+    print!("{}", retrieval_eval::text_report(&report));
+    0
+}
+
 #[cfg(feature = "gui")]
 fn main() -> Result<(), eframe::Error> {
-    gui::run()
+    // `--profile <name>` picks a profile at launch, otherwise reuse the last one
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(code) = run_headless(&args) {
+        std::process::exit(code);
+    }
+    let attach = arg_value(&args, "--attach").map(std::path::PathBuf::from);
+    let link = desktop::find_link(&args).map(String::from);
+    gui::run(launch_profile(&args), attach, link)
 }
 
 #[cfg(not(feature = "gui"))]
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(code) = run_headless(&args) {
+        std::process::exit(code);
+    }
     println!("GUI feature not enabled; build with `--features gui` and add the `eframe` dependency to Cargo.toml to enable the GUI.");
-}
\ No newline at end of file
+}
@@ -0,0 +1,109 @@
+// --- MODELFILE AUTHORING ---
+// Turns a persona (base model + system prompt + parameters) into a real Ollama model.
+
+use crate::backend::BackendConfig;
+use crate::shell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crossbeam_channel::Sender;
+
+pub const MODELFILES_DIR: &str = "modelfiles"; // In the profile's data folder
+
+// PARAMETER keys Ollama understands, offered in the editor dropdown
+pub const KNOWN_PARAMETERS: [&str; 9] = [
+    "temperature", "num_ctx", "top_k", "top_p", "repeat_penalty",
+    "repeat_last_n", "seed", "num_predict", "stop",
+];
+
+// What `ollama create` accepts: [namespace/]model[:tag], each part starting with a letter
+// or digit and made of letters, digits, '.', '-' and '_'. Checked before the name reaches
+// the command line, where a leading '-' would read as an option.
+pub fn validate_model_name(name: &str) -> Result<(), String> {
+    let (path, tag) = match name.split_once(':') {
+        Some((path, tag)) => (path, Some(tag)),
+        None => (name, None),
+    };
+    let part_ok = |part: &str| {
+        part.len() <= 80
+            && part.chars().next().is_some_and(|c| c.is_ascii_alphanumeric())
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+    };
+    let parts_ok = path.split('/').all(part_ok) && path.split('/').count() <= 3;
+    if !parts_ok || !tag.map_or(true, part_ok) {
+        return Err(format!(
+            "\"{}\" is not a valid model name: use letters, digits, '.', '-' and '_', optionally namespace/ and :tag",
+            name
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub struct ModelfileSpec {
+    pub name: String,
+    pub base: String,
+    pub system: String,
+    pub parameters: Vec<(String, String)>,
+}
+
+impl Default for ModelfileSpec {
+    fn default() -> Self {
+        Self {
+            name: "ee-tutor".to_string(),
+            base: "gemma3:27b".to_string(),
+            system: String::new(),
+            parameters: vec![("temperature".to_string(), "0.7".to_string())],
+        }
+    }
+}
+
+impl ModelfileSpec {
+    pub fn render(&self) -> String {
+        let mut out = format!("FROM {}\n", self.base.trim());
+
+        for (key, value) in &self.parameters {
+            if key.trim().is_empty() || value.trim().is_empty() {
+                continue;
+            }
+            // Stop sequences need quoting so spaces survive
+            if key == "stop" {
+                out.push_str(&format!("PARAMETER stop \"{}\"\n", value.trim().replace('"', "\\\"")));
+            } else {
+                out.push_str(&format!("PARAMETER {} {}\n", key.trim(), value.trim()));
+            }
+        }
+
+        if !self.system.trim().is_empty() {
+            out.push_str(&format!("SYSTEM \"\"\"{}\"\"\"\n", self.system.trim()));
+        }
+        out
+    }
+
+    // `root` is the profile's data folder
+    pub fn path(&self, root: &Path) -> PathBuf {
+        // "me/tutor:v2" is one file, not a folder, and ':' isn't allowed in Windows names
+        root.join(MODELFILES_DIR).join(format!("{}.Modelfile", self.name.trim().replace(['/', ':'], "_")))
+    }
+
+    // Blocking: save the Modelfile next to the others and run `ollama create` against
+    // the configured server. Output lines go out as "__MODELFILE__:<line>".
+    pub fn create(&self, root: &Path, backend: &BackendConfig, tx: &Sender<String>) -> Result<(), String> {
+        let name = self.name.trim();
+        validate_model_name(name)?;
+        if self.base.trim().is_empty() {
+            return Err("Base model is required".to_string());
+        }
+        // A triple-quoted block has no escapes, so this would end SYSTEM early
+        if self.system.contains("\"\"\"") {
+            return Err("The system prompt can't contain \"\"\" (it would end the SYSTEM block)".to_string());
+        }
+
+        let path = self.path(root);
+        fs::create_dir_all(root.join(MODELFILES_DIR)).map_err(|e| e.to_string())?;
+        fs::write(&path, self.render()).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        let cmd: Vec<String> = vec!["ollama".to_string(), "create".to_string(), name.to_string(), "-f".to_string(), path.display().to_string()];
+        let _ = tx.send(format!("__MODELFILE__:$ {}", shell::display_command(&cmd)));
+        shell::stream_command_env(&cmd, &backend.cli_env(), tx, "__MODELFILE__")
+    }
+}
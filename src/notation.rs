@@ -0,0 +1,256 @@
+// --- ENGINEERING NOTATION ---
+// Display pass over model replies: quantities with an electrical unit are rewritten in
+// engineering notation with an SI prefix (4700 ohms -> 4.7 kΩ, 0.0000022 F -> 2.2 µF).
+// Code (fenced or inline) is left untouched.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum DecimalMark {
+    Point, // 4.7 kΩ
+    Comma, // 4,7 kΩ
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct NotationConfig {
+    pub enabled: bool,
+    pub decimal: DecimalMark,
+    pub sig_figs: usize,
+}
+
+impl Default for NotationConfig {
+    fn default() -> Self {
+        Self { enabled: false, decimal: DecimalMark::Point, sig_figs: 3 }
+    }
+}
+
+// (canonical symbol, spellings accepted after the number)
+const UNITS: &[(&str, &[&str])] = &[
+    ("Ω", &["Ω", "ohm", "ohms", "Ohm", "Ohms"]),
+    ("F", &["F", "farad", "farads"]),
+    ("H", &["H", "henry", "henries", "henrys"]),
+    ("V", &["V", "volt", "volts"]),
+    ("A", &["A", "amp", "amps", "ampere", "amperes"]),
+    ("W", &["W", "watt", "watts"]),
+    ("Hz", &["Hz", "hertz"]),
+    ("s", &["s", "sec", "seconds"]),
+];
+
+const PREFIXES: &[(char, i32)] = &[
+    ('p', -12), ('n', -9), ('u', -6), ('µ', -6), ('μ', -6), ('m', -3), ('k', 3), ('K', 3), ('M', 6), ('G', 9),
+];
+
+const OUTPUT_PREFIXES: &[(i32, &str)] = &[
+    (-12, "p"), (-9, "n"), (-6, "µ"), (-3, "m"), (0, ""), (3, "k"), (6, "M"), (9, "G"),
+];
+
+pub fn format_units(content: &str, config: &NotationConfig) -> String {
+    let mut out = String::new();
+    for (i, fenced) in content.split("```").enumerate() {
+        if i > 0 {
+            out.push_str("```");
+        }
+        if i % 2 == 1 {
+            out.push_str(fenced);
+            continue;
+        }
+        for (j, inline) in fenced.split('`').enumerate() {
+            if j > 0 {
+                out.push('`');
+            }
+            if j % 2 == 1 {
+                out.push_str(inline);
+            } else {
+                out.push_str(&format_prose(inline, config));
+            }
+        }
+    }
+    out
+}
+
+fn format_prose(text: &str, config: &NotationConfig) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let starts_number = chars[i].is_ascii_digit()
+            && (i == 0 || !(chars[i - 1].is_alphanumeric() || matches!(chars[i - 1], '.' | ',' | '_')));
+
+        if starts_number {
+            if let Some((value, unit, written, end)) = quantity_at(&chars, i) {
+                // A leading minus stays in the text as-is
+                out.push_str(&engineering(value, unit, config.sig_figs.max(written), config));
+                i = end;
+                continue;
+            }
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+    out
+}
+
+// Significant digits as written: "4700" has 2, "4700.0" has 5, "0.0022" has 2
+fn written_sig_figs(number: &str) -> usize {
+    let mantissa = number.split(['e', 'E']).next().unwrap_or_default();
+    let digits: String = mantissa.chars().filter(|c| c.is_ascii_digit()).collect();
+    let digits = digits.trim_start_matches('0');
+    let digits = if mantissa.contains('.') { digits } else { digits.trim_end_matches('0') };
+    digits.len()
+}
+
+// Number + optional single space + (prefix)unit, ending on a word boundary. Returns the
+// value in base units, the unit, the significant digits written, and the end index.
+fn quantity_at(chars: &[char], start: usize) -> Option<(f64, &'static str, usize, usize)> {
+    let mut i = start;
+    let mut number = String::new();
+
+    // 1. Digits, "1,000" style groups, fraction, exponent
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_digit() {
+            number.push(c);
+            i += 1;
+        } else if c == ','
+            && chars.get(i + 1..i + 4).is_some_and(|g| g.iter().all(|d| d.is_ascii_digit()))
+            && !chars.get(i + 4).is_some_and(|d| d.is_ascii_digit())
+        {
+            i += 1; // Thousands separator
+        } else {
+            break;
+        }
+    }
+    if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+        number.push('.');
+        i += 1;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            number.push(chars[i]);
+            i += 1;
+        }
+    }
+    if matches!(chars.get(i), Some('e') | Some('E')) {
+        let mut j = i + 1;
+        let mut exp = String::from("e");
+        if matches!(chars.get(j), Some('-') | Some('+')) {
+            exp.push(chars[j]);
+            j += 1;
+        }
+        if chars.get(j).is_some_and(|c| c.is_ascii_digit()) {
+            while j < chars.len() && chars[j].is_ascii_digit() {
+                exp.push(chars[j]);
+                j += 1;
+            }
+            number.push_str(&exp);
+            i = j;
+        }
+    }
+    let value: f64 = number.parse().ok()?;
+
+    // 2. Unit word, which must end the word: "5 Vdc" and "3 A4" are not quantities
+    let spaced = chars.get(i) == Some(&' ');
+    if spaced {
+        i += 1;
+    }
+    let word_start = i;
+    while i < chars.len() && (chars[i].is_alphabetic() || matches!(chars[i], 'Ω' | 'µ' | 'μ')) {
+        i += 1;
+    }
+    if chars.get(i).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+        return None;
+    }
+    let word: String = chars[word_start..i].iter().collect();
+    let (unit, exponent) = parse_unit(&word)?;
+
+    // 3. A bare "s" right after the number is a plural ("1990s", "the 80s"); seconds need
+    //    the space or a prefix ("5 s", "5ms")
+    if word == "s" && !spaced {
+        return None;
+    }
+
+    Some((value * 10f64.powi(exponent), unit, written_sig_figs(&number), i))
+}
+
+fn parse_unit(word: &str) -> Option<(&'static str, i32)> {
+    let lookup = |w: &str| UNITS.iter().find(|(_, names)| names.contains(&w)).map(|(symbol, _)| *symbol);
+
+    if let Some(symbol) = lookup(word) {
+        return Some((symbol, 0));
+    }
+    let mut chars = word.chars();
+    let first = chars.next()?;
+    let rest = chars.as_str();
+    let (_, exponent) = PREFIXES.iter().find(|(p, _)| *p == first)?;
+    lookup(rest).map(|symbol| (symbol, *exponent))
+}
+
+// `sig_figs` is at least what the reply wrote, so rewriting never drops its digits
+fn engineering(value: f64, unit: &str, sig_figs: usize, config: &NotationConfig) -> String {
+    if value == 0.0 {
+        return format!("0 {}", unit);
+    }
+    let sig_figs = sig_figs.clamp(1, 15) as i32;
+
+    // 1. Pick the prefix so the mantissa lands in [1, 1000)
+    let mut exp3 = ((value.abs().log10() / 3.0).floor() as i32 * 3).clamp(-12, 9);
+    let mut mantissa = value / 10f64.powi(exp3);
+
+    // 2. Round to significant figures; 999.95 may round up into the next prefix
+    let digits_before = mantissa.abs().log10().floor() as i32 + 1;
+    let decimals = (sig_figs - digits_before).max(0) as usize;
+    let scale = 10f64.powi(decimals as i32);
+    mantissa = (mantissa * scale).round() / scale;
+    if mantissa.abs() >= 1000.0 && exp3 < 9 {
+        exp3 += 3;
+        mantissa /= 1000.0;
+    }
+
+    let mut number = format!("{:.*}", decimals, mantissa);
+    if number.contains('.') {
+        number = number.trim_end_matches('0').trim_end_matches('.').to_string();
+    }
+    if config.decimal == DecimalMark::Comma {
+        number = number.replace('.', ",");
+    }
+
+    let prefix = OUTPUT_PREFIXES.iter().find(|(e, _)| *e == exp3).map(|(_, p)| *p).unwrap_or("");
+    format!("{} {}{}", number, prefix, unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point() -> NotationConfig {
+        NotationConfig { enabled: true, ..Default::default() }
+    }
+
+    #[test]
+    fn rewrites_quantities_with_a_prefix() {
+        assert_eq!(format_units("R1 is 4700 ohms.", &point()), "R1 is 4.7 kΩ.");
+        assert_eq!(format_units("C = 0.0000022 F", &point()), "C = 2.2 µF");
+        assert_eq!(format_units("a 5ms pulse", &point()), "a 5 ms pulse");
+        assert_eq!(format_units("wait 5 s", &point()), "wait 5 s");
+    }
+
+    #[test]
+    fn keeps_the_digits_the_reply_wrote() {
+        assert_eq!(format_units("1234 Ω", &point()), "1.234 kΩ");
+        assert_eq!(format_units("10V", &point()), "10 V");
+    }
+
+    #[test]
+    fn leaves_decades_and_longer_words_alone() {
+        for text in ["the 1990s", "back in the 80s", "a 5 Vdc rail", "3 A4 pages"] {
+            assert_eq!(format_units(text, &point()), text);
+        }
+    }
+
+    #[test]
+    fn skips_code_and_uses_the_decimal_mark() {
+        assert_eq!(format_units("`4700 ohms` vs 4700 ohms", &point()), "`4700 ohms` vs 4.7 kΩ");
+        let comma = NotationConfig { decimal: DecimalMark::Comma, ..point() };
+        assert_eq!(format_units("4700 ohms", &comma), "4,7 kΩ");
+    }
+}
@@ -0,0 +1,158 @@
+// --- FILE ORGANIZER ---
+// Guarded tool: the model only *proposes* a move plan for the files in one folder.
+// Nothing touches the disk until the plan is confirmed, and every executed move is
+// appended to a journal so the last batch can be undone.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+pub const JOURNAL_FILE: &str = "organizer_journal.jsonl";
+const MAX_FILES: usize = 400; // Keeps the prompt within a normal context window
+
+const PLANNER: &str = "You organize files into subfolders. You get an instruction and a list of file names. \
+Reply with ONLY a JSON array like [{\"file\": \"name.pdf\", \"folder\": \"Texas Instruments\"}]. \
+Use folder names relative to the current folder, never absolute paths or '..'. Leave out files that don't match the instruction.";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PlannedMove {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub include: bool, // Unticked rows are skipped on execute
+}
+
+#[derive(Deserialize)]
+struct ModelMove {
+    file: String,
+    folder: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct JournalEntry {
+    batch: String, // Timestamp (to the millisecond) shared by all moves of one execute
+    op: String,    // "move" or "undo"
+    from: PathBuf,
+    to: PathBuf,
+}
+
+// Regular files directly inside `dir`, sorted by name
+fn list_files(dir: &Path) -> Result<Vec<String>, String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names.truncate(MAX_FILES);
+    Ok(names)
+}
+
+// Single relative path segment(s) only: no "..", no root, no drive prefix
+fn safe_folder(folder: &str) -> bool {
+    let path = Path::new(folder.trim());
+    !folder.trim().is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+// Blocking: asks the model for a plan and validates every row against the real listing
+pub fn propose(backend: &BackendConfig, model: &str, dir: &Path, instruction: &str) -> Result<Vec<PlannedMove>, String> {
+    let files = list_files(dir)?;
+    if files.is_empty() {
+        return Err(format!("{} has no files to organize", dir.display()));
+    }
+
+    let prompt = format!("Instruction: {}\n\nFiles:\n{}", instruction, files.join("\n"));
+    let reply = llm::complete(backend, model, PLANNER, &prompt)?;
+
+    let start = reply.find('[').ok_or("Model reply had no JSON plan")?;
+    let end = reply.rfind(']').ok_or("Model reply had no JSON plan")?;
+    let rows: Vec<ModelMove> = serde_json::from_str(&reply[start..=end]).map_err(|e| format!("Bad plan JSON: {}", e))?;
+
+    let plan: Vec<PlannedMove> = rows.into_iter()
+        .filter(|row| files.contains(&row.file) && safe_folder(&row.folder))
+        .map(|row| PlannedMove {
+            from: dir.join(&row.file),
+            to: dir.join(row.folder.trim()).join(&row.file),
+            include: true,
+        })
+        .filter(|m| !m.to.exists())
+        .collect();
+
+    if plan.is_empty() {
+        return Err("The model proposed no valid moves".to_string());
+    }
+    Ok(plan)
+}
+
+fn append_journal(journal: &Path, entry: &JournalEntry) -> Result<(), String> {
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal)
+        .map_err(|e| format!("{}: {}", journal.display(), e))?;
+    writeln!(file, "{}", line).map_err(|e| e.to_string())
+}
+
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if to.exists() {
+        return Err(format!("{} already exists", to.display()));
+    }
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {}", parent.display(), e))?;
+    }
+    fs::rename(from, to).map_err(|e| format!("{} -> {}: {}", from.display(), to.display(), e))
+}
+
+// Moves the ticked rows, journaling each one as soon as it succeeds. Stops at the first failure.
+pub fn execute(plan: &[PlannedMove], journal: &Path) -> Result<usize, String> {
+    let batch = new_batch_id(journal);
+    let mut done = 0;
+    for m in plan.iter().filter(|m| m.include) {
+        move_file(&m.from, &m.to)?;
+        append_journal(journal, &JournalEntry { batch: batch.clone(), op: "move".to_string(), from: m.from.clone(), to: m.to.clone() })?;
+        done += 1;
+    }
+    Ok(done)
+}
+
+// Unique in the journal, so undo never merges two runs: a second run within the same
+// millisecond gets a counter
+fn new_batch_id(journal: &Path) -> String {
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+    let raw = fs::read_to_string(journal).unwrap_or_default();
+    let taken: Vec<String> = raw.lines().filter_map(|l| serde_json::from_str::<JournalEntry>(l).ok()).map(|e| e.batch).collect();
+    let mut batch = stamp.clone();
+    let mut n = 1;
+    while taken.contains(&batch) {
+        n += 1;
+        batch = format!("{}_{}", stamp, n);
+    }
+    batch
+}
+
+// Reverses the most recent batch that still has moves not undone
+pub fn undo_last(journal: &Path) -> Result<usize, String> {
+    let raw = fs::read_to_string(journal).map_err(|_| "Nothing to undo".to_string())?;
+    let entries: Vec<JournalEntry> = raw.lines().filter_map(|l| serde_json::from_str(l).ok()).collect();
+
+    // An undo entry's `from` is the destination of the move it reversed
+    let is_pending = |m: &JournalEntry| {
+        m.op == "move" && !entries.iter().any(|u| u.op == "undo" && u.batch == m.batch && u.from == m.to)
+    };
+    let batch = entries.iter().rev()
+        .find(|e| is_pending(e))
+        .map(|e| e.batch.clone())
+        .ok_or("Nothing to undo")?;
+
+    let mut restored = 0;
+    for e in entries.iter().rev().filter(|e| e.batch == batch && is_pending(e)) {
+        move_file(&e.to, &e.from)?;
+        append_journal(journal, &JournalEntry { batch: batch.clone(), op: "undo".to_string(), from: e.to.clone(), to: e.from.clone() })?;
+        restored += 1;
+    }
+    Ok(restored)
+}
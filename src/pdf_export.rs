@@ -0,0 +1,246 @@
+// --- PDF REPORT EXPORT ---
+// Renders the open conversation, and the research excerpts its replies used, into a
+// plain paginated PDF (Letter, built-in Helvetica/Courier) for homework and lab reports.
+// Written with lopdf, which the research scan already uses for reading.
+
+use crate::export;
+use crate::research::SourceChunk;
+use crate::session::Message;
+use crate::timestamps::Clock;
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document, Object, Stream};
+use std::path::Path;
+
+const PAGE_WIDTH: f32 = 612.0;
+const PAGE_HEIGHT: f32 = 792.0;
+const MARGIN: f32 = 54.0;
+const BODY_SIZE: f32 = 10.5;
+const CODE_SIZE: f32 = 9.0;
+const EXCERPT_CHARS: usize = 500; // Per source in the appendix
+
+#[derive(Clone, Copy, PartialEq)]
+enum Font {
+    Body,
+    Bold,
+    Code,
+}
+
+impl Font {
+    fn resource(self) -> &'static str {
+        match self {
+            Self::Body => "F1",
+            Self::Bold => "F2",
+            Self::Code => "F3",
+        }
+    }
+
+    // Average glyph width as a fraction of the size; Courier is exact, Helvetica close enough
+    fn char_width(self) -> f32 {
+        match self {
+            Self::Body => 0.5,
+            Self::Bold => 0.55,
+            Self::Code => 0.6,
+        }
+    }
+}
+
+struct Line {
+    font: Font,
+    size: f32,
+    text: String,
+    gap_before: f32,
+}
+
+struct Layout {
+    lines: Vec<Line>,
+}
+
+impl Layout {
+    fn push(&mut self, font: Font, size: f32, text: &str, gap_before: f32) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN;
+        let max_chars = ((width / (size * font.char_width())) as usize).max(10);
+        let mut gap = gap_before;
+        for line in wrap(text, max_chars, font == Font::Code) {
+            self.lines.push(Line { font, size, text: line, gap_before: gap });
+            gap = 0.0;
+        }
+    }
+}
+
+// Word wrap; code is cut at the column instead so indentation survives
+fn wrap(text: &str, max_chars: usize, hard: bool) -> Vec<String> {
+    if text.is_empty() {
+        return vec![String::new()];
+    }
+    let chars: Vec<char> = text.chars().collect();
+    if hard {
+        return chars.chunks(max_chars).map(|c| c.iter().collect()).collect();
+    }
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    lines.push(current);
+    lines
+}
+
+// The standard fonts use WinAnsi, which covers Spanish and the usual symbols
+fn encode(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c {
+            '\u{2018}' | '\u{2019}' => b'\'',
+            '\u{201C}' | '\u{201D}' => b'"',
+            '\u{2013}' | '\u{2014}' => b'-',
+            '\u{2022}' => 0x95,
+            '\u{2026}' => 0x85,
+            '\u{2039}' => 0x8B,
+            '\u{203A}' => 0x9B, // The "›" in every citation
+            '\t' => b' ',
+            c if (c as u32) < 0x100 => c as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+// Markdown emphasis markers would just print as symbols
+fn plain(line: &str) -> String {
+    line.replace("**", "").replace('`', "")
+}
+
+fn role_name(role: &str) -> &str {
+    match role {
+        "user" => "You",
+        "assistant" => "Assistant",
+        other => other,
+    }
+}
+
+fn layout(messages: &[Message], title: &str, model: &str, clock: &Clock) -> Layout {
+    let mut out = Layout { lines: Vec::new() };
+    out.push(Font::Bold, 16.0, if title.trim().is_empty() { "Conversation" } else { title.trim() }, 0.0);
+    let date = clock.date(chrono::Utc::now().timestamp());
+    let meta = if model.is_empty() { format!("Exported {}", date) } else { format!("Model: {} · Exported {}", model, date) };
+    out.push(Font::Body, 9.0, &meta, 4.0);
+
+    // 1. The conversation, with the same numbered markers as the Markdown export
+    let mut notes = Vec::new();
+    for msg in messages {
+        let when = msg.timestamp.map(|ms| format!(" · {}", clock.date_time_millis(ms))).unwrap_or_default();
+        out.push(Font::Bold, 11.0, &format!("{}{}", role_name(&msg.role), when), 14.0);
+        if msg.has_image || msg.attachment.is_some() {
+            out.push(Font::Body, BODY_SIZE, "[image attached]", 2.0);
+        }
+        let content = export::with_footnotes(&msg.content, &msg.sources, &mut notes, |n| format!("[{}]", n));
+        let mut in_code = false;
+        for line in content.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                out.push(Font::Code, CODE_SIZE, line, 0.0);
+            } else if let Some(heading) = line.trim_start().strip_prefix('#') {
+                out.push(Font::Bold, BODY_SIZE, heading.trim_start_matches('#').trim(), 4.0);
+            } else {
+                out.push(Font::Body, BODY_SIZE, &plain(line), 0.0);
+            }
+        }
+    }
+
+    // 2. Appendix: each note with the excerpts the replies were grounded on
+    if !notes.is_empty() {
+        let sources: Vec<&SourceChunk> = messages.iter().flat_map(|m| &m.sources).collect();
+        out.push(Font::Bold, 13.0, "Sources", 20.0);
+        for (i, citations) in notes.iter().enumerate() {
+            out.push(Font::Bold, 9.5, &format!("[{}] {}", i + 1, citations.join("; ")), 8.0);
+            for citation in citations {
+                let Some(source) = sources.iter().find(|s| s.citation() == *citation) else { continue };
+                let excerpt: String = source.text.chars().take(EXCERPT_CHARS).collect();
+                let excerpt = excerpt.split_whitespace().collect::<Vec<_>>().join(" ");
+                out.push(Font::Body, 9.0, &format!("{}…", excerpt), 0.0);
+            }
+        }
+    }
+    out
+}
+
+fn page_content(lines: &[Line], page: usize, pages: usize) -> Content {
+    let mut ops = Vec::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+    for line in lines {
+        y -= line.gap_before + line.size * 1.3;
+        ops.push(Operation::new("BT", vec![]));
+        ops.push(Operation::new("Tf", vec![line.font.resource().into(), line.size.into()]));
+        ops.push(Operation::new("Td", vec![MARGIN.into(), y.into()]));
+        ops.push(Operation::new("Tj", vec![Object::string_literal(encode(&line.text))]));
+        ops.push(Operation::new("ET", vec![]));
+    }
+    // Footer
+    ops.push(Operation::new("BT", vec![]));
+    ops.push(Operation::new("Tf", vec!["F1".into(), 8.0f32.into()]));
+    ops.push(Operation::new("Td", vec![(PAGE_WIDTH / 2.0 - 12.0).into(), (MARGIN / 2.0).into()]));
+    ops.push(Operation::new("Tj", vec![Object::string_literal(format!("{} / {}", page, pages))]));
+    ops.push(Operation::new("ET", vec![]));
+    Content { operations: ops }
+}
+
+// Splits the lines into pages by height
+fn paginate(lines: Vec<Line>) -> Vec<Vec<Line>> {
+    let mut pages = vec![Vec::new()];
+    let mut used = 0.0;
+    for line in lines {
+        let height = line.gap_before + line.size * 1.3;
+        if used + height > PAGE_HEIGHT - 2.0 * MARGIN {
+            pages.push(Vec::new());
+            used = 0.0;
+        }
+        used += height;
+        pages.last_mut().expect("starts with one page").push(line);
+    }
+    pages
+}
+
+pub fn export_pdf(out: &Path, messages: &[Message], title: &str, model: &str, clock: &Clock) -> Result<(), String> {
+    let pages = paginate(layout(messages, title, model, clock).lines);
+
+    let mut doc = Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let font = |name: &str| dictionary! { "Type" => "Font", "Subtype" => "Type1", "BaseFont" => name, "Encoding" => "WinAnsiEncoding" };
+    let fonts = dictionary! {
+        "F1" => doc.add_object(font("Helvetica")),
+        "F2" => doc.add_object(font("Helvetica-Bold")),
+        "F3" => doc.add_object(font("Courier")),
+    };
+    let resources_id = doc.add_object(dictionary! { "Font" => fonts });
+
+    let mut kids = Vec::new();
+    for (i, lines) in pages.iter().enumerate() {
+        let content = page_content(lines, i + 1, pages.len()).encode().map_err(|e| e.to_string())?;
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        kids.push(Object::from(page_id));
+    }
+    let count = kids.len() as i64;
+    doc.objects.insert(pages_id, Object::Dictionary(dictionary! {
+        "Type" => "Pages",
+        "Kids" => kids,
+        "Count" => count,
+        "Resources" => resources_id,
+        "MediaBox" => vec![0i64.into(), 0i64.into(), PAGE_WIDTH.into(), PAGE_HEIGHT.into()],
+    }));
+    let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+    doc.trailer.set("Root", catalog_id);
+    doc.compress();
+    doc.save(out).map(|_| ()).map_err(|e| format!("{}: {}", out.display(), e))
+}
@@ -0,0 +1,419 @@
+// --- PDF FIGURES ---
+// Pulls the embedded images out of the research PDFs so plots and schematics can be
+// browsed in the gallery and sent to a vision model. JPEGs are copied as they are;
+// plain 8-bit RGB/gray bitmaps are re-encoded as PNG. Other encodings (JPX, CCITT,
+// indexed palettes) and vector figures are skipped. A caption goes with the image it
+// sits under (or over) on the page, found from where the content stream draws both.
+
+use crate::assets::{GalleryItem, ASSETS_DIR};
+use crate::session;
+use lopdf::{Dictionary, Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const FIGURES_DIR: &str = "figures"; // Under sessions/assets
+const INDEX_FILE: &str = "figures.json";
+const MIN_SIDE: i64 = 100; // Smaller images are logos, icons and bullets
+const CAPTION_GAP: f32 = 150.0; // Points between an image and its caption, at most
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Figure {
+    pub document: String, // PDF path as scanned
+    pub page: usize,
+    pub file: String,     // Relative to the sessions folder, like Message::attachment
+    pub caption: String,  // "Figure 6.3 Miller effect ..." when the page has one
+    pub extracted: i64,   // Unix seconds
+}
+
+impl Figure {
+    pub fn document_name(&self) -> String {
+        Path::new(&self.document).file_name().unwrap_or_default().to_string_lossy().to_string()
+    }
+
+    // "Razavi.pdf p. 212 · Figure 6.3 ..."
+    pub fn label(&self) -> String {
+        let base = format!("{} p. {}", self.document_name(), self.page);
+        if self.caption.is_empty() {
+            base
+        } else {
+            format!("{} · {}", base, self.caption)
+        }
+    }
+
+    pub fn gallery_item(&self, sessions_dir: &Path) -> GalleryItem {
+        GalleryItem {
+            file: sessions_dir.join(&self.file),
+            session: PathBuf::from(&self.document),
+            message_index: self.page,
+            created: self.extracted,
+            caption: self.label(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct FigureIndex {
+    pub documents: std::collections::BTreeMap<String, i64>, // PDF path -> mtime it was read at
+    pub figures: Vec<Figure>,
+}
+
+fn figures_dir(sessions_dir: &Path) -> PathBuf {
+    sessions_dir.join(ASSETS_DIR).join(FIGURES_DIR)
+}
+
+impl FigureIndex {
+    pub fn load(sessions_dir: &Path) -> Self {
+        fs::read_to_string(figures_dir(sessions_dir).join(INDEX_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, sessions_dir: &Path) -> Result<(), String> {
+        let path = figures_dir(sessions_dir).join(INDEX_FILE);
+        let raw = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        session::write_atomic(&path, &raw).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+fn mtime(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+// Blocking: extracts figures from every PDF that is new or changed since the last run.
+// Returns how many figures were added.
+pub fn update(sessions_dir: &Path, documents: &[PathBuf], progress: impl Fn(&str)) -> Result<usize, String> {
+    let dir = figures_dir(sessions_dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut index = FigureIndex::load(sessions_dir);
+    let mut added = 0;
+
+    for (n, path) in documents.iter().enumerate() {
+        let key = path.display().to_string();
+        let modified = mtime(path);
+        if index.documents.get(&key) == Some(&modified) {
+            continue;
+        }
+        progress(&format!("Reading {} ({}/{})", path.display(), n + 1, documents.len()));
+
+        // 1. Drop what an older version of this PDF produced
+        for old in index.figures.iter().filter(|f| f.document == key) {
+            let _ = fs::remove_file(sessions_dir.join(&old.file));
+        }
+        index.figures.retain(|f| f.document != key);
+
+        // 2. Extract; an unreadable PDF is remembered so it isn't retried every time
+        let figures = match Document::load(path) {
+            Ok(doc) => extract(&doc, path, &dir),
+            Err(_) => Vec::new(),
+        };
+        added += figures.len();
+        index.figures.extend(figures);
+        index.documents.insert(key, modified);
+        index.save(sessions_dir)?;
+    }
+    Ok(added)
+}
+
+// FNV-1a of the full path: "notes/a.pdf" and "old/a.pdf" get different file names,
+// and the same PDF keeps its names from run to run
+fn path_hash(path: &Path) -> String {
+    let hash = path.display().to_string().bytes().fold(0xcbf29ce484222325u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    format!("{:08x}", hash as u32)
+}
+
+fn extract(doc: &Document, path: &Path, dir: &Path) -> Vec<Figure> {
+    let stem: String = path.file_stem().unwrap_or_default().to_string_lossy()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    let stem = format!("{}_{}", stem, path_hash(path));
+    let now = chrono::Utc::now().timestamp();
+    let mut seen: HashSet<ObjectId> = HashSet::new(); // Logos repeat on every page
+    let mut figures = Vec::new();
+
+    for (page, page_id) in doc.get_pages() {
+        let images: Vec<(Vec<u8>, ObjectId, Vec<u8>, &str)> = page_images(doc, page_id)
+            .into_iter()
+            .filter(|(_, id, _, _)| seen.insert(*id))
+            .collect();
+        if images.is_empty() {
+            continue;
+        }
+        let captions = doc.extract_text(&[page]).map(|text| captions(&text)).unwrap_or_default();
+        let layout = PageLayout::read(doc, page_id);
+        // Positions are only trusted when they found the same captions as the text did
+        let placed = layout.captions.len() == captions.len();
+
+        for (k, (xobject, _, bytes, ext)) in images.into_iter().enumerate() {
+            let name = format!("{}_p{}_{}.{}", stem, page, k + 1, ext);
+            if fs::write(dir.join(&name), bytes).is_err() {
+                continue;
+            }
+            let caption = match layout.images.get(&xobject) {
+                Some(rect) if placed => caption_for(rect, &layout.captions).and_then(|i| captions.get(i)),
+                // No layout: only a lone caption on a lone-image page is certain
+                _ if captions.len() == 1 && layout.images.len() <= 1 => captions.first(),
+                _ => None,
+            };
+            figures.push(Figure {
+                document: path.display().to_string(),
+                page: page as usize,
+                file: format!("{}/{}/{}", ASSETS_DIR, FIGURES_DIR, name),
+                caption: caption.cloned().unwrap_or_default(),
+                extracted: now,
+            });
+        }
+    }
+    figures
+}
+
+// Where a page draws things, in PDF points from the bottom left
+#[derive(Default)]
+struct PageLayout {
+    images: HashMap<Vec<u8>, Rect>, // XObject name -> where it is first drawn
+    captions: Vec<(f32, f32)>,     // Start of each caption line, in content order
+}
+
+#[derive(Clone, Copy)]
+struct Rect {
+    x0: f32,
+    y0: f32,
+    x1: f32,
+    y1: f32,
+}
+
+type Matrix = [f32; 6];
+
+const IDENTITY: Matrix = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+// m, then n
+fn concat(m: &Matrix, n: &Matrix) -> Matrix {
+    [
+        m[0] * n[0] + m[1] * n[2],
+        m[0] * n[1] + m[1] * n[3],
+        m[2] * n[0] + m[3] * n[2],
+        m[2] * n[1] + m[3] * n[3],
+        m[4] * n[0] + m[5] * n[2] + n[4],
+        m[4] * n[1] + m[5] * n[3] + n[5],
+    ]
+}
+
+fn apply(m: &Matrix, x: f32, y: f32) -> (f32, f32) {
+    (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5])
+}
+
+fn numbers(operands: &[Object]) -> Vec<f32> {
+    operands.iter().filter_map(|o| o.as_float().ok()).collect()
+}
+
+// Bytes of a Tj/TJ operand as far as they read as ASCII; enough to spot "Figure 3"
+fn shown_text(operands: &[Object]) -> String {
+    let mut text = String::new();
+    for operand in operands {
+        match operand {
+            Object::String(bytes, _) => text.extend(bytes.iter().filter(|b| b.is_ascii_graphic() || **b == b' ').map(|b| *b as char)),
+            Object::Array(items) => {
+                for item in items {
+                    match item {
+                        Object::String(..) => text.push_str(&shown_text(std::slice::from_ref(item))),
+                        // A wide negative kern is a word gap
+                        kern => {
+                            if kern.as_float().is_ok_and(|k| k < -200.0) {
+                                text.push(' ');
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+impl PageLayout {
+    fn read(doc: &Document, page_id: ObjectId) -> Self {
+        let mut layout = Self::default();
+        let Ok(content) = doc.get_and_decode_page_content(page_id) else {
+            return layout;
+        };
+        let mut ctm = IDENTITY;
+        let mut stack = Vec::new();
+        let (mut tm, mut tlm) = (IDENTITY, IDENTITY);
+        let mut leading = 0.0;
+        let mut line: Option<(f32, f32, String)> = None; // Start and text of the current line
+
+        for op in &content.operations {
+            let n = numbers(&op.operands);
+            match op.operator.as_str() {
+                "q" => stack.push(ctm),
+                "Q" => ctm = stack.pop().unwrap_or(IDENTITY),
+                "cm" if n.len() == 6 => ctm = concat(&[n[0], n[1], n[2], n[3], n[4], n[5]], &ctm),
+                "Do" => {
+                    if let Some(Ok(name)) = op.operands.first().map(Object::as_name) {
+                        let corners = [apply(&ctm, 0.0, 0.0), apply(&ctm, 1.0, 0.0), apply(&ctm, 0.0, 1.0), apply(&ctm, 1.0, 1.0)];
+                        let rect = Rect {
+                            x0: corners.iter().map(|c| c.0).fold(f32::MAX, f32::min),
+                            y0: corners.iter().map(|c| c.1).fold(f32::MAX, f32::min),
+                            x1: corners.iter().map(|c| c.0).fold(f32::MIN, f32::max),
+                            y1: corners.iter().map(|c| c.1).fold(f32::MIN, f32::max),
+                        };
+                        layout.images.entry(name.to_vec()).or_insert(rect);
+                    }
+                }
+                "BT" => (tm, tlm) = (IDENTITY, IDENTITY),
+                "Tm" if n.len() == 6 => {
+                    tlm = [n[0], n[1], n[2], n[3], n[4], n[5]];
+                    tm = tlm;
+                }
+                "Td" | "TD" if n.len() == 2 => {
+                    if op.operator == "TD" {
+                        leading = -n[1];
+                    }
+                    tlm = concat(&[1.0, 0.0, 0.0, 1.0, n[0], n[1]], &tlm);
+                    tm = tlm;
+                }
+                "TL" if n.len() == 1 => leading = n[0],
+                "T*" | "'" | "\"" => {
+                    tlm = concat(&[1.0, 0.0, 0.0, 1.0, 0.0, -leading], &tlm);
+                    tm = tlm;
+                }
+                _ => {}
+            }
+            if matches!(op.operator.as_str(), "Tj" | "TJ" | "'" | "\"") {
+                let (x, y) = apply(&concat(&tm, &ctm), 0.0, 0.0);
+                let text = shown_text(&op.operands);
+                match &mut line {
+                    Some((_, line_y, so_far)) if (*line_y - y).abs() < 2.0 => so_far.push_str(&text),
+                    _ => {
+                        layout.end_line(line.take());
+                        line = Some((x, y, text));
+                    }
+                }
+            }
+        }
+        layout.end_line(line);
+        layout
+    }
+
+    fn end_line(&mut self, line: Option<(f32, f32, String)>) {
+        if let Some((x, y, text)) = line {
+            if is_caption(text.trim()) {
+                self.captions.push((x, y));
+            }
+        }
+    }
+}
+
+// The nearest caption starting just below the image (figures), else just above it
+// (tables and some styles), among those that start within its width
+fn caption_for(image: &Rect, captions: &[(f32, f32)]) -> Option<usize> {
+    let beside = |x: f32| x >= image.x0 - 72.0 && x <= image.x1;
+    let nearest = |gap: &dyn Fn(f32) -> f32| {
+        captions
+            .iter()
+            .enumerate()
+            .filter(|(_, (x, y))| beside(*x) && (0.0..=CAPTION_GAP).contains(&gap(*y)))
+            .min_by(|a, b| gap(a.1 .1).total_cmp(&gap(b.1 .1)))
+            .map(|(i, _)| i)
+    };
+    nearest(&|y| image.y0 - y).or_else(|| nearest(&|y| y - image.y1))
+}
+
+// The page's own Resources, or the nearest inherited ones
+fn resources(doc: &Document, page_id: ObjectId) -> Option<&Dictionary> {
+    let mut node = page_id;
+    loop {
+        let dict = doc.get_dictionary(node).ok()?;
+        if let Ok(res) = dict.get(b"Resources") {
+            return doc.dereference(res).ok()?.1.as_dict().ok();
+        }
+        node = dict.get(b"Parent").ok()?.as_reference().ok()?;
+    }
+}
+
+// (XObject name, object id, file bytes, extension) for every image XObject we can decode
+fn page_images(doc: &Document, page_id: ObjectId) -> Vec<(Vec<u8>, ObjectId, Vec<u8>, &'static str)> {
+    let Some(xobjects) = resources(doc, page_id)
+        .and_then(|res| res.get(b"XObject").ok())
+        .and_then(|x| doc.dereference(x).ok())
+        .and_then(|(_, x)| x.as_dict().ok())
+    else {
+        return Vec::new();
+    };
+    xobjects
+        .iter()
+        .filter_map(|(name, obj)| {
+            let id = obj.as_reference().ok()?;
+            let stream = doc.get_object(id).ok()?.as_stream().ok()?;
+            if stream.dict.get(b"Subtype").ok()?.as_name().ok()? != b"Image" {
+                return None;
+            }
+            let (bytes, ext) = encode_image(stream)?;
+            Some((name.clone(), id, bytes, ext))
+        })
+        .collect()
+}
+
+fn filters(dict: &Dictionary) -> Vec<Vec<u8>> {
+    match dict.get(b"Filter") {
+        Ok(Object::Name(name)) => vec![name.clone()],
+        Ok(Object::Array(list)) => list.iter().filter_map(|o| o.as_name().ok().map(|n| n.to_vec())).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn encode_image(stream: &lopdf::Stream) -> Option<(Vec<u8>, &'static str)> {
+    let dict = &stream.dict;
+    let width = dict.get(b"Width").ok()?.as_i64().ok()?;
+    let height = dict.get(b"Height").ok()?.as_i64().ok()?;
+    if width < MIN_SIDE || height < MIN_SIDE {
+        return None;
+    }
+    let filters = filters(dict);
+    if filters == [b"DCTDecode".to_vec()] {
+        return Some((stream.content.clone(), "jpg"));
+    }
+    if filters.iter().any(|f| f.as_slice() != b"FlateDecode") {
+        return None;
+    }
+    if dict.get(b"BitsPerComponent").ok()?.as_i64().ok()? != 8 {
+        return None;
+    }
+    let pixels = if filters.is_empty() { stream.content.clone() } else { stream.decompressed_content().ok()? };
+    let (w, h) = (width as u32, height as u32);
+    let image = match dict.get(b"ColorSpace").ok()?.as_name().ok()? {
+        b"DeviceRGB" => image::DynamicImage::ImageRgb8(image::RgbImage::from_raw(w, h, pixels)?),
+        b"DeviceGray" => image::DynamicImage::ImageLuma8(image::GrayImage::from_raw(w, h, pixels)?),
+        _ => return None,
+    };
+    let mut png = std::io::Cursor::new(Vec::new());
+    image.write_to(&mut png, image::ImageOutputFormat::Png).ok()?;
+    Some((png.into_inner(), "png"))
+}
+
+// "Figure 3.2 ...", "Fig. 7 ...", "FIG 2 ..."
+fn is_caption(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    ["figure", "fig.", "fig"].iter().any(|prefix| {
+        lower.strip_prefix(prefix).is_some_and(|rest| rest.trim_start().starts_with(|c: char| c.is_ascii_digit()))
+    })
+}
+
+// Caption lines in page order
+fn captions(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| is_caption(line))
+        .map(|line| line.chars().take(160).collect())
+        .collect()
+}
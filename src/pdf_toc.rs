@@ -0,0 +1,76 @@
+// --- PDF OUTLINES ---
+// Reads a PDF's bookmark tree so a match can be placed in its chapter/section
+// ("Razavi › 6 Frequency Response › 6.2 Miller Effect") and retrieval can be limited
+// to one part of a book.
+
+use lopdf::Document;
+use std::path::Path;
+
+struct Entry {
+    level: usize,
+    title: String,
+    page: usize,
+}
+
+pub struct Outline {
+    doc: Document,
+    entries: Vec<Entry>, // In document order
+}
+
+impl Outline {
+    // None for unreadable PDFs and PDFs without bookmarks
+    pub fn load(path: &Path) -> Option<Self> {
+        let doc = Document::load(path).ok()?;
+        let toc = doc.get_toc().ok()?;
+        let entries: Vec<Entry> = toc.toc
+            .into_iter()
+            .map(|t| Entry { level: t.level, title: t.title.trim().to_string(), page: t.page })
+            .filter(|e| !e.title.is_empty())
+            .collect();
+        if entries.is_empty() {
+            return None;
+        }
+        Some(Self { doc, entries })
+    }
+
+    // Chapter down to the deepest section that starts on or before `page`
+    pub fn breadcrumb(&self, page: usize) -> Vec<String> {
+        let mut crumbs: Vec<(usize, &str)> = Vec::new();
+        for entry in self.entries.iter().filter(|e| e.page <= page) {
+            crumbs.retain(|(level, _)| *level < entry.level);
+            crumbs.push((entry.level, &entry.title));
+        }
+        crumbs.into_iter().map(|(_, title)| title.to_string()).collect()
+    }
+
+    // (page number, text) for every page, 1-based like the outline
+    pub fn pages(&self) -> impl Iterator<Item = (usize, String)> + '_ {
+        self.doc.get_pages()
+            .into_keys()
+            .filter_map(|n| self.doc.extract_text(&[n]).ok().map(|text| (n as usize, text)))
+    }
+}
+
+// "Chapter 6", "ch 6.2" and "6" match numbered titles ("6 Frequency Response",
+// "Chapter 6: ..."); anything else is a case-insensitive substring of a title
+pub fn section_matches(filter: &str, crumbs: &[String]) -> bool {
+    let filter = filter.trim().to_lowercase();
+    if filter.is_empty() {
+        return true;
+    }
+    let strip_chapter = |s: &str| {
+        s.trim_start_matches("chapter").trim_start_matches("ch.").trim_start_matches("ch").trim().to_string()
+    };
+    let number = strip_chapter(&filter);
+    let is_number = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit() || c == '.');
+
+    crumbs.iter().any(|title| {
+        let title = title.to_lowercase();
+        if is_number {
+            let rest = strip_chapter(&title);
+            rest.starts_with(&number) && !rest[number.len()..].starts_with(|c: char| c.is_ascii_digit())
+        } else {
+            title.contains(&filter)
+        }
+    })
+}
@@ -0,0 +1,33 @@
+// --- STUDY TIMER ---
+// Pomodoro blocks are recorded in the session as start/stop events between messages;
+// `/recap` asks the model to summarize the messages of the last block.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use crate::session::{Message, TimerEvent, TimerEventKind};
+use crate::summary;
+
+pub const RECAP_COMMAND: &str = "/recap";
+
+const RECAPPER: &str = "You are a study assistant. Recap what was covered in this study block: \
+the main ideas, any results or formulas worked out, and open questions to pick up next time. \
+Use short bullet points. Only use what is in the conversation.";
+
+// Messages of the most recent block: from its start to its stop, or to now while it runs
+pub fn last_block(events: &[TimerEvent], message_count: usize) -> Option<std::ops::Range<usize>> {
+    let start = events.iter().rposition(|e| e.kind == TimerEventKind::Start)?;
+    let end = events[start + 1..]
+        .iter()
+        .find(|e| e.kind != TimerEventKind::Start)
+        .map(|e| e.message_index)
+        .unwrap_or(message_count);
+    Some(events[start].message_index.min(end)..end)
+}
+
+// Blocking
+pub fn recap(backend: &BackendConfig, model: &str, block: &[Message]) -> Result<String, String> {
+    if block.is_empty() {
+        return Err("Nothing was discussed during the last study block".to_string());
+    }
+    llm::complete(backend, model, RECAPPER, &summary::transcript(block))
+}
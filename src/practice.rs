@@ -0,0 +1,59 @@
+// --- PRACTICE PROBLEMS ---
+// Study mode: the model writes a problem plus a worked solution; the solution stays
+// hidden (a <details> block) until revealed, and typed attempts are graded against it.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use serde::{Deserialize, Serialize};
+
+const GENERATOR: &str = "You write practice problems for an electrical engineering student. \
+Reply in exactly this shape:\nPROBLEM:\n<the problem statement, with all given values>\nSOLUTION:\n<a complete worked solution ending with the final answer>";
+
+const GRADER: &str = "You grade a student's attempt against a reference solution. Start with one of \
+'✅ Correct', '🟡 Partially correct' or '❌ Incorrect', then explain which steps were right or wrong \
+without simply restating the whole reference solution.";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PracticeProblem {
+    pub topic: String,
+    pub problem: String,
+    pub solution: String,
+}
+
+impl PracticeProblem {
+    // Chat text: the statement visible, the solution collapsed
+    pub fn as_message(&self) -> String {
+        format!(
+            "🎓 **Practice problem: {}**\n\n{}\n\n<details><summary>Reveal solution</summary>\n{}\n</details>\n\nType your attempt below and press Send to have it graded.",
+            self.topic, self.problem, self.solution
+        )
+    }
+}
+
+fn parse(topic: &str, reply: &str) -> Result<PracticeProblem, String> {
+    let p = reply.find("PROBLEM:").ok_or("Model reply had no PROBLEM section")?;
+    let s = reply.find("SOLUTION:").ok_or("Model reply had no SOLUTION section")?;
+    if s < p {
+        return Err("Model put the solution before the problem".to_string());
+    }
+    Ok(PracticeProblem {
+        topic: topic.to_string(),
+        problem: reply[p + "PROBLEM:".len()..s].trim().to_string(),
+        solution: reply[s + "SOLUTION:".len()..].trim().to_string(),
+    })
+}
+
+// Blocking
+pub fn generate(backend: &BackendConfig, model: &str, topic: &str) -> Result<PracticeProblem, String> {
+    let reply = llm::complete(backend, model, GENERATOR, &format!("Topic: {}", topic))?;
+    parse(topic, &reply)
+}
+
+// Blocking
+pub fn grade(backend: &BackendConfig, model: &str, problem: &PracticeProblem, attempt: &str) -> Result<String, String> {
+    let prompt = format!(
+        "PROBLEM:\n{}\n\nREFERENCE SOLUTION:\n{}\n\nSTUDENT ATTEMPT:\n{}",
+        problem.problem, problem.solution, attempt
+    );
+    llm::complete(backend, model, GRADER, &prompt)
+}
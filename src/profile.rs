@@ -0,0 +1,198 @@
+// --- USER PROFILES ---
+// Each person on the machine gets their own config, sessions and modelfiles.
+// Everything lives under the data root: the platform data folder (~/.local/share/ship-of-theseus,
+// %APPDATA%\ship-of-theseus), or $SHIP_OF_THESEUS_HOME. The working directory is never
+// the root: data that older versions kept there is moved over once, on the first run
+// that finds it.
+
+use crate::config::CONFIG_FILE;
+use crate::session::SESSIONS_DIR;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const PROFILES_DIR: &str = "profiles";
+pub const DEFAULT_PROFILE: &str = "default";
+const ACTIVE_FILE: &str = "active"; // Inside PROFILES_DIR: name of the profile used last
+const HOME_VAR: &str = "SHIP_OF_THESEUS_HOME";
+
+const MOVED_FILE: &str = "legacy_moved"; // In the data root once ./ data was moved in
+
+// What older versions kept in the working directory
+const LEGACY_ITEMS: [&str; 12] = [
+    CONFIG_FILE,
+    SESSIONS_DIR,
+    PROFILES_DIR,
+    crate::search_index::INDEX_FILE,
+    crate::topics::TOPICS_FILE,
+    crate::jobs::JOBS_FILE,
+    crate::glossary::NOTES_FILE,
+    crate::retrieval_eval::EVAL_FILE,
+    crate::organizer::JOURNAL_FILE,
+    crate::instance::INSTANCE_FILE,
+    crate::modelfile::MODELFILES_DIR,
+    crate::calendar::CALENDAR_FILE,
+];
+
+static MIGRATION_NOTE: OnceLock<String> = OnceLock::new();
+
+// Decided once per run
+pub fn data_root() -> PathBuf {
+    static ROOT: OnceLock<PathBuf> = OnceLock::new();
+    ROOT.get_or_init(|| {
+        if let Some(home) = std::env::var_os(HOME_VAR).filter(|h| !h.is_empty()) {
+            return PathBuf::from(home);
+        }
+        let Some(root) = directories::ProjectDirs::from("", "", "ship-of-theseus").map(|dirs| dirs.data_dir().to_path_buf()) else {
+            return PathBuf::from("."); // No home folder at all
+        };
+        if let Some(note) = move_legacy_data(&root) {
+            eprintln!("{}", note);
+            let _ = MIGRATION_NOTE.set(note);
+        }
+        root
+    })
+    .clone()
+}
+
+// What the move of ./ data did this run, for the log
+pub fn migration_note() -> Option<&'static str> {
+    MIGRATION_NOTE.get().map(String::as_str)
+}
+
+// Moves ./ data into `root` the first time; an item that already exists there is left
+// alone in both places. Later runs, from any directory, skip this.
+fn move_legacy_data(root: &Path) -> Option<String> {
+    let present: Vec<&str> = LEGACY_ITEMS.into_iter().filter(|item| Path::new(item).exists()).collect();
+    if present.is_empty() || root.join(MOVED_FILE).exists() {
+        return None;
+    }
+    let cwd = std::env::current_dir().ok()?;
+    if cwd == root {
+        return None;
+    }
+    if let Err(e) = fs::create_dir_all(root) {
+        return Some(format!("Could not create {}: {}; data in {} was not moved", root.display(), e, cwd.display()));
+    }
+
+    let mut moved = Vec::new();
+    let mut kept = Vec::new();
+    for item in present {
+        let to = root.join(item);
+        if to.exists() {
+            kept.push(format!("{} (already in {})", item, root.display()));
+            continue;
+        }
+        match move_item(Path::new(item), &to) {
+            Ok(()) => moved.push(item),
+            Err(e) => kept.push(format!("{} ({})", item, e)),
+        }
+    }
+    let _ = fs::write(root.join(MOVED_FILE), cwd.display().to_string());
+
+    let mut note = format!("Moved {} from {} to {}", moved.join(", "), cwd.display(), root.display());
+    if !kept.is_empty() {
+        note.push_str(&format!("; left in place: {}", kept.join(", ")));
+    }
+    Some(note)
+}
+
+// Rename, or copy then delete when the data folder is on another filesystem
+fn move_item(from: &Path, to: &Path) -> Result<(), String> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_tree(from, to).map_err(|e| e.to_string())?;
+    match from.is_dir() {
+        true => fs::remove_dir_all(from),
+        false => fs::remove_file(from),
+    }
+    .map_err(|e| format!("copied, but the original could not be removed: {}", e))
+}
+
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    if !from.is_dir() {
+        return fs::copy(from, to).map(|_| ());
+    }
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+fn profiles_dir() -> PathBuf {
+    data_root().join(PROFILES_DIR)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub name: String,
+}
+
+impl Profile {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.trim().to_string() }
+    }
+
+    fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+
+    pub fn root(&self) -> PathBuf {
+        if self.is_default() {
+            data_root()
+        } else {
+            profiles_dir().join(&self.name)
+        }
+    }
+
+    pub fn sessions_dir(&self) -> PathBuf {
+        self.root().join(SESSIONS_DIR)
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        self.root().join(CONFIG_FILE)
+    }
+
+    pub fn create_dirs(&self) -> Result<(), String> {
+        fs::create_dir_all(self.sessions_dir()).map_err(|e| e.to_string())
+    }
+
+    // Remember this profile for the next launch
+    pub fn mark_active(&self) {
+        let _ = fs::create_dir_all(profiles_dir());
+        let _ = fs::write(profiles_dir().join(ACTIVE_FILE), &self.name);
+    }
+
+    pub fn last_active() -> Self {
+        fs::read_to_string(profiles_dir().join(ACTIVE_FILE))
+            .ok()
+            .map(|name| Self::new(&name))
+            .filter(|p| !p.name.is_empty() && (p.is_default() || p.root().is_dir()))
+            .unwrap_or_else(|| Self::new(DEFAULT_PROFILE))
+    }
+}
+
+pub fn list_profiles() -> Vec<String> {
+    let mut names = vec![DEFAULT_PROFILE.to_string()];
+    if let Ok(entries) = fs::read_dir(profiles_dir()) {
+        let mut others: Vec<String> = entries
+            .flatten()
+            .filter(|e| e.path().is_dir())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        others.sort();
+        names.extend(others);
+    }
+    names
+}
+
+// Profile names become directory names
+pub fn valid_name(name: &str) -> bool {
+    let name = name.trim();
+    !name.is_empty()
+        && name != DEFAULT_PROFILE
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
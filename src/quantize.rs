@@ -0,0 +1,89 @@
+// --- LOW-VRAM SUGGESTIONS ---
+// When a model won't fit on the GPU, propose smaller quantizations of the same model.
+// Ollama has no registry search API, so candidate tags follow the library naming
+// ("<name>:<size>-<variant>-q4_K_M") and sizes are estimated from bits per weight;
+// a tag that doesn't exist simply fails to pull.
+
+use crate::backend::LocalModel;
+use serde::{Deserialize, Serialize};
+
+// Approximate bits per weight, largest first
+const QUANTS: &[(&str, f64)] = &[
+    ("fp16", 16.0),
+    ("q8_0", 8.5),
+    ("q6_K", 6.6),
+    ("q5_K_M", 5.7),
+    ("q4_K_M", 4.8),
+    ("q4_0", 4.5),
+    ("q3_K_M", 3.9),
+    ("q2_K", 3.4),
+];
+
+// Share of VRAM left for the KV cache and other processes
+const HEADROOM: f64 = 0.9;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Suggestion {
+    pub tag: String,
+    pub quant: String,
+    pub estimated_bytes: u64,
+    pub current_bytes: u64,
+}
+
+impl Suggestion {
+    // "≈ 4.9 GB (−3.6 GB)"
+    pub fn size_label(&self) -> String {
+        let saved = self.current_bytes.saturating_sub(self.estimated_bytes);
+        format!("≈ {} (−{})", gigabytes(self.estimated_bytes), gigabytes(saved))
+    }
+}
+
+pub fn gigabytes(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1e9)
+}
+
+pub fn fits(model: &LocalModel, vram_total_mb: u64) -> bool {
+    vram_total_mb == 0 || (model.size as f64) <= vram_total_mb as f64 * 1e6 * HEADROOM
+}
+
+// Smaller quantizations that should fit, best quality first
+pub fn suggest(model: &LocalModel, vram_total_mb: u64) -> Vec<Suggestion> {
+    if fits(model, vram_total_mb) {
+        return Vec::new();
+    }
+    let budget = vram_total_mb as f64 * 1e6 * HEADROOM;
+    let current = model.details.quantization_level.to_lowercase();
+    let current_bits = QUANTS.iter()
+        .find(|(q, _)| q.to_lowercase() == current || (current == "f16" && *q == "fp16"))
+        .map(|(_, bits)| *bits)
+        .unwrap_or(4.8); // Unknown: Ollama's usual default
+
+    QUANTS.iter()
+        .filter(|(_, bits)| *bits < current_bits)
+        .map(|(quant, bits)| Suggestion {
+            tag: candidate_tag(model, quant),
+            quant: quant.to_string(),
+            estimated_bytes: (model.size as f64 * bits / current_bits) as u64,
+            current_bytes: model.size,
+        })
+        .filter(|s| (s.estimated_bytes as f64) <= budget)
+        .collect()
+}
+
+// "llama3:8b-instruct-q8_0" -> "llama3:8b-instruct-q4_K_M"; "gemma3:27b" -> "gemma3:27b-it-q4_K_M"
+fn candidate_tag(model: &LocalModel, quant: &str) -> String {
+    let (repo, tag) = model.name.split_once(':').unwrap_or((&model.name, "latest"));
+    let current = model.details.quantization_level.to_lowercase();
+
+    // 1. Tag already names its quantization: swap it
+    if !current.is_empty() {
+        if let Some(pos) = tag.to_lowercase().rfind(&current) {
+            return format!("{}:{}{}", repo, &tag[..pos], quant);
+        }
+    }
+
+    // 2. Otherwise build "<size>-<variant>-<quant>" from the model details
+    let size = if tag == "latest" { model.details.parameter_size.to_lowercase().replace(".0b", "b") } else { tag.to_string() };
+    let variant = if model.details.family.starts_with("gemma") { "it" } else { "instruct" };
+    format!("{}:{}-{}-{}", repo, size, variant, quant)
+}
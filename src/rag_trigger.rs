@@ -0,0 +1,122 @@
+// --- AUTO-RAG TRIGGER ---
+// Decides whether a prompt should go through the document scan first. Cheap keyword
+// heuristics answer most prompts; the undecided rest can be sent to a one-word
+// classifier call.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum RagMode {
+    Off,
+    Always, // The old "Reasoning Mode" checkbox
+    Auto,
+}
+
+impl RagMode {
+    pub const ALL: [RagMode; 3] = [RagMode::Off, RagMode::Always, RagMode::Auto];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            RagMode::Off => "Off",
+            RagMode::Always => "Always",
+            RagMode::Auto => "Auto",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct RagTriggerConfig {
+    pub mode: RagMode,
+    pub citation_keywords: Vec<String>, // Any of these => retrieve
+    pub question_words: bool,           // Questions lean towards retrieving
+    pub classifier: bool,               // Ask the model when the heuristics can't tell
+}
+
+impl Default for RagTriggerConfig {
+    fn default() -> Self {
+        Self {
+            mode: RagMode::Off,
+            citation_keywords: ["cite", "citation", "source", "reference", "according to", "datasheet", "paper", "my notes", "my documents", "pdf"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            question_words: true,
+            classifier: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Decision {
+    pub retrieve: bool,
+    pub reason: String,
+}
+
+impl Decision {
+    pub fn new(retrieve: bool, reason: impl Into<String>) -> Self {
+        Self { retrieve, reason: reason.into() }
+    }
+}
+
+const QUESTION_WORDS: &[&str] = &["what", "how", "why", "which", "when", "where", "explain", "compare", "does", "is"];
+const CHAT_OPENERS: &[&str] = &["hi", "hello", "hey", "thanks", "thank", "ok", "okay", "write", "rewrite", "translate", "summarize"];
+
+const CLASSIFIER: &str = "Decide whether answering the user's message requires looking things up in their \
+personal library of PDFs (datasheets, papers, lecture notes). Reply with exactly one word: yes or no.";
+
+// Whole words only, so "resource" is not "source" and "newspaper" is not "paper"; the
+// last word may be plural ("sources", "my notes" already is)
+fn mentions(words: &[&str], keyword: &str) -> bool {
+    let keyword = keyword.to_lowercase();
+    let wanted: Vec<&str> = keyword.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+    let Some((last, leading)) = wanted.split_last() else { return false };
+    words.windows(wanted.len()).any(|window| {
+        let word = window[leading.len()];
+        window[..leading.len()] == *leading
+            && (word == *last || word.strip_suffix('s').is_some_and(|w| w == *last || w.strip_suffix('e') == Some(*last)))
+    })
+}
+
+// None = undecided
+pub fn heuristic(prompt: &str, config: &RagTriggerConfig) -> Option<Decision> {
+    let lower = prompt.to_lowercase();
+    let words: Vec<&str> = lower.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+
+    // 1. Explicit ask for sources always wins
+    if let Some(k) = config.citation_keywords.iter().find(|k| mentions(&words, k)) {
+        return Some(Decision::new(true, format!("mentions '{}'", k)));
+    }
+
+    // 2. Chit-chat and pure writing tasks don't need the library
+    if words.len() < 4 {
+        return Some(Decision::new(false, "short message"));
+    }
+    if let Some(first) = words.first().filter(|w| CHAT_OPENERS.contains(w)) {
+        return Some(Decision::new(false, format!("starts with '{}'", first)));
+    }
+
+    // 3. Knowledge questions lean towards retrieval
+    if config.question_words {
+        if let Some(q) = words.first().filter(|w| QUESTION_WORDS.contains(w)) {
+            return Some(Decision::new(true, format!("question ('{}')", q)));
+        }
+        if prompt.trim_end().ends_with('?') {
+            return Some(Decision::new(true, "ends with '?'"));
+        }
+    }
+    None
+}
+
+// Blocking: the classifier fallback
+pub fn classify(backend: &BackendConfig, model: &str, prompt: &str) -> Decision {
+    match llm::complete(backend, model, CLASSIFIER, prompt) {
+        Ok(reply) => {
+            let yes = reply.trim().to_lowercase().starts_with("yes");
+            Decision::new(yes, format!("classifier said '{}'", reply.trim().chars().take(12).collect::<String>()))
+        }
+        Err(e) => Decision::new(false, format!("classifier failed ({}), not retrieving", e)),
+    }
+}
@@ -0,0 +1,116 @@
+// --- REFUSAL RETRY ---
+// Some models answer benign EE questions (mains wiring, battery packs, gate drivers) with
+// boilerplate refusals or nothing at all. A finished reply is checked against the usual
+// phrasings, and when enabled the prompt is sent once more with a clarifying note or to
+// another model.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum RetryStrategy {
+    Clarify,        // Same model, prompt plus a note on why it is being asked
+    AlternateModel, // The same prompt to `alternate_model`
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct RetryConfig {
+    pub enabled: bool,
+    pub strategy: RetryStrategy,
+    pub alternate_model: String, // Empty = clarify with the same model instead
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self { enabled: false, strategy: RetryStrategy::Clarify, alternate_model: String::new() }
+    }
+}
+
+// One retried reply, for the trace panel
+#[derive(Clone, Debug)]
+pub struct RetryTrace {
+    pub at: i64,                 // Unix millis when the refusal was caught
+    pub message_index: usize,    // The prompt that was retried
+    pub reason: String,          // What matched
+    pub model: String,           // Model that refused
+    pub refused: String,         // The reply that was replaced
+    pub retry_model: String,
+    pub retry_prompt: String,
+    pub outcome: Option<String>, // Filled in when the retry finishes
+}
+
+// Only looked for near the start: long answers often end with a safety caveat
+const OPENING_CHARS: usize = 240;
+const MAX_REFUSAL_CHARS: usize = 700;
+
+const REFUSALS: &[&str] = &[
+    "i'm sorry, but i can't",
+    "i am sorry, but i cannot",
+    "i can't help with",
+    "i cannot help with",
+    "i can't assist with",
+    "i cannot assist with",
+    "i'm not able to help",
+    "i am not able to help",
+    "i'm unable to help",
+    "i am unable to provide",
+    "i cannot provide",
+    "i can't provide",
+    "i won't be able to",
+    "as an ai language model",
+    "as an ai, i",
+    "i must decline",
+    "consult a licensed electrician",
+    "consult a qualified professional",
+];
+
+const NON_ANSWERS: &[&str] = &["i don't know", "i do not know", "i'm not sure", "i am not sure", "no answer"];
+
+// Why `reply` looks like a refusal or a non-answer, if it does
+pub fn detect(reply: &str) -> Option<String> {
+    let text = reply.trim();
+    if text.is_empty() {
+        return Some("empty reply".to_string());
+    }
+    if text.chars().count() > MAX_REFUSAL_CHARS {
+        return None;
+    }
+    let opening: String = text.chars().take(OPENING_CHARS).collect::<String>().to_lowercase().replace('’', "'");
+    if let Some(phrase) = REFUSALS.iter().find(|p| opening.contains(*p)) {
+        return Some(format!("refusal: \"{}\"", phrase));
+    }
+    // A whole reply of "I don't know." and little else
+    if text.chars().count() < 80 {
+        if let Some(phrase) = NON_ANSWERS.iter().find(|p| opening.contains(*p)) {
+            return Some(format!("non-answer: \"{}\"", phrase));
+        }
+    }
+    None
+}
+
+pub fn clarified_prompt(prompt: &str) -> String {
+    format!(
+        "{}\n\n(Context: this is a legitimate electrical-engineering question from coursework or lab work. \
+         Answer it directly and technically; where there is a hazard, state the precautions as part of the answer instead of declining.)",
+        prompt
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detect;
+
+    #[test]
+    fn catches_refusals_and_non_answers() {
+        assert_eq!(detect("  ").as_deref(), Some("empty reply"));
+        assert_eq!(detect("I’m sorry, but I can’t help with mains wiring.").as_deref(), Some("refusal: \"i'm sorry, but i can't\""));
+        assert_eq!(detect("I don't know.").as_deref(), Some("non-answer: \"i don't know\""));
+    }
+
+    #[test]
+    fn answers_with_a_closing_caveat_pass() {
+        let reply = format!("{} For the panel itself, consult a licensed electrician.", "Use a 20 A breaker on 12 AWG wire. ".repeat(8));
+        assert_eq!(detect(&reply), None);
+        assert_eq!(detect("The Miller effect multiplies the gate-drain capacitance by the gain."), None);
+    }
+}
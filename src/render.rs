@@ -0,0 +1,122 @@
+// --- OUTPUT STRUCTURE ---
+// Splits a model reply into plain text, collapsible <details> sections and spoilers
+// so the chat view can hide worked solutions until they are asked for.
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Block {
+    Text(String),
+    Details { summary: String, body: Vec<Block> },
+    Spoiler(String), // ||hidden|| or <spoiler>hidden</spoiler>
+}
+
+// Post-processor: lines that start a heading named like one of `titles`
+// ("Solution:", "**Solution**", "### Solution") get wrapped in <details>
+pub fn collapse_sections(content: &str, titles: &[String]) -> String {
+    if titles.is_empty() {
+        return content.to_string();
+    }
+
+    let heading_title = |line: &str| -> Option<String> {
+        let bare = line.trim().trim_start_matches('#').trim().trim_matches('*').trim().trim_end_matches(':').trim();
+        titles.iter()
+            .find(|t| !t.trim().is_empty() && bare.eq_ignore_ascii_case(t.trim()))
+            .map(|_| bare.to_string())
+    };
+    let is_heading = |line: &str| line.trim_start().starts_with('#');
+
+    let mut out = String::new();
+    let mut open = false;
+    for line in content.lines() {
+        if let Some(title) = heading_title(line) {
+            if open {
+                out.push_str("</details>\n");
+            }
+            out.push_str(&format!("<details><summary>{}</summary>\n", title));
+            open = true;
+            continue;
+        }
+        // Any other heading ends the hidden section
+        if open && is_heading(line) {
+            out.push_str("</details>\n");
+            open = false;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    if open {
+        out.push_str("</details>\n");
+    }
+    out
+}
+
+pub fn parse(content: &str) -> Vec<Block> {
+    // Reasoning models wrap their chain of thought in <think>; show it as a collapsed section
+    let content = content.replace("<think>", "<details><summary>Thinking</summary>").replace("</think>", "</details>");
+    parse_details(&content)
+}
+
+fn parse_details(content: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut rest = content;
+
+    while let Some(start) = rest.find("<details>") {
+        push_text(&mut blocks, &rest[..start]);
+        let after = &rest[start + "<details>".len()..];
+
+        // Find the matching </details>, allowing nesting
+        let mut depth = 1;
+        let mut cursor = 0;
+        let mut end = None;
+        while depth > 0 {
+            let next_open = after[cursor..].find("<details>");
+            let next_close = after[cursor..].find("</details>");
+            match (next_open, next_close) {
+                (Some(o), Some(c)) if o < c => { depth += 1; cursor += o + "<details>".len(); }
+                (_, Some(c)) => {
+                    depth -= 1;
+                    if depth == 0 { end = Some(cursor + c); }
+                    cursor += c + "</details>".len();
+                }
+                _ => break, // Unclosed (e.g. still streaming): keep the whole tail inside
+            }
+        }
+
+        let inner = &after[..end.unwrap_or(after.len())];
+        let (summary, body) = match (inner.find("<summary>"), inner.find("</summary>")) {
+            (Some(s), Some(e)) if s < e => (inner[s + "<summary>".len()..e].trim().to_string(), &inner[e + "</summary>".len()..]),
+            _ => ("Details".to_string(), inner),
+        };
+        blocks.push(Block::Details { summary, body: parse_details(body) });
+
+        rest = match end {
+            Some(e) => &after[e + "</details>".len()..],
+            None => "",
+        };
+    }
+    push_text(&mut blocks, rest);
+    blocks
+}
+
+// Plain text, with spoilers pulled out as their own blocks.
+// Fenced code is left alone so `a || b` stays code.
+fn push_text(blocks: &mut Vec<Block>, text: &str) {
+    for (i, segment) in text.split("```").enumerate() {
+        if i % 2 == 1 {
+            blocks.push(Block::Text(format!("```{}```", segment)));
+            continue;
+        }
+
+        let segment = segment.replace("<spoiler>", "||").replace("</spoiler>", "||");
+        for (j, part) in segment.split("||").enumerate() {
+            if part.trim().is_empty() {
+                continue;
+            }
+            // Odd parts sit between a pair of markers
+            if j % 2 == 1 {
+                blocks.push(Block::Spoiler(part.trim().to_string()));
+            } else {
+                blocks.push(Block::Text(part.trim_matches('\n').to_string()));
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+// --- SESSION REPLAY ---
+// Turns per-message timestamps into a timeline the replay view can scrub through.
+// Messages saved before timestamps existed are spaced a fixed interval apart.
+
+use crate::session::Message;
+
+const LEGACY_GAP_SECS: f64 = 5.0;
+
+pub const SPEEDS: [f64; 5] = [1.0, 2.0, 5.0, 20.0, 100.0];
+
+#[derive(Clone, Debug, Default)]
+pub struct Timeline {
+    pub offsets: Vec<f64>, // Seconds from the first message
+    pub duration: f64,
+}
+
+impl Timeline {
+    // `max_gap` caps idle stretches (lunch breaks) so playback doesn't sit on nothing
+    pub fn build(messages: &[Message], max_gap: Option<f64>) -> Self {
+        let mut offsets = Vec::with_capacity(messages.len());
+        let mut clock = 0.0;
+        let mut previous: Option<i64> = None;
+
+        for (i, msg) in messages.iter().enumerate() {
+            if i > 0 {
+                let gap = match (previous, msg.timestamp) {
+                    (Some(a), Some(b)) => ((b - a) as f64 / 1000.0).max(0.0),
+                    _ => LEGACY_GAP_SECS,
+                };
+                clock += max_gap.map_or(gap, |cap| gap.min(cap));
+            }
+            offsets.push(clock);
+            previous = msg.timestamp.or(previous);
+        }
+        Self { duration: clock, offsets }
+    }
+
+    // How many messages have "happened" by `position`
+    pub fn visible_at(&self, position: f64) -> usize {
+        self.offsets.iter().take_while(|t| **t <= position).count()
+    }
+}
+
+// "1:05:09" / "4:32"
+pub fn clock_label(seconds: f64) -> String {
+    let s = seconds.max(0.0) as u64;
+    if s >= 3600 {
+        format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+    } else {
+        format!("{}:{:02}", s / 60, s % 60)
+    }
+}
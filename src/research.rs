@@ -0,0 +1,293 @@
+// --- RESEARCH LIBRARY ---
+// Which documents under a research directory the scanner is allowed to read, how a
+// query is matched against them and the matches ranked, and how a match is cited and
+// recorded. Chat, batch runs and the retrieval eval all go through `match_document`
+// and `rank`, so the eval scores exactly what a chat would have been given.
+
+use chrono::{DateTime, Datelike, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Glob patterns relative to the research directory, e.g. `**/Archive/**`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct DirFilters {
+    pub include: Vec<String>, // Empty = every PDF
+    pub exclude: Vec<String>,
+}
+
+impl DirFilters {
+    pub fn allows(&self, root: &Path, path: &Path) -> bool {
+        let rel = path.strip_prefix(root).unwrap_or(path);
+        let matches = |patterns: &[String]| {
+            patterns.iter()
+                .map(|p| p.trim())
+                .filter(|p| !p.is_empty())
+                .filter_map(|p| glob::Pattern::new(p).ok())
+                .any(|p| p.matches_path(rel))
+        };
+
+        let has_includes = self.include.iter().any(|p| !p.trim().is_empty());
+        (!has_includes || matches(&self.include)) && !matches(&self.exclude)
+    }
+}
+
+// Every PDF under `dir` that passes the directory's filters, plus the folder overviews
+// written by "Summarize folder" so retrieval can find them too
+pub fn collect_documents(dir: &str, filters: &DirFilters) -> Vec<PathBuf> {
+    let root = Path::new(dir);
+    let patterns = [format!("{}/**/*.pdf", dir), format!("{}/**/*{}", dir, crate::folder_summary::OVERVIEW_SUFFIX)];
+
+    patterns
+        .iter()
+        .filter_map(|pattern| glob::glob(pattern).ok())
+        .flat_map(|paths| paths.flatten())
+        .filter(|p| filters.allows(root, p))
+        .collect()
+}
+
+pub fn is_pdf(path: &Path) -> bool {
+    path.extension().map_or(false, |e| e.eq_ignore_ascii_case("pdf"))
+}
+
+pub const MAX_SOURCES: usize = 8; // Best-ranked excerpts a reply is given
+
+const STOPWORDS: [&str; 40] = [
+    "the", "and", "for", "with", "what", "how", "why", "does", "this", "that", "from", "are", "was", "were",
+    "can", "you", "your", "about", "into", "when", "which", "who", "whom", "there", "their", "them", "then",
+    "than", "have", "has", "had", "not", "but", "all", "any", "some", "explain", "tell", "please", "between",
+];
+
+// The words of a query worth looking for, lowercase and deduplicated: three letters or
+// more, or anything with a digit ("Q1", "3dB"), minus stopwords. A query of nothing
+// else is looked for as a whole.
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for word in query.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '.')).map(|w| w.trim_matches(['-', '.'])) {
+        let word = word.to_lowercase();
+        let keep = word.chars().count() >= 3 || word.chars().any(|c| c.is_ascii_digit());
+        if keep && !STOPWORDS.contains(&word.as_str()) && !terms.contains(&word) {
+            terms.push(word);
+        }
+    }
+    if terms.is_empty() && !query.trim().is_empty() {
+        terms.push(query.trim().to_lowercase());
+    }
+    terms
+}
+
+// Where the earliest term occurs and how well the text matches: term hits per 1000
+// chars, weighted by the share of terms present. None unless at least half of the
+// terms occur. The offset is a byte index into `content`.
+fn best_hit(content: &str, terms: &[String]) -> Option<(usize, f32)> {
+    let (lower, origin) = fold_case(content);
+    let mut first = None;
+    let (mut hits, mut present) = (0, 0);
+    for term in terms {
+        let mut found = lower.match_indices(term.as_str()).map(|(at, _)| origin[at]).peekable();
+        if let Some(&at) = found.peek() {
+            first = Some(first.map_or(at, |f: usize| f.min(at)));
+            present += 1;
+            hits += found.count();
+        }
+    }
+    if terms.is_empty() || present * 2 < terms.len() {
+        return None;
+    }
+    let density = hits as f32 * 1000.0 / content.len().max(1) as f32;
+    Some((first?, density * present as f32 / terms.len() as f32))
+}
+
+// `text` lowercased the same way as the terms, plus for every byte of it the byte
+// offset of the character it came from (lowercasing can change a char's length)
+fn fold_case(text: &str) -> (String, Vec<usize>) {
+    let mut lower = String::with_capacity(text.len());
+    let mut origin = Vec::with_capacity(text.len());
+    for (at, c) in text.char_indices() {
+        for l in c.to_lowercase() {
+            lower.push(l);
+            origin.resize(lower.len(), at);
+        }
+    }
+    (lower, origin)
+}
+
+// Best first and at most MAX_SOURCES; ties keep scan order
+pub fn rank(mut sources: Vec<SourceChunk>) -> Vec<SourceChunk> {
+    sources.sort_by(|a, b| b.score.total_cmp(&a.score));
+    sources.truncate(MAX_SOURCES);
+    sources
+}
+
+// Byte range of the text around `index`
+fn snippet_range(content: &str, index: usize) -> (usize, usize) {
+    let mut start = index.saturating_sub(200).min(content.len());
+    let mut end = (index + 500).min(content.len());
+    while !content.is_char_boundary(start) {
+        start -= 1;
+    }
+    while !content.is_char_boundary(end) {
+        end -= 1;
+    }
+    (start, end)
+}
+
+// One injected excerpt, kept with the reply it informed so an old session still shows
+// its sources after the library or index has changed
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SourceChunk {
+    pub file: String,         // Path as scanned
+    pub page: Option<usize>,  // Only for PDFs with bookmarks
+    pub section: String,      // "6 Frequency Response › 6.2 Miller Effect"
+    pub start: usize,         // Byte offsets of `text` in the page (or whole document) text
+    pub end: usize,
+    pub score: f32,           // Query-term hits per 1000 chars of the page/document, see `best_hit`
+    pub text: String,
+}
+
+impl SourceChunk {
+    fn new(path: &Path, content: &str, terms: &[String], page: Option<usize>, section: String) -> Option<Self> {
+        let (index, score) = best_hit(content, terms)?;
+        let (start, end) = snippet_range(content, index);
+        Some(Self {
+            file: path.display().to_string(),
+            page,
+            section,
+            start,
+            end,
+            score,
+            text: content[start..end].to_string(),
+        })
+    }
+
+    pub fn file_name(&self) -> String {
+        Path::new(&self.file).file_name().unwrap_or_default().to_string_lossy().to_string()
+    }
+
+    // "Razavi.pdf › 6 Frequency Response, p. 212"
+    pub fn citation(&self) -> String {
+        match self.page {
+            Some(page) => format!("{} › {}, p. {}", self.file_name(), self.section, page),
+            None => self.file_name(),
+        }
+    }
+
+    // The "[SOURCE: ...]" block the model sees
+    pub fn block(&self) -> String {
+        format!("\n[SOURCE: {}]\n{}\n", self.citation(), self.text)
+    }
+}
+
+// All blocks, in the order given (ranked, see `rank`)
+pub fn context_text(sources: &[SourceChunk]) -> String {
+    sources.iter().map(SourceChunk::block).collect()
+}
+
+// The matching excerpt of one document, or None when it has no match. PDFs with
+// bookmarks are searched page by page so the citation can name the section, and only
+// those can satisfy a `section` filter ("Chapter 6"); their best-matching page is used.
+pub fn match_document(path: &Path, terms: &[String], section: &str) -> Option<SourceChunk> {
+    if !is_pdf(path) {
+        // Folder overview note: plain Markdown, no chapters
+        if !section.trim().is_empty() {
+            return None;
+        }
+        let content = fs::read_to_string(path).ok()?;
+        return SourceChunk::new(path, &content, terms, None, String::new());
+    }
+
+    if let Some(outline) = crate::pdf_toc::Outline::load(path) {
+        let mut best: Option<SourceChunk> = None;
+        for (page, text) in outline.pages() {
+            let crumbs = outline.breadcrumb(page);
+            if !crate::pdf_toc::section_matches(section, &crumbs) {
+                continue;
+            }
+            let mut place = crumbs.join(" › ");
+            if place.is_empty() {
+                place = "front matter".to_string();
+            }
+            if let Some(chunk) = SourceChunk::new(path, &text, terms, Some(page), place) {
+                if best.as_ref().map_or(true, |b| chunk.score > b.score) {
+                    best = Some(chunk);
+                }
+            }
+        }
+        return best;
+    }
+    if !section.trim().is_empty() {
+        return None; // No outline, so no way to tell which chapter a match is in
+    }
+
+    let content = pdf_extract::extract_text(path).ok()?;
+    SourceChunk::new(path, &content, terms, None, String::new())
+}
+
+// Blocking scan for headless runs: what a chat asking `query` would be given
+pub fn scan(dir: &str, filters: &DirFilters, query: &str, section: &str) -> Vec<SourceChunk> {
+    let terms = query_terms(query);
+    rank(collect_documents(dir, filters)
+        .into_iter()
+        .filter_map(|entry| match_document(&entry, &terms, section))
+        .collect())
+}
+
+// Retrieval filter on document modification date
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TimeRange {
+    Any,
+    LastWeek,
+    LastMonth,
+    ThisSemester,
+    ThisYear,
+}
+
+impl TimeRange {
+    pub const ALL: [TimeRange; 5] = [
+        TimeRange::Any, TimeRange::LastWeek, TimeRange::LastMonth, TimeRange::ThisSemester, TimeRange::ThisYear,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeRange::Any => "Any time",
+            TimeRange::LastWeek => "Last 7 days",
+            TimeRange::LastMonth => "Last 30 days",
+            TimeRange::ThisSemester => "This semester",
+            TimeRange::ThisYear => "This year",
+        }
+    }
+
+    // Oldest modification time that still passes
+    fn cutoff(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        let month_start = |month: u32| Local.with_ymd_and_hms(now.year(), month, 1, 0, 0, 0).single();
+        match self {
+            TimeRange::Any => None,
+            TimeRange::LastWeek => Some(now - chrono::Duration::days(7)),
+            TimeRange::LastMonth => Some(now - chrono::Duration::days(30)),
+            // Spring Jan-May, Summer Jun-Jul, Fall Aug-Dec
+            TimeRange::ThisSemester => match now.month() {
+                1..=5 => month_start(1),
+                6..=7 => month_start(6),
+                _ => month_start(8),
+            },
+            TimeRange::ThisYear => month_start(1),
+        }
+    }
+
+    // Same cutoff as a Unix timestamp, for SQL filters
+    pub fn cutoff_timestamp(&self) -> Option<i64> {
+        self.cutoff(Local::now()).map(|c| c.timestamp())
+    }
+
+    pub fn allows(&self, path: &Path) -> bool {
+        let Some(cutoff) = self.cutoff(Local::now()) else {
+            return true;
+        };
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map(|t| DateTime::<Local>::from(t) >= cutoff)
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,120 @@
+// --- RETRIEVAL EVALUATION ---
+// Developer tool: a labeled set of query -> expected-document pairs is run through the
+// same document scan and ranking chat uses, and the result is scored with recall@k and MRR so a
+// change to the scanner or the library can be compared against the last run.
+
+use crate::research::{self, DirFilters};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const EVAL_FILE: &str = "retrieval_eval.json"; // In the profile folder
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct EvalCase {
+    pub query: String,
+    pub expected: Vec<String>, // File names or path suffixes ("Razavi.pdf", "Datasheets/LM358.pdf")
+    pub section: String,       // Same as the Research Station's "Only from"; empty = anywhere
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct EvalSet {
+    pub k: usize,
+    pub cases: Vec<EvalCase>,
+    pub last: Option<EvalReport>, // Previous run, for the comparison column
+}
+
+impl Default for EvalSet {
+    fn default() -> Self {
+        Self { k: 5, cases: Vec::new(), last: None }
+    }
+}
+
+impl EvalSet {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct CaseResult {
+    pub query: String,
+    pub retrieved: Vec<String>,      // File names, best first
+    pub ranks: Vec<Option<usize>>,   // 1-based rank of each expected document
+    pub recall: f64,                 // Share of expected documents in the top k
+    pub reciprocal_rank: f64,        // 1 / rank of the first expected document; 0 when none
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct EvalReport {
+    pub k: usize,
+    pub cases: Vec<CaseResult>,
+    pub recall_at_k: f64,
+    pub mrr: f64,
+    pub finished: i64, // Unix seconds
+}
+
+fn is_expected(file: &str, expected: &str) -> bool {
+    let file = file.replace('\\', "/").to_lowercase();
+    let expected = expected.trim().replace('\\', "/").to_lowercase();
+    !expected.is_empty() && (file == expected || file.ends_with(&format!("/{}", expected)))
+}
+
+// Blocking: scans the library once per case. `progress` gets "i/n" before each.
+pub fn run(dir: &str, filters: &DirFilters, set: &EvalSet, mut progress: impl FnMut(usize, usize)) -> EvalReport {
+    let k = set.k.max(1);
+    let cases: Vec<&EvalCase> = set.cases.iter().filter(|c| !c.query.trim().is_empty()).collect();
+    let mut results = Vec::with_capacity(cases.len());
+    for (i, case) in cases.iter().enumerate() {
+        progress(i + 1, cases.len());
+
+        // The excerpts a chat would get, in the order it would get them
+        let sources = research::scan(dir, filters, case.query.trim(), &case.section);
+
+        let ranks: Vec<Option<usize>> = case.expected
+            .iter()
+            .filter(|e| !e.trim().is_empty())
+            .map(|e| sources.iter().position(|s| is_expected(&s.file, e)).map(|at| at + 1))
+            .collect();
+        let found = ranks.iter().filter(|r| r.is_some_and(|r| r <= k)).count();
+        results.push(CaseResult {
+            query: case.query.clone(),
+            retrieved: sources.iter().take(k).map(|s| s.file_name()).collect(),
+            recall: if ranks.is_empty() { 0.0 } else { found as f64 / ranks.len() as f64 },
+            reciprocal_rank: ranks.iter().flatten().min().map_or(0.0, |&r| 1.0 / r as f64),
+            ranks,
+        });
+    }
+
+    let n = results.len().max(1) as f64;
+    EvalReport {
+        k,
+        recall_at_k: results.iter().map(|r| r.recall).sum::<f64>() / n,
+        mrr: results.iter().map(|r| r.reciprocal_rank).sum::<f64>() / n,
+        cases: results,
+        finished: chrono::Utc::now().timestamp(),
+    }
+}
+
+// For `--eval-retrieval`: one line per case, then the totals
+pub fn text_report(report: &EvalReport) -> String {
+    let mut out = String::new();
+    for case in &report.cases {
+        let ranks: Vec<String> = case.ranks.iter().map(|r| r.map_or("-".to_string(), |r| r.to_string())).collect();
+        out.push_str(&format!("{:>5.2}  {:>5.2}  [{}]  {}\n", case.recall, case.reciprocal_rank, ranks.join(","), case.query));
+    }
+    out.push_str(&format!("recall@{} {:.3}  MRR {:.3}  ({} queries)\n", report.k, report.recall_at_k, report.mrr, report.cases.len()));
+    out
+}
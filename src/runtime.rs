@@ -0,0 +1,188 @@
+// --- SHARED ASYNC RUNTIME ---
+// One multi-threaded tokio runtime for the whole process. The GUI submits Ollama and
+// RAG work to it instead of building a runtime per request, and keeps the task
+// handles so work can be aborted.
+//
+// Blocking jobs (indexing, PDF parsing, summaries) go through `spawn_background`, which
+// runs them on a pool of its own threads with the background worker settings applied: a
+// lower priority and a subset of cores, so token streaming on the async workers and the
+// UI keep theirs. Tokio's blocking pool is left alone; its threads also serve file and
+// DNS work for the async side, which must not inherit the background niceness.
+
+use crossbeam_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{OnceLock, RwLock};
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::task::JoinHandle;
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+pub fn shared() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("ship-async")
+            .build()
+            .expect("could not start the async runtime")
+    })
+}
+
+// For plain threads and `spawn_blocking` closures; never call from inside an async task
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    shared().block_on(future)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct WorkerConfig {
+    pub nice: i32,         // 0 = same as the UI, 19 = only idle CPU time
+    pub cores: Vec<usize>, // CPUs background work may run on; empty = any
+}
+
+static WORKERS: RwLock<WorkerConfig> = RwLock::new(WorkerConfig { nice: 0, cores: Vec::new() });
+
+// What a thread has actually been set to; a part that failed stays None and is tried
+// again on the next job
+#[derive(Default)]
+struct Applied {
+    nice: Option<i32>,
+    cores: Option<Vec<usize>>,
+}
+
+// Highest niceness any thread has been given; threads can't go back below it
+static NICE_FLOOR: AtomicI32 = AtomicI32::new(0);
+
+thread_local! {
+    // Per thread, so a reused pool thread skips the syscalls
+    static APPLIED: RefCell<Applied> = RefCell::new(Applied::default());
+}
+
+// From the settings; jobs started afterwards pick it up
+pub fn set_worker_config(config: &WorkerConfig) {
+    *WORKERS.write().unwrap_or_else(|e| e.into_inner()) = config.clone();
+}
+
+// At the start of every background job and poller thread, on that thread
+pub fn enter_background() {
+    let config = WORKERS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    APPLIED.with(|applied| {
+        let mut applied = applied.borrow_mut();
+        // Best effort, each part on its own: an unprivileged thread can't raise its
+        // priority back, which must neither skip the core pinning nor stop the job
+        if applied.nice != Some(config.nice) && set_nice(config.nice).is_ok() {
+            applied.nice = Some(config.nice);
+            NICE_FLOOR.fetch_max(config.nice, Ordering::Relaxed);
+        }
+        if applied.cores.as_ref() != Some(&config.cores) && set_cores(&config.cores).is_ok() {
+            applied.cores = Some(config.cores);
+        }
+    });
+}
+
+// For the settings: a niceness below this only reaches threads after a restart
+pub fn nice_floor() -> i32 {
+    NICE_FLOOR.load(Ordering::Relaxed)
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static POOL: OnceLock<Sender<Job>> = OnceLock::new();
+
+// One thread per core, started on first use; idle threads just wait on the queue
+fn pool() -> &'static Sender<Job> {
+    POOL.get_or_init(|| {
+        let (tx, rx) = crossbeam_channel::unbounded::<Job>();
+        for i in 0..cpu_count() {
+            let rx: Receiver<Job> = rx.clone();
+            let _ = std::thread::Builder::new()
+                .name(format!("ship-background-{}", i))
+                .spawn(move || {
+                    for job in rx {
+                        enter_background();
+                        job();
+                    }
+                });
+        }
+        tx
+    })
+}
+
+// The handle behaves like tokio's: awaiting it yields the result or the job's panic,
+// and aborting it before the job starts means the job never runs
+pub fn spawn_background<F, R>(handle: &Handle, work: F) -> JoinHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let (done, result) = tokio::sync::oneshot::channel();
+    let job: Job = Box::new(move || {
+        if done.is_closed() {
+            return; // Aborted while queued
+        }
+        let _ = done.send(panic::catch_unwind(AssertUnwindSafe(work)));
+    });
+    let _ = pool().send(job);
+    handle.spawn(async move {
+        match result.await {
+            Ok(Ok(value)) => value,
+            Ok(Err(payload)) => panic::resume_unwind(payload),
+            Err(_) => panic!("background pool stopped"),
+        }
+    })
+}
+
+pub fn cpu_count() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+pub const SUPPORTED: bool = cfg!(target_os = "linux");
+
+#[cfg(target_os = "linux")]
+fn set_nice(nice: i32) -> std::io::Result<()> {
+    // SAFETY: plain syscalls on the calling thread
+    unsafe {
+        let tid = libc::syscall(libc::SYS_gettid) as libc::id_t;
+        if libc::setpriority(libc::PRIO_PROCESS, tid, nice.clamp(0, 19)) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn set_cores(cores: &[usize]) -> std::io::Result<()> {
+    // SAFETY: a plain syscall on the calling thread with a zeroed, then filled, cpu_set_t
+    unsafe {
+        let online = libc::sysconf(libc::_SC_NPROCESSORS_ONLN).max(1) as usize;
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        let mut any = false;
+        for core in 0..online {
+            if cores.is_empty() || cores.contains(&core) {
+                libc::CPU_SET(core, &mut set);
+                any = true;
+            }
+        }
+        // Cores that don't exist (config from another machine) mean any core
+        if !any {
+            (0..online).for_each(|core| libc::CPU_SET(core, &mut set));
+        }
+        if libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_nice(_nice: i32) -> std::io::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_cores(_cores: &[usize]) -> std::io::Result<()> {
+    Ok(())
+}
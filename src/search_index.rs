@@ -0,0 +1,178 @@
+// --- SESSION SEARCH INDEX ---
+// SQLite FTS5 index over every saved session, so history search is a ranked query
+// instead of re-parsing each session. Sessions are re-indexed only when their write time
+// in the store changes; the index lives next to the sessions folder and can always be rebuilt.
+
+use crate::session_store::SessionStore;
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+
+pub const INDEX_FILE: &str = "search_index.sqlite";
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchFilter {
+    pub model: String,       // Empty = any
+    pub tag: String,         // Empty = any
+    pub since: Option<i64>,  // Unix seconds
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchHit {
+    pub path: PathBuf, // Session name in the store
+    pub message_index: usize,
+    pub role: String,
+    pub snippet: String, // Match wrapped in [ ]
+    pub model: String,
+    pub modified: i64,
+}
+
+pub struct SearchIndex {
+    conn: Connection,
+}
+
+// Bare words are ANDed; "quoted phrases" stay phrases. Everything else is quoted so
+// FTS5 operators typed by accident (-, :, *) can't break the query.
+fn fts_query(input: &str) -> String {
+    let mut terms = Vec::new();
+    for (i, part) in input.split('"').enumerate() {
+        if i % 2 == 1 {
+            if !part.trim().is_empty() {
+                terms.push(format!("\"{}\"", part.trim()));
+            }
+        } else {
+            terms.extend(part.split_whitespace().map(|w| format!("\"{}\"", w.replace('"', ""))));
+        }
+    }
+    terms.join(" ")
+}
+
+impl SearchIndex {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                path TEXT PRIMARY KEY,
+                mtime INTEGER NOT NULL,
+                model TEXT NOT NULL DEFAULT '',
+                tags TEXT NOT NULL DEFAULT ''
+            );
+            CREATE VIRTUAL TABLE IF NOT EXISTS messages USING fts5(
+                content,
+                role UNINDEXED,
+                path UNINDEXED,
+                idx UNINDEXED,
+                tokenize = 'porter unicode61'
+            );",
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(Self { conn })
+    }
+
+    // Brings the index in line with the store; returns how many sessions were (re)indexed.
+    // Rows are keyed by session name, with the store's write time (millis) as `mtime`.
+    pub fn sync(&mut self, store: &SessionStore) -> Result<usize, String> {
+        let sessions = store.list();
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        let mut updated = 0;
+
+        // 1. Drop sessions that no longer exist (and rows keyed by path from older builds)
+        let known: Vec<(String, i64)> = {
+            let mut stmt = tx.prepare("SELECT path, mtime FROM sessions").map_err(|e| e.to_string())?;
+            let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?))).map_err(|e| e.to_string())?;
+            rows.flatten().collect()
+        };
+        for (path, _) in &known {
+            if !sessions.iter().any(|s| s.name == *path) {
+                tx.execute("DELETE FROM messages WHERE path = ?1", params![path]).map_err(|e| e.to_string())?;
+                tx.execute("DELETE FROM sessions WHERE path = ?1", params![path]).map_err(|e| e.to_string())?;
+            }
+        }
+
+        // 2. (Re)index new or changed sessions
+        for entry in &sessions {
+            let key = &entry.name;
+            let modified = entry.version();
+            if known.iter().any(|(p, m)| p == key && *m == modified) {
+                continue;
+            }
+            let Ok(loaded) = store.load(key) else { continue };
+
+            tx.execute("DELETE FROM messages WHERE path = ?1", params![key]).map_err(|e| e.to_string())?;
+            for (i, msg) in loaded.messages.iter().enumerate() {
+                tx.execute(
+                    "INSERT INTO messages (content, role, path, idx) VALUES (?1, ?2, ?3, ?4)",
+                    params![msg.content, msg.role, key, i as i64],
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            tx.execute(
+                "INSERT OR REPLACE INTO sessions (path, mtime, model, tags) VALUES (?1, ?2, ?3, ?4)",
+                params![key, modified, loaded.meta.model, loaded.meta.tags.join(",")],
+            )
+            .map_err(|e| e.to_string())?;
+            updated += 1;
+        }
+
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(updated)
+    }
+
+    // Best matches first (bm25), at most `limit`, snippets of about `snippet_tokens` words
+    pub fn search(&self, query: &str, filter: &SearchFilter, limit: usize, snippet_tokens: i32) -> Result<Vec<SearchHit>, String> {
+        let query = fts_query(query);
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut stmt = self.conn.prepare(
+            "SELECT m.path, m.idx, m.role, snippet(messages, 0, '[', ']', '…', ?2), s.model, s.mtime / 1000
+             FROM messages m JOIN sessions s ON s.path = m.path
+             WHERE messages MATCH ?1
+               AND (?3 = '' OR s.model = ?3)
+               AND (?4 = '' OR ',' || s.tags || ',' LIKE '%,' || ?4 || ',%')
+               AND (?5 IS NULL OR s.mtime >= ?5 * 1000)
+             ORDER BY bm25(messages)
+             LIMIT ?6",
+        )
+        .map_err(|e| e.to_string())?;
+
+        let rows = stmt
+            .query_map(
+                params![query, snippet_tokens.clamp(1, 64), filter.model, filter.tag.trim(), filter.since, limit as i64],
+                |r| {
+                    Ok(SearchHit {
+                        path: PathBuf::from(r.get::<_, String>(0)?),
+                        message_index: r.get::<_, i64>(1)? as usize,
+                        role: r.get(2)?,
+                        snippet: r.get(3)?,
+                        model: r.get(4)?,
+                        modified: r.get(5)?,
+                    })
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        Ok(rows.flatten().collect())
+    }
+
+    // Distinct values for the filter dropdowns
+    pub fn models_and_tags(&self) -> (Vec<String>, Vec<String>) {
+        let mut models = Vec::new();
+        let mut tags = Vec::new();
+        if let Ok(mut stmt) = self.conn.prepare("SELECT DISTINCT model, tags FROM sessions") {
+            if let Ok(rows) = stmt.query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))) {
+                for (model, tag_list) in rows.flatten() {
+                    if !model.is_empty() && !models.contains(&model) {
+                        models.push(model);
+                    }
+                    for tag in tag_list.split(',').filter(|t| !t.is_empty()) {
+                        if !tags.iter().any(|t| t == tag) {
+                            tags.push(tag.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        models.sort();
+        tags.sort();
+        (models, tags)
+    }
+}
@@ -0,0 +1,35 @@
+// --- SECRETS ---
+// Tokens and passwords live in the OS keyring (Secret Service / Keychain / Credential
+// Manager), never in ship_config.toml. The config only stores the account name.
+
+const SERVICE: &str = "ship-of-theseus";
+
+fn entry(account: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(SERVICE, account).map_err(|e| e.to_string())
+}
+
+pub fn get(account: &str) -> Option<String> {
+    entry(account).ok()?.get_password().ok()
+}
+
+pub fn set(account: &str, secret: &str) -> Result<(), String> {
+    entry(account)?.set_password(secret).map_err(|e| e.to_string())
+}
+
+pub fn delete(account: &str) -> Result<(), String> {
+    match entry(account)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+// Suggested slots for the settings UI; any other name works too
+pub const KNOWN_SECRETS: [&str; 4] = ["openai_api_key", "anthropic_api_key", "web_search_api_key", "webhook_url"];
+
+// The keyring can't enumerate entries, so the config keeps the names (never values)
+pub fn remember(names: &mut Vec<String>, account: &str) {
+    if !names.iter().any(|n| n == account) {
+        names.push(account.to_string());
+        names.sort();
+    }
+}
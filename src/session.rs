@@ -0,0 +1,302 @@
+// --- SESSION STORAGE ---
+// Every file in SESSIONS_DIR is one saved conversation: metadata plus the messages.
+// Files carry a schema_version; older layouts are migrated step by step on load, so
+// changes to Message or the metadata never strand existing sessions.
+
+use crate::chat_backend::HostedModel;
+use crate::context::ContextPlacement;
+use crate::rag_trigger::RagMode;
+use crate::research::SourceChunk;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SESSIONS_DIR: &str = "sessions";
+pub const LATEST_FILE: &str = "session_latest.json"; // The open chat until it is archived
+
+// 0: bare array of messages, 1: { meta, messages }, 2: 1 plus schema_version
+pub const SCHEMA_VERSION: u64 = 2;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Message {
+    pub role: String,
+    #[serde(default)]
+    pub has_image: bool,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>, // Unix millis when the message was created; older files have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<String>, // Image file, relative to the sessions folder
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<SourceChunk>, // Research excerpts injected for this reply
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub model: String, // Model that wrote this reply; empty for prompts and older files
+}
+
+impl Message {
+    pub fn new(role: &str, content: String, has_image: bool) -> Self {
+        Self { role: role.to_string(), has_image, content, timestamp: Some(chrono::Utc::now().timestamp_millis()), attachment: None, sources: Vec::new(), model: String::new() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SessionSummary {
+    pub summary: String,
+    pub takeaways: Vec<String>,
+    pub message_count: usize, // How many messages the summary covers
+}
+
+impl SessionSummary {
+    // Tooltip text for the session list
+    pub fn preview(&self) -> String {
+        let mut out = self.summary.clone();
+        for t in &self.takeaways {
+            out.push_str(&format!("\n• {}", t));
+        }
+        out
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SessionMeta {
+    pub title: String, // Set by renaming; empty = first prompt
+    pub summary: Option<SessionSummary>,
+    pub partial_reply: bool, // Last assistant message was checkpointed mid-generation
+    pub model: String,       // Model used most recently in this session
+    pub tags: Vec<String>,
+    pub reactions: Vec<Reaction>, // Ratings of individual replies
+    pub settings: Option<SessionSettings>, // Restored when the session is opened again
+    pub timer_events: Vec<TimerEvent>,     // Study-timer starts and stops, in order
+    pub zone: String,                      // IANA zone of the machine that created it; empty = older session
+    pub utc_offset: Option<i32>,           // Seconds east of UTC at its first write; used when `zone` is empty
+}
+
+// Per-chat choices that should come back with the conversation
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SessionSettings {
+    pub research_dir: String,
+    #[serde(default)]
+    pub research_section: String,
+    pub rag: RagMode,
+    pub context_placement: ContextPlacement,
+    #[serde(default)]
+    pub persona: String, // Persona id (a name in older files); empty = the default system prompt
+    #[serde(default)]
+    pub overrides: GenerationOverrides,
+}
+
+// Settings that apply to this chat only and are never written back to the config, so an
+// experiment in one chat can't leak into the next
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct GenerationOverrides {
+    pub temperature: Option<f32>,
+    pub persona: Option<String>, // Persona id; Some("") = the default system prompt
+    pub rag: Option<RagMode>,
+    pub context_placement: Option<ContextPlacement>,
+    pub hosted: Option<HostedModel>, // A cloud API instead of the configured server
+}
+
+impl GenerationOverrides {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum TimerEventKind {
+    Start,
+    Stop,   // Stopped early
+    Finish, // Ran the full length
+}
+
+// A study-timer event, placed in the conversation before `message_index`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimerEvent {
+    pub kind: TimerEventKind,
+    pub at: i64, // Unix millis
+    pub message_index: usize,
+    pub minutes: u32, // Planned length of the block
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Rating {
+    Up,
+    Down,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Reaction {
+    pub message_index: usize,
+    pub model: String, // Model that wrote the reply
+    pub rating: Option<Rating>,
+    pub labels: Vec<String>, // "hallucinated", "great derivation", ...
+    pub confidence: Option<u8>, // Self-reported by the model, 0-100
+}
+
+impl Reaction {
+    pub fn is_empty(&self) -> bool {
+        self.rating.is_none() && self.labels.is_empty() && self.confidence.is_none()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SessionFile {
+    #[serde(default)]
+    pub meta: SessionMeta,
+    pub messages: Vec<Message>,
+}
+
+impl SessionFile {
+    pub fn has_fresh_summary(&self) -> bool {
+        self.meta.summary.as_ref().is_some_and(|s| s.message_count == self.messages.len())
+    }
+
+    // For the session list: the given title, else the start of the first prompt
+    pub fn title(&self) -> String {
+        if !self.meta.title.trim().is_empty() {
+            return self.meta.title.trim().to_string();
+        }
+        let first = self.messages.iter().find(|m| m.role == "user").map(|m| m.content.trim()).unwrap_or_default();
+        let line = first.lines().next().unwrap_or_default();
+        match line.char_indices().nth(TITLE_CHARS) {
+            Some((cut, _)) => format!("{}…", &line[..cut]),
+            None if line.is_empty() => "(empty)".to_string(),
+            None => line.to_string(),
+        }
+    }
+}
+
+const TITLE_CHARS: usize = 40;
+
+// What save_session writes: the file plus its version, without cloning the messages
+#[derive(Serialize)]
+struct OnDisk<'a> {
+    schema_version: u64,
+    meta: &'a SessionMeta,
+    messages: &'a [Message],
+}
+
+fn schema_version(value: &serde_json::Value) -> u64 {
+    match value {
+        serde_json::Value::Array(_) => 0,
+        _ => value.get("schema_version").and_then(|v| v.as_u64()).unwrap_or(1),
+    }
+}
+
+// One step at a time up to SCHEMA_VERSION; each arm only knows its own change
+fn migrate(mut value: serde_json::Value) -> Result<serde_json::Value, String> {
+    loop {
+        let version = schema_version(&value);
+        value = match version {
+            0 => serde_json::json!({ "meta": {}, "messages": value }),
+            1 => {
+                value["schema_version"] = serde_json::json!(2);
+                value
+            }
+            SCHEMA_VERSION => return Ok(value),
+            newer => return Err(format!("written by a newer version of the app (schema {}, this build reads up to {})", newer, SCHEMA_VERSION)),
+        };
+    }
+}
+
+// All saved sessions, newest first
+pub fn list_sessions(dir: &Path) -> Vec<PathBuf> {
+    let pattern = format!("{}/*.json", dir.display());
+    let mut files: Vec<PathBuf> = glob::glob(&pattern)
+        .map(|paths| paths.flatten().collect())
+        .unwrap_or_default();
+
+    files.sort_by_key(|p| std::cmp::Reverse(fs::metadata(p).and_then(|m| m.modified()).ok()));
+    files
+}
+
+pub fn load_session(path: &Path) -> Result<SessionFile, String> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let value: serde_json::Value = serde_json::from_str(&raw).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let value = migrate(value).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_value(value).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub fn load_messages(path: &Path) -> Result<Vec<Message>, String> {
+    load_session(path).map(|file| file.messages)
+}
+
+pub fn save_session(path: &Path, file: &SessionFile) -> Result<(), String> {
+    let on_disk = OnDisk { schema_version: SCHEMA_VERSION, meta: &file.meta, messages: &file.messages };
+    let raw = serde_json::to_string_pretty(&on_disk).map_err(|e| e.to_string())?;
+    write_atomic(path, raw.as_bytes()).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Write to a sibling temp file, flush it to disk, then rename over the target: a crash
+// leaves either the old file or the new one, never half of one
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp"); // "x.json.tmp" stays out of the *.json session list
+    let tmp = path.with_file_name(tmp_name);
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        e
+    })
+}
+
+pub fn set_title(path: &Path, title: &str) -> Result<(), String> {
+    let mut file = load_session(path)?;
+    file.meta.title = title.trim().to_string();
+    save_session(path, &file)
+}
+
+pub fn delete_session(path: &Path) -> Result<(), String> {
+    fs::remove_file(path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Writes new messages while keeping whatever metadata the file already had;
+// `edit` updates it (partial flag, model, tags) in the same write
+pub fn write_messages(path: &Path, messages: &[Message], edit: impl FnOnce(&mut SessionMeta)) -> Result<(), String> {
+    // A file we can't read is replaced, unless it is from a newer build and would be lost
+    let mut meta = match load_session(path) {
+        Ok(file) => file.meta,
+        Err(e) if path.exists() && e.contains("newer version") => return Err(e),
+        Err(_) => SessionMeta::default(),
+    };
+    edit(&mut meta);
+    save_session(path, &SessionFile { meta, messages: messages.to_vec() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn bare_arrays_migrate_to_the_current_schema() {
+        let value = migrate(json!([{ "role": "user", "content": "hi" }])).unwrap();
+        assert_eq!(schema_version(&value), SCHEMA_VERSION);
+        let file: SessionFile = serde_json::from_value(value).unwrap();
+        assert_eq!(file.messages.len(), 1);
+        assert_eq!(file.messages[0].content, "hi");
+        assert_eq!(file.messages[0].timestamp, None);
+    }
+
+    #[test]
+    fn unversioned_files_get_a_version() {
+        let value = migrate(json!({ "meta": { "title": "Lab 3" }, "messages": [] })).unwrap();
+        assert_eq!(value["schema_version"], json!(SCHEMA_VERSION));
+        assert_eq!(value["meta"]["title"], json!("Lab 3"));
+    }
+
+    #[test]
+    fn newer_files_are_refused() {
+        let error = migrate(json!({ "schema_version": SCHEMA_VERSION + 1, "messages": [] })).unwrap_err();
+        assert!(error.contains("newer version"), "{}", error);
+    }
+}
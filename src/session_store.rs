@@ -0,0 +1,449 @@
+// --- SESSION STORE ---
+// Where the open chat, the session browser and summaries keep sessions: the JSON files
+// in SESSIONS_DIR (default), or one SQLite database with tables for sessions, messages
+// and attachments. Each time the database is opened, JSON sessions written since the last
+// import (or never imported) are copied into it; the files themselves are left alone.
+
+use crate::research::SourceChunk;
+use crate::session::{self, Message, SessionFile, SessionMeta};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const DB_FILE: &str = "sessions.sqlite"; // Inside SESSIONS_DIR, next to the JSON files
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum StoreKind {
+    Json,
+    Sqlite,
+}
+
+impl StoreKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StoreKind::Json => "JSON files",
+            StoreKind::Sqlite => "SQLite database",
+        }
+    }
+}
+
+// Cheap to clone and Send, so background tasks (summaries) can take one along;
+// every SQLite call opens its own connection
+#[derive(Clone, Debug)]
+pub enum SessionStore {
+    Json { dir: PathBuf },
+    Sqlite { db: PathBuf },
+}
+
+// One row of the session browser
+#[derive(Clone, Debug)]
+pub struct StoredSession {
+    pub name: String, // File name, also the key in the database ("chat_20250101_120000.json")
+    pub title: String,
+    pub preview: Option<String>, // Auto-summary
+    pub modified: Option<chrono::DateTime<chrono::Local>>,
+}
+
+impl StoredSession {
+    // Unix millis of the last write, 0 when unknown; changes with every save
+    pub fn version(&self) -> i64 {
+        self.modified.map_or(0, |t| t.timestamp_millis())
+    }
+}
+
+// A conversation containing the phrase: its first match, split for highlighting
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PhraseHit {
+    pub name: String,
+    pub title: String,
+    pub message_index: usize,
+    pub matches: usize, // Messages in the session that contain the phrase
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+}
+
+// Lowercased char by char, so indices line up with the original text; matching and
+// counting both use it (SQLite's LIKE only folds ASCII)
+fn fold(text: &str) -> Vec<char> {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+fn position(haystack: &[char], needle: &[char]) -> Option<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+// Case-insensitive, with `context` chars on each side; None when `phrase` doesn't occur
+fn phrase_snippet(content: &str, phrase: &str, context: usize) -> Option<(String, String, String)> {
+    let chars: Vec<char> = content.chars().collect();
+    let needle = fold(phrase);
+    let at = position(&fold(content), &needle)?;
+    let end = at + needle.len();
+    let from = at.saturating_sub(context);
+    let to = (end + context).min(chars.len());
+    let clean = |s: &[char]| s.iter().map(|c| if c.is_whitespace() { ' ' } else { *c }).collect::<String>();
+    let mut before = clean(&chars[from..at]);
+    let mut after = clean(&chars[end..to]);
+    if from > 0 {
+        before.insert(0, '…');
+    }
+    if to < chars.len() {
+        after.push('…');
+    }
+    Some((before, clean(&chars[at..end]), after))
+}
+
+fn find_in(name: &str, file: &SessionFile, phrase: &str, context: usize) -> Option<PhraseHit> {
+    let mut hit: Option<PhraseHit> = None;
+    for (i, msg) in file.messages.iter().enumerate() {
+        match &mut hit {
+            Some(h) => {
+                if position(&fold(&msg.content), &fold(phrase)).is_some() {
+                    h.matches += 1;
+                }
+            }
+            None => {
+                if let Some((before, matched, after)) = phrase_snippet(&msg.content, phrase, context) {
+                    hit = Some(PhraseHit { name: name.to_string(), title: file.title(), message_index: i, matches: 1, before, matched, after });
+                }
+            }
+        }
+    }
+    hit
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    e.to_string()
+}
+
+impl SessionStore {
+    pub fn open(kind: StoreKind, sessions_dir: &Path) -> Result<Self, String> {
+        match kind {
+            StoreKind::Json => Ok(Self::Json { dir: sessions_dir.to_path_buf() }),
+            StoreKind::Sqlite => {
+                let store = Self::Sqlite { db: sessions_dir.join(DB_FILE) };
+                let conn = store.connect()?;
+                create_tables(&conn)?;
+                import_new_json(&conn, sessions_dir)?;
+                Ok(store)
+            }
+        }
+    }
+
+    pub fn kind(&self) -> StoreKind {
+        match self {
+            Self::Json { .. } => StoreKind::Json,
+            Self::Sqlite { .. } => StoreKind::Sqlite,
+        }
+    }
+
+    fn connect(&self) -> Result<Connection, String> {
+        match self {
+            Self::Sqlite { db } => Connection::open(db).map_err(|e| format!("{}: {}", db.display(), e)),
+            Self::Json { .. } => Err("not a database store".to_string()),
+        }
+    }
+
+    pub fn load(&self, name: &str) -> Result<SessionFile, String> {
+        match self {
+            Self::Json { dir } => session::load_session(&dir.join(name)),
+            Self::Sqlite { .. } => load_row(&self.connect()?, name)?.ok_or_else(|| format!("No session named {}", name)),
+        }
+    }
+
+    pub fn save(&self, name: &str, file: &SessionFile) -> Result<(), String> {
+        match self {
+            Self::Json { dir } => session::save_session(&dir.join(name), file),
+            Self::Sqlite { .. } => {
+                let mut conn = self.connect()?;
+                let tx = conn.transaction().map_err(db_error)?;
+                save_row(&tx, name, file)?;
+                tx.commit().map_err(db_error)
+            }
+        }
+    }
+
+    // Same contract as session::write_messages: metadata is kept, `edit` updates it
+    pub fn write_messages(&self, name: &str, messages: &[Message], edit: impl FnOnce(&mut SessionMeta)) -> Result<(), String> {
+        match self {
+            Self::Json { dir } => session::write_messages(&dir.join(name), messages, edit),
+            Self::Sqlite { .. } => {
+                let mut conn = self.connect()?;
+                let tx = conn.transaction().map_err(db_error)?;
+                let mut meta = load_row(&tx, name)?.map(|f| f.meta).unwrap_or_default();
+                edit(&mut meta);
+                save_row(&tx, name, &SessionFile { meta, messages: messages.to_vec() })?;
+                tx.commit().map_err(db_error)
+            }
+        }
+    }
+
+    // "<prefix>_<timestamp to the millisecond>.json", with a counter if even that is taken
+    pub fn unused_name(&self, prefix: &str) -> String {
+        let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+        let mut name = format!("{}_{}.json", prefix, stamp);
+        let mut n = 1;
+        while self.load(&name).is_ok() {
+            n += 1;
+            name = format!("{}_{}_{}.json", prefix, stamp, n);
+        }
+        name
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), String> {
+        match self {
+            Self::Json { dir } => session::delete_session(&dir.join(name)),
+            Self::Sqlite { .. } => {
+                let conn = self.connect()?;
+                for table in ["attachments", "messages"] {
+                    conn.execute(&format!("DELETE FROM {} WHERE session = ?1", table), params![name]).map_err(db_error)?;
+                }
+                conn.execute("DELETE FROM sessions WHERE name = ?1", params![name]).map_err(db_error)?;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn set_title(&self, name: &str, title: &str) -> Result<(), String> {
+        let mut file = self.load(name)?;
+        file.meta.title = title.trim().to_string();
+        self.save(name, &file)
+    }
+
+    // Every session containing `phrase`, newest first; `context` chars around the first match.
+    // Blocking: it may load every session.
+    pub fn find(&self, phrase: &str, context: usize) -> Vec<PhraseHit> {
+        let phrase = phrase.trim();
+        if phrase.is_empty() {
+            return Vec::new();
+        }
+        let names: Vec<String> = match self {
+            Self::Json { .. } => self.list().into_iter().map(|s| s.name).collect(),
+            // LIKE only folds ASCII, so it can narrow an ASCII phrase down; "Ñ" would miss "ñ"
+            Self::Sqlite { .. } if phrase.is_ascii() => self.matching_names(phrase).unwrap_or_default(),
+            Self::Sqlite { .. } => self.list().into_iter().map(|s| s.name).collect(),
+        };
+        names
+            .iter()
+            .filter_map(|name| self.load(name).ok().and_then(|file| find_in(name, &file, phrase, context)))
+            .collect()
+    }
+
+    fn matching_names(&self, phrase: &str) -> Result<Vec<String>, String> {
+        let conn = self.connect()?;
+        let pattern = format!("%{}%", phrase.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_"));
+        let mut stmt = conn
+            .prepare(
+                "SELECT s.name FROM sessions s
+                 WHERE EXISTS (SELECT 1 FROM messages m WHERE m.session = s.name AND m.content LIKE ?1 ESCAPE '\\')
+                 ORDER BY s.updated DESC",
+            )
+            .map_err(db_error)?;
+        let rows = stmt.query_map(params![pattern], |r| r.get::<_, String>(0)).map_err(db_error)?;
+        Ok(rows.flatten().collect())
+    }
+
+    // Newest first
+    pub fn list(&self) -> Vec<StoredSession> {
+        match self {
+            Self::Json { dir } => session::list_sessions(dir)
+                .into_iter()
+                .map(|path| {
+                    let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                    let file = session::load_session(&path).ok();
+                    StoredSession {
+                        name,
+                        title: file.as_ref().map(|f| f.title()).unwrap_or_else(|| "(unreadable)".to_string()),
+                        preview: file.and_then(|f| f.meta.summary).map(|s| s.preview()),
+                        modified: std::fs::metadata(&path).and_then(|m| m.modified()).ok().map(chrono::DateTime::from),
+                    }
+                })
+                .collect(),
+            Self::Sqlite { .. } => self.list_rows().unwrap_or_default(),
+        }
+    }
+
+    fn list_rows(&self) -> Result<Vec<StoredSession>, String> {
+        let conn = self.connect()?;
+        let mut stmt = conn.prepare("SELECT name, meta, updated FROM sessions ORDER BY updated DESC").map_err(db_error)?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get::<_, i64>(2)?)))
+            .map_err(db_error)?;
+        let mut out = Vec::new();
+        for (name, meta, updated) in rows.flatten() {
+            // The title fallback needs the first prompt, so look it up for untitled rows only
+            let meta: SessionMeta = serde_json::from_str(&meta).unwrap_or_default();
+            let title = match meta.title.trim().is_empty() {
+                true => load_row(&conn, &name)?.map(|f| f.title()).unwrap_or_default(),
+                false => meta.title.trim().to_string(),
+            };
+            out.push(StoredSession {
+                name,
+                title,
+                preview: meta.summary.map(|s| s.preview()),
+                modified: updated_time(updated).map(|t| t.with_timezone(&chrono::Local)),
+            });
+        }
+        Ok(out)
+    }
+}
+
+fn create_tables(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS store_info (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS sessions (
+            name TEXT PRIMARY KEY,
+            meta TEXT NOT NULL,
+            updated INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS messages (
+            session TEXT NOT NULL,
+            idx INTEGER NOT NULL,
+            role TEXT NOT NULL,
+            content TEXT NOT NULL,
+            has_image INTEGER NOT NULL DEFAULT 0,
+            timestamp INTEGER,
+            sources TEXT NOT NULL DEFAULT '[]',
+            model TEXT NOT NULL DEFAULT '',
+            PRIMARY KEY (session, idx)
+        );
+        CREATE TABLE IF NOT EXISTS attachments (
+            session TEXT NOT NULL,
+            idx INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            PRIMARY KEY (session, idx)
+        );",
+    )
+    .map_err(db_error)?;
+
+    // Columns added after the first release; CREATE TABLE IF NOT EXISTS skips old tables
+    let has_model: bool = conn
+        .query_row("SELECT COUNT(*) > 0 FROM pragma_table_info('messages') WHERE name = 'model'", [], |r| r.get(0))
+        .map_err(db_error)?;
+    if !has_model {
+        conn.execute_batch("ALTER TABLE messages ADD COLUMN model TEXT NOT NULL DEFAULT ''").map_err(db_error)?;
+    }
+    Ok(())
+}
+
+// Copies sessions/*.json written since the last import into the database, unless the
+// database row was saved after the file (the session was continued in SQLite mode)
+fn import_new_json(conn: &Connection, sessions_dir: &Path) -> Result<(), String> {
+    let info = |key: &str| -> Result<Option<String>, String> {
+        conn.query_row("SELECT value FROM store_info WHERE key = ?1", params![key], |r| r.get(0)).optional().map_err(db_error)
+    };
+    // Databases from before incremental imports only recorded "N sessions at <rfc3339>";
+    // without that, sessions deleted in SQLite mode would come back from their old files
+    let last = match info("json_imported_until")? {
+        Some(millis) => millis.parse().unwrap_or(0),
+        None => info("imported_json")?
+            .and_then(|v| v.rsplit(" at ").next().and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok()))
+            .map_or(0, |t| t.timestamp_millis()),
+    };
+    let started = chrono::Utc::now().timestamp_millis();
+
+    conn.execute_batch("BEGIN").map_err(db_error)?;
+    for path in session::list_sessions(sessions_dir) {
+        let modified = file_millis(&path);
+        if modified <= last {
+            continue;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let updated: Option<i64> = conn
+            .query_row("SELECT updated FROM sessions WHERE name = ?1", params![name], |r| r.get(0))
+            .optional()
+            .map_err(db_error)?;
+        if updated.and_then(updated_time).is_some_and(|t| t.timestamp_millis() >= modified) {
+            continue;
+        }
+        // Unreadable files (e.g. from a newer build) stay as they are
+        let Ok(file) = session::load_session(&path) else { continue };
+        save_row(conn, &name, &file)?;
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO store_info (key, value) VALUES ('json_imported_until', ?1)",
+        params![started.to_string()],
+    )
+    .map_err(db_error)?;
+    conn.execute_batch("COMMIT").map_err(db_error)
+}
+
+fn file_millis(path: &Path) -> i64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis() as i64)
+}
+
+// `updated` is Unix millis; rows written by earlier builds hold seconds
+fn updated_time(updated: i64) -> Option<chrono::DateTime<chrono::Utc>> {
+    match updated < 100_000_000_000 {
+        true => chrono::DateTime::from_timestamp(updated, 0),
+        false => chrono::DateTime::from_timestamp_millis(updated),
+    }
+}
+
+// Callers wrap this in a transaction
+fn save_row(conn: &Connection, name: &str, file: &SessionFile) -> Result<(), String> {
+    let meta = serde_json::to_string(&file.meta).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO sessions (name, meta, updated) VALUES (?1, ?2, ?3)",
+        params![name, meta, chrono::Utc::now().timestamp_millis()],
+    )
+    .map_err(db_error)?;
+    conn.execute("DELETE FROM messages WHERE session = ?1", params![name]).map_err(db_error)?;
+    conn.execute("DELETE FROM attachments WHERE session = ?1", params![name]).map_err(db_error)?;
+    for (i, msg) in file.messages.iter().enumerate() {
+        let sources = serde_json::to_string(&msg.sources).map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO messages (session, idx, role, content, has_image, timestamp, sources, model) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![name, i as i64, msg.role, msg.content, msg.has_image, msg.timestamp, sources, msg.model],
+        )
+        .map_err(db_error)?;
+        if let Some(path) = &msg.attachment {
+            conn.execute("INSERT INTO attachments (session, idx, path) VALUES (?1, ?2, ?3)", params![name, i as i64, path])
+                .map_err(db_error)?;
+        }
+    }
+    Ok(())
+}
+
+fn load_row(conn: &Connection, name: &str) -> Result<Option<SessionFile>, String> {
+    let meta: Option<String> = conn
+        .query_row("SELECT meta FROM sessions WHERE name = ?1", params![name], |r| r.get(0))
+        .optional()
+        .map_err(db_error)?;
+    let Some(meta) = meta else { return Ok(None) };
+    let meta: SessionMeta = serde_json::from_str(&meta).map_err(|e| format!("{}: {}", name, e))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.role, m.content, m.has_image, m.timestamp, m.sources, a.path, m.model
+             FROM messages m LEFT JOIN attachments a ON a.session = m.session AND a.idx = m.idx
+             WHERE m.session = ?1 ORDER BY m.idx",
+        )
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map(params![name], |r| {
+            let sources: String = r.get(4)?;
+            Ok(Message {
+                role: r.get(0)?,
+                content: r.get(1)?,
+                has_image: r.get(2)?,
+                timestamp: r.get(3)?,
+                sources: serde_json::from_str::<Vec<SourceChunk>>(&sources).unwrap_or_default(),
+                attachment: r.get(5)?,
+                model: r.get(6)?,
+            })
+        })
+        .map_err(db_error)?;
+    let messages = rows.collect::<Result<Vec<_>, _>>().map_err(db_error)?;
+    Ok(Some(SessionFile { meta, messages }))
+}
@@ -0,0 +1,118 @@
+// --- EXTERNAL TOOLS ---
+// Helpers for running CLI tools (ollama, training scripts) off the UI thread.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use crossbeam_channel::Sender;
+
+// Words of a command line, split on whitespace with '…' and "…" grouping; no shell
+// expansion of any kind, so values substituted into a word stay one argument
+pub fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    for c in line.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => word.push(c),
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            None => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err(format!("Unclosed quote in `{}`", line.trim()));
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+// For logs: the command as it could be typed into a shell
+pub fn display_command(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|w| match w.is_empty() || w.contains(|c: char| c.is_whitespace() || "'\"\\$`;&|<>()*?".contains(c)) {
+            true => format!("'{}'", w.replace('\'', "'\\''")),
+            false => w.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Runs `words[0]` with the rest as its arguments (no shell) and forwards every
+// stdout/stderr line as "<prefix>:<line>". Blocks until the process exits, so call it
+// from a worker thread.
+pub fn stream_command(words: &[String], tx: &Sender<String>, prefix: &str) -> Result<(), String> {
+    stream_command_env(words, &[], tx, prefix)
+}
+
+// Same as `stream_command`, with extra environment variables set on the child
+pub fn stream_command_env(words: &[String], env: &[(&str, String)], tx: &Sender<String>, prefix: &str) -> Result<(), String> {
+    let cmd = display_command(words);
+    let (program, args) = words.split_first().ok_or("Empty command")?;
+    let mut child = Command::new(program)
+        .args(args)
+        .envs(env.iter().map(|(k, v)| (*k, v.as_str())))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start `{}`: {}", cmd, e))?;
+
+    // 1. stderr gets its own reader so a chatty process can't block on a full pipe
+    let stderr_reader = child.stderr.take().map(|stderr| {
+        let tx = tx.clone();
+        let prefix = prefix.to_string();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                let _ = tx.send(format!("{}:{}", prefix, line));
+            }
+        })
+    });
+
+    // 2. stdout on this thread
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            let _ = tx.send(format!("{}:{}", prefix, line));
+        }
+    }
+
+    if let Some(handle) = stderr_reader {
+        let _ = handle.join();
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("`{}` exited with {}", cmd, status))
+    }
+}
+
+// Hands a URL or file to the desktop's default handler (mail client, browser, viewer)
+pub fn open_external(target: &str) -> Result<(), String> {
+    let mut cmd = if cfg!(target_os = "windows") {
+        // Not `cmd /C start`: cmd would split mailto: queries on '&'
+        let mut c = Command::new("rundll32");
+        c.arg("url.dll,FileProtocolHandler");
+        c
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    cmd.arg(target).spawn().map(|_| ()).map_err(|e| format!("Could not open {}: {}", target, e))
+}
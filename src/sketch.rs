@@ -0,0 +1,68 @@
+// --- SKETCH PAD ---
+// Rasterizes whiteboard strokes into a PNG so vision models can read the sketch.
+
+use base64::Engine;
+use image::{Rgba, RgbaImage};
+use std::io::Cursor;
+
+pub const CANVAS_SIZE: (u32, u32) = (640, 420);
+
+#[derive(Clone, Debug)]
+pub struct Stroke {
+    pub points: Vec<[f32; 2]>, // Canvas pixels, origin top-left
+    pub width: f32,
+    pub color: [u8; 3],
+}
+
+// Stamp a filled disc; dense stamping along a segment gives a round-capped line
+fn stamp(img: &mut RgbaImage, x: f32, y: f32, radius: f32, color: Rgba<u8>) {
+    let (w, h) = img.dimensions();
+    let r = radius.max(0.5);
+    let x0 = (x - r).floor().max(0.0) as u32;
+    let y0 = (y - r).floor().max(0.0) as u32;
+    let x1 = ((x + r).ceil() as u32).min(w.saturating_sub(1));
+    let y1 = ((y + r).ceil() as u32).min(h.saturating_sub(1));
+
+    for py in y0..=y1 {
+        for px in x0..=x1 {
+            let (dx, dy) = (px as f32 - x, py as f32 - y);
+            if dx * dx + dy * dy <= r * r {
+                img.put_pixel(px, py, color);
+            }
+        }
+    }
+}
+
+pub fn rasterize(strokes: &[Stroke]) -> RgbaImage {
+    let (w, h) = CANVAS_SIZE;
+    let mut img = RgbaImage::from_pixel(w, h, Rgba([255, 255, 255, 255]));
+
+    for stroke in strokes {
+        let color = Rgba([stroke.color[0], stroke.color[1], stroke.color[2], 255]);
+        let radius = stroke.width / 2.0;
+
+        // A single click is still a dot
+        if let [only] = stroke.points.as_slice() {
+            stamp(&mut img, only[0], only[1], radius, color);
+        }
+        for seg in stroke.points.windows(2) {
+            let (a, b) = (seg[0], seg[1]);
+            let len = ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt();
+            let steps = (len * 2.0).ceil().max(1.0) as usize;
+            for i in 0..=steps {
+                let t = i as f32 / steps as f32;
+                stamp(&mut img, a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, radius, color);
+            }
+        }
+    }
+    img
+}
+
+// Same encoding the Ollama request expects for attached images
+pub fn to_png_base64(strokes: &[Stroke]) -> Result<String, String> {
+    let mut bytes = Cursor::new(Vec::new());
+    image::DynamicImage::ImageRgba8(rasterize(strokes))
+        .write_to(&mut bytes, image::ImageOutputFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes.into_inner()))
+}
@@ -0,0 +1,89 @@
+// --- SESSION SUMMARIES ---
+// Short recap + key takeaways generated when a session is closed or left,
+// stored in the session file so the session list can preview it.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use crate::session::{Message, SessionSummary};
+use crate::session_store::SessionStore;
+
+const SYSTEM: &str = "You write concise study notes. Reply with a 2-3 sentence summary, then a line \
+'Key takeaways:' followed by at most 5 bullet points starting with '- '.";
+
+// Keep the prompt inside a modest context window
+pub const MAX_TRANSCRIPT_CHARS: usize = 12_000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ConversationSize {
+    pub words: usize,
+    pub chars: usize,
+    pub tokens: usize, // Estimate: ~4 characters per token for English prose
+}
+
+impl ConversationSize {
+    // Past this the summary only sees the most recent part of the conversation
+    pub fn over_summary_limit(&self) -> bool {
+        self.chars > MAX_TRANSCRIPT_CHARS
+    }
+}
+
+pub fn measure(messages: &[Message]) -> ConversationSize {
+    let mut size = ConversationSize::default();
+    for msg in messages {
+        size.words += msg.content.split_whitespace().count();
+        size.chars += msg.content.chars().count();
+    }
+    size.tokens = size.chars.div_ceil(4);
+    size
+}
+
+pub fn transcript(messages: &[Message]) -> String {
+    let mut out = String::new();
+    for msg in messages {
+        out.push_str(&format!("{}: {}\n\n", msg.role, msg.content));
+    }
+    // Most recent part wins if the conversation is too long
+    let len = out.chars().count();
+    if len > MAX_TRANSCRIPT_CHARS {
+        out = out.chars().skip(len - MAX_TRANSCRIPT_CHARS).collect();
+    }
+    out
+}
+
+fn parse(reply: &str, message_count: usize) -> SessionSummary {
+    let (summary, rest) = match reply.find("Key takeaways:") {
+        Some(i) => (&reply[..i], &reply[i + "Key takeaways:".len()..]),
+        None => (reply, ""),
+    };
+    let takeaways = rest
+        .lines()
+        .map(|l| l.trim().trim_start_matches(['-', '*', '•']).trim())
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect();
+
+    SessionSummary {
+        summary: summary.trim().to_string(),
+        takeaways,
+        message_count,
+    }
+}
+
+// Blocking: summarize session `name` and write the result into its metadata
+pub fn summarize_session(backend: &BackendConfig, store: &SessionStore, name: &str, model: &str) -> Result<SessionSummary, String> {
+    let file = store.load(name)?;
+    if file.messages.is_empty() {
+        return Err("Nothing to summarize".to_string());
+    }
+
+    let reply = llm::complete(backend, model, SYSTEM, &transcript(&file.messages))?;
+    let summary = parse(&reply, file.messages.len());
+
+    // Read again: the chat may have been saved with more messages or a new title while
+    // the model was writing. A grown chat keeps this summary until the next one, since
+    // message_count no longer matches.
+    let mut file = store.load(name)?;
+    file.meta.summary = Some(summary.clone());
+    store.save(name, &file)?;
+    Ok(summary)
+}
@@ -0,0 +1,48 @@
+// --- SYSTEM MONITOR ---
+// RAM and CPU load of the host, next to the VRAM readout: a model that doesn't fit in
+// VRAM is partly run from system RAM on the CPU, which shows up here first. Read on the
+// same background thread as the GPU (see status_bar.rs).
+
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+const MIB: u64 = 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Reading {
+    pub used_mb: u64,
+    pub total_mb: u64,
+    pub cpu_percent: u32, // All cores averaged, since the previous reading
+}
+
+impl Reading {
+    // "RAM: 12.4 / 31.2 GB · CPU 38%"
+    pub fn summary(&self) -> String {
+        let gb = |mb: u64| mb as f64 / 1024.0;
+        format!("RAM: {:.1} / {:.1} GB · CPU {}%", gb(self.used_mb), gb(self.total_mb), self.cpu_percent)
+    }
+}
+
+pub struct Monitor {
+    system: System,
+}
+
+impl Monitor {
+    pub fn load() -> Self {
+        let mut system = System::new();
+        // CPU load is the difference between two refreshes; this is the first
+        system.refresh_cpu();
+        Self { system }
+    }
+
+    // Call at least sysinfo::MINIMUM_CPU_UPDATE_INTERVAL apart, or the CPU load reads 0
+    pub fn read(&mut self) -> Reading {
+        self.system.refresh_memory();
+        self.system.refresh_cpu();
+        Reading {
+            used_mb: self.system.used_memory() / MIB,
+            total_mb: self.system.total_memory() / MIB,
+            cpu_percent: self.system.global_cpu_info().cpu_usage().round() as u32,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// --- OUTPUT TEE ---
+// Copies assistant replies into a plain Markdown file as they stream, so a long
+// brainstorm can be followed with `tail -f` or grepped while it is still running.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub struct Tee {
+    path: PathBuf,
+    file: File,
+}
+
+impl Tee {
+    // Appends a heading for the reply about to stream
+    pub fn begin(path: &Path, session: &str, model: &str, prompt: &str) -> Result<Self, String> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut tee = Self { path: path.to_path_buf(), file };
+        let quoted: String = prompt.lines().map(|l| format!("> {}\n", l)).collect();
+        let stamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
+        tee.write(&format!("\n## {} · {} · {}\n\n{}\n", stamp, session, model, quoted))?;
+        Ok(tee)
+    }
+
+    // Unbuffered on purpose: every token is on disk as soon as it arrives
+    pub fn write(&mut self, text: &str) -> Result<(), String> {
+        self.file.write_all(text.as_bytes()).map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+
+    pub fn end(mut self, note: &str) {
+        let _ = self.write(&format!("{}\n", note));
+    }
+}
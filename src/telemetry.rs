@@ -0,0 +1,60 @@
+// --- EVENT STREAM ---
+// Structured JSONL events (requests, generation speed, retrieval, errors) for outside
+// tools: append to a file that Promtail/Vector tails, or write to a UNIX socket that a
+// collector listens on. One JSON object per line, always with `ts` and `event`.
+
+use serde_json::{json, Value};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+pub const SOCKET_PREFIX: &str = "unix:";
+
+pub enum EventSink {
+    File(File),
+    #[cfg(unix)]
+    Socket(std::os::unix::net::UnixStream),
+}
+
+impl EventSink {
+    // `target` is a file path, or `unix:/path/to.sock`
+    pub fn open(target: &str) -> Result<Self, String> {
+        let target = target.trim();
+        if let Some(socket) = target.strip_prefix(SOCKET_PREFIX) {
+            #[cfg(unix)]
+            {
+                let stream = std::os::unix::net::UnixStream::connect(socket).map_err(|e| format!("{}: {}", socket, e))?;
+                // A stalled collector must never freeze the UI
+                stream.set_nonblocking(true).map_err(|e| e.to_string())?;
+                return Ok(Self::Socket(stream));
+            }
+            #[cfg(not(unix))]
+            return Err(format!("{}: UNIX sockets are not available on this platform", socket));
+        }
+        let path = Path::new(target);
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Self::File(file))
+    }
+
+    // `fields` must be a JSON object; its keys are merged next to `ts` and `event`
+    pub fn emit(&mut self, event: &str, fields: Value) -> Result<(), String> {
+        let mut line = json!({
+            "ts": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            "event": event,
+        });
+        if let (Some(line), Value::Object(fields)) = (line.as_object_mut(), fields) {
+            line.extend(fields);
+        }
+        let mut bytes = line.to_string().into_bytes();
+        bytes.push(b'\n');
+        let result = match self {
+            Self::File(file) => file.write_all(&bytes),
+            #[cfg(unix)]
+            Self::Socket(stream) => stream.write_all(&bytes),
+        };
+        result.map_err(|e| e.to_string())
+    }
+}
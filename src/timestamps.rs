@@ -0,0 +1,241 @@
+// --- TIMESTAMPS ---
+// Messages, jobs and sessions store UTC; this turns those instants into text in the
+// configured zone and date style. Sessions also remember the time zone of the machine
+// they were created on, so a chat written elsewhere can be read in its author's time.
+// Daylight saving is followed through the zone rules: a chat that runs across a DST
+// change shows each message at the offset that applied to it.
+
+use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum DateStyle {
+    #[default]
+    Iso,      // 2025-03-14
+    DayMonth, // 14.03.2025
+    MonthDay, // 03/14/2025
+}
+
+impl DateStyle {
+    pub const ALL: [DateStyle; 3] = [DateStyle::Iso, DateStyle::DayMonth, DateStyle::MonthDay];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Iso => "2025-03-14",
+            Self::DayMonth => "14.03.2025",
+            Self::MonthDay => "03/14/2025",
+        }
+    }
+
+    fn pattern(self) -> &'static str {
+        match self {
+            Self::Iso => "%Y-%m-%d",
+            Self::DayMonth => "%d.%m.%Y",
+            Self::MonthDay => "%m/%d/%Y",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TimeConfig {
+    pub zone: String, // IANA name ("Europe/Madrid"), "UTC", or empty = this machine's zone
+    pub date_style: DateStyle,
+    pub hour12: bool,
+    pub session_zone: bool, // Show a session in the zone it was created in
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Zone {
+    Local,
+    Named(chrono_tz::Tz),
+    Fixed(FixedOffset),
+}
+
+// Formats instants for display; build one per frame or export from the config
+#[derive(Clone, Copy, Debug)]
+pub struct Clock {
+    zone: Zone,
+    date: &'static str,
+    time: &'static str,
+}
+
+pub fn parse_zone(name: &str) -> Result<Option<chrono_tz::Tz>, String> {
+    match name.trim() {
+        "" => Ok(None),
+        name => name.parse::<chrono_tz::Tz>().map(Some).map_err(|_| format!("Unknown time zone '{}'", name)),
+    }
+}
+
+// Seconds east of UTC on this machine right now
+pub fn local_offset() -> i32 {
+    Local::now().offset().local_minus_utc()
+}
+
+// Where a session was created. Sessions saved before zone names were recorded only have
+// the offset of their first write.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CreatedZone {
+    Named(chrono_tz::Tz),
+    Offset(i32),
+}
+
+impl CreatedZone {
+    // This machine: its IANA zone when the OS reports one, else its current offset
+    pub fn here() -> Self {
+        match iana_time_zone::get_timezone().ok().and_then(|name| name.parse().ok()) {
+            Some(tz) => Self::Named(tz),
+            None => Self::Offset(local_offset()),
+        }
+    }
+
+    // From the session metadata; an unknown name falls back to the stored offset
+    pub fn from_meta(zone: &str, utc_offset: Option<i32>) -> Option<Self> {
+        match parse_zone(zone) {
+            Ok(Some(tz)) => Some(Self::Named(tz)),
+            _ => utc_offset.map(Self::Offset),
+        }
+    }
+
+    // Into the metadata; the offset stays for older builds reading the file
+    pub fn store(&self, zone: &mut String, utc_offset: &mut Option<i32>) {
+        match self {
+            Self::Named(tz) => {
+                *zone = tz.name().to_string();
+                *utc_offset = Some(Utc::now().with_timezone(tz).offset().fix().local_minus_utc());
+            }
+            Self::Offset(seconds) => *utc_offset = Some(*seconds),
+        }
+    }
+
+    // Same zone as this machine, or for an offset-only session the same offset right now
+    pub fn is_here(&self) -> bool {
+        match (self, Self::here()) {
+            (Self::Named(tz), Self::Named(local)) => *tz == local,
+            (Self::Named(tz), Self::Offset(_)) => Utc::now().with_timezone(tz).offset().fix().local_minus_utc() == local_offset(),
+            (Self::Offset(seconds), _) => *seconds == local_offset(),
+        }
+    }
+
+    // "America/Chicago" or "UTC+02:00"
+    pub fn label(&self) -> String {
+        match self {
+            Self::Named(tz) => tz.name().to_string(),
+            Self::Offset(seconds) => offset_label(*seconds),
+        }
+    }
+}
+
+// 7200 -> "UTC+02:00"
+pub fn offset_label(seconds: i32) -> String {
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+    format!("UTC{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+}
+
+impl Clock {
+    // An unknown zone name falls back to local time; the settings window flags it
+    pub fn new(config: &TimeConfig) -> Self {
+        let zone = match parse_zone(&config.zone) {
+            Ok(Some(tz)) => Zone::Named(tz),
+            _ => Zone::Local,
+        };
+        Self { zone, date: config.date_style.pattern(), time: if config.hour12 { "%I:%M %p" } else { "%H:%M" } }
+    }
+
+    // Same formats, shown in the zone a session was created in
+    pub fn in_zone(self, created: &CreatedZone) -> Self {
+        match created {
+            CreatedZone::Named(tz) => Self { zone: Zone::Named(*tz), ..self },
+            CreatedZone::Offset(seconds) => match FixedOffset::east_opt(*seconds) {
+                Some(offset) => Self { zone: Zone::Fixed(offset), ..self },
+                None => self,
+            },
+        }
+    }
+
+    fn format(&self, instant: DateTime<Utc>, pattern: &str) -> String {
+        match self.zone {
+            Zone::Local => instant.with_timezone(&Local).format(pattern).to_string(),
+            Zone::Named(tz) => instant.with_timezone(&tz).format(pattern).to_string(),
+            Zone::Fixed(offset) => instant.with_timezone(&offset).format(pattern).to_string(),
+        }
+    }
+
+    fn pattern(&self, date: bool, time: bool) -> String {
+        match (date, time) {
+            (true, true) => format!("{} {}", self.date, self.time),
+            (true, false) => self.date.to_string(),
+            _ => self.time.to_string(),
+        }
+    }
+
+    // Unix seconds; empty when out of range
+    pub fn date_time(&self, seconds: i64) -> String {
+        DateTime::from_timestamp(seconds, 0).map(|t| self.format(t, &self.pattern(true, true))).unwrap_or_default()
+    }
+
+    pub fn date(&self, seconds: i64) -> String {
+        DateTime::from_timestamp(seconds, 0).map(|t| self.format(t, &self.pattern(true, false))).unwrap_or_default()
+    }
+
+    // Unix millis, as messages and timer events store them
+    pub fn date_time_millis(&self, millis: i64) -> String {
+        DateTime::from_timestamp_millis(millis).map(|t| self.format(t, &self.pattern(true, true))).unwrap_or_default()
+    }
+
+    pub fn time_millis(&self, millis: i64) -> String {
+        DateTime::from_timestamp_millis(millis).map(|t| self.format(t, &self.pattern(false, true))).unwrap_or_default()
+    }
+
+    // "Europe/Madrid", "UTC+02:00" or "local time"
+    pub fn zone_label(&self) -> String {
+        match self.zone {
+            Zone::Local => "local time".to_string(),
+            Zone::Named(tz) => tz.name().to_string(),
+            Zone::Fixed(offset) => offset_label(offset.local_minus_utc()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn clock(zone: &str, date_style: DateStyle, hour12: bool) -> Clock {
+        Clock::new(&TimeConfig { zone: zone.to_string(), date_style, hour12, session_zone: false })
+    }
+
+    #[test]
+    fn formats_in_the_configured_style() {
+        assert_eq!(clock("UTC", DateStyle::Iso, false).date_time(0), "1970-01-01 00:00");
+        assert_eq!(clock("UTC", DateStyle::DayMonth, true).date_time(13 * 3600 + 5 * 60), "01.01.1970 01:05 PM");
+        assert_eq!(clock("UTC", DateStyle::MonthDay, false).date(0), "01/01/1970");
+    }
+
+    #[test]
+    fn follows_daylight_saving() {
+        let madrid = clock("Europe/Madrid", DateStyle::Iso, false);
+        let winter = Utc.with_ymd_and_hms(2025, 1, 15, 12, 0, 0).unwrap().timestamp();
+        let summer = Utc.with_ymd_and_hms(2025, 7, 15, 12, 0, 0).unwrap().timestamp();
+        assert_eq!(madrid.date_time(winter), "2025-01-15 13:00");
+        assert_eq!(madrid.date_time(summer), "2025-07-15 14:00");
+    }
+
+    #[test]
+    fn shows_a_session_in_its_created_zone() {
+        let utc = clock("UTC", DateStyle::Iso, false);
+        let shifted = utc.in_zone(&CreatedZone::Offset(7200));
+        assert_eq!(shifted.date_time(0), "1970-01-01 02:00");
+        assert_eq!(shifted.zone_label(), "UTC+02:00");
+        assert_eq!(offset_label(-16200), "UTC-04:30");
+    }
+
+    #[test]
+    fn falls_back_to_the_stored_offset() {
+        assert_eq!(CreatedZone::from_meta("Bogus/Zone", Some(3600)), Some(CreatedZone::Offset(3600)));
+        assert_eq!(CreatedZone::from_meta("", None), None);
+        assert_eq!(CreatedZone::from_meta("Asia/Tokyo", Some(0)), Some(CreatedZone::Named(chrono_tz::Asia::Tokyo)));
+    }
+}
@@ -0,0 +1,196 @@
+// --- CROSS-SESSION TOPIC INDEX ---
+// One small model call per saved session lists the technical topics it covers and the
+// messages where each comes up. The results are kept in a JSON index next to the
+// sessions folder; a session is only re-read when it has grown since it was indexed.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use crate::session::Message;
+use crate::session_store::SessionStore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+pub const TOPICS_FILE: &str = "topic_index.json";
+const MESSAGE_CHARS: usize = 600; // Per message in the extraction prompt
+const MAX_TOPICS: usize = 12; // Per session
+const CHUNK_CHARS: usize = 12_000; // Per extraction call; longer sessions take several
+const MAX_FAILURES: usize = 3; // In a row: then it is the server, not the sessions
+
+const EXTRACTOR: &str = "You index a tutoring conversation by topic. List the technical topics \
+(components, concepts, methods, e.g. \"op-amps\", \"Miller effect\", \"Laplace transform\") that \
+are actually explained or worked on, with the numbers of the messages where each comes up. \
+Reply with ONLY a JSON object like {\"op-amps\": [0, 3], \"Bode plots\": [5]}. Reply {} if there are none.";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Mention {
+    pub topic: String, // As the model wrote it
+    pub messages: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct SessionTopics {
+    pub message_count: usize, // Length when indexed
+    pub mentions: Vec<Mention>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct TopicIndex {
+    pub sessions: BTreeMap<String, SessionTopics>, // Keyed by session name
+}
+
+// One entry of the topic page: every session (and message) that discussed it
+pub struct Topic {
+    pub name: String,
+    pub sessions: Vec<(String, Vec<usize>)>,
+}
+
+impl Topic {
+    pub fn message_count(&self) -> usize {
+        self.sessions.iter().map(|(_, m)| m.len().max(1)).sum()
+    }
+}
+
+// "Op-Amps", "op amp" and "op-amps" group together
+fn topic_key(topic: &str) -> String {
+    let key: String = topic.trim().to_lowercase().replace(['-', '_'], " ").split_whitespace().collect::<Vec<_>>().join(" ");
+    match key.strip_suffix('s') {
+        Some(singular) if singular.len() > 3 && !singular.ends_with('s') => singular.to_string(),
+        _ => key,
+    }
+}
+
+impl TopicIndex {
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let raw = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        crate::session::write_atomic(path, raw.as_bytes()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    // Most-discussed first; the display name is the most common spelling
+    pub fn topics(&self) -> Vec<Topic> {
+        let mut groups: HashMap<String, (HashMap<&str, usize>, Vec<(String, Vec<usize>)>)> = HashMap::new();
+        for (session, entry) in &self.sessions {
+            for mention in &entry.mentions {
+                let (spellings, sessions) = groups.entry(topic_key(&mention.topic)).or_default();
+                *spellings.entry(mention.topic.trim()).or_default() += 1;
+                match sessions.iter_mut().find(|(s, _)| s == session) {
+                    Some((_, messages)) => messages.extend(&mention.messages),
+                    None => sessions.push((session.clone(), mention.messages.clone())),
+                }
+            }
+        }
+        let mut topics: Vec<Topic> = groups
+            .into_values()
+            .map(|(spellings, mut sessions)| {
+                for (_, messages) in &mut sessions {
+                    messages.sort_unstable();
+                    messages.dedup();
+                }
+                sessions.sort_by(|a, b| b.0.cmp(&a.0)); // Newest session names first
+                let name = spellings.into_iter().max_by_key(|(s, n)| (*n, std::cmp::Reverse(*s))).map(|(s, _)| s.to_string()).unwrap_or_default();
+                Topic { name, sessions }
+            })
+            .collect();
+        topics.sort_by(|a, b| b.sessions.len().cmp(&a.sessions.len()).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+        topics
+    }
+}
+
+// Blocking. `first` is the session index of messages[0], so the numbers the model sees
+// (and returns) are the session's own.
+fn extract_chunk(backend: &BackendConfig, model: &str, messages: &[Message], first: usize) -> Result<Vec<Mention>, String> {
+    let mut prompt = String::new();
+    for (i, msg) in messages.iter().enumerate() {
+        let text: String = msg.content.chars().take(MESSAGE_CHARS).collect();
+        prompt.push_str(&format!("[{}] {}: {}\n\n", first + i, msg.role, text));
+    }
+    let reply = llm::complete(backend, model, EXTRACTOR, &prompt)?;
+    let json = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err(format!("Not a topic list: {}", reply.trim())),
+    };
+    let raw: BTreeMap<String, Vec<usize>> = serde_json::from_str(json).map_err(|e| format!("Bad topic list: {}", e))?;
+    // Indices the model made up are dropped
+    Ok(raw
+        .into_iter()
+        .filter(|(topic, _)| !topic.trim().is_empty())
+        .map(|(topic, indices)| Mention { topic, messages: indices.into_iter().filter(|i| (first..first + messages.len()).contains(i)).collect() })
+        .collect())
+}
+
+// Blocking: one call per CHUNK_CHARS of prompt, with the mentions of each topic merged
+// and the most-mentioned MAX_TOPICS kept
+pub fn extract(backend: &BackendConfig, model: &str, messages: &[Message]) -> Result<Vec<Mention>, String> {
+    let mut merged: Vec<Mention> = Vec::new();
+    let mut start = 0;
+    while start < messages.len() {
+        let mut end = start;
+        let mut chars = 0;
+        while end < messages.len() && (end == start || chars + MESSAGE_CHARS <= CHUNK_CHARS) {
+            chars += messages[end].content.chars().count().min(MESSAGE_CHARS);
+            end += 1;
+        }
+        for mention in extract_chunk(backend, model, &messages[start..end], start)? {
+            match merged.iter_mut().find(|m| topic_key(&m.topic) == topic_key(&mention.topic)) {
+                Some(m) => m.messages.extend(mention.messages),
+                None => merged.push(mention),
+            }
+        }
+        start = end;
+    }
+    merged.sort_by(|a, b| b.messages.len().cmp(&a.messages.len()));
+    merged.truncate(MAX_TOPICS);
+    Ok(merged)
+}
+
+// Blocking: indexes new and grown sessions, saving after each one so an interrupted
+// run keeps its progress. A session the model can't index is passed to `skipped` with
+// the reason and tried again next run. Returns how many sessions were (re)indexed.
+pub fn update(
+    backend: &BackendConfig,
+    model: &str,
+    store: &SessionStore,
+    index_path: &Path,
+    mut progress: impl FnMut(&str),
+    mut skipped: impl FnMut(&str),
+) -> Result<usize, String> {
+    let mut index = TopicIndex::load(index_path);
+    let stored = store.list();
+    index.sessions.retain(|name, _| stored.iter().any(|s| &s.name == name));
+
+    let mut updated = 0;
+    let mut failures = 0;
+    for (i, entry) in stored.iter().enumerate() {
+        let file = match store.load(&entry.name) {
+            Ok(file) => file,
+            Err(_) => continue, // Unreadable sessions are skipped, not fatal
+        };
+        let count = file.messages.len();
+        if count == 0 || index.sessions.get(&entry.name).is_some_and(|s| s.message_count == count) {
+            continue;
+        }
+        progress(&format!("Indexing {}/{}: {}", i + 1, stored.len(), entry.title));
+        let mentions = match extract(backend, model, &file.messages) {
+            Ok(mentions) => mentions,
+            Err(e) if failures + 1 >= MAX_FAILURES => return Err(e),
+            Err(e) => {
+                failures += 1;
+                skipped(&format!("{}: {}", entry.title, e));
+                continue;
+            }
+        };
+        failures = 0;
+        index.sessions.insert(entry.name.clone(), SessionTopics { message_count: count, mentions });
+        index.save(index_path)?;
+        updated += 1;
+    }
+    index.save(index_path)?;
+    Ok(updated)
+}
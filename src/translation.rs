@@ -0,0 +1,115 @@
+// --- BACK-TRANSLATION CHECK ---
+// For Spanish <-> English replies: translate the model's output back into the source
+// language and score how close it lands to what was originally written.
+
+use crate::backend::BackendConfig;
+use crate::llm;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Lang {
+    English,
+    Spanish,
+}
+
+impl Lang {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lang::English => "English",
+            Lang::Spanish => "Spanish",
+        }
+    }
+}
+
+const ENGLISH_HINTS: &[&str] = &["the", "and", "is", "of", "to", "with", "that", "for", "this", "are", "which", "be"];
+const SPANISH_HINTS: &[&str] = &["el", "la", "los", "las", "de", "que", "y", "en", "es", "con", "para", "una", "por", "del"];
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TranslationCheck {
+    pub message_index: usize,
+    pub score: f32, // 0..1
+    pub back_translation: String,
+}
+
+impl TranslationCheck {
+    pub fn badge(&self) -> &'static str {
+        match self.score {
+            s if s >= 0.6 => "🟢",
+            s if s >= 0.4 => "🟡",
+            _ => "🔴",
+        }
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+// Stopword vote; None when the text is too short or neither language dominates
+pub fn detect(text: &str) -> Option<Lang> {
+    let words = words(text);
+    if words.len() < 5 {
+        return None;
+    }
+    let en = words.iter().filter(|w| ENGLISH_HINTS.contains(&w.as_str())).count();
+    let es = words.iter().filter(|w| SPANISH_HINTS.contains(&w.as_str())).count();
+    match (en, es) {
+        (en, es) if en > es * 2 && en >= 2 => Some(Lang::English),
+        (en, es) if es > en * 2 && es >= 2 => Some(Lang::Spanish),
+        _ => None,
+    }
+}
+
+// The text to translate when the prompt asks for a translation: "Translate to Spanish:
+// <text>", or the instruction on its own first line with the text below it. None for
+// any other prompt, so a reply that merely switches language isn't checked.
+pub fn translation_source(prompt: &str) -> Option<&str> {
+    let first_line = prompt.lines().next().unwrap_or_default();
+    let lower = first_line.to_lowercase();
+    if !(lower.contains("translat") || lower.contains("traduc")) {
+        return None;
+    }
+    let rest = match first_line.find(':') {
+        Some(colon) if colon < 60 => &prompt[colon + 1..],
+        _ => &prompt[first_line.len()..],
+    };
+    Some(rest.trim()).filter(|text| !text.is_empty())
+}
+
+// Cosine similarity over word unigrams + bigrams; numbers and units count like any other word
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let bag = |text: &str| {
+        let words = words(text);
+        let mut counts: HashMap<String, f32> = HashMap::new();
+        for w in &words {
+            *counts.entry(w.clone()).or_default() += 1.0;
+        }
+        for pair in words.windows(2) {
+            *counts.entry(format!("{} {}", pair[0], pair[1])).or_default() += 1.0;
+        }
+        counts
+    };
+    let (a, b) = (bag(a), bag(b));
+    let dot: f32 = a.iter().filter_map(|(k, v)| b.get(k).map(|w| v * w)).sum();
+    let norm = |m: &HashMap<String, f32>| m.values().map(|v| v * v).sum::<f32>().sqrt();
+    let denom = norm(&a) * norm(&b);
+    if denom == 0.0 { 0.0 } else { dot / denom }
+}
+
+// Blocking
+pub fn check(backend: &BackendConfig, model: &str, source: &str, translation: &str, source_lang: Lang, message_index: usize) -> Result<TranslationCheck, String> {
+    let system = format!(
+        "Translate the user's text into {}. Keep technical terms, numbers and units exact. Reply with the translation only.",
+        source_lang.name()
+    );
+    let back_translation = llm::complete(backend, model, &system, translation)?;
+    Ok(TranslationCheck {
+        message_index,
+        score: similarity(source, &back_translation),
+        back_translation,
+    })
+}
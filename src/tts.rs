@@ -0,0 +1,59 @@
+// --- TEXT TO SPEECH ---
+// Reads answers aloud through espeak-ng; each voice keeps its own speed and pitch.
+
+use serde::{Deserialize, Serialize};
+use std::process::{Child, Command, Stdio};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct VoiceConfig {
+    pub voice: String, // espeak-ng voice id, e.g. "en-us" or "es-419"
+    pub speed: u32,    // Words per minute
+    pub pitch: u32,    // 0..=99
+}
+
+impl Default for VoiceConfig {
+    fn default() -> Self {
+        Self {
+            voice: "en-us".to_string(),
+            speed: 175,
+            pitch: 50,
+        }
+    }
+}
+
+pub const PREVIEW_TEXT: &str = "The voltage across the capacitor rises exponentially with time constant R C.";
+
+// Installed voice ids (second column of `espeak-ng --voices`)
+pub fn list_voices() -> Vec<String> {
+    let output = match Command::new("espeak-ng").arg("--voices").output() {
+        Ok(o) => o,
+        Err(_) => return Vec::new(),
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(String::from)
+        .collect()
+}
+
+// Markdown symbols read aloud as "asterisk asterisk" are just noise
+pub fn speakable(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, '*' | '#' | '`' | '_' | '>' | '|'))
+        .collect()
+}
+
+// Starts speaking in the background; kill the returned child to stop early
+pub fn speak(text: &str, voice: &VoiceConfig) -> Result<Child, String> {
+    Command::new("espeak-ng")
+        .arg("-v").arg(&voice.voice)
+        .arg("-s").arg(voice.speed.to_string())
+        .arg("-p").arg(voice.pitch.min(99).to_string())
+        .arg(speakable(text))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to start espeak-ng: {}", e))
+}
@@ -0,0 +1,212 @@
+// --- HANDS-FREE VOICE CONVERSATION ---
+// The microphone is read through an external recorder (arecord by default) as raw 16 kHz
+// mono PCM. An energy-based voice activity detector cuts the stream into utterances, each
+// is written as a WAV file and transcribed by an external speech-to-text command
+// (whisper.cpp by default). Replies are spoken with the normal TTS voice.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+const SAMPLE_RATE: u32 = 16_000;
+const FRAME_SAMPLES: usize = 480; // 30 ms
+pub const LEVEL_HISTORY: usize = 100; // Frames kept for the waveform (3 s)
+const MIN_SPEECH_FRAMES: usize = 8; // Shorter bursts (clicks, coughs) are dropped
+const PRE_ROLL_FRAMES: usize = 5; // Kept from before the onset so the first syllable isn't cut
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct VoiceChatConfig {
+    pub record_command: String, // Must write raw signed 16-bit 16 kHz mono PCM to stdout
+    pub stt_command: String,    // "{wav}" is replaced by the utterance file; prints the transcript
+    pub vad_threshold: f32,     // RMS level (0..1) that counts as speech
+    pub silence_ms: u32,        // Pause that ends an utterance
+}
+
+impl Default for VoiceChatConfig {
+    fn default() -> Self {
+        Self {
+            record_command: "arecord -q -f S16_LE -r 16000 -c 1 -t raw".to_string(),
+            stt_command: "whisper-cli -m models/ggml-base.en.bin -nt -np -f {wav}".to_string(),
+            vad_threshold: 0.02,
+            silence_ms: 900,
+        }
+    }
+}
+
+// A running recorder; dropping it stops the recording
+pub struct Microphone {
+    child: Child,
+    levels: Arc<Mutex<VecDeque<f32>>>,
+    muted: Arc<AtomicBool>,
+    speaking: Arc<AtomicBool>,
+}
+
+impl Microphone {
+    // `on_utterance` runs on the reader thread with each transcript (or the STT error)
+    pub fn start(config: &VoiceChatConfig, on_utterance: impl Fn(Result<String, String>) + Send + 'static) -> Result<Self, String> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&config.record_command)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start `{}`: {}", config.record_command, e))?;
+        let stdout = child.stdout.take().ok_or("Recorder has no output")?;
+
+        let levels = Arc::new(Mutex::new(VecDeque::with_capacity(LEVEL_HISTORY)));
+        let muted = Arc::new(AtomicBool::new(false));
+        let speaking = Arc::new(AtomicBool::new(false));
+        let detector = Detector {
+            threshold: config.vad_threshold,
+            silence_frames: (config.silence_ms as usize * SAMPLE_RATE as usize / 1000 / FRAME_SAMPLES).max(1),
+            stt_command: config.stt_command.clone(),
+            levels: levels.clone(),
+            muted: muted.clone(),
+            speaking: speaking.clone(),
+        };
+        std::thread::spawn(move || detector.run(stdout, on_utterance));
+        Ok(Self { child, levels, muted, speaking })
+    }
+
+    // Ignore the microphone while the reply is generated and read out, so it can't hear itself
+    pub fn set_muted(&self, muted: bool) {
+        self.muted.store(muted, Ordering::Relaxed);
+    }
+
+    pub fn is_speaking(&self) -> bool {
+        self.speaking.load(Ordering::Relaxed)
+    }
+
+    // Oldest first, for the waveform
+    pub fn levels(&self) -> Vec<f32> {
+        self.levels.lock().map(|l| l.iter().copied().collect()).unwrap_or_default()
+    }
+}
+
+impl Drop for Microphone {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+struct Detector {
+    threshold: f32,
+    silence_frames: usize,
+    stt_command: String,
+    levels: Arc<Mutex<VecDeque<f32>>>,
+    muted: Arc<AtomicBool>,
+    speaking: Arc<AtomicBool>,
+}
+
+impl Detector {
+    fn run(self, mut pcm: impl Read, on_utterance: impl Fn(Result<String, String>)) {
+        let mut bytes = vec![0u8; FRAME_SAMPLES * 2];
+        let mut pre_roll: VecDeque<Vec<i16>> = VecDeque::new();
+        let mut utterance: Vec<i16> = Vec::new();
+        let (mut voiced, mut quiet) = (0usize, 0usize);
+
+        // Ends when the recorder exits (stopped or failed)
+        while pcm.read_exact(&mut bytes).is_ok() {
+            let frame: Vec<i16> = bytes.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect();
+            let level = rms(&frame);
+            if let Ok(mut levels) = self.levels.lock() {
+                if levels.len() == LEVEL_HISTORY {
+                    levels.pop_front();
+                }
+                levels.push_back(level);
+            }
+            if self.muted.load(Ordering::Relaxed) {
+                utterance.clear();
+                (voiced, quiet) = (0, 0);
+                self.speaking.store(false, Ordering::Relaxed);
+                continue;
+            }
+
+            // 1. Waiting for speech: keep a little audio from before the onset
+            if voiced == 0 {
+                if level < self.threshold {
+                    pre_roll.push_back(frame);
+                    if pre_roll.len() > PRE_ROLL_FRAMES {
+                        pre_roll.pop_front();
+                    }
+                    continue;
+                }
+                utterance.extend(pre_roll.drain(..).flatten());
+                self.speaking.store(true, Ordering::Relaxed);
+            }
+
+            // 2. In an utterance until the pause is long enough
+            utterance.extend_from_slice(&frame);
+            voiced += 1;
+            quiet = if level < self.threshold { quiet + 1 } else { 0 };
+            if quiet < self.silence_frames {
+                continue;
+            }
+
+            // 3. Transcribe on this thread; audio read meanwhile queues in the pipe
+            self.speaking.store(false, Ordering::Relaxed);
+            if voiced - quiet >= MIN_SPEECH_FRAMES {
+                match transcribe(&self.stt_command, &utterance) {
+                    Ok(text) if text.is_empty() => {}
+                    result => on_utterance(result),
+                }
+            }
+            utterance.clear();
+            (voiced, quiet) = (0, 0);
+        }
+    }
+}
+
+fn rms(frame: &[i16]) -> f32 {
+    let sum: f64 = frame.iter().map(|&s| (s as f64 / i16::MAX as f64).powi(2)).sum();
+    (sum / frame.len().max(1) as f64).sqrt() as f32
+}
+
+fn write_wav(path: &Path, samples: &[i16]) -> Result<(), String> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut out = Vec::with_capacity(44 + data_len as usize);
+    out.extend_from_slice(b"RIFF");
+    out.extend_from_slice(&(36 + data_len).to_le_bytes());
+    out.extend_from_slice(b"WAVEfmt ");
+    out.extend_from_slice(&16u32.to_le_bytes()); // PCM header size
+    out.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    out.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    out.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    out.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // Byte rate
+    out.extend_from_slice(&2u16.to_le_bytes()); // Block align
+    out.extend_from_slice(&16u16.to_le_bytes()); // Bits per sample
+    out.extend_from_slice(b"data");
+    out.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        out.extend_from_slice(&s.to_le_bytes());
+    }
+    std::fs::write(path, out).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// Blocking
+fn transcribe(stt_command: &str, samples: &[i16]) -> Result<String, String> {
+    let wav = std::env::temp_dir().join(format!("ship_utterance_{}.wav", std::process::id()));
+    write_wav(&wav, samples)?;
+    let cmd = stt_command.replace("{wav}", &format!("\"{}\"", wav.display()));
+    let output = Command::new("sh").arg("-c").arg(&cmd).output();
+    let _ = std::fs::remove_file(&wav);
+    let output = output.map_err(|e| format!("Failed to start `{}`: {}", cmd, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Speech-to-text failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+    }
+    // whisper.cpp prints one line per segment; "[BLANK_AUDIO]" and friends are noise
+    let text = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !(l.starts_with('[') && l.ends_with(']')))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Ok(text)
+}
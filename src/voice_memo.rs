@@ -0,0 +1,206 @@
+// --- VOICE MEMOS ---
+// An answer, a whole conversation or its summary rendered to one audio file: espeak-ng
+// speaks each part to WAV, ffmpeg joins and encodes them, and every part becomes a
+// chapter (ID3 CHAP frames in MP3, CHAPTERxx comments in Ogg).
+
+use crate::tts::{self, VoiceConfig};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoFormat {
+    Mp3,
+    Ogg,
+}
+
+impl MemoFormat {
+    pub const ALL: [MemoFormat; 2] = [MemoFormat::Mp3, MemoFormat::Ogg];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::Ogg => "ogg",
+        }
+    }
+
+    fn codec(self) -> &'static str {
+        match self {
+            Self::Mp3 => "libmp3lame",
+            Self::Ogg => "libvorbis",
+        }
+    }
+}
+
+// One chapter: its title is spoken before the text
+pub struct Chapter {
+    pub title: String,
+    pub text: String,
+}
+
+// Length of a PCM WAV in milliseconds, from its fmt and data chunks
+fn wav_millis(path: &Path) -> Result<u64, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let le32 = |at: usize| bytes.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as u64);
+    let mut at = 12; // After "RIFF" <size> "WAVE"
+    let mut byte_rate = None;
+    while at + 8 <= bytes.len() {
+        let id = &bytes[at..at + 4];
+        let size = le32(at + 4).unwrap_or(0);
+        match id {
+            b"fmt " => byte_rate = le32(at + 16),
+            // espeak-ng writes 0x7fffffff as the data size when it streams to a file
+            b"data" => {
+                let data = size.min((bytes.len() - at - 8) as u64);
+                let rate = byte_rate.filter(|&r| r > 0).ok_or("WAV without a format chunk")?;
+                return Ok(data * 1000 / rate);
+            }
+            _ => {}
+        }
+        at += 8 + size as usize + (size as usize & 1);
+    }
+    Err(format!("{}: no audio data", path.display()))
+}
+
+// ffmpeg's metadata format: values escape =;#\ and newlines
+fn escape(text: &str) -> String {
+    let mut out = String::new();
+    for c in text.chars() {
+        match c {
+            '=' | ';' | '#' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push(' '),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// Blocking: `progress` is called before each step. Needs espeak-ng and ffmpeg on PATH.
+pub fn render(
+    out: &Path,
+    title: &str,
+    chapters: &[Chapter],
+    voice: &VoiceConfig,
+    format: MemoFormat,
+    mut progress: impl FnMut(&str),
+) -> Result<(), String> {
+    let work = std::env::temp_dir().join(format!("ship_memo_{}", std::process::id()));
+    std::fs::create_dir_all(&work).map_err(|e| format!("{}: {}", work.display(), e))?;
+    let result = render_in(&work, out, title, chapters, voice, format, &mut progress);
+    let _ = std::fs::remove_dir_all(&work);
+    result
+}
+
+fn render_in(
+    work: &Path,
+    out: &Path,
+    title: &str,
+    chapters: &[Chapter],
+    voice: &VoiceConfig,
+    format: MemoFormat,
+    progress: &mut impl FnMut(&str),
+) -> Result<(), String> {
+    // 1. One WAV per chapter
+    let mut parts: Vec<(PathBuf, u64)> = Vec::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        progress(&format!("Speaking {}/{}...", i + 1, chapters.len()));
+        let wav = work.join(format!("part_{:03}.wav", i));
+        // From a file: a long chapter would overrun the argument size limit, and text
+        // starting with '-' would be read as an option
+        let text_file = work.join(format!("part_{:03}.txt", i));
+        let text = format!("{}.\n\n{}", chapter.title, chapter.text);
+        std::fs::write(&text_file, tts::speakable(&text)).map_err(|e| format!("{}: {}", text_file.display(), e))?;
+        let status = Command::new("espeak-ng")
+            .arg("-v").arg(&voice.voice)
+            .arg("-s").arg(voice.speed.to_string())
+            .arg("-p").arg(voice.pitch.min(99).to_string())
+            .arg("-w").arg(&wav)
+            .arg("-f").arg(&text_file)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map_err(|e| format!("Failed to start espeak-ng: {}", e))?;
+        if !status.success() {
+            return Err(format!("espeak-ng failed on part {}", i + 1));
+        }
+        let millis = wav_millis(&wav)?;
+        parts.push((wav, millis));
+    }
+
+    // 2. Concat list and chapter marks
+    let list: String = parts.iter().map(|(wav, _)| format!("file '{}'\n", wav.display().to_string().replace('\'', "'\\''"))).collect();
+    let list_file = work.join("parts.txt");
+    std::fs::write(&list_file, list).map_err(|e| e.to_string())?;
+
+    let mut meta = format!(";FFMETADATA1\ntitle={}\n", escape(title));
+    let mut start = 0;
+    for ((_, millis), chapter) in parts.iter().zip(chapters) {
+        meta.push_str(&format!("\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n", start, start + millis, escape(&chapter.title)));
+        start += millis;
+    }
+    let meta_file = work.join("chapters.txt");
+    std::fs::write(&meta_file, meta).map_err(|e| e.to_string())?;
+
+    // 3. Encode
+    progress("Encoding...");
+    let output = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-f", "concat", "-safe", "0", "-i"])
+        .arg(&list_file)
+        .arg("-i")
+        .arg(&meta_file)
+        .args(["-map", "0:a", "-map_metadata", "1", "-map_chapters", "1", "-c:a", format.codec(), "-q:a", "4"])
+        .arg(out)
+        .output()
+        .map_err(|e| format!("Failed to start ffmpeg: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("ffmpeg failed: {}", stderr.lines().last().unwrap_or("unknown error")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wav_millis;
+
+    // 16 kHz mono 16-bit: 32000 bytes per second
+    fn wav(data_size: u32, data: usize) -> Vec<u8> {
+        let mut bytes = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes()); // PCM
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(16000u32.to_le_bytes());
+        bytes.extend(32000u32.to_le_bytes());
+        bytes.extend(2u16.to_le_bytes());
+        bytes.extend(16u16.to_le_bytes());
+        bytes.extend(b"data");
+        bytes.extend(data_size.to_le_bytes());
+        bytes.extend(vec![0u8; data]);
+        bytes
+    }
+
+    fn millis_of(name: &str, bytes: &[u8]) -> Result<u64, String> {
+        let path = std::env::temp_dir().join(format!("ship_{}_{}.wav", name, std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        let millis = wav_millis(&path);
+        let _ = std::fs::remove_file(&path);
+        millis
+    }
+
+    #[test]
+    fn reads_the_length_from_the_data_chunk() {
+        assert_eq!(millis_of("wav_second", &wav(32000, 32000)), Ok(1000));
+    }
+
+    #[test]
+    fn streamed_files_use_the_bytes_present() {
+        assert_eq!(millis_of("wav_streamed", &wav(0x7fff_ffff, 16000)), Ok(500));
+    }
+
+    #[test]
+    fn needs_a_format_chunk() {
+        assert!(millis_of("wav_headless", b"RIFF\0\0\0\0WAVEdata\x04\0\0\0\0\0\0\0").is_err());
+    }
+}
@@ -0,0 +1,66 @@
+// --- SCHEDULED WARM-UP ---
+// "Load gemma3:27b at 08:50 on weekdays": entries in the config are checked once a
+// minute while the app runs, and a due entry loads its model with a keep_alive so it is
+// already resident when the study block starts.
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+// A launch this long after the scheduled time still warms up (e.g. the app was opened late)
+pub const GRACE_MINUTES: i64 = 30;
+
+pub const DAYS: [Weekday; 7] = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Warmup {
+    pub enabled: bool,
+    pub model: String,
+    pub time: String,       // HH:MM, local
+    pub days: Vec<String>,  // "Mon".."Sun"
+    pub keep_alive: String, // Sent to Ollama: "2h", "45m", or seconds
+}
+
+impl Default for Warmup {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            model: String::new(),
+            time: "08:50".to_string(),
+            days: DAYS[..5].iter().map(|d| d.to_string()).collect(),
+            keep_alive: "2h".to_string(),
+        }
+    }
+}
+
+impl Warmup {
+    pub fn parsed_time(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(self.time.trim(), "%H:%M").ok()
+    }
+
+    pub fn runs_on(&self, day: Weekday) -> bool {
+        self.days.iter().any(|d| d.eq_ignore_ascii_case(&day.to_string()))
+    }
+
+    // Due when today is one of its days and `now` is within the grace period after its
+    // time; `last_run` stops it from firing twice on the same day
+    pub fn is_due(&self, now: NaiveDateTime, last_run: Option<NaiveDate>) -> bool {
+        let Some(time) = self.parsed_time() else {
+            return false;
+        };
+        if !self.enabled || self.model.trim().is_empty() || !self.runs_on(now.weekday()) || last_run == Some(now.date()) {
+            return false;
+        }
+        let late = now.signed_duration_since(now.date().and_time(time)).num_minutes();
+        (0..=GRACE_MINUTES).contains(&late)
+    }
+
+    pub fn describe(&self) -> String {
+        let days = match self.days.len() {
+            7 => "every day".to_string(),
+            5 if DAYS[..5].iter().all(|d| self.runs_on(*d)) => "weekdays".to_string(),
+            _ => self.days.join(", "),
+        };
+        format!("{} at {} {}", self.model, self.time, days)
+    }
+}
@@ -0,0 +1,139 @@
+// --- WORD CLOUD ---
+// Term counts over one session or the whole history, computed locally: no model call,
+// just the words of every message minus stop words, with plurals folded into the
+// singular. The topic bar chart next to it reads the existing topic index.
+
+use crate::session::Message;
+use crate::session_store::SessionStore;
+use crate::topics::TopicIndex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const MAX_TERMS: usize = 80;
+pub const MAX_TOPICS: usize = 15;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Term {
+    pub word: String, // The most common spelling
+    pub count: usize,
+}
+
+// English and Spanish function words, plus chat filler
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "are", "but", "not", "you", "your", "with", "this", "that", "these", "those", "from", "have", "has",
+    "had", "was", "were", "will", "would", "can", "could", "should", "into", "about", "than", "then", "them", "they", "their",
+    "there", "here", "what", "when", "where", "which", "while", "who", "why", "how", "also", "just", "only", "some", "such",
+    "each", "any", "all", "more", "most", "other", "very", "like", "use", "used", "using", "its", "it's", "our", "out", "one",
+    "two", "let", "get", "does", "did", "doing", "been", "being", "because", "over", "under", "same", "both", "between", "through",
+    "yes", "okay", "please", "thanks", "thank", "sure", "need", "want", "know", "think", "see", "make", "way", "well", "much",
+    "many", "may", "might", "must", "now", "i'm", "don't", "can't", "isn't", "that's", "here's", "let's", "you're", "we're",
+    "example", "answer", "question", "means", "mean", "note", "first", "second", "give", "gives", "given", "find", "below", "above",
+    "los", "las", "del", "que", "por", "para", "con", "una", "uno", "como", "más", "pero", "esta", "este", "esto", "son", "hay",
+];
+
+// "Capacitors" and "capacitor" count together; short words keep their s ("gas", "bus")
+fn fold(word: &str) -> String {
+    match word.strip_suffix('s') {
+        Some(singular) if singular.len() > 3 && !singular.ends_with('s') => singular.to_string(),
+        _ => word.to_string(),
+    }
+}
+
+fn is_term(word: &str) -> bool {
+    word.chars().count() >= 3 && word.chars().any(|c| c.is_alphabetic()) && !STOP_WORDS.contains(&word)
+}
+
+// Folded word -> (count, how often each spelling was used)
+type Counts = HashMap<String, (usize, HashMap<String, usize>)>;
+
+fn count(counts: &mut Counts, messages: &[Message]) {
+    for message in messages {
+        // Code blocks are identifiers and syntax, not what the chat was about
+        let mut in_code = false;
+        for line in message.content.lines() {
+            if line.trim_start().starts_with("```") {
+                in_code = !in_code;
+                continue;
+            }
+            if in_code {
+                continue;
+            }
+            for raw in line.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '\'')) {
+                let word = raw.trim_matches(|c| c == '-' || c == '\'').to_lowercase();
+                if !is_term(&word) {
+                    continue;
+                }
+                let (count, spellings) = counts.entry(fold(&word)).or_default();
+                *count += 1;
+                *spellings.entry(word).or_default() += 1;
+            }
+        }
+    }
+}
+
+// Most frequent first, at most `limit`
+pub fn terms(messages: &[Message], limit: usize) -> Vec<Term> {
+    let mut counts = Counts::new();
+    count(&mut counts, messages);
+    ranked(counts, limit)
+}
+
+fn ranked(counts: Counts, limit: usize) -> Vec<Term> {
+    let mut terms: Vec<Term> = counts
+        .into_values()
+        .filter(|(count, _)| *count > 1)
+        .map(|(count, spellings)| {
+            let word = spellings.into_iter().max_by_key(|(s, n)| (*n, std::cmp::Reverse(s.clone()))).map(|(s, _)| s).unwrap_or_default();
+            Term { word, count }
+        })
+        .collect();
+    terms.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    terms.truncate(limit);
+    terms
+}
+
+// Blocking: reads every saved session, one at a time, so only the counts stay in memory
+pub fn history_terms(store: &SessionStore, limit: usize) -> Vec<Term> {
+    let mut counts = Counts::new();
+    for session in store.list() {
+        if let Ok(file) = store.load(&session.name) {
+            count(&mut counts, &file.messages);
+        }
+    }
+    ranked(counts, limit)
+}
+
+// (topic, messages) from the topic index, for one session or all of them
+pub fn topic_counts(index: &TopicIndex, session: Option<&str>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = match session {
+        Some(name) => index.sessions
+            .get(name)
+            .map(|entry| entry.mentions.iter().map(|m| (m.topic.clone(), m.messages.len().max(1))).collect())
+            .unwrap_or_default(),
+        None => index.topics().into_iter().map(|t| {
+            let count = t.message_count();
+            (t.name, count)
+        }).collect(),
+    };
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(MAX_TOPICS);
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fold;
+
+    #[test]
+    fn plurals_fold_into_the_singular() {
+        assert_eq!(fold("capacitors"), "capacitor");
+        assert_eq!(fold("resistors"), "resistor");
+    }
+
+    #[test]
+    fn short_words_and_double_s_keep_their_s() {
+        for word in ["gas", "bus", "amps", "class", "loss"] {
+            assert_eq!(fold(word), word);
+        }
+    }
+}